@@ -7,9 +7,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Create some test sessions
     println!("Creating test sessions...");
-    client.create_session("example-1")?;
-    client.create_session("example-2")?;
-    client.create_session("example-3")?;
+    client.create_session("example-1", None, None)?;
+    client.create_session("example-2", None, None)?;
+    client.create_session("example-3", None, None)?;
 
     // List all sessions
     println!("\nAll tmux sessions:");
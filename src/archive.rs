@@ -0,0 +1,123 @@
+//! Sessions archived instead of killed, to be restored later like from a recycle bin
+//!
+//! Archiving records the windows, splits and working directories of a session the
+//! way a snapshot does, then kills it. Each archived session is a JSON file in the
+//! archive directory named after the session and the time it was archived, e.g.
+//! `work-1714555800.json`.
+
+use crate::tmux::SessionSnapshot;
+use anyhow::{Context, Result};
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    /// Label of the configured server the session ran on, `None` for the default one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub archived_at: DateTime<Utc>,
+    pub session: SessionSnapshot,
+    /// File the session is kept in
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// Directory of archived sessions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Archive {
+    dir: PathBuf,
+}
+
+impl Archive {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write a recorded session to a new file in the archive
+    pub fn add(&self, server: Option<&str>, session: SessionSnapshot) -> Result<ArchivedSession> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create archive {}", self.dir.display()))?;
+        // Whole seconds, as the file keeps them
+        let archived_at = Utc::now().trunc_subsecs(0);
+        let stem = format!("{}-{}", file_stem(&session.name), archived_at.timestamp());
+        let mut path = self.dir.join(format!("{}.json", stem));
+        let mut n = 1;
+        while path.exists() {
+            n += 1;
+            path = self.dir.join(format!("{}-{}.json", stem, n));
+        }
+
+        let archived = ArchivedSession {
+            server: server.map(str::to_string),
+            archived_at,
+            session,
+            path,
+        };
+        let json = serde_json::to_string_pretty(&archived)?;
+        fs::write(&archived.path, json)
+            .with_context(|| format!("Failed to write {}", archived.path.display()))?;
+        Ok(archived)
+    }
+
+    /// Archived sessions, most recently archived first. A missing archive is empty;
+    /// files that cannot be read are skipped.
+    pub fn list(&self) -> Result<Vec<ArchivedSession>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read archive {}", self.dir.display()))
+            }
+        };
+
+        let mut archived = Vec::new();
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match load(&path) {
+                Ok(session) => archived.push(session),
+                Err(e) => tracing::warn!("skipping archived session: {:#}", e),
+            }
+        }
+        archived.sort_by(|a, b| {
+            b.archived_at
+                .cmp(&a.archived_at)
+                .then_with(|| a.session.name.cmp(&b.session.name))
+        });
+        Ok(archived)
+    }
+
+    /// Delete an archived session, once restored or no longer wanted
+    pub fn remove(&self, archived: &ArchivedSession) -> Result<()> {
+        fs::remove_file(&archived.path)
+            .with_context(|| format!("Failed to delete {}", archived.path.display()))
+    }
+}
+
+fn load(path: &Path) -> Result<ArchivedSession> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut archived: ArchivedSession = serde_json::from_str(&contents)
+        .with_context(|| format!("Invalid archived session {}", path.display()))?;
+    archived.path = path.to_path_buf();
+    Ok(archived)
+}
+
+/// Session name made safe for a file name
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! Copying text to the system clipboard with the OSC 52 escape sequence.
+//!
+//! The terminal tmux-ui runs in sets its own clipboard, so this works over ssh and
+//! needs no clipboard tool. Most terminals support it, some only after enabling it.
+//! Inside tmux the sequence is passed through to the outer terminal, which needs
+//! `set -g allow-passthrough on` in tmux 3.3 and later.
+
+use anyhow::{Context, Result};
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 with padding, as OSC 52 expects
+pub fn base64(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (bits >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// The escape sequence that puts `text` on the clipboard; inside tmux it is
+/// wrapped so tmux passes it on instead of handling it itself
+pub fn osc52(text: &str, inside_tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    if inside_tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// Copy text to the clipboard of the terminal. The sequence goes to the controlling
+/// terminal, so it arrives even when the output is redirected.
+pub fn copy(text: &str) -> Result<()> {
+    let sequence = osc52(text, env::var_os("TMUX").is_some());
+    match OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(mut tty) => tty.write_all(sequence.as_bytes()),
+        Err(_) => {
+            let mut stdout = io::stdout();
+            stdout
+                .write_all(sequence.as_bytes())
+                .and_then(|()| stdout.flush())
+        }
+    }
+    .context("Failed to write to the terminal")
+}
@@ -0,0 +1,345 @@
+//! User configuration loaded from `~/.config/tmux-ui/config.toml`
+
+use crate::layouts::SavedLayouts;
+use crate::logging;
+use crate::sort::SortSpec;
+use crate::template::expand_home;
+use crate::tmux::{split_args, PopupSize, Socket, TmuxClient, TmuxVersion, MIN_TMUX_VERSION};
+use crate::tui::icons::IconSet;
+use crate::tui::keymap::Keymap;
+use crate::tui::table::SessionColumn;
+use crate::tui::theme::Theme;
+use crate::usage::UsageLog;
+use anyhow::{Context, Result};
+use chrono::TimeDelta;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Top-level configuration file contents
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Built-in columns of the session table, in display order
+    pub table_columns: Vec<String>,
+    /// Extra session list columns backed by tmux format strings
+    pub columns: Vec<ColumnConfig>,
+    /// Additional tmux servers shown alongside the default one
+    pub servers: Vec<ServerConfig>,
+    /// Session ordering, e.g. `["attached", "activity:desc", "name"]`
+    pub sort: Vec<String>,
+    /// Record session activity locally for `tmux-ui report`; off unless opted in
+    pub track_usage: bool,
+    /// Key overrides by action name, e.g. `down = ["j", "Down"]`
+    pub keys: BTreeMap<String, KeyList>,
+    /// Navigate the lists with vim keys and counts before the `[keys]` overrides
+    pub vim_mode: bool,
+    /// Start panes split off in the TUI in the split pane's working directory
+    pub split_inherit_cwd: bool,
+    /// Turn off automatic-rename on windows renamed in the TUI, so the name sticks
+    pub lock_window_names: bool,
+    /// Command that runs a program in a new terminal window, e.g. `alacritty -e`
+    pub terminal: Option<String>,
+    /// Name of the color scheme, e.g. `nord`
+    pub theme: Option<String>,
+    /// Characters session icons are drawn with: `nerd-font`, `unicode` or `ascii`
+    pub icons: String,
+    /// Detach other clients whenever a session is attached from tmux-ui
+    pub exclusive_attach: bool,
+    /// Start with the session list grouped by tag
+    pub group_by_tag: bool,
+    /// Seconds between background refreshes of the lists; 0 turns them off
+    pub refresh_interval: u64,
+    /// Level of the debug log in `~/.cache/tmux-ui/log`, e.g. `debug`; off when unset
+    pub log_level: Option<String>,
+    /// Oldest tmux version tmux-ui starts with, e.g. `3.2`
+    pub min_tmux_version: String,
+    /// Directory pane output is logged to, e.g. `~/logs/tmux`
+    pub pane_log_dir: Option<String>,
+    /// Size of popups, e.g. `80%x60%` or `120x40`
+    pub popup_size: String,
+    /// File layouts saved by name are kept in, e.g. `~/dotfiles/tmux-layouts.toml`
+    pub layouts_file: Option<String>,
+    /// Days without activity after which a detached session is shown as stale and
+    /// offered for cleanup; 0 turns it off
+    pub stale_after_days: u32,
+    /// Directory archived sessions are kept in
+    pub archive_dir: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            table_columns: SessionColumn::ALL
+                .iter()
+                .map(|(_, name)| name.to_string())
+                .collect(),
+            columns: Vec::new(),
+            servers: Vec::new(),
+            sort: Vec::new(),
+            track_usage: false,
+            keys: BTreeMap::new(),
+            vim_mode: false,
+            split_inherit_cwd: true,
+            lock_window_names: false,
+            terminal: None,
+            theme: None,
+            icons: IconSet::default().to_string(),
+            exclusive_attach: false,
+            group_by_tag: false,
+            refresh_interval: 3,
+            log_level: None,
+            min_tmux_version: MIN_TMUX_VERSION.to_string(),
+            pane_log_dir: None,
+            popup_size: PopupSize::default().to_string(),
+            layouts_file: None,
+            stale_after_days: 7,
+            archive_dir: None,
+        }
+    }
+}
+
+/// One key or a list of keys bound to an action
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn to_vec(&self) -> Vec<String> {
+        match self {
+            KeyList::One(key) => vec![key.clone()],
+            KeyList::Many(keys) => keys.clone(),
+        }
+    }
+}
+
+/// A user-defined column, e.g. `{ header = "cmd", format = "#{pane_current_command}" }`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    pub header: String,
+    pub format: String,
+    /// Maximum display width; longer values are truncated
+    pub width: Option<usize>,
+}
+
+/// An additional tmux server, e.g. `{ name = "work", socket_name = "work" }`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Label shown in the server column
+    pub name: String,
+    /// Named socket (`tmux -L`)
+    pub socket_name: Option<String>,
+    /// Socket path (`tmux -S`)
+    pub socket_path: Option<PathBuf>,
+    /// Machine to run tmux on over ssh, e.g. `user@devbox` or a `~/.ssh/config` alias
+    pub host: Option<String>,
+}
+
+impl ServerConfig {
+    /// The socket described by this entry
+    pub fn socket(&self) -> Result<Socket> {
+        match (&self.socket_name, &self.socket_path) {
+            (Some(name), None) => Ok(Socket::Name(name.clone())),
+            (None, Some(path)) => Ok(Socket::Path(path.clone())),
+            (None, None) => Ok(Socket::Default),
+            (Some(_), Some(_)) => anyhow::bail!(
+                "Server '{}' sets both socket_name and socket_path",
+                self.name
+            ),
+        }
+    }
+
+    /// Client for the server described by this entry
+    pub fn client(&self) -> Result<TmuxClient> {
+        let client = TmuxClient::new().with_socket(self.socket()?);
+        match self.host.as_deref().map(str::trim) {
+            None => Ok(client),
+            Some("") => anyhow::bail!("Server '{}' has an empty host", self.name),
+            Some(host) => Ok(client.with_host(host)),
+        }
+    }
+}
+
+impl Config {
+    /// Default location of the config file, honoring `XDG_CONFIG_HOME`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("tmux-ui").join("config.toml"))
+    }
+
+    /// Load the config from the default location, falling back to defaults if it does not exist
+    pub fn load() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Load the config from a specific file
+    pub fn load_from(path: &std::path::Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Parse config file contents
+    pub fn parse(contents: &str) -> Result<Self> {
+        let config: Config = toml::from_str(contents)?;
+        for server in &config.servers {
+            server.client()?;
+        }
+        config.sort_spec()?;
+        config.table_columns()?;
+        config.keymap()?;
+        config.theme()?;
+        config.icons()?;
+        config.min_tmux_version()?;
+        config.popup_size()?;
+        if let Some(level) = &config.log_level {
+            logging::parse_level(level)?;
+        }
+        if let Some(terminal) = &config.terminal {
+            if split_args(terminal)?.is_empty() {
+                anyhow::bail!("terminal must not be empty");
+            }
+        }
+        Ok(config)
+    }
+
+    /// The configured session ordering
+    pub fn sort_spec(&self) -> Result<SortSpec> {
+        SortSpec::from_keys(&self.sort)
+    }
+
+    /// The built-in columns shown in the session table
+    pub fn table_columns(&self) -> Result<Vec<SessionColumn>> {
+        if self.table_columns.is_empty() {
+            anyhow::bail!("table_columns must list at least one column");
+        }
+        self.table_columns.iter().map(|c| c.parse()).collect()
+    }
+
+    /// Default or vim key bindings with the `[keys]` overrides applied
+    pub fn keymap(&self) -> Result<Keymap> {
+        let overrides = self
+            .keys
+            .iter()
+            .map(|(action, keys)| (action.clone(), keys.to_vec()))
+            .collect();
+        let keymap = if self.vim_mode {
+            Keymap::vim()
+        } else {
+            Keymap::default()
+        };
+        keymap.overridden(&overrides)
+    }
+
+    /// The configured color scheme, or the default one
+    pub fn theme(&self) -> Result<Theme> {
+        match &self.theme {
+            None => Ok(Theme::default()),
+            Some(name) => Theme::named(name).with_context(|| {
+                format!(
+                    "Unknown theme '{}' (expected one of: {})",
+                    name,
+                    Theme::NAMES.join(", ")
+                )
+            }),
+        }
+    }
+
+    pub fn icons(&self) -> Result<IconSet> {
+        self.icons.parse().context("Invalid icons")
+    }
+
+    /// The usage log to record actions in, unless tracking is disabled
+    pub fn usage_log(&self) -> Option<UsageLog> {
+        if !self.track_usage {
+            return None;
+        }
+        UsageLog::default_path().map(UsageLog::new)
+    }
+
+    /// Command opening a new terminal window: the `terminal` setting, or `$TERMINAL -e`
+    pub fn terminal_command(&self) -> Option<String> {
+        self.terminal.clone().or_else(|| {
+            env::var("TERMINAL")
+                .ok()
+                .filter(|terminal| !terminal.is_empty())
+                .map(|terminal| format!("{} -e", terminal))
+        })
+    }
+
+    /// Where pane output is logged: `pane_log_dir`, or `tmux-ui/pane-logs` in `XDG_DATA_HOME`
+    pub fn pane_log_dir(&self) -> Option<PathBuf> {
+        if let Some(dir) = &self.pane_log_dir {
+            return Some(expand_home(dir));
+        }
+        let base = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?)
+                .join(".local")
+                .join("share"),
+        };
+        Some(base.join("tmux-ui").join("pane-logs"))
+    }
+
+    /// Where archived sessions are kept: `archive_dir`, or `tmux-ui/archive` in `XDG_DATA_HOME`
+    pub fn archive_dir(&self) -> Option<PathBuf> {
+        if let Some(dir) = &self.archive_dir {
+            return Some(expand_home(dir));
+        }
+        let base = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?)
+                .join(".local")
+                .join("share"),
+        };
+        Some(base.join("tmux-ui").join("archive"))
+    }
+
+    /// Where layouts saved by name are kept: `layouts_file`, or `layouts.toml` next
+    /// to the config file
+    pub fn layouts_file(&self) -> Option<PathBuf> {
+        match &self.layouts_file {
+            Some(path) => Some(expand_home(path)),
+            None => SavedLayouts::default_path(),
+        }
+    }
+
+    /// How often the TUI reloads its lists in the background, if at all
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        (self.refresh_interval > 0).then(|| Duration::from_secs(self.refresh_interval))
+    }
+
+    /// How long a detached session may go without activity before it is stale, if ever
+    pub fn stale_after(&self) -> Option<TimeDelta> {
+        (self.stale_after_days > 0).then(|| TimeDelta::days(i64::from(self.stale_after_days)))
+    }
+
+    pub fn min_tmux_version(&self) -> Result<TmuxVersion> {
+        self.min_tmux_version
+            .parse()
+            .context("Invalid min_tmux_version")
+    }
+
+    pub fn popup_size(&self) -> Result<PopupSize> {
+        self.popup_size.parse().context("Invalid popup_size")
+    }
+
+    /// Format strings of the user-defined columns, in display order
+    pub fn column_formats(&self) -> Vec<String> {
+        self.columns.iter().map(|c| c.format.clone()).collect()
+    }
+}
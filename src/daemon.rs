@@ -0,0 +1,435 @@
+//! `tmux-ui daemon`: one process that keeps the session list of a tmux server up
+//! to date and shares it, and the operations on it, over a Unix domain socket.
+//!
+//! The protocol is JSON lines. A request is an object on a line of its own,
+//! `{"id": 1, "method": "kill", "params": {"name": "scratch"}}`, and is answered by
+//! one line with the same `id` and either a `result` or an `error` shaped like the
+//! JSON errors of the CLI, `{"message", "kind", "exit_code"}`. After `subscribe`,
+//! the connection also receives `{"event": "sessions", "sessions": [...]}` whenever
+//! the session list changes.
+
+use crate::tmux::{validate_session_name, Key, TmuxBackend, TmuxSession};
+use crate::Result;
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::Duration;
+
+/// How often the session list is reloaded when `refresh_interval` is off; the
+/// daemon is of little use without it
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Methods a request may call
+pub const METHODS: [&str; 13] = [
+    "ping",
+    "sessions",
+    "refresh",
+    "windows",
+    "panes",
+    "create",
+    "kill",
+    "rename",
+    "new-window",
+    "kill-window",
+    "rename-window",
+    "send-keys",
+    "subscribe",
+];
+
+/// A request line
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct SessionParams {
+    session: String,
+}
+
+#[derive(Deserialize)]
+struct TargetParams {
+    target: String,
+}
+
+#[derive(Deserialize)]
+struct NameParams {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct CreateParams {
+    name: String,
+    dir: Option<PathBuf>,
+    command: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RenameParams {
+    old: String,
+    new: String,
+}
+
+#[derive(Deserialize)]
+struct NewWindowParams {
+    session: String,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RenameWindowParams {
+    target: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct SendKeysParams {
+    target: String,
+    text: String,
+    #[serde(default = "default_enter")]
+    enter: bool,
+}
+
+fn default_enter() -> bool {
+    true
+}
+
+/// A request that could not be understood, as opposed to one tmux refused
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct InvalidRequest(String);
+
+/// The session list of one tmux server, shared by every connection
+pub struct Daemon<C: TmuxBackend> {
+    client: C,
+    sessions: Mutex<Vec<TmuxSession>>,
+    /// `sessions` events for subscribed connections
+    updates: broadcast::Sender<String>,
+}
+
+impl<C: TmuxBackend> Daemon<C> {
+    pub fn new(client: C) -> Arc<Self> {
+        let (updates, _) = broadcast::channel(16);
+        Arc::new(Self {
+            client,
+            sessions: Mutex::new(Vec::new()),
+            updates,
+        })
+    }
+
+    /// Sessions as of the last reload
+    pub fn sessions(&self) -> Vec<TmuxSession> {
+        self.sessions.lock().unwrap().clone()
+    }
+
+    /// `sessions` events from now on, one JSON line each
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.updates.subscribe()
+    }
+
+    /// Reload the session list, telling subscribers if it changed
+    pub async fn refresh(&self) -> Result<Vec<TmuxSession>> {
+        let sessions = self
+            .client
+            .blocking(|client| client.list_sessions_with_columns(&[]))
+            .await?;
+        let mut cached = self.sessions.lock().unwrap();
+        if *cached != sessions {
+            *cached = sessions.clone();
+            let event = json!({ "event": "sessions", "sessions": sessions });
+            // Nobody may be subscribed, which is fine
+            let _ = self.updates.send(format!("{}\n", event));
+        }
+        Ok(sessions)
+    }
+
+    /// Reload the sessions now and then every `interval`, in the background
+    pub async fn start_polling(self: &Arc<Self>, interval: Duration) -> Result<()> {
+        self.refresh().await?;
+        let poller = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            loop {
+                ticks.tick().await;
+                if let Err(e) = poller.refresh().await {
+                    tracing::warn!("Failed to reload sessions: {:#}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Serve connections on `listener`, reloading the sessions every `interval`
+    pub async fn serve(self: Arc<Self>, listener: UnixListener, interval: Duration) -> Result<()> {
+        self.start_polling(interval).await?;
+        loop {
+            let (stream, _) = listener.accept().await?;
+            tokio::spawn(Arc::clone(&self).connection(stream));
+        }
+    }
+
+    /// Answer the requests of one connection until it closes
+    async fn connection(self: Arc<Self>, stream: UnixStream) {
+        let (read, mut write) = stream.into_split();
+        // Responses and events interleave, so one task does all the writing
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        let writer = tokio::spawn(async move {
+            while let Some(line) = rx.recv().await {
+                if write.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut subscriptions = Vec::new();
+        let mut lines = BufReader::new(read).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (response, subscribe) = self.handle(&line).await;
+            if tx.send(format!("{}\n", response)).is_err() {
+                break;
+            }
+            if subscribe {
+                let mut updates = self.subscribe();
+                let tx = tx.clone();
+                subscriptions.push(tokio::spawn(async move {
+                    loop {
+                        match updates.recv().await {
+                            Ok(event) => {
+                                if tx.send(event).is_err() {
+                                    break;
+                                }
+                            }
+                            // A slow reader skips to the next list, which is complete
+                            Err(broadcast::error::RecvError::Lagged(_)) => {}
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                }));
+            }
+        }
+
+        for subscription in subscriptions {
+            subscription.abort();
+        }
+        drop(tx);
+        let _ = writer.await;
+    }
+
+    /// Answer one request line; also returns whether the connection subscribed
+    pub async fn handle(&self, line: &str) -> (Value, bool) {
+        let request: Request = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = anyhow::Error::new(InvalidRequest(format!("Invalid request: {}", e)));
+                return (
+                    json!({ "id": Value::Null, "error": error_json(&error) }),
+                    false,
+                );
+            }
+        };
+        tracing::debug!("daemon request {}", request.method);
+        let subscribe = request.method == "subscribe";
+        let response = match self.call(&request.method, request.params).await {
+            Ok(result) => json!({ "id": request.id, "result": result }),
+            Err(error) => json!({ "id": request.id, "error": error_json(&error) }),
+        };
+        (response, subscribe)
+    }
+
+    /// Call one of the [`METHODS`] with its params
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let value = match method {
+            "ping" => json!("pong"),
+            "sessions" | "subscribe" => json!(self.sessions()),
+            "refresh" => json!(self.refresh().await?),
+            "windows" => {
+                let SessionParams { session } = parse_params(params)?;
+                let windows = self
+                    .client
+                    .blocking(move |client| client.list_windows(&session))
+                    .await?;
+                json!(windows)
+            }
+            "panes" => {
+                let TargetParams { target } = parse_params(params)?;
+                let panes = self
+                    .client
+                    .blocking(move |client| client.list_panes(&target))
+                    .await?;
+                json!(panes)
+            }
+            "create" => {
+                let CreateParams { name, dir, command } = parse_params(params)?;
+                let name = validate_session_name(&name)?;
+                let created = name.clone();
+                self.client
+                    .blocking(move |client| {
+                        client.create_session(&name, dir.as_deref(), command.as_deref())
+                    })
+                    .await?;
+                self.refresh().await?;
+                json!({ "created": created })
+            }
+            "kill" => {
+                let NameParams { name } = parse_params(params)?;
+                let killed = name.clone();
+                self.client
+                    .blocking(move |client| client.kill_session(&name))
+                    .await?;
+                self.refresh().await?;
+                json!({ "killed": killed })
+            }
+            "rename" => {
+                let RenameParams { old, new } = parse_params(params)?;
+                let new = validate_session_name(&new)?;
+                let renamed = json!({ "renamed": old, "to": new });
+                self.client
+                    .blocking(move |client| client.rename_session(&old, &new))
+                    .await?;
+                self.refresh().await?;
+                renamed
+            }
+            "new-window" => {
+                let NewWindowParams { session, name } = parse_params(params)?;
+                let created = json!({ "session": session, "name": name });
+                self.client
+                    .blocking(move |client| client.create_window(&session, name.as_deref()))
+                    .await?;
+                self.refresh().await?;
+                json!({ "created": created })
+            }
+            "kill-window" => {
+                let TargetParams { target } = parse_params(params)?;
+                let killed = target.clone();
+                self.client
+                    .blocking(move |client| client.kill_window(&target))
+                    .await?;
+                self.refresh().await?;
+                json!({ "killed": killed })
+            }
+            "rename-window" => {
+                let RenameWindowParams { target, name } = parse_params(params)?;
+                let renamed = json!({ "renamed": target, "to": name });
+                self.client
+                    .blocking(move |client| client.rename_window(&target, &name))
+                    .await?;
+                self.refresh().await?;
+                renamed
+            }
+            "send-keys" => {
+                let SendKeysParams {
+                    target,
+                    text,
+                    enter,
+                } = parse_params(params)?;
+                self.client
+                    .blocking(move |client| client.send_keys(&target, &[Key::Literal(text)], enter))
+                    .await?;
+                json!({ "sent": true })
+            }
+            other => {
+                return Err(InvalidRequest(format!(
+                    "Unknown method '{}' (expected one of: {})",
+                    other,
+                    METHODS.join(", ")
+                ))
+                .into())
+            }
+        };
+        Ok(value)
+    }
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T> {
+    serde_json::from_value(params)
+        .map_err(|e| InvalidRequest(format!("Invalid params: {}", e)).into())
+}
+
+/// An error as the CLI prints it with `--format json`: tmux failures keep their own
+/// kind and exit code, requests that could not be understood are usage errors
+pub fn error_json(error: &anyhow::Error) -> Value {
+    let tmux_error = error
+        .chain()
+        .find_map(|e| e.downcast_ref::<crate::tmux::Error>());
+    let (kind, code) = match tmux_error {
+        Some(e) => (e.kind(), e.exit_code()),
+        None if error.downcast_ref::<InvalidRequest>().is_some() => ("invalid_request", 64),
+        None => ("error", 1),
+    };
+    json!({ "message": format!("{:#}", error), "kind": kind, "exit_code": code })
+}
+
+/// Default location of the daemon's socket, in `XDG_RUNTIME_DIR` or else the
+/// temporary directory
+pub fn default_socket_path() -> PathBuf {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("tmux-ui").join("daemon.sock"),
+        _ => {
+            let user = env::var("USER").unwrap_or_default();
+            env::temp_dir()
+                .join(format!("tmux-ui-{}", user))
+                .join("daemon.sock")
+        }
+    }
+}
+
+/// Create the directory of the default socket, accessible only to the user.
+///
+/// The socket only becomes private after `bind`, so the directory must be
+/// private from the start; in the temporary directory, another user may have
+/// created it first.
+pub fn create_socket_dir(dir: &Path) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    let metadata = std::fs::symlink_metadata(dir)
+        .with_context(|| format!("Failed to inspect {}", dir.display()))?;
+    // SAFETY: geteuid has no preconditions and cannot fail
+    let uid = unsafe { libc::geteuid() };
+    if !metadata.is_dir() || metadata.uid() != uid {
+        anyhow::bail!(
+            "{} is not a directory owned by the current user; refusing to put the socket there",
+            dir.display()
+        );
+    }
+    if metadata.mode() & 0o077 != 0 {
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// Listen on `path`, replacing the socket of a daemon that is no longer running.
+/// The socket is only accessible to the user, since it can type into any pane;
+/// its directory is left as it is.
+pub async fn bind(path: &Path) -> Result<UnixListener> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            anyhow::bail!("A daemon is already listening on {}", path.display());
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+    let listener = UnixListener::bind(path)
+        .with_context(|| format!("Failed to listen on {}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
@@ -0,0 +1,96 @@
+//! The `.tmux.conf` line `tmux-ui install-keybinding` prints or appends, which
+//! launches tmux-ui from a tmux key
+
+use crate::tmux::{remote::shell_quote, PopupSize};
+use crate::Result;
+use anyhow::Context;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where the key opens tmux-ui
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Launch {
+    /// A popup over the client, with the compact layout of `--popup`
+    Popup(PopupSize),
+    /// A window of its own in the current session
+    Window,
+}
+
+/// A tmux key that launches tmux-ui
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keybinding {
+    /// Key in tmux's notation, e.g. `S` or `C-s`
+    pub key: String,
+    /// Bind the key in the root table, so it works without the prefix
+    pub no_prefix: bool,
+    /// Path of the tmux-ui binary, or just `tmux-ui` to look it up in `PATH`
+    pub program: String,
+    pub launch: Launch,
+}
+
+impl Keybinding {
+    /// The `bind-key` line, e.g.
+    /// `bind-key S run-shell -b "tmux-ui --popup=80%x80%"`
+    pub fn line(&self) -> String {
+        let program = shell_quote(&self.program);
+        let command = match &self.launch {
+            // tmux-ui opens the popup itself, in the background so the key returns
+            Launch::Popup(size) => format!("run-shell -b \"{} --popup={}\"", program, size),
+            Launch::Window => format!("new-window -n tmux-ui \"{}\"", program),
+        };
+        let table = if self.no_prefix { " -n" } else { "" };
+        format!("bind-key{} {} {}", table, self.key, command)
+    }
+
+    /// Whether `conf` already contains this line
+    pub fn is_in(&self, conf: &str) -> bool {
+        let line = self.line();
+        conf.lines().any(|l| l.trim() == line)
+    }
+
+    /// Append the line to the config file at `path`, unless it is already there;
+    /// returns whether the file changed
+    pub fn append_to(&self, path: &Path) -> Result<bool> {
+        let conf = match fs::read_to_string(path) {
+            Ok(conf) => conf,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        if self.is_in(&conf) {
+            return Ok(false);
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let separator = if conf.is_empty() || conf.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        write!(file, "{}\n# Launch tmux-ui\n{}\n", separator, self.line())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(true)
+    }
+}
+
+/// The tmux config file of the user: `~/.tmux.conf`, or the XDG location that tmux
+/// 3.1 and later read when only that one exists
+pub fn tmux_conf_path() -> Option<PathBuf> {
+    let home = PathBuf::from(env::var_os("HOME")?);
+    let classic = home.join(".tmux.conf");
+    let xdg = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home.join(".config"),
+    }
+    .join("tmux")
+    .join("tmux.conf");
+    if !classic.exists() && xdg.exists() {
+        Some(xdg)
+    } else {
+        Some(classic)
+    }
+}
@@ -0,0 +1,73 @@
+//! Pane layouts saved by name, to arrange windows of any session the same way
+//!
+//! Layouts are `#{window_layout}` strings kept in `~/.config/tmux-ui/layouts.toml`
+//! as `name = "layout"` lines, written by the TUI and editable by hand.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SavedLayouts {
+    layouts: BTreeMap<String, String>,
+}
+
+impl SavedLayouts {
+    /// Default location, next to the config file
+    pub fn default_path() -> Option<PathBuf> {
+        Config::default_path().and_then(|path| path.parent().map(|dir| dir.join("layouts.toml")))
+    }
+
+    /// Read the saved layouts; a missing file has none
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read layouts {}", path.display()))?;
+        let layouts = toml::from_str(&contents)
+            .with_context(|| format!("Invalid layouts file {}", path.display()))?;
+        Ok(Self { layouts })
+    }
+
+    /// Write all layouts, replacing the file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = toml::to_string(&self.layouts)?;
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write layouts {}", path.display()))
+    }
+
+    /// Names of the saved layouts, sorted
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layouts.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.layouts.get(name).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layouts.is_empty()
+    }
+
+    /// Save a layout under a name, replacing one saved under the same name;
+    /// the name is trimmed and must not be empty
+    pub fn insert(&mut self, name: &str, layout: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Layout name cannot be empty");
+        }
+        self.layouts.insert(name.to_string(), layout.to_string());
+        Ok(())
+    }
+
+    /// Forget a layout; returns whether it was saved
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.layouts.remove(name).is_some()
+    }
+}
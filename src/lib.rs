@@ -2,8 +2,26 @@
 //!
 //! This is a TUI application for managing tmux sessions, windows, and panes.
 
+pub mod archive;
+pub mod clipboard;
+pub mod config;
+#[cfg(unix)]
+pub mod daemon;
+pub mod keybinding;
+pub mod layouts;
+pub mod logging;
+pub mod process;
+pub mod prompt;
+pub mod script;
+#[cfg(all(feature = "serve", unix))]
+pub mod serve;
+pub mod sort;
+pub mod template;
 pub mod tmux;
 pub mod tui;
+pub mod usage;
+#[cfg(all(feature = "web", unix))]
+pub mod web;
 
 pub use tmux::*;
 
@@ -0,0 +1,58 @@
+//! Debug log written to `~/.cache/tmux-ui/log`.
+//!
+//! The TUI owns the terminal while it runs, so stderr is no place for diagnostics.
+//! With a log level set (`log_level` in the config or `--log-level`), every tmux
+//! command is logged with its exit status and stderr, along with the keys and
+//! actions of the TUI and the messages it showed.
+
+use anyhow::{Context, Result};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+
+/// Names accepted for the log level, least verbose first
+pub const LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// Parse a log level such as `debug`; `off` disables logging
+pub fn parse_level(level: &str) -> Result<LevelFilter> {
+    level.parse().ok().with_context(|| {
+        format!(
+            "Unknown log level '{}' (expected one of: {})",
+            level,
+            LEVELS.join(", ")
+        )
+    })
+}
+
+/// Default log location, honoring `XDG_CACHE_HOME`
+pub fn default_path() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".cache"),
+    };
+    Some(base.join("tmux-ui").join("log"))
+}
+
+/// Append log records of `level` and above to `path` for the rest of the process
+pub fn init(level: LevelFilter, path: &Path) -> Result<()> {
+    if level == LevelFilter::OFF {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create log directory {}", dir.display()))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open log file {}", path.display()))?;
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .try_init()
+        .map_err(|e| anyhow::anyhow!("Failed to start logging: {}", e))
+}
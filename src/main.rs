@@ -1,76 +1,1303 @@
-use clap::{Parser, Subcommand};
-use tmux_ui::{tmux::TmuxClient, tui::App};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::aot::Shell;
+use clap_complete::engine::{ArgValueCandidates, CompletionCandidate};
+use clap_complete::env::{self as complete_env, CompleteEnv, EnvCompleter};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use tmux_ui::daemon::{self, Daemon};
+use tmux_ui::{
+    clipboard,
+    config::Config,
+    keybinding::{self, Keybinding, Launch},
+    logging,
+    prompt::{PromptStatus, SegmentStyle},
+    script::Script,
+    sort::SortSpec,
+    template::Template,
+    tmux::{
+        parse_tags, validate_session_name, validate_window_name, Capabilities, Key, PopupSize,
+        Snapshot, Socket, SplitDirection, TmuxClient,
+    },
+    tui::{icons::IconSet, switcher::SessionSwitcher, terminal::TerminalSupport, App},
+    usage::{self, EventKind, ReportFormat, Since, UsageLog, UsageReport},
+};
 
 /// A terminal user interface for managing tmux sessions
 #[derive(Parser)]
 #[command(name = "tmux-ui")]
 #[command(about = "A TUI for managing tmux sessions, windows, and panes")]
 struct Cli {
+    /// Output format of the subcommands
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    format: OutputFormat,
+    /// Use a named tmux socket, like `tmux -L`
+    #[arg(short = 'L', long, global = true, value_name = "NAME")]
+    socket: Option<String>,
+    /// Use a tmux socket path, like `tmux -S`
+    #[arg(
+        short = 'S',
+        long,
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "socket"
+    )]
+    socket_path: Option<PathBuf>,
+    /// Manage the tmux server of another machine over ssh, e.g. `user@devbox`
+    #[arg(long, global = true, value_name = "HOST")]
+    host: Option<String>,
+    /// Print every tmux command line to stderr before running it
+    #[arg(short = 'v', long, global = true)]
+    verbose: bool,
+    /// Print the tmux commands that would change something instead of running them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Write a debug log to ~/.cache/tmux-ui/log (off, error, warn, info, debug, trace),
+    /// overriding `log_level` in the config file
+    #[arg(long, global = true, value_name = "LEVEL")]
+    log_level: Option<String>,
+    /// Draw only ASCII characters, for terminals that garble symbols and emoji
+    #[arg(long, global = true)]
+    ascii: bool,
+    /// Draw without colors (also set by the NO_COLOR environment variable)
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Print nothing but errors; the exit code tells whether a subcommand succeeded
+    #[arg(short = 'q', long, global = true)]
+    quiet: bool,
+    /// Inside tmux, open the TUI with a compact layout in a popup over the current
+    /// client, sized e.g. `80%x60%` (defaults to `popup_size` in the config file)
+    #[arg(long, value_name = "SIZE", num_args = 0..=1, require_equals = true)]
+    popup: Option<Option<PopupSize>>,
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
+/// Output format of the CLI subcommands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    /// Markdown tables (`report` only)
+    Markdown,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Start the interactive TUI (default)
     Tui,
     /// List all tmux sessions
-    List,
+    List {
+        /// Comma-separated sort keys, e.g. "attached,activity:desc,name"
+        /// (defaults to the `sort` setting in the config file)
+        #[arg(long)]
+        sort: Option<SortSpec>,
+        /// Only list sessions with this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
     /// Create a new tmux session
     New {
         /// Session name
+        #[arg(add = ArgValueCandidates::new(session_candidates))]
         name: String,
+        /// Directory the session starts in
+        #[arg(short = 'c', long)]
+        dir: Option<PathBuf>,
+        /// Command to run instead of the shell, e.g. "nvim ."
+        #[arg(long)]
+        command: Option<String>,
+    },
+    /// Attach to a session, creating it first if it does not exist
+    Go {
+        /// Session name
+        #[arg(add = ArgValueCandidates::new(session_candidates))]
+        name: String,
+        /// Create the session from this template (name or path) instead of with a shell
+        #[arg(short, long)]
+        template: Option<String>,
+        /// Directory a new session starts in (the template's root with --template)
+        #[arg(short = 'c', long)]
+        dir: Option<PathBuf>,
+        /// Only create the session, do not attach to it
+        #[arg(long)]
+        detached: bool,
+    },
+    /// Choose a session from a minimal fuzzy list and print its name, e.g. for a
+    /// shell keybinding or `tmux display-popup`
+    Pick {
+        /// Attach to (or inside tmux, switch to) the chosen session instead of printing it
+        #[arg(long)]
+        attach: bool,
+    },
+    /// Copy a session's windows, splits and directories under a new name
+    Clone {
+        /// Session to copy
+        source: String,
+        /// Name of the copy
+        name: String,
+        /// Start only shells, not the editors and pagers running in the original
+        #[arg(long)]
+        no_commands: bool,
     },
     /// Kill a tmux session
     Kill {
         /// Session name
+        #[arg(add = ArgValueCandidates::new(session_candidates))]
         name: String,
     },
     /// Attach to a tmux session
     Attach {
+        /// Session name
+        #[arg(add = ArgValueCandidates::new(session_candidates))]
+        name: String,
+        /// Detach every other client from the session (always on with `exclusive_attach`)
+        #[arg(short = 'd', long)]
+        detach_others: bool,
+    },
+    /// Rename a session
+    Rename {
+        /// Current session name
+        #[arg(add = ArgValueCandidates::new(session_candidates))]
+        old: String,
+        /// New session name
+        new: String,
+    },
+    /// Rename a window, e.g. `work:1` or `@3`
+    RenameWindow {
+        target: String,
+        /// New window name
+        new: String,
+    },
+    /// List the windows of a session
+    Windows {
+        /// Session name
+        #[arg(add = ArgValueCandidates::new(session_candidates))]
+        session: String,
+    },
+    /// List the panes of a window, e.g. `work:editor` (a session lists its active window)
+    Panes { target: String },
+    /// Create a new window in a session
+    NewWindow {
+        /// Session name
+        #[arg(add = ArgValueCandidates::new(session_candidates))]
+        session: String,
+        /// Window name (tmux names it after its command if not given)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+    /// Kill a window, e.g. `work:editor` or `@3`
+    KillWindow { target: String },
+    /// Split a pane, e.g. `work`, `work:editor` or `%3` (a session or window splits its
+    /// active pane)
+    Split {
+        target: String,
+        /// Put the new pane to the right instead of below
+        #[arg(short = 'H', long)]
+        horizontal: bool,
+        /// Size of the new pane in percent (tmux splits in half if not given)
+        #[arg(short = 'p', long, value_name = "PERCENT")]
+        size: Option<u8>,
+        /// Directory the new pane starts in
+        #[arg(short = 'c', long)]
+        dir: Option<PathBuf>,
+    },
+    /// Show or replace the tags of a session
+    Tag {
         /// Session name
         name: String,
+        /// New tags, replacing the current ones (prints the tags if none are given)
+        tags: Vec<String>,
+        /// Remove all tags
+        #[arg(long, conflicts_with = "tags")]
+        clear: bool,
+    },
+    /// Kill detached sessions whose panes only run the default shell
+    Prune {
+        /// Only list the sessions that would be killed
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Kill the tmux server and all of its sessions
+    KillServer,
+    /// Summarize tracked usage (sessions created/killed, time attached per session)
+    Report {
+        /// How far back to look, e.g. 7d, 24h, 2w
+        #[arg(long, default_value = "7d")]
+        since: Since,
+    },
+    /// Type a command into a pane and press Enter, without attaching
+    Send {
+        /// Target pane, e.g. `work`, `work:editor` or `%3` (a session or window
+        /// selects its active pane)
+        target: String,
+        /// Command to type; several arguments are joined with spaces
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+        /// Type the command without pressing Enter
+        #[arg(long)]
+        no_enter: bool,
+    },
+    /// List, show, set or paste the tmux paste buffers
+    Buffer {
+        #[command(subcommand)]
+        command: Option<BufferCommand>,
+    },
+    /// Run a raw tmux command, e.g. `tmux-ui raw -- list-keys -T prefix`
+    Raw {
+        /// Arguments passed to tmux
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
     },
+    /// Create a session from a project template in ~/.config/tmux-ui/templates
+    Start {
+        /// Template name, or path to a template file
+        template: String,
+        /// Do not attach to the new session
+        #[arg(long)]
+        detached: bool,
+    },
+    /// Run a provisioning script (sessions, windows, splits, keys, options) without the TUI
+    Exec {
+        /// Script file, or `-` to read it from stdin
+        #[arg(long)]
+        script: PathBuf,
+        /// Only check the script for errors
+        #[arg(long)]
+        check: bool,
+    },
+    /// Save all sessions, windows and panes to a snapshot file
+    Save {
+        /// Snapshot file (defaults to ~/.local/share/tmux-ui/snapshot.json)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Recreate the sessions of a snapshot that do not exist yet
+    Restore {
+        /// Snapshot file (defaults to ~/.local/share/tmux-ui/snapshot.json)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Print a compact status segment for shell prompts
+    PromptSegment {
+        /// Output style
+        #[arg(long, value_enum, default_value = "plain")]
+        style: SegmentStyle,
+    },
+    /// Keep the session list up to date and share it, and operations on it, with other
+    /// tools over a Unix socket (JSON lines)
+    #[cfg(unix)]
+    Daemon {
+        /// Socket to listen on (defaults to $XDG_RUNTIME_DIR/tmux-ui/daemon.sock)
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Serve a REST API for sessions over HTTP (GET/POST /sessions, DELETE
+    /// /sessions/NAME, POST /sessions/NAME/send-keys), and with the `web` feature a
+    /// page at / for managing sessions from a browser
+    #[cfg(all(feature = "serve", unix))]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = tmux_ui::serve::DEFAULT_LISTEN)]
+        listen: std::net::SocketAddr,
+        /// Bearer token every request must carry (also read from TMUX_UI_TOKEN);
+        /// required to listen on other addresses than localhost
+        #[arg(long)]
+        token: Option<String>,
+    },
+    /// Print a .tmux.conf line binding a key to tmux-ui, or append it to the file
+    InstallKeybinding {
+        /// Key to bind, in tmux's notation, e.g. `S` or `C-s` (pressed after the prefix
+        /// unless --no-prefix is given)
+        #[arg(short, long, default_value = "S")]
+        key: String,
+        /// Bind the key without the prefix
+        #[arg(long)]
+        no_prefix: bool,
+        /// Open tmux-ui in a new window instead of a popup
+        #[arg(long, conflicts_with = "size")]
+        window: bool,
+        /// Size of the popup, e.g. `80%x60%` (defaults to `popup_size` in the config file)
+        #[arg(long)]
+        size: Option<PopupSize>,
+        /// Append the line to the tmux config file, after asking
+        #[arg(long)]
+        append: bool,
+        /// Append without asking
+        #[arg(short = 'y', long, requires = "append")]
+        yes: bool,
+        /// tmux config file to append to (defaults to ~/.tmux.conf)
+        #[arg(long, requires = "append")]
+        file: Option<PathBuf>,
+    },
+    /// Print the shell completion script, e.g. `source <(tmux-ui completions bash)`
+    Completions {
+        /// Shell to complete in
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// Environment variable the completion scripts call tmux-ui back with
+const COMPLETE_VAR: &str = "COMPLETE";
+
+#[derive(Subcommand)]
+enum BufferCommand {
+    /// List the buffers, most recent first (default)
+    List,
+    /// Print the content of a buffer
+    Show {
+        /// Buffer name (defaults to the most recent buffer)
+        name: Option<String>,
+        /// Also copy the content to the system clipboard (OSC 52)
+        #[arg(long)]
+        clipboard: bool,
+    },
+    /// Store text in a buffer
+    Set {
+        /// Text to store (read from stdin if not given)
+        text: Option<String>,
+        /// Buffer name (tmux picks one, e.g. `buffer3`, if not given)
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Also copy the text to the system clipboard (OSC 52)
+        #[arg(long)]
+        clipboard: bool,
+    },
+    /// Paste a buffer into a pane, e.g. `work`, `work:editor` or `%3`
+    Paste {
+        target: String,
+        /// Buffer name (defaults to the most recent buffer)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+}
+
+/// Exit code of invalid command lines, EX_USAGE of sysexits.h; clap's own 2 is
+/// taken by a missing session
+const EXIT_USAGE: i32 = 64;
+
+/// Exit code of `pick` when nothing was chosen, like fzf's on Esc or Ctrl-C
+const EXIT_CANCELLED: i32 = 130;
+
+/// Set in the environment of the tmux-ui `--popup` starts in a popup, which then
+/// shows the TUI itself instead of opening another popup
+const POPUP_VAR: &str = "TMUX_UI_POPUP";
+
+/// Set by `--quiet`, which silences everything but errors
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// `println!` that `--quiet` silences
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if !QUIET.load(Ordering::Relaxed) {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// `print!` that `--quiet` silences
+macro_rules! put {
+    ($($arg:tt)*) => {
+        if !QUIET.load(Ordering::Relaxed) {
+            print!($($arg)*);
+        }
+    };
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-    let client = TmuxClient::new();
+async fn main() {
+    // Answers the completion scripts, which run tmux-ui with COMPLETE set
+    CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_VAR)
+        .complete();
+    let cli = Cli::try_parse().unwrap_or_else(|e| exit_usage(e));
+    let json = cli.format == OutputFormat::Json;
+    QUIET.store(cli.quiet, Ordering::Relaxed);
+
+    if let Err(error) = run(cli).await {
+        // Failures of tmux calls keep their own code, whatever context was added
+        let tmux_error = error
+            .chain()
+            .find_map(|e| e.downcast_ref::<tmux_ui::Error>());
+        let code = tmux_error.map_or(1, tmux_ui::Error::exit_code);
+        if json {
+            let report = serde_json::json!({
+                "error": format!("{:#}", error),
+                "kind": tmux_error.map_or("error", tmux_ui::Error::kind),
+                "exit_code": code,
+            });
+            eprintln!("{}", report);
+        } else {
+            eprintln!("Error: {:?}", error);
+        }
+        std::process::exit(code);
+    }
+}
+
+/// Print a command line error (or the help asked for) and exit
+fn exit_usage(error: clap::Error) -> ! {
+    let _ = error.print();
+    std::process::exit(if error.use_stderr() { EXIT_USAGE } else { 0 })
+}
+
+async fn run(cli: Cli) -> tmux_ui::Result<()> {
+    // Only the report has tables; elsewhere markdown would quietly print text
+    if cli.format == OutputFormat::Markdown && !matches!(cli.command, Some(Commands::Report { .. }))
+    {
+        exit_usage(Cli::command().error(
+            ErrorKind::ArgumentConflict,
+            "--format markdown only applies to the report subcommand",
+        ));
+    }
+    let json = cli.format == OutputFormat::Json;
+    let client = server_client(cli.socket, cli.socket_path, cli.host)
+        .with_verbose(cli.verbose)
+        .with_dry_run(cli.dry_run);
+    // A broken config must not break every shell prompt
+    let config = match cli.command {
+        Some(Commands::PromptSegment { .. }) => Config::load().unwrap_or_default(),
+        _ => Config::load()?,
+    };
+    if let Some(level) = cli.log_level.as_ref().or(config.log_level.as_ref()) {
+        let level = logging::parse_level(level)?;
+        if let Some(path) = logging::default_path() {
+            logging::init(level, &path)?;
+        }
+    }
+    let terminal = TerminalSupport::detect().limited(cli.ascii, cli.no_color);
+    let icons = if terminal.unicode {
+        config.icons()?
+    } else {
+        IconSet::Ascii
+    };
+    let usage_log = config.usage_log();
+    let dry_run = cli.dry_run;
+    let track = |kind: EventKind, session: &str| {
+        if dry_run {
+            return;
+        }
+        if let Some(log) = &usage_log {
+            log.record(kind, session);
+        }
+    };
+
+    // Switch to a session from inside tmux, or attach to it from outside
+    let enter_session = |name: &str| -> tmux_ui::Result<()> {
+        if client.is_inside_this_server() {
+            client.switch_client(name)?;
+            return Ok(());
+        }
+        track(EventKind::Attached, name);
+        let result = if config.exclusive_attach {
+            client.attach_session_exclusive(name)
+        } else {
+            client.attach_session(name)
+        };
+        // attach-session blocks until the client detaches
+        track(EventKind::Detached, name);
+        result?;
+        Ok(())
+    };
 
     match cli.command {
         Some(Commands::Tui) | None => {
+            // The TUI owns the terminal, so there is nowhere to print commands to
+            if cli.verbose || cli.dry_run {
+                exit_usage(Cli::command().error(
+                    ErrorKind::ArgumentConflict,
+                    "--verbose and --dry-run only apply to subcommands, not the TUI",
+                ));
+            }
+            // Fail before taking over the terminal rather than in every operation
+            let info = client.server_info()?;
+            tracing::info!("{}", info.description);
+            info.require(config.min_tmux_version()?)?;
+            let capabilities = Capabilities::from(&info);
+            let in_popup = std::env::var_os(POPUP_VAR).is_some();
+            if let Some(size) = cli.popup {
+                // Outside tmux, or with a tmux without popups, the TUI simply starts here
+                if !in_popup && client.is_inside_this_server() && capabilities.has_popup() {
+                    let size = match size {
+                        Some(size) => size,
+                        None => config.popup_size()?,
+                    };
+                    let exe = std::env::current_exe()?;
+                    let argv: Vec<String> = ["env".to_string(), format!("{}=1", POPUP_VAR)]
+                        .into_iter()
+                        .chain([exe.to_string_lossy().into_owned()])
+                        .chain(std::env::args().skip(1))
+                        .collect();
+                    client.run_in_popup(&argv, &size)?;
+                    return Ok(());
+                }
+            }
             // Default to TUI mode
-            let mut app = App::new(client);
+            let mut app = App::new(client)
+                .with_config(config)
+                .with_terminal(terminal)
+                .with_capabilities(capabilities)
+                .with_popup(in_popup);
             app.run().await?;
         }
-        Some(Commands::List) => {
-            let sessions = client.list_sessions()?;
-            if sessions.is_empty() {
-                println!("No tmux sessions found.");
+        Some(Commands::List { sort, tag }) => {
+            let sort = match sort {
+                Some(sort) => sort,
+                None => config.sort_spec()?,
+            };
+            let mut sessions = client.list_sessions()?;
+            if let Some(tag) = &tag {
+                sessions.retain(|s| s.tags.contains(tag));
+            }
+            sort.sort(&mut sessions);
+            if json {
+                print_json(&sessions)?;
+            } else if sessions.is_empty() {
+                say!("No tmux sessions found.");
             } else {
-                println!("tmux sessions:");
+                say!("tmux sessions:");
                 for session in sessions {
-                    let attached = if session.attached { "●" } else { "○" };
-                    println!(
-                        "  {} {} - {} window(s)",
-                        attached, session.name, session.windows
+                    let attached = icons.attached(session.attached);
+                    let alerts = if session.alerts.any() {
+                        format!(" {}", session.alerts.badges())
+                    } else {
+                        String::new()
+                    };
+                    let tags: String = session.tags.iter().map(|t| format!(" #{}", t)).collect();
+                    say!(
+                        "  {} {}{} - {} window(s){}",
+                        attached,
+                        session.name,
+                        alerts,
+                        session.windows,
+                        tags
                     );
                 }
             }
         }
-        Some(Commands::New { name }) => {
-            client.create_session(&name)?;
-            println!("Session '{}' created.", name);
+        Some(Commands::New { name, dir, command }) => {
+            let name = validate_session_name(&name)?;
+            client.create_session(&name, dir.as_deref(), command.as_deref())?;
+            track(EventKind::Created, &name);
+            if json {
+                print_json(&serde_json::json!({ "created": name }))?;
+            } else {
+                say!("Session '{}' created.", name);
+            }
+        }
+        Some(Commands::Go {
+            name,
+            template,
+            dir,
+            detached,
+        }) => {
+            let name = validate_session_name(&name)?;
+            let exists = client.list_sessions()?.iter().any(|s| s.name == name);
+            if !exists {
+                match &template {
+                    Some(template) => {
+                        let mut template = Template::load(template)?;
+                        template.name = Some(name.clone());
+                        if let Some(dir) = &dir {
+                            template.root = Some(dir.to_string_lossy().into_owned());
+                        }
+                        template.start(&client)?;
+                    }
+                    None => client.create_session(&name, dir.as_deref(), None)?,
+                }
+                track(EventKind::Created, &name);
+            }
+            if json {
+                print_json(&serde_json::json!({ "session": name, "created": !exists }))?;
+            } else if !exists {
+                say!("Session '{}' created.", name);
+            }
+            if !detached {
+                enter_session(&name)?;
+            }
+        }
+        Some(Commands::Pick { attach }) => {
+            let mut sessions = client.list_sessions()?;
+            config.sort_spec()?.sort(&mut sessions);
+            let names = sessions.into_iter().map(|s| s.name).collect();
+            let theme = config.theme()?.for_terminal(terminal.color);
+            let Some(name) = SessionSwitcher::new(names).run(&theme)? else {
+                std::process::exit(EXIT_CANCELLED);
+            };
+            if attach {
+                enter_session(&name)?;
+            } else if json {
+                print_json(&serde_json::json!({ "session": name }))?;
+            } else {
+                say!("{}", name);
+            }
+        }
+        Some(Commands::Clone {
+            source,
+            name,
+            no_commands,
+        }) => {
+            let name = validate_session_name(&name)?;
+            client.clone_session(&source, &name, !no_commands)?;
+            track(EventKind::Created, &name);
+            if json {
+                print_json(&serde_json::json!({ "created": name, "source": source }))?;
+            } else {
+                say!("Session '{}' copied to '{}'.", source, name);
+            }
         }
         Some(Commands::Kill { name }) => {
             client.kill_session(&name)?;
-            println!("Session '{}' killed.", name);
+            track(EventKind::Killed, &name);
+            if json {
+                print_json(&serde_json::json!({ "killed": name }))?;
+            } else {
+                say!("Session '{}' killed.", name);
+            }
+        }
+        Some(Commands::Attach {
+            name,
+            detach_others,
+        }) => {
+            track(EventKind::Attached, &name);
+            let result = if detach_others || config.exclusive_attach {
+                client.attach_session_exclusive(&name)
+            } else {
+                client.attach_session(&name)
+            };
+            // attach-session blocks until the client detaches
+            track(EventKind::Detached, &name);
+            result?;
+        }
+        Some(Commands::Rename { old, new }) => {
+            let new = validate_session_name(&new)?;
+            client.rename_session(&old, &new)?;
+            track(EventKind::Renamed, &new);
+            if json {
+                print_json(&serde_json::json!({ "renamed": old, "name": new }))?;
+            } else {
+                say!("Session '{}' renamed to '{}'.", old, new);
+            }
+        }
+        Some(Commands::RenameWindow { target, new }) => {
+            let new = validate_window_name(&new)?;
+            client.rename_window(&target, &new)?;
+            if json {
+                print_json(&serde_json::json!({ "renamed": target, "name": new }))?;
+            } else {
+                say!("Window {} renamed to '{}'.", target, new);
+            }
+        }
+        Some(Commands::Windows { session }) => {
+            let windows = client.list_windows(&session)?;
+            if json {
+                print_json(&windows)?;
+            } else {
+                let rows: Vec<Vec<String>> = windows
+                    .iter()
+                    .map(|window| {
+                        let mut flags = window.alerts.badges();
+                        for (set, flag) in [
+                            (window.zoomed, 'Z'),
+                            (window.synchronized, 'S'),
+                            (window.linked, 'L'),
+                        ] {
+                            if set {
+                                flags.push(flag);
+                            }
+                        }
+                        vec![
+                            icons.attached(window.active).to_string(),
+                            window.index.to_string(),
+                            window.name.clone(),
+                            window.panes.to_string(),
+                            window.id.clone(),
+                            flags,
+                        ]
+                    })
+                    .collect();
+                print_table(&["", "INDEX", "NAME", "PANES", "ID", "FLAGS"], &rows);
+            }
+        }
+        Some(Commands::Panes { target }) => {
+            let panes = client.list_panes(&target)?;
+            if json {
+                print_json(&panes)?;
+            } else {
+                let rows: Vec<Vec<String>> = panes
+                    .iter()
+                    .map(|pane| {
+                        let command = if pane.dead {
+                            format!("{} (dead)", pane.current_command)
+                        } else {
+                            pane.current_command.clone()
+                        };
+                        vec![
+                            icons.attached(pane.active).to_string(),
+                            pane.index.to_string(),
+                            pane.id.clone(),
+                            format!("{}x{}", pane.width, pane.height),
+                            command,
+                            pane.current_path.clone(),
+                        ]
+                    })
+                    .collect();
+                print_table(&["", "INDEX", "ID", "SIZE", "COMMAND", "PATH"], &rows);
+            }
+        }
+        Some(Commands::NewWindow { session, name }) => {
+            client.create_window(&session, name.as_deref())?;
+            if json {
+                print_json(&serde_json::json!({ "session": session, "name": name }))?;
+            } else {
+                say!("Window created in session '{}'.", session);
+            }
         }
-        Some(Commands::Attach { name }) => {
-            client.attach_session(&name)?;
+        Some(Commands::KillWindow { target }) => {
+            client.kill_window(&target)?;
+            if json {
+                print_json(&serde_json::json!({ "killed": target }))?;
+            } else {
+                say!("Window {} killed.", target);
+            }
+        }
+        Some(Commands::Split {
+            target,
+            horizontal,
+            size,
+            dir,
+        }) => {
+            let direction = if horizontal {
+                SplitDirection::Horizontal
+            } else {
+                SplitDirection::Vertical
+            };
+            let pane = client.split_window(&target, direction, size, dir.as_deref())?;
+            if json {
+                print_json(&serde_json::json!({ "target": target, "pane": pane }))?;
+            } else {
+                say!("Split {}; the new pane is {}.", target, pane);
+            }
+        }
+        Some(Commands::Tag { name, tags, clear }) => {
+            let session = client
+                .list_sessions()?
+                .into_iter()
+                .find(|s| s.name == name)
+                .ok_or_else(|| tmux_ui::Error::SessionNotFound(name.clone()))?;
+            let tags = if clear || !tags.is_empty() {
+                let tags = parse_tags(&tags.join(","))?;
+                client.set_session_tags(&session.id, &tags)?;
+                tags
+            } else {
+                session.tags
+            };
+            if json {
+                print_json(&serde_json::json!({ "session": name, "tags": tags }))?;
+            } else if tags.is_empty() {
+                say!("Session '{}' has no tags.", name);
+            } else {
+                say!("{}", tags.join(", "));
+            }
+        }
+        Some(Commands::Prune { dry_run }) => {
+            let names: Vec<String> = if dry_run {
+                client
+                    .idle_sessions()?
+                    .into_iter()
+                    .map(|s| s.name)
+                    .collect()
+            } else {
+                let pruned = client.prune_sessions()?;
+                for name in &pruned {
+                    track(EventKind::Killed, name);
+                }
+                pruned
+            };
+            if json {
+                print_json(&serde_json::json!({ "dry_run": dry_run, "sessions": names }))?;
+            } else if names.is_empty() {
+                say!("No idle sessions.");
+            } else {
+                let verb = if dry_run { "Would kill" } else { "Killed" };
+                for name in &names {
+                    say!("{} session '{}'", verb, name);
+                }
+            }
+        }
+        Some(Commands::KillServer) => {
+            let sessions = client.list_sessions()?;
+            client.kill_server()?;
+            for session in &sessions {
+                track(EventKind::Killed, &session.name);
+            }
+            if json {
+                print_json(&serde_json::json!({ "killed": sessions.len() }))?;
+            } else {
+                say!("tmux server killed ({} session(s)).", sessions.len());
+            }
+        }
+        Some(Commands::Report { since }) => {
+            let format = match cli.format {
+                OutputFormat::Text => ReportFormat::Text,
+                OutputFormat::Json => ReportFormat::Json,
+                OutputFormat::Markdown => ReportFormat::Markdown,
+            };
+            let log = usage_log
+                .clone()
+                .or_else(|| UsageLog::default_path().map(UsageLog::new));
+            let events = match &log {
+                Some(log) => log.read()?,
+                None => Vec::new(),
+            };
+            let until = usage::now();
+            let report = UsageReport::build(&events, until.saturating_sub(since.0), until);
+            put!("{}", report.render(format)?);
+            if format == ReportFormat::Json {
+                say!();
+            }
+            if !config.track_usage && !cli.quiet {
+                eprintln!(
+                    "Note: usage tracking is off; enable it with track_usage = true in the config"
+                );
+            }
+        }
+        Some(Commands::Send {
+            target,
+            command,
+            no_enter,
+        }) => {
+            let command = command.join(" ");
+            client.send_keys(&target, &[Key::Literal(command.clone())], !no_enter)?;
+            if json {
+                print_json(&serde_json::json!({ "target": target, "sent": command }))?;
+            } else {
+                say!("Sent '{}' to {}.", command, target);
+            }
+        }
+        Some(Commands::Buffer { command }) => match command.unwrap_or(BufferCommand::List) {
+            BufferCommand::List => {
+                let buffers = client.list_buffers()?;
+                if json {
+                    print_json(&buffers)?;
+                } else if buffers.is_empty() {
+                    say!("No paste buffers.");
+                } else {
+                    for buffer in buffers {
+                        say!(
+                            "{}: {} bytes: \"{}\"",
+                            buffer.name,
+                            buffer.size,
+                            buffer.sample
+                        );
+                    }
+                }
+            }
+            BufferCommand::Show { name, clipboard } => {
+                let name = buffer_name(&client, name)?;
+                let content = client.show_buffer(&name)?;
+                if clipboard {
+                    clipboard::copy(&content)?;
+                }
+                if json {
+                    print_json(&serde_json::json!({ "name": name, "content": content }))?;
+                } else {
+                    put!("{}", content);
+                }
+            }
+            BufferCommand::Set {
+                text,
+                name,
+                clipboard,
+            } => {
+                let text = match text {
+                    Some(text) => text,
+                    None => {
+                        let mut text = String::new();
+                        std::io::stdin().read_to_string(&mut text)?;
+                        text
+                    }
+                };
+                client.set_buffer(name.as_deref(), &text)?;
+                if clipboard {
+                    clipboard::copy(&text)?;
+                }
+                if json {
+                    print_json(&serde_json::json!({ "name": name, "size": text.len() }))?;
+                } else {
+                    say!("Stored {} bytes.", text.len());
+                }
+            }
+            BufferCommand::Paste { target, name } => {
+                let name = buffer_name(&client, name)?;
+                client.paste_buffer(&name, &target)?;
+                if json {
+                    print_json(&serde_json::json!({ "name": name, "target": target }))?;
+                } else {
+                    say!("Pasted buffer '{}' into {}.", name, target);
+                }
+            }
+        },
+        Some(Commands::Raw { args }) => {
+            let output = client.run_raw(&args)?;
+            if json {
+                print_json(&output)?;
+            } else {
+                put!("{}", output.stdout);
+                eprint!("{}", output.stderr);
+            }
+            if !output.success() {
+                std::process::exit(output.code.unwrap_or(1));
+            }
+        }
+        Some(Commands::Start { template, detached }) => {
+            let name = Template::load(&template)?.start(&client)?;
+            track(EventKind::Created, &name);
+            if json {
+                print_json(&serde_json::json!({ "created": name, "template": template }))?;
+            } else {
+                say!("Session '{}' started from template '{}'.", name, template);
+            }
+            if !detached {
+                enter_session(&name)?;
+            }
+        }
+        Some(Commands::Exec { script, check }) => {
+            let parsed = Script::load(&script)?;
+            let created = if check {
+                Vec::new()
+            } else {
+                parsed.run(&client)?
+            };
+            for name in &created {
+                track(EventKind::Created, name);
+            }
+            if json {
+                print_json(&serde_json::json!({
+                    "script": script,
+                    "steps": parsed.steps.len(),
+                    "created": created,
+                }))?;
+            } else if check {
+                say!("Script is valid ({} commands).", parsed.steps.len());
+            } else {
+                say!(
+                    "Ran {} commands; created {} session(s){}",
+                    parsed.steps.len(),
+                    created.len(),
+                    if created.is_empty() {
+                        ".".to_string()
+                    } else {
+                        format!(": {}", created.join(", "))
+                    }
+                );
+            }
+        }
+        Some(Commands::Save { file }) => {
+            let path = snapshot_path(file)?;
+            let snapshot = Snapshot::capture(&client)?;
+            snapshot.save(&path)?;
+            if json {
+                print_json(&serde_json::json!({
+                    "file": path,
+                    "sessions": snapshot.sessions.len(),
+                }))?;
+            } else {
+                say!(
+                    "Saved {} session(s) to {}",
+                    snapshot.sessions.len(),
+                    path.display()
+                );
+            }
+        }
+        Some(Commands::Restore { file }) => {
+            let path = snapshot_path(file)?;
+            let report = Snapshot::load(&path)?.restore(&client)?;
+            for name in &report.restored {
+                track(EventKind::Created, name);
+            }
+            if json {
+                print_json(&report)?;
+            } else {
+                for name in &report.restored {
+                    say!("Restored session '{}'", name);
+                }
+                for name in &report.skipped {
+                    say!("Skipped session '{}' (already exists)", name);
+                }
+            }
+        }
+        Some(Commands::PromptSegment { style }) => {
+            // Prompts are redrawn constantly, so stay quiet on errors
+            let status = PromptStatus::query(&client).unwrap_or_default();
+            if json {
+                print_json(&status)?;
+                return Ok(());
+            }
+            let segment = status.render(style);
+            if !segment.is_empty() {
+                say!("{}", segment);
+            }
+        }
+        #[cfg(unix)]
+        Some(Commands::Daemon { path }) => {
+            // Only the default directory is ours to create; one given with --path,
+            // such as the home directory, is left alone
+            let path = match path {
+                Some(path) => path,
+                None => {
+                    let path = daemon::default_socket_path();
+                    if let Some(dir) = path.parent() {
+                        daemon::create_socket_dir(dir)?;
+                    }
+                    path
+                }
+            };
+            let listener = daemon::bind(&path).await?;
+            let interval = config
+                .refresh_interval()
+                .unwrap_or(daemon::DEFAULT_INTERVAL);
+            if json {
+                print_json(&serde_json::json!({ "listening": path }))?;
+            } else {
+                say!("Listening on {}", path.display());
+            }
+            // Either signal stops it, and the socket is removed on the way out
+            let mut terminate =
+                tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+            let result = tokio::select! {
+                result = Daemon::new(client).serve(listener, interval) => result,
+                _ = tokio::signal::ctrl_c() => Ok(()),
+                _ = terminate.recv() => Ok(()),
+            };
+            let _ = std::fs::remove_file(&path);
+            result?;
+        }
+        #[cfg(all(feature = "serve", unix))]
+        Some(Commands::Serve { listen, token }) => {
+            use anyhow::Context;
+            use tmux_ui::serve::Api;
+
+            let token = token.or_else(|| std::env::var("TMUX_UI_TOKEN").ok());
+            // Anyone who can reach the API can type into every pane
+            if !listen.ip().is_loopback() && token.is_none() {
+                anyhow::bail!(
+                    "Listening on {} needs a --token, since the API can run commands in any pane",
+                    listen
+                );
+            }
+            let listener = tokio::net::TcpListener::bind(listen)
+                .await
+                .with_context(|| format!("Failed to listen on {}", listen))?;
+            let interval = config
+                .refresh_interval()
+                .unwrap_or(daemon::DEFAULT_INTERVAL);
+            if json {
+                print_json(&serde_json::json!({ "listening": listen }))?;
+            } else {
+                say!("Listening on http://{}", listen);
+                #[cfg(feature = "web")]
+                match &token {
+                    // The page reads the token from the fragment, which browsers never send
+                    Some(token) => say!("Web UI: http://{}/#token={}", listen, token),
+                    None => say!("Web UI: http://{}/", listen),
+                }
+            }
+            Api::new(Daemon::new(client), token)
+                .serve(listener, interval)
+                .await?;
+        }
+        Some(Commands::InstallKeybinding {
+            key,
+            no_prefix,
+            window,
+            size,
+            append,
+            yes,
+            file,
+        }) => {
+            let launch = match (window, size) {
+                (true, _) => Launch::Window,
+                (false, Some(size)) => Launch::Popup(size),
+                (false, None) => Launch::Popup(config.popup_size()?),
+            };
+            // tmux runs the line with the server's PATH, which may lack cargo's bin
+            let program = std::env::current_exe()?.to_string_lossy().into_owned();
+            let binding = Keybinding {
+                key,
+                no_prefix,
+                program,
+                launch,
+            };
+            let line = binding.line();
+            if !append {
+                if json {
+                    print_json(&serde_json::json!({ "line": line }))?;
+                } else {
+                    say!("{}", line);
+                }
+                return Ok(());
+            }
+
+            let path = file.or_else(keybinding::tmux_conf_path).ok_or_else(|| {
+                anyhow::anyhow!("Cannot determine tmux.conf location; pass --file")
+            })?;
+            if !yes && !confirm(&format!("Append to {}?\n  {}\n", path.display(), line))? {
+                anyhow::bail!("Nothing appended");
+            }
+            let appended = binding.append_to(&path)?;
+            if json {
+                print_json(&serde_json::json!({
+                    "line": line,
+                    "file": path,
+                    "appended": appended,
+                }))?;
+            } else if appended {
+                say!(
+                    "Appended to {}; reload it with: tmux source-file {}",
+                    path.display(),
+                    path.display()
+                );
+            } else {
+                say!("{} already has this key binding.", path.display());
+            }
+        }
+        Some(Commands::Completions { shell }) => {
+            let completer: &dyn EnvCompleter = match shell {
+                Shell::Bash => &complete_env::Bash,
+                Shell::Elvish => &complete_env::Elvish,
+                Shell::Fish => &complete_env::Fish,
+                Shell::PowerShell => &complete_env::Powershell,
+                Shell::Zsh => &complete_env::Zsh,
+                other => anyhow::bail!("Completions for {} are not supported", other),
+            };
+            completer.write_registration(
+                COMPLETE_VAR,
+                "tmux-ui",
+                "tmux-ui",
+                "tmux-ui",
+                &mut std::io::stdout(),
+            )?;
         }
     }
 
     Ok(())
 }
+
+/// Print a value as pretty-printed JSON on stdout
+fn print_json<T: serde::Serialize>(value: &T) -> anyhow::Result<()> {
+    say!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print rows in columns padded to their widest cell, under a header
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        say!("{}", padded.join("  ").trim_end());
+    };
+    line(header.to_vec());
+    for row in rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
+
+/// The client of the server chosen with `-L`, `-S` or `--host`
+fn server_client(
+    socket: Option<String>,
+    socket_path: Option<PathBuf>,
+    host: Option<String>,
+) -> TmuxClient {
+    let socket = match (socket, socket_path) {
+        (Some(name), _) => Socket::Name(name),
+        (None, Some(path)) => Socket::Path(path),
+        (None, None) => Socket::Default,
+    };
+    let client = TmuxClient::new().with_socket(socket);
+    match host {
+        Some(host) => client.with_host(host),
+        None => client,
+    }
+}
+
+/// The client of the server chosen on the command line being completed, which the
+/// completion scripts pass after `--`
+fn completion_client() -> TmuxClient {
+    let words = std::env::args_os().skip_while(|arg| arg != "--").skip(1);
+    let Ok(mut matches) = Cli::command()
+        .ignore_errors(true)
+        .try_get_matches_from(words)
+    else {
+        return TmuxClient::new();
+    };
+    // Global options may come after the subcommand, where they are kept
+    let (mut socket, mut socket_path, mut host) = (None, None, None);
+    loop {
+        socket = matches.remove_one::<String>("socket").or(socket);
+        socket_path = matches.remove_one::<PathBuf>("socket_path").or(socket_path);
+        host = matches.remove_one::<String>("host").or(host);
+        match matches.remove_subcommand() {
+            Some((_, subcommand)) => matches = subcommand,
+            None => break,
+        }
+    }
+    server_client(socket, socket_path, host)
+}
+
+/// Names of the sessions on the server being completed for, offered when completing
+/// a session name; the completion scripts ask for them on every completion
+fn session_candidates() -> Vec<CompletionCandidate> {
+    let sessions = completion_client().list_sessions().unwrap_or_default();
+    sessions
+        .into_iter()
+        .map(|session| {
+            let help = format!("{} window(s)", session.windows);
+            CompletionCandidate::new(session.name).help(Some(help.into()))
+        })
+        .collect()
+}
+
+/// Ask a yes/no question on stderr and read the answer from stdin; anything but
+/// `y` or `yes` is no
+fn confirm(question: &str) -> anyhow::Result<bool> {
+    use std::io::Write;
+    eprint!("{}[y/N] ", question);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+/// The buffer given on the command line, or the most recent one
+fn buffer_name(client: &TmuxClient, name: Option<String>) -> anyhow::Result<String> {
+    match name {
+        Some(name) => Ok(name),
+        None => client
+            .list_buffers()?
+            .into_iter()
+            .next()
+            .map(|buffer| buffer.name)
+            .ok_or_else(|| anyhow::anyhow!("No paste buffers")),
+    }
+}
+
+/// The snapshot file given on the command line, or the default one
+fn snapshot_path(file: Option<PathBuf>) -> anyhow::Result<PathBuf> {
+    file.or_else(Snapshot::default_path)
+        .ok_or_else(|| anyhow::anyhow!("Cannot determine snapshot location; pass --file"))
+}
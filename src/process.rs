@@ -0,0 +1,145 @@
+//! Processes running in a pane, read from `/proc`
+//!
+//! tmux only knows the pid of the program a pane was started with (`#{pane_pid}`),
+//! usually a shell; what runs under it is found by walking the parent links of every
+//! process. Linux only: elsewhere the tree cannot be read.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Clock ticks per second of the times in `/proc/<pid>/stat`; 100 on every common
+/// Linux configuration
+const CLOCK_TICKS: f64 = 100.0;
+
+/// One process of the tree under a pane
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Command line, or the process name in brackets for kernel threads and zombies
+    pub command: String,
+    /// Share of one CPU used since the process started, in percent
+    pub cpu_percent: f64,
+    /// Resident memory in KiB
+    pub rss_kib: u64,
+    /// Distance from the pane's process, which is at depth 0
+    pub depth: usize,
+}
+
+/// Fields of `/proc/<pid>/stat` needed for the tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessStat {
+    pub pid: u32,
+    /// Process name, at most 15 characters
+    pub name: String,
+    pub ppid: u32,
+    /// User and system time, in clock ticks
+    pub cpu_ticks: u64,
+    /// Start time after boot, in clock ticks
+    pub start_ticks: u64,
+}
+
+/// Parse the contents of `/proc/<pid>/stat`. The name is in parentheses and may
+/// itself contain spaces and parentheses, so the fields are counted from the last `)`.
+pub fn parse_stat(contents: &str) -> Option<ProcessStat> {
+    let (head, rest) = contents.rsplit_once(')')?;
+    let (pid, name) = head.split_once(" (")?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // `rest` starts at field 3 (state); utime, stime and starttime are fields 14, 15 and 22
+    let field = |n: usize| fields.get(n - 3).and_then(|f| f.parse::<u64>().ok());
+    Some(ProcessStat {
+        pid: pid.trim().parse().ok()?,
+        name: name.to_string(),
+        ppid: u32::try_from(field(4)?).ok()?,
+        cpu_ticks: field(14)? + field(15)?,
+        start_ticks: field(22)?,
+    })
+}
+
+/// The process `root` and everything running under it, depth first
+pub fn process_tree(root: u32) -> Result<Vec<ProcessInfo>> {
+    let proc = Path::new("/proc");
+    let uptime = fs::read_to_string(proc.join("uptime"))
+        .context("Process trees can only be read from /proc (Linux)")?;
+    let uptime: f64 = uptime
+        .split_whitespace()
+        .next()
+        .and_then(|secs| secs.parse().ok())
+        .context("Unexpected /proc/uptime")?;
+
+    let mut stats: HashMap<u32, ProcessStat> = HashMap::new();
+    for entry in fs::read_dir(proc)?.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        // Processes may exit while the directory is read
+        if let Some(stat) = fs::read_to_string(entry.path().join("stat"))
+            .ok()
+            .and_then(|contents| parse_stat(&contents))
+        {
+            stats.insert(pid, stat);
+        }
+    }
+    if !stats.contains_key(&root) {
+        anyhow::bail!("No process {}", root);
+    }
+
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for stat in stats.values() {
+        children.entry(stat.ppid).or_default().push(stat.pid);
+    }
+    for pids in children.values_mut() {
+        pids.sort_unstable();
+    }
+
+    let mut tree = Vec::new();
+    let mut stack = vec![(root, 0)];
+    while let Some((pid, depth)) = stack.pop() {
+        let stat = &stats[&pid];
+        let running = uptime - stat.start_ticks as f64 / CLOCK_TICKS;
+        let cpu_percent = if running > 0.0 {
+            stat.cpu_ticks as f64 / CLOCK_TICKS / running * 100.0
+        } else {
+            0.0
+        };
+        tree.push(ProcessInfo {
+            pid,
+            ppid: stat.ppid,
+            command: command_line(proc, pid).unwrap_or_else(|| format!("[{}]", stat.name)),
+            cpu_percent,
+            rss_kib: resident_memory(proc, pid).unwrap_or(0),
+            depth,
+        });
+        if let Some(pids) = children.get(&pid) {
+            stack.extend(pids.iter().rev().map(|&child| (child, depth + 1)));
+        }
+    }
+    Ok(tree)
+}
+
+/// Arguments of a process joined by spaces; `None` when it has none, e.g. a zombie
+fn command_line(proc: &Path, pid: u32) -> Option<String> {
+    let raw = fs::read(proc.join(pid.to_string()).join("cmdline")).ok()?;
+    let args: Vec<String> = raw
+        .split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    (!args.is_empty()).then(|| args.join(" "))
+}
+
+/// `VmRSS` from `/proc/<pid>/status`, in KiB
+fn resident_memory(proc: &Path, pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(proc.join(pid.to_string()).join("status")).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|kib| kib.parse().ok())
+}
@@ -0,0 +1,131 @@
+//! Compact session status for embedding in shell prompts (starship, p10k, ...)
+
+use crate::tmux::TmuxClient;
+use anyhow::Result;
+use serde::Serialize;
+use std::env;
+
+/// How long to wait for the daemon before asking tmux instead
+#[cfg(unix)]
+const DAEMON_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Output style of the prompt segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SegmentStyle {
+    /// Plain text, e.g. `main !2`
+    Plain,
+    /// Powerline separators, for use with a powerline or Nerd Font
+    Powerline,
+}
+
+/// What the prompt segment shows
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PromptStatus {
+    /// Session the shell is running in, if inside tmux
+    pub current_session: Option<String>,
+    /// Number of sessions with bell, activity or silence alerts
+    pub alerting_sessions: usize,
+}
+
+impl PromptStatus {
+    /// Gather the status from the daemon's cached session list, or with a single
+    /// `tmux list-sessions` call when no daemon is running
+    pub fn query(client: &TmuxClient) -> Result<Self> {
+        // $TMUX is "<socket path>,<server pid>,<session id number>"
+        let current_id = env::var("TMUX")
+            .ok()
+            .filter(|_| client.is_inside_this_server())
+            .and_then(|tmux| tmux.rsplit(',').next().map(|id| format!("${}", id)));
+
+        // The daemon serves the default server; with -L, -S or --host it may not be ours
+        #[cfg(unix)]
+        if *client.socket() == crate::tmux::Socket::Default && client.host().is_none() {
+            let path = crate::daemon::default_socket_path();
+            if let Some(sessions) = daemon_sessions(&path) {
+                return Ok(Self::from_sessions(current_id.as_deref(), sessions));
+            }
+        }
+        Ok(Self::from_sessions(
+            current_id.as_deref(),
+            client.list_session_alerts()?,
+        ))
+    }
+
+    /// The status of `(session id, session name, has alerts)` entries
+    pub fn from_sessions(
+        current_id: Option<&str>,
+        sessions: impl IntoIterator<Item = (String, String, bool)>,
+    ) -> Self {
+        let mut status = PromptStatus::default();
+        for (id, name, alerts) in sessions {
+            if current_id == Some(id.as_str()) {
+                status.current_session = Some(name);
+            }
+            if alerts {
+                status.alerting_sessions += 1;
+            }
+        }
+        status
+    }
+
+    /// Render the segment; empty when there is nothing to show
+    pub fn render(&self, style: SegmentStyle) -> String {
+        let alerts = (self.alerting_sessions > 0).then_some(self.alerting_sessions);
+
+        match style {
+            SegmentStyle::Plain => match (&self.current_session, alerts) {
+                (Some(name), Some(n)) => format!("{} !{}", name, n),
+                (Some(name), None) => name.clone(),
+                (None, Some(n)) => format!("!{}", n),
+                (None, None) => String::new(),
+            },
+            SegmentStyle::Powerline => {
+                let mut parts = Vec::new();
+                if let Some(name) = &self.current_session {
+                    parts.push(format!(" \u{e795} {} ", name));
+                }
+                if let Some(n) = alerts {
+                    parts.push(format!(" \u{f0f3} {} ", n));
+                }
+                if parts.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}\u{e0b0}", parts.join("\u{e0b1}"))
+                }
+            }
+        }
+    }
+}
+
+/// Ask the daemon listening on `path` for its cached sessions, as
+/// `(session id, session name, has alerts)`; `None` if it is not running or
+/// does not answer in time
+#[cfg(unix)]
+pub fn daemon_sessions(path: &std::path::Path) -> Option<Vec<(String, String, bool)>> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(path).ok()?;
+    stream.set_read_timeout(Some(DAEMON_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(DAEMON_TIMEOUT)).ok()?;
+    stream
+        .write_all(b"{\"id\": 1, \"method\": \"sessions\"}\n")
+        .ok()?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).ok()?;
+    let response: serde_json::Value = serde_json::from_str(&line).ok()?;
+    response["result"]
+        .as_array()?
+        .iter()
+        .map(|session| {
+            let alerts = session["alerts"]
+                .as_object()
+                .is_some_and(|alerts| alerts.values().any(|set| set == true));
+            Some((
+                session["id"].as_str()?.to_string(),
+                session["name"].as_str()?.to_string(),
+                alerts,
+            ))
+        })
+        .collect()
+}
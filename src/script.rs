@@ -0,0 +1,342 @@
+//! Headless provisioning scripts (`tmux-ui exec --script <file>`)
+//!
+//! A script is a list of commands, one per line, run in order without starting
+//! the TUI. Words are separated by whitespace and may be quoted with `'...'` or
+//! `"..."`; lines starting with `#` are comments. Each command acts on the
+//! session, window and pane created last:
+//!
+//! ```text
+//! session NAME [DIR]                 create a detached session
+//! window NAME [DIR]                  add a window to the session
+//! split vertical|horizontal [N%] [DIR]
+//!                                    split the pane, below it or to its right
+//! send TEXT...                       type text into the pane and press Enter
+//! keys KEYS                          send keys, e.g. `keys C-c 'make' Enter`
+//! option NAME VALUE                  set a session option
+//! layout NAME                        apply a tmux layout to the window
+//! ```
+//!
+//! Relative directories are resolved against the directory of the enclosing
+//! session (for windows) or window (for splits).
+
+use crate::template::{expand_home, push_cwd, resolve, run};
+use crate::tmux::{parse_keys, validate_session_name, Key, SplitDirection, TmuxClient};
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// A single script command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Session {
+        name: String,
+        dir: Option<String>,
+    },
+    Window {
+        name: String,
+        dir: Option<String>,
+    },
+    Split {
+        direction: SplitDirection,
+        percent: Option<u8>,
+        dir: Option<String>,
+    },
+    Send(String),
+    Keys(Vec<Key>),
+    Option {
+        name: String,
+        value: String,
+    },
+    Layout(String),
+}
+
+/// A parsed script; each step keeps its line number for error messages
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    pub steps: Vec<(usize, Step)>,
+}
+
+impl Script {
+    /// Read a script from a file, or from stdin if the path is `-`
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = if path == Path::new("-") {
+            let mut contents = String::new();
+            std::io::stdin()
+                .read_to_string(&mut contents)
+                .context("Failed to read script from stdin")?;
+            contents
+        } else {
+            fs::read_to_string(path)
+                .with_context(|| format!("Failed to read script {}", path.display()))?
+        };
+        Self::parse(&contents).with_context(|| format!("Invalid script {}", path.display()))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut steps = Vec::new();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let step = parse_step(line).with_context(|| format!("line {}", i + 1))?;
+            steps.push((i + 1, step));
+        }
+        if steps.is_empty() {
+            anyhow::bail!("Script has no commands");
+        }
+        Ok(Self { steps })
+    }
+
+    /// Run the script against a server, returning the names of the sessions it created
+    pub fn run(&self, client: &TmuxClient) -> Result<Vec<String>> {
+        let mut state = State::default();
+        for (line, step) in &self.steps {
+            state
+                .apply(client, step)
+                .with_context(|| format!("line {}", line))?;
+        }
+        Ok(state.created)
+    }
+}
+
+fn parse_step(line: &str) -> Result<Step> {
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    // Key sequences keep their quotes, which mark literal text
+    if command == "keys" {
+        let keys = parse_keys(rest)?;
+        if keys.is_empty() {
+            anyhow::bail!("keys needs at least one key");
+        }
+        return Ok(Step::Keys(keys));
+    }
+
+    let args = split_words(rest)?;
+    let step = match (command, args.as_slice()) {
+        ("session", [name]) => Step::Session {
+            name: validate_session_name(name)?,
+            dir: None,
+        },
+        ("session", [name, dir]) => Step::Session {
+            name: validate_session_name(name)?,
+            dir: Some(dir.clone()),
+        },
+        ("window", [name]) => Step::Window {
+            name: name.clone(),
+            dir: None,
+        },
+        ("window", [name, dir]) => Step::Window {
+            name: name.clone(),
+            dir: Some(dir.clone()),
+        },
+        ("split", [direction, more @ ..]) if more.len() <= 2 => {
+            let direction = match direction.as_str() {
+                "vertical" | "v" => SplitDirection::Vertical,
+                "horizontal" | "h" => SplitDirection::Horizontal,
+                other => anyhow::bail!(
+                    "Unknown split direction '{}' (expected vertical or horizontal)",
+                    other
+                ),
+            };
+            let (percent, dir) = match more {
+                [] => (None, None),
+                [size, dir] => (Some(parse_percent(size)?), Some(dir.clone())),
+                [arg] => match arg.strip_suffix('%') {
+                    Some(_) => (Some(parse_percent(arg)?), None),
+                    None => (None, Some(arg.clone())),
+                },
+                _ => unreachable!(),
+            };
+            Step::Split {
+                direction,
+                percent,
+                dir,
+            }
+        }
+        ("send", [_, ..]) => Step::Send(args.join(" ")),
+        ("option", [name, value]) => Step::Option {
+            name: name.clone(),
+            value: value.clone(),
+        },
+        ("layout", [layout]) => Step::Layout(layout.clone()),
+        ("session" | "window" | "split" | "send" | "option" | "layout", _) => {
+            anyhow::bail!("Wrong number of arguments for '{}'", command)
+        }
+        _ => anyhow::bail!("Unknown command '{}'", command),
+    };
+    Ok(step)
+}
+
+/// Parse a split size such as `30%`
+fn parse_percent(size: &str) -> Result<u8> {
+    size.strip_suffix('%')
+        .and_then(|n| n.parse().ok())
+        .filter(|n| (1..=99).contains(n))
+        .with_context(|| format!("Invalid split size '{}' (expected 1% to 99%)", size))
+}
+
+/// Split a line into words, like a shell without expansions
+fn split_words(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            if c != '\'' && c != '"' {
+                word.push(c);
+                continue;
+            }
+            let mut closed = false;
+            while let Some(ch) = chars.next() {
+                if ch == c {
+                    closed = true;
+                    break;
+                }
+                // Only double-quoted strings support escapes, like a shell
+                if ch == '\\' && c == '"' {
+                    if let Some(escaped) = chars.next() {
+                        word.push(escaped);
+                        continue;
+                    }
+                }
+                word.push(ch);
+            }
+            if !closed {
+                anyhow::bail!("Unterminated quote");
+            }
+        }
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// What the next command acts on
+#[derive(Default)]
+struct State {
+    session: Option<(String, Option<PathBuf>)>,
+    window: Option<(String, Option<PathBuf>)>,
+    pane: Option<String>,
+    created: Vec<String>,
+}
+
+impl State {
+    fn apply(&mut self, client: &TmuxClient, step: &Step) -> Result<()> {
+        match step {
+            Step::Session { name, dir } => {
+                if client.list_sessions()?.iter().any(|s| &s.name == name) {
+                    anyhow::bail!("Session '{}' already exists", name);
+                }
+                let dir = dir.as_deref().map(expand_home);
+                let mut args = vec!["new-session".into(), "-d".into(), "-s".into(), name.clone()];
+                push_cwd(&mut args, dir.as_deref());
+                args.extend([
+                    "-P".into(),
+                    "-F".into(),
+                    "#{session_id} #{window_id} #{pane_id}".into(),
+                ]);
+                let created = run(client, &args)?;
+                let [session_id, window_id, pane_id] = ids(&created)?;
+                self.session = Some((session_id, dir.clone()));
+                self.window = Some((window_id, dir));
+                self.pane = Some(pane_id);
+                self.created.push(name.clone());
+            }
+            Step::Window { name, dir } => {
+                let (session_id, session_dir) = self
+                    .session
+                    .as_ref()
+                    .context("'window' needs a session first")?;
+                let dir = resolve(session_dir.as_deref(), dir.as_deref());
+                let mut args = vec![
+                    "new-window".into(),
+                    "-d".into(),
+                    "-t".into(),
+                    format!("{}:", session_id),
+                    "-n".into(),
+                    name.clone(),
+                ];
+                push_cwd(&mut args, dir.as_deref());
+                args.extend(["-P".into(), "-F".into(), "#{window_id} #{pane_id}".into()]);
+                let created = run(client, &args)?;
+                let [window_id, pane_id] = ids(&created)?;
+                self.window = Some((window_id, dir));
+                self.pane = Some(pane_id);
+            }
+            Step::Split {
+                direction,
+                percent,
+                dir,
+            } => {
+                let (_, window_dir) = self
+                    .window
+                    .as_ref()
+                    .context("'split' needs a session first")?;
+                let dir = resolve(window_dir.as_deref(), dir.as_deref());
+                let pane = self.current_pane()?;
+                self.pane =
+                    Some(client.split_window(pane, *direction, *percent, dir.as_deref())?);
+            }
+            Step::Send(text) => {
+                client.send_keys(self.current_pane()?, &[Key::Literal(text.clone())], true)?;
+            }
+            Step::Keys(keys) => {
+                client.send_keys(self.current_pane()?, keys, false)?;
+            }
+            Step::Option { name, value } => {
+                let (session_id, _) = self
+                    .session
+                    .as_ref()
+                    .context("'option' needs a session first")?;
+                run(
+                    client,
+                    &[
+                        "set-option".into(),
+                        "-t".into(),
+                        session_id.clone(),
+                        name.clone(),
+                        value.clone(),
+                    ],
+                )?;
+            }
+            Step::Layout(layout) => {
+                let (window_id, _) = self
+                    .window
+                    .as_ref()
+                    .context("'layout' needs a session first")?;
+                run(
+                    client,
+                    &[
+                        "select-layout".into(),
+                        "-t".into(),
+                        window_id.clone(),
+                        layout.clone(),
+                    ],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn current_pane(&self) -> Result<&str> {
+        self.pane
+            .as_deref()
+            .context("No pane yet; start the script with 'session'")
+    }
+}
+
+/// The ids printed by a `-P -F` format of space-separated ids
+fn ids<const N: usize>(output: &str) -> Result<[String; N]> {
+    let ids: Vec<String> = output.split_whitespace().map(str::to_string).collect();
+    ids.try_into()
+        .map_err(|_| anyhow::anyhow!("Unexpected tmux output '{}'", output))
+}
@@ -0,0 +1,355 @@
+//! `tmux-ui serve`: a REST API over HTTP for home-lab automation and dashboards,
+//! built with the `serve` feature.
+//!
+//! The endpoints are thin wrappers of the [`Daemon`] methods, sharing its cached
+//! session list and its JSON errors. hyper handles the HTTP connections; each
+//! request is read into an [`HttpRequest`] and routed by [`Api::route`].
+
+use crate::daemon::{error_json, Daemon};
+use crate::tmux::TmuxBackend;
+use crate::Result;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::body::{Body, Bytes, Incoming};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::Duration;
+
+/// Address `serve` listens on without `--listen`
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:8787";
+
+/// Largest request body accepted; session names and keys are short
+const MAX_BODY: usize = 64 * 1024;
+
+/// Most headers accepted in a request; browsers send a dozen or two
+const MAX_HEADERS: usize = 100;
+
+/// How long a client has to send its request headers, and then its body
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed HTTP request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    /// Query string without the `?`, empty if there is none
+    pub query: String,
+    /// Header names in lower case
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// A decoded query parameter
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        form_urlencoded::parse(self.query.as_bytes())
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.into_owned())
+    }
+}
+
+/// A response, usually with a JSON body
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn json(status: u16, value: &Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn error(status: u16, kind: &str, message: &str) -> Self {
+        // Like the daemon, requests that could not be understood are usage errors
+        let exit_code = if kind == "invalid_request" { 64 } else { 1 };
+        Self::json(
+            status,
+            &json!({ "error": { "message": message, "kind": kind, "exit_code": exit_code } }),
+        )
+    }
+
+    /// The body as JSON, for tests
+    pub fn json_body(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or(Value::Null)
+    }
+
+    fn into_response(self) -> Response<Full<Bytes>> {
+        let mut response = Response::new(Full::new(Bytes::from(self.body)));
+        *response.status_mut() =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type));
+        response
+    }
+}
+
+/// Compare two secrets in a time that depends only on their lengths
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Decode `%XX` escapes of a path segment, e.g. a session name with a space
+pub fn percent_decode(segment: &str) -> Option<String> {
+    percent_encoding::percent_decode_str(segment)
+        .decode_utf8()
+        .ok()
+        .map(|decoded| decoded.into_owned())
+}
+
+/// Whether a `Host` header names this machine: `localhost` or a loopback address,
+/// with or without a port
+fn is_local_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        // [::1]:8787
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// HTTP status of a daemon error
+fn status_of(error: &Value) -> u16 {
+    match error["kind"].as_str().unwrap_or_default() {
+        "session_not_found" => 404,
+        "duplicate_session" => 409,
+        "invalid_request" | "invalid_input" => 400,
+        "server_not_running" | "tmux_not_installed" => 503,
+        _ => 500,
+    }
+}
+
+/// The REST API over a [`Daemon`]
+pub struct Api<C: TmuxBackend> {
+    daemon: Arc<Daemon<C>>,
+    /// Bearer token every request must carry, if any
+    token: Option<String>,
+}
+
+impl<C: TmuxBackend> Api<C> {
+    pub fn new(daemon: Arc<Daemon<C>>, token: Option<String>) -> Arc<Self> {
+        Arc::new(Self { daemon, token })
+    }
+
+    /// Answer a request
+    pub async fn route(&self, request: &HttpRequest) -> HttpResponse {
+        if let Err(message) = self.check_origin(request) {
+            return HttpResponse::error(403, "forbidden", message);
+        }
+        // The page itself holds no data; it asks for the token when opening its socket
+        #[cfg(feature = "web")]
+        if request.method == "GET" && request.path == "/" {
+            return HttpResponse {
+                status: 200,
+                content_type: "text/html; charset=utf-8",
+                body: crate::web::INDEX_HTML.as_bytes().to_vec(),
+            };
+        }
+        if let Err(response) = self.check_token(request) {
+            return response;
+        }
+
+        let segments: Vec<&str> = request
+            .path
+            .trim_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+        let Some(decoded) = segments
+            .iter()
+            .map(|s| percent_decode(s))
+            .collect::<Option<Vec<String>>>()
+        else {
+            return HttpResponse::error(400, "invalid_request", "Malformed path");
+        };
+        let segments: Vec<&str> = decoded.iter().map(String::as_str).collect();
+        let body = || -> std::result::Result<Value, HttpResponse> {
+            if request.body.is_empty() {
+                return Ok(json!({}));
+            }
+            serde_json::from_slice(&request.body).map_err(|e| {
+                HttpResponse::error(400, "invalid_request", &format!("Invalid JSON body: {}", e))
+            })
+        };
+
+        let (method, params, created) = match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["sessions"]) => ("sessions", json!({}), false),
+            ("POST", ["sessions"]) => match body() {
+                Ok(params) => ("create", params, true),
+                Err(response) => return response,
+            },
+            ("DELETE", ["sessions", name]) => ("kill", json!({ "name": name }), false),
+            ("POST", ["sessions", name, "send-keys"]) => match body() {
+                Ok(mut params) => {
+                    if let Some(params) = params.as_object_mut() {
+                        params.insert("target".to_string(), json!(name));
+                    }
+                    ("send-keys", params, false)
+                }
+                Err(response) => return response,
+            },
+            (_, ["sessions"]) | (_, ["sessions", _]) | (_, ["sessions", _, "send-keys"]) => {
+                return HttpResponse::error(405, "invalid_request", "Method not allowed")
+            }
+            _ => return HttpResponse::error(404, "not_found", "No such endpoint"),
+        };
+
+        match self.daemon.call(method, params).await {
+            Ok(result) => HttpResponse::json(if created { 201 } else { 200 }, &result),
+            Err(error) => {
+                let error = error_json(&error);
+                HttpResponse::json(status_of(&error), &json!({ "error": error }))
+            }
+        }
+    }
+
+    /// Check the bearer token, if the server has one. Browsers cannot set headers
+    /// on a WebSocket, so the token may also come as the `token` query parameter.
+    fn check_token(&self, request: &HttpRequest) -> std::result::Result<(), HttpResponse> {
+        let Some(token) = &self.token else {
+            return Ok(());
+        };
+        let given = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::to_string)
+            .or_else(|| request.query_param("token"));
+        let matches =
+            given.is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()));
+        if !matches {
+            return Err(HttpResponse::error(
+                401,
+                "unauthorized",
+                "Missing or wrong bearer token",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Refuse requests that web pages make from the user's browser. Browsers send
+    /// their page's `Origin` along, which must be this server; and without a token, the
+    /// `Host` must be local, so a page cannot rebind its own domain to 127.0.0.1.
+    fn check_origin(&self, request: &HttpRequest) -> std::result::Result<(), &'static str> {
+        let host = request.header("host").unwrap_or_default();
+        if let Some(origin) = request.header("origin") {
+            let origin = origin
+                .strip_prefix("http://")
+                .or_else(|| origin.strip_prefix("https://"));
+            if origin != Some(host) {
+                return Err("Cross-origin requests are not allowed");
+            }
+        }
+        if self.token.is_none() && !host.is_empty() && !is_local_host(host) {
+            return Err("Requests without a token must be made to localhost");
+        }
+        Ok(())
+    }
+
+    /// Serve requests on `listener`, reloading the sessions every `interval`
+    pub async fn serve(self: Arc<Self>, listener: TcpListener, interval: Duration) -> Result<()> {
+        self.daemon.start_polling(interval).await?;
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            tokio::spawn(Arc::clone(&self).connection(stream, peer));
+        }
+    }
+
+    async fn connection(self: Arc<Self>, stream: TcpStream, peer: SocketAddr) {
+        let service = service_fn(move |request| {
+            let api = Arc::clone(&self);
+            async move { Ok::<_, Infallible>(api.answer(request, peer).await) }
+        });
+        let served = http1::Builder::new()
+            .timer(TokioTimer::new())
+            .header_read_timeout(REQUEST_TIMEOUT)
+            .max_headers(MAX_HEADERS)
+            .serve_connection(TokioIo::new(stream), service)
+            .with_upgrades()
+            .await;
+        if let Err(e) = served {
+            tracing::debug!("Connection of {} failed: {}", peer, e);
+        }
+    }
+
+    async fn answer(&self, request: Request<Incoming>, peer: SocketAddr) -> Response<Full<Bytes>> {
+        let mut parsed = HttpRequest {
+            method: request.method().to_string(),
+            path: request.uri().path().to_string(),
+            query: request.uri().query().unwrap_or_default().to_string(),
+            headers: request
+                .headers()
+                .iter()
+                .map(|(name, value)| {
+                    let value = String::from_utf8_lossy(value.as_bytes());
+                    (name.as_str().to_string(), value.into_owned())
+                })
+                .collect(),
+            body: Vec::new(),
+        };
+
+        #[cfg(feature = "web")]
+        if crate::web::is_upgrade(&parsed) {
+            tracing::debug!("{} opened the web socket", peer);
+            let authorized = self
+                .check_origin(&parsed)
+                .map_err(|message| HttpResponse::error(403, "forbidden", message))
+                .and_then(|()| self.check_token(&parsed));
+            return match authorized {
+                Ok(()) => {
+                    let mut request = request;
+                    crate::web::upgrade(Arc::clone(&self.daemon), &mut request)
+                }
+                Err(response) => response.into_response(),
+            };
+        }
+
+        // Refuse a declared length up front, rather than waiting for that much
+        if request.body().size_hint().lower() > MAX_BODY as u64 {
+            return HttpResponse::error(413, "invalid_request", "Request body too large")
+                .into_response();
+        }
+        // A client that never finishes its body would hold the connection forever
+        let body = Limited::new(request.into_body(), MAX_BODY).collect();
+        parsed.body = match tokio::time::timeout(REQUEST_TIMEOUT, body).await {
+            Ok(Ok(body)) => body.to_bytes().to_vec(),
+            Ok(Err(e)) if e.is::<LengthLimitError>() => {
+                return HttpResponse::error(413, "invalid_request", "Request body too large")
+                    .into_response()
+            }
+            Ok(Err(_)) => {
+                return HttpResponse::error(400, "invalid_request", "Truncated request body")
+                    .into_response()
+            }
+            Err(_) => {
+                return HttpResponse::error(408, "invalid_request", "Timed out reading the request")
+                    .into_response()
+            }
+        };
+        tracing::debug!("{} {} {}", peer, parsed.method, parsed.path);
+        self.route(&parsed).await.into_response()
+    }
+}
@@ -0,0 +1,146 @@
+//! Compound session ordering shared by the CLI `--sort` flag and the TUI
+
+use crate::tmux::TmuxSession;
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// A field sessions can be ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    /// Session name, A to Z
+    Name,
+    /// Attached sessions first
+    Attached,
+    /// Fewest windows first
+    Windows,
+    /// Fewest panes first
+    Panes,
+    /// Oldest first
+    Created,
+    /// Least recently active first
+    Activity,
+}
+
+impl SortField {
+    fn compare(self, a: &TmuxSession, b: &TmuxSession) -> Ordering {
+        match self {
+            SortField::Name => a.name.cmp(&b.name),
+            SortField::Attached => b.attached.cmp(&a.attached),
+            SortField::Windows => a.windows.cmp(&b.windows),
+            SortField::Panes => a.panes.cmp(&b.panes),
+            SortField::Created => a.created_at.cmp(&b.created_at),
+            SortField::Activity => a.last_activity.cmp(&b.last_activity),
+        }
+    }
+}
+
+/// One sort key: a field plus whether its natural order is reversed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl FromStr for SortKey {
+    type Err = anyhow::Error;
+
+    /// Parse `field`, `field:asc` or `field:desc`
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, direction) = match s.trim().split_once(':') {
+            Some((name, direction)) => (name, Some(direction)),
+            None => (s.trim(), None),
+        };
+
+        let field = match name.to_ascii_lowercase().as_str() {
+            "name" => SortField::Name,
+            "attached" => SortField::Attached,
+            "windows" => SortField::Windows,
+            "panes" => SortField::Panes,
+            "created" => SortField::Created,
+            "activity" => SortField::Activity,
+            other => anyhow::bail!(
+                "Unknown sort key '{}' (expected name, attached, windows, panes, created or activity)",
+                other
+            ),
+        };
+
+        let descending = match direction.map(|d| d.to_ascii_lowercase()) {
+            None => false,
+            Some(d) if d == "asc" => false,
+            Some(d) if d == "desc" => true,
+            Some(d) => anyhow::bail!("Unknown sort direction '{}' (expected asc or desc)", d),
+        };
+
+        Ok(Self { field, descending })
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.field {
+            SortField::Name => "name",
+            SortField::Attached => "attached",
+            SortField::Windows => "windows",
+            SortField::Panes => "panes",
+            SortField::Created => "created",
+            SortField::Activity => "activity",
+        };
+        if self.descending {
+            write!(f, "{}:desc", name)
+        } else {
+            write!(f, "{}", name)
+        }
+    }
+}
+
+/// An ordered list of sort keys; later keys break ties of earlier ones
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortSpec {
+    pub keys: Vec<SortKey>,
+}
+
+impl SortSpec {
+    /// Parse a list of keys such as `["attached", "activity:desc", "name"]`
+    pub fn from_keys<S: AsRef<str>>(keys: &[S]) -> Result<Self> {
+        let keys = keys
+            .iter()
+            .map(|k| k.as_ref().parse())
+            .collect::<Result<Vec<SortKey>>>()?;
+        Ok(Self { keys })
+    }
+
+    /// Compare two sessions by every key in turn
+    pub fn compare(&self, a: &TmuxSession, b: &TmuxSession) -> Ordering {
+        self.keys
+            .iter()
+            .map(|key| {
+                let ordering = key.field.compare(a, b);
+                if key.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Sort sessions in place; an empty spec keeps tmux's order
+    pub fn sort(&self, sessions: &mut [TmuxSession]) {
+        if !self.keys.is_empty() {
+            sessions.sort_by(|a, b| self.compare(a, b));
+        }
+    }
+}
+
+impl FromStr for SortSpec {
+    type Err = anyhow::Error;
+
+    /// Parse a comma-separated list such as `attached,activity:desc,name`
+    fn from_str(s: &str) -> Result<Self> {
+        let keys: Vec<&str> = s.split(',').filter(|k| !k.trim().is_empty()).collect();
+        Self::from_keys(&keys)
+    }
+}
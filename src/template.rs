@@ -0,0 +1,244 @@
+//! Project layouts (`tmux-ui start <template>`)
+//!
+//! A template describes the windows and panes of a session, with working
+//! directories and start commands. Templates are TOML files in
+//! `~/.config/tmux-ui/templates/<name>.toml`.
+
+use crate::config::Config;
+use crate::tmux::{validate_session_name, Error, Key, SplitDirection, TmuxClient};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A session layout
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    /// Session name; defaults to the template name
+    pub name: Option<String>,
+    /// Working directory of every window and pane without its own `cwd`
+    pub root: Option<String>,
+    pub windows: Vec<WindowTemplate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WindowTemplate {
+    pub name: Option<String>,
+    /// Working directory, relative to `root` unless absolute
+    pub cwd: Option<String>,
+    /// tmux layout applied after all panes are created, e.g. `main-vertical`
+    pub layout: Option<String>,
+    /// Panes in creation order; a window without panes gets a single shell
+    #[serde(default)]
+    pub panes: Vec<PaneTemplate>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PaneTemplate {
+    /// Command typed into the pane's shell once it is created
+    pub command: Option<String>,
+    /// Working directory, relative to the window's directory unless absolute
+    pub cwd: Option<String>,
+    /// `vertical` puts the pane below the previous one, `horizontal` to its right
+    /// (ignored for the first pane)
+    #[serde(default)]
+    pub split: SplitDirection,
+}
+
+impl Template {
+    /// Directory templates are read from, next to the config file
+    pub fn dir() -> Option<PathBuf> {
+        Config::default_path().and_then(|path| path.parent().map(|dir| dir.join("templates")))
+    }
+
+    /// Names of the available templates, sorted
+    pub fn list() -> Result<Vec<String>> {
+        let Some(dir) = Self::dir().filter(|dir| dir.is_dir()) else {
+            return Ok(Vec::new());
+        };
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read template directory {}", dir.display()))?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "toml" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_string_lossy().into_owned())
+            })
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Load a template by name, or from a path to a `.toml` file
+    pub fn load(name: &str) -> Result<Self> {
+        let path = if name.ends_with(".toml") || name.contains('/') {
+            PathBuf::from(name)
+        } else {
+            Self::dir()
+                .context("Cannot determine template directory")?
+                .join(format!("{}.toml", name))
+        };
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read template {}", path.display()))?;
+        let mut template = Self::parse(&contents)
+            .with_context(|| format!("Invalid template {}", path.display()))?;
+        template.name.get_or_insert(stem);
+        Ok(template)
+    }
+
+    /// Parse template file contents
+    pub fn parse(contents: &str) -> Result<Self> {
+        let template: Template = toml::from_str(contents)?;
+        if template.windows.is_empty() {
+            anyhow::bail!("Template has no windows");
+        }
+        Ok(template)
+    }
+
+    /// Create the session described by this template, returning its name
+    pub fn start(&self, client: &TmuxClient) -> Result<String> {
+        let name = validate_session_name(
+            self.name
+                .as_deref()
+                .context("Template has no session name")?,
+        )?;
+        if client.list_sessions()?.iter().any(|s| s.name == name) {
+            return Err(Error::DuplicateSession(name).into());
+        }
+
+        let root = self.root.as_deref().map(expand_home);
+        let mut session_id: Option<String> = None;
+        let mut first_window = None;
+
+        for (i, window) in self.windows.iter().enumerate() {
+            let window_dir = resolve(root.as_deref(), window.cwd.as_deref());
+            let default_pane = PaneTemplate::default();
+            let panes: Vec<&PaneTemplate> = if window.panes.is_empty() {
+                vec![&default_pane]
+            } else {
+                window.panes.iter().collect()
+            };
+            let first_dir = resolve(window_dir.as_deref(), panes[0].cwd.as_deref());
+
+            let mut args: Vec<String> = match &session_id {
+                None => vec!["new-session".into(), "-d".into(), "-s".into(), name.clone()],
+                Some(id) => vec![
+                    "new-window".into(),
+                    "-d".into(),
+                    "-t".into(),
+                    format!("{}:", id),
+                ],
+            };
+            if let Some(window_name) = &window.name {
+                args.extend(["-n".into(), window_name.clone()]);
+            }
+            push_cwd(&mut args, first_dir.as_deref());
+            args.extend([
+                "-P".into(),
+                "-F".into(),
+                "#{session_id} #{window_id} #{pane_id}".into(),
+            ]);
+            let created = run(client, &args)?;
+            let mut ids = created.split_whitespace().map(|s| s.to_string());
+            let (Some(id), Some(window_id), Some(first_pane)) =
+                (ids.next(), ids.next(), ids.next())
+            else {
+                anyhow::bail!("Unexpected tmux output '{}'", created);
+            };
+            session_id.get_or_insert(id);
+            if i == 0 {
+                first_window = Some(window_id.clone());
+            }
+
+            let mut pane_ids = vec![first_pane];
+            for pane in &panes[1..] {
+                let dir = resolve(window_dir.as_deref(), pane.cwd.as_deref());
+                let previous = pane_ids
+                    .last()
+                    .cloned()
+                    .unwrap_or_else(|| window_id.clone());
+                pane_ids.push(client.split_window(&previous, pane.split, None, dir.as_deref())?);
+            }
+
+            if let Some(layout) = &window.layout {
+                run(
+                    client,
+                    &[
+                        "select-layout".into(),
+                        "-t".into(),
+                        window_id.clone(),
+                        layout.clone(),
+                    ],
+                )?;
+            }
+            for (pane, pane_id) in panes.iter().zip(&pane_ids) {
+                if let Some(command) = &pane.command {
+                    client.send_keys(pane_id, &[Key::Literal(command.clone())], true)?;
+                }
+            }
+        }
+
+        if let Some(window_id) = first_window {
+            client.select_window(&window_id)?;
+        }
+        Ok(name)
+    }
+}
+
+/// Replace the home directory at the start of a path with `~`, for display
+pub(crate) fn collapse_home(path: &str) -> String {
+    let home = env::var("HOME").unwrap_or_default();
+    match path.strip_prefix(home.as_str()) {
+        Some(rest) if !home.is_empty() && (rest.is_empty() || rest.starts_with('/')) => {
+            format!("~{}", rest)
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Replace a leading `~` with the home directory
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), env::var_os("HOME")) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(home).join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// A directory relative to `base`, unless it is absolute (or starts with `~`)
+pub(crate) fn resolve(base: Option<&Path>, dir: Option<&str>) -> Option<PathBuf> {
+    match (base, dir) {
+        (_, Some(dir)) if dir.starts_with('~') || Path::new(dir).is_absolute() => {
+            Some(expand_home(dir))
+        }
+        (Some(base), Some(dir)) => Some(base.join(dir)),
+        (None, Some(dir)) => Some(PathBuf::from(dir)),
+        (base, None) => base.map(Path::to_path_buf),
+    }
+}
+
+pub(crate) fn push_cwd(args: &mut Vec<String>, dir: Option<&Path>) {
+    if let Some(dir) = dir {
+        args.extend(["-c".into(), dir.to_string_lossy().into_owned()]);
+    }
+}
+
+/// Run a tmux command and return its trimmed stdout
+pub(crate) fn run(client: &TmuxClient, args: &[String]) -> Result<String> {
+    let output = client.run_raw(args)?;
+    if !output.success() {
+        anyhow::bail!("tmux {} failed: {}", args[0], output.stderr.trim());
+    }
+    Ok(output.stdout.trim().to_string())
+}
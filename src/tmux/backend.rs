@@ -0,0 +1,466 @@
+//! The operations the TUI needs from tmux, behind a trait so the TUI can run
+//! against [`MockBackend`](super::mock::MockBackend) in tests instead of a live server.
+
+use super::{
+    AttachedClient, ControlMode, Error, HookScope, Key, OptionScope, PopupSize, RawOutput,
+    RestoreReport, Result, SessionDetails, SessionSnapshot, SessionTree, Snapshot, SplitDirection,
+    TmuxBuffer, TmuxClient, TmuxHook, TmuxOption, TmuxPane, TmuxSession, TmuxVariable, TmuxWindow,
+    WindowSnapshot,
+};
+use crate::config::ServerConfig;
+use crate::template::Template;
+use std::future::Future;
+use std::path::Path;
+
+/// A tmux server the TUI can manage. [`TmuxClient`] runs the real tmux binary.
+pub trait TmuxBackend: Clone + Send + Sync + 'static {
+    /// Backend for an additional server from the config file
+    fn for_server(&self, server: &ServerConfig) -> anyhow::Result<Self>;
+
+    /// Run backend calls on tokio's blocking thread pool.
+    ///
+    /// Every method of the backend may wait for a tmux process; async callers such as
+    /// the TUI go through here so a slow server (e.g. behind an SSH-forwarded
+    /// socket) does not stall the runtime.
+    fn blocking<T, E, F>(&self, f: F) -> impl Future<Output = Result<T, E>> + Send
+    where
+        F: FnOnce(&Self) -> Result<T, E> + Send + 'static,
+        T: Send + 'static,
+        E: From<Error> + Send + 'static,
+    {
+        let backend = self.clone();
+        async move {
+            tokio::task::spawn_blocking(move || f(&backend))
+                .await
+                .map_err(|e| Error::Io(e.into()))?
+        }
+    }
+
+    // Where tmux-ui runs
+    fn host(&self) -> Option<&str>;
+    fn is_inside_tmux(&self) -> bool;
+    fn is_inside_this_server(&self) -> bool;
+    fn get_current_session(&self) -> Result<Option<String>>;
+    fn control_mode(&self, session: &str) -> Result<ControlMode>;
+
+    // Sessions
+    fn list_sessions_with_columns(&self, columns: &[String]) -> Result<Vec<TmuxSession>>;
+    fn list_tree(&self) -> Result<Vec<SessionTree>>;
+    fn create_session(
+        &self,
+        name: &str,
+        start_directory: Option<&Path>,
+        command: Option<&str>,
+    ) -> Result<()>;
+    fn clone_session(&self, source: &str, new_name: &str, commands: bool) -> anyhow::Result<()>;
+    fn kill_session(&self, name: &str) -> Result<()>;
+    fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()>;
+    fn set_session_tags(&self, name: &str, tags: &[String]) -> Result<()>;
+    fn idle_sessions(&self) -> Result<Vec<TmuxSession>>;
+    fn running_commands(&self, session: &str) -> Result<Vec<String>>;
+    fn session_details(&self, session: &str) -> Result<SessionDetails>;
+    fn switch_client(&self, name: &str) -> Result<()>;
+    fn switch_client_last(&self) -> Result<()>;
+    fn attach_session(&self, name: &str) -> Result<()>;
+    fn attach_session_exclusive(&self, name: &str) -> Result<()>;
+    fn attach_in_new_terminal(&self, session: &str, terminal_cmd: &str) -> Result<()>;
+    fn attach_in_popup(&self, session: &str) -> Result<()>;
+    fn run_in_window(&self, session: &str, command: &str) -> Result<()>;
+    fn display_popup(&self, target: &str, command: Option<&str>, size: &PopupSize) -> Result<()>;
+    fn detach_current_client(&self) -> Result<()>;
+    fn detach_session(&self, name: &str) -> Result<()>;
+    fn detach_other_clients(&self, name: &str) -> Result<usize>;
+
+    // Clients
+    fn list_clients(&self) -> Result<Vec<AttachedClient>>;
+    fn detach_client(&self, client: &str) -> Result<()>;
+
+    // Windows
+    fn list_windows(&self, session: &str) -> Result<Vec<TmuxWindow>>;
+    fn create_window(&self, session: &str, name: Option<&str>) -> Result<()>;
+    fn kill_window(&self, target: &str) -> Result<()>;
+    fn rename_window(&self, target: &str, new_name: &str) -> Result<()>;
+    fn disable_automatic_rename(&self, target: &str) -> Result<()>;
+    fn set_window_option(&self, target: &str, option: &str, value: &str) -> Result<()>;
+    fn select_layout(&self, target: &str, layout: &str) -> Result<()>;
+    fn select_window(&self, target: &str) -> Result<()>;
+    fn swap_window(&self, source: &str, target: &str) -> Result<()>;
+    fn move_window(&self, window: &str, session: &str) -> Result<()>;
+
+    // Panes
+    fn list_panes(&self, target: &str) -> Result<Vec<TmuxPane>>;
+    fn select_pane(&self, target: &str) -> Result<()>;
+    fn toggle_zoom(&self, pane: &str) -> Result<()>;
+    fn kill_pane(&self, target: &str) -> Result<()>;
+    fn respawn_pane(&self, target: &str, command: Option<&str>) -> Result<()>;
+    fn pipe_pane(&self, target: &str, file: Option<&Path>) -> Result<()>;
+    fn split_window(
+        &self,
+        target: &str,
+        direction: SplitDirection,
+        percent: Option<u8>,
+        cwd: Option<&Path>,
+    ) -> Result<String>;
+    fn break_pane(&self, pane: &str) -> Result<String>;
+    fn join_pane(&self, pane: &str, window: &str, direction: SplitDirection) -> Result<()>;
+    fn swap_pane(&self, source: &str, target: &str) -> Result<()>;
+    fn link_window(&self, window: &str, session: &str) -> Result<()>;
+    fn unlink_window(&self, session: &str, window: &str) -> Result<()>;
+    fn rotate_window(&self, target: &str, reverse: bool) -> Result<()>;
+    fn capture_pane(&self, target: &str) -> Result<String>;
+    fn send_keys(&self, target: &str, keys: &[Key], enter: bool) -> Result<()>;
+
+    // Paste buffers
+    fn list_buffers(&self) -> Result<Vec<TmuxBuffer>>;
+    fn show_buffer(&self, name: &str) -> Result<String>;
+    fn set_buffer(&self, name: Option<&str>, data: &str) -> Result<()>;
+    fn paste_buffer(&self, name: &str, target: &str) -> Result<()>;
+    fn delete_buffer(&self, name: &str) -> Result<()>;
+
+    // Options
+    fn show_options(&self, scope: &OptionScope) -> Result<Vec<TmuxOption>>;
+    fn set_option(&self, scope: &OptionScope, name: &str, value: &str) -> Result<()>;
+    fn unset_option(&self, scope: &OptionScope, name: &str) -> Result<()>;
+
+    // Environment, of a session or the global one when `session` is `None`
+    fn show_environment(&self, session: Option<&str>) -> Result<Vec<TmuxVariable>>;
+    fn set_environment(&self, session: Option<&str>, name: &str, value: &str) -> Result<()>;
+    fn unset_environment(&self, session: Option<&str>, name: &str) -> Result<()>;
+
+    // Hooks
+    fn show_hooks(&self, scope: &HookScope) -> Result<Vec<TmuxHook>>;
+    fn set_hook(&self, scope: &HookScope, hook: &str, command: &str, append: bool) -> Result<()>;
+    fn unset_hook(&self, scope: &HookScope, hook: &str) -> Result<()>;
+
+    // Everything else
+    fn display_message(&self, target: Option<&str>, format: &str) -> Result<String>;
+    fn run_raw(&self, args: &[String]) -> Result<RawOutput>;
+    fn capture_snapshot(&self) -> anyhow::Result<Snapshot>;
+    fn restore_snapshot(&self, snapshot: &Snapshot) -> anyhow::Result<RestoreReport>;
+    fn snapshot_session(&self, session: &str) -> anyhow::Result<SessionSnapshot>;
+    fn snapshot_window(&self, window: &str) -> anyhow::Result<SessionSnapshot>;
+    fn restore_session(&self, session: &SessionSnapshot) -> anyhow::Result<()>;
+    fn restore_window(&self, session: &str, window: &WindowSnapshot) -> anyhow::Result<()>;
+    fn start_template(&self, template: &Template) -> anyhow::Result<String>;
+}
+
+impl TmuxBackend for TmuxClient {
+    fn for_server(&self, server: &ServerConfig) -> anyhow::Result<Self> {
+        server.client()
+    }
+
+    fn host(&self) -> Option<&str> {
+        TmuxClient::host(self)
+    }
+
+    fn is_inside_tmux(&self) -> bool {
+        TmuxClient::is_inside_tmux(self)
+    }
+
+    fn is_inside_this_server(&self) -> bool {
+        TmuxClient::is_inside_this_server(self)
+    }
+
+    fn get_current_session(&self) -> Result<Option<String>> {
+        TmuxClient::get_current_session(self)
+    }
+
+    fn control_mode(&self, session: &str) -> Result<ControlMode> {
+        ControlMode::spawn(self, session)
+    }
+
+    fn list_sessions_with_columns(&self, columns: &[String]) -> Result<Vec<TmuxSession>> {
+        TmuxClient::list_sessions_with_columns(self, columns)
+    }
+
+    fn list_tree(&self) -> Result<Vec<SessionTree>> {
+        TmuxClient::list_tree(self)
+    }
+
+    fn create_session(
+        &self,
+        name: &str,
+        start_directory: Option<&Path>,
+        command: Option<&str>,
+    ) -> Result<()> {
+        TmuxClient::create_session(self, name, start_directory, command)
+    }
+
+    fn clone_session(&self, source: &str, new_name: &str, commands: bool) -> anyhow::Result<()> {
+        TmuxClient::clone_session(self, source, new_name, commands)
+    }
+
+    fn kill_session(&self, name: &str) -> Result<()> {
+        TmuxClient::kill_session(self, name)
+    }
+
+    fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()> {
+        TmuxClient::rename_session(self, old_name, new_name)
+    }
+
+    fn set_session_tags(&self, name: &str, tags: &[String]) -> Result<()> {
+        TmuxClient::set_session_tags(self, name, tags)
+    }
+
+    fn idle_sessions(&self) -> Result<Vec<TmuxSession>> {
+        TmuxClient::idle_sessions(self)
+    }
+
+    fn running_commands(&self, session: &str) -> Result<Vec<String>> {
+        TmuxClient::running_commands(self, session)
+    }
+
+    fn session_details(&self, session: &str) -> Result<SessionDetails> {
+        TmuxClient::session_details(self, session)
+    }
+
+    fn switch_client(&self, name: &str) -> Result<()> {
+        TmuxClient::switch_client(self, name)
+    }
+
+    fn switch_client_last(&self) -> Result<()> {
+        TmuxClient::switch_client_last(self)
+    }
+
+    fn attach_session(&self, name: &str) -> Result<()> {
+        TmuxClient::attach_session(self, name)
+    }
+
+    fn attach_session_exclusive(&self, name: &str) -> Result<()> {
+        TmuxClient::attach_session_exclusive(self, name)
+    }
+
+    fn attach_in_new_terminal(&self, session: &str, terminal_cmd: &str) -> Result<()> {
+        TmuxClient::attach_in_new_terminal(self, session, terminal_cmd)
+    }
+
+    fn attach_in_popup(&self, session: &str) -> Result<()> {
+        TmuxClient::attach_in_popup(self, session)
+    }
+
+    fn run_in_window(&self, session: &str, command: &str) -> Result<()> {
+        TmuxClient::run_in_window(self, session, command)
+    }
+
+    fn display_popup(&self, target: &str, command: Option<&str>, size: &PopupSize) -> Result<()> {
+        TmuxClient::display_popup(self, target, command, size)
+    }
+
+    fn detach_current_client(&self) -> Result<()> {
+        TmuxClient::detach_current_client(self)
+    }
+
+    fn detach_session(&self, name: &str) -> Result<()> {
+        TmuxClient::detach_session(self, name)
+    }
+
+    fn detach_other_clients(&self, name: &str) -> Result<usize> {
+        TmuxClient::detach_other_clients(self, name)
+    }
+
+    fn list_clients(&self) -> Result<Vec<AttachedClient>> {
+        TmuxClient::list_clients(self)
+    }
+
+    fn detach_client(&self, client: &str) -> Result<()> {
+        TmuxClient::detach_client(self, client)
+    }
+
+    fn list_windows(&self, session: &str) -> Result<Vec<TmuxWindow>> {
+        TmuxClient::list_windows(self, session)
+    }
+
+    fn create_window(&self, session: &str, name: Option<&str>) -> Result<()> {
+        TmuxClient::create_window(self, session, name)
+    }
+
+    fn kill_window(&self, target: &str) -> Result<()> {
+        TmuxClient::kill_window(self, target)
+    }
+
+    fn rename_window(&self, target: &str, new_name: &str) -> Result<()> {
+        TmuxClient::rename_window(self, target, new_name)
+    }
+
+    fn disable_automatic_rename(&self, target: &str) -> Result<()> {
+        TmuxClient::disable_automatic_rename(self, target)
+    }
+
+    fn select_layout(&self, target: &str, layout: &str) -> Result<()> {
+        TmuxClient::select_layout(self, target, layout)
+    }
+
+    fn set_window_option(&self, target: &str, option: &str, value: &str) -> Result<()> {
+        TmuxClient::set_window_option(self, target, option, value)
+    }
+
+    fn select_window(&self, target: &str) -> Result<()> {
+        TmuxClient::select_window(self, target)
+    }
+
+    fn swap_window(&self, source: &str, target: &str) -> Result<()> {
+        TmuxClient::swap_window(self, source, target)
+    }
+
+    fn move_window(&self, window: &str, session: &str) -> Result<()> {
+        TmuxClient::move_window(self, window, session)
+    }
+
+    fn list_panes(&self, target: &str) -> Result<Vec<TmuxPane>> {
+        TmuxClient::list_panes(self, target)
+    }
+
+    fn select_pane(&self, target: &str) -> Result<()> {
+        TmuxClient::select_pane(self, target)
+    }
+
+    fn toggle_zoom(&self, pane: &str) -> Result<()> {
+        TmuxClient::toggle_zoom(self, pane)
+    }
+
+    fn pipe_pane(&self, target: &str, file: Option<&Path>) -> Result<()> {
+        TmuxClient::pipe_pane(self, target, file)
+    }
+
+    fn respawn_pane(&self, target: &str, command: Option<&str>) -> Result<()> {
+        TmuxClient::respawn_pane(self, target, command)
+    }
+
+    fn kill_pane(&self, target: &str) -> Result<()> {
+        TmuxClient::kill_pane(self, target)
+    }
+
+    fn split_window(
+        &self,
+        target: &str,
+        direction: SplitDirection,
+        percent: Option<u8>,
+        cwd: Option<&Path>,
+    ) -> Result<String> {
+        TmuxClient::split_window(self, target, direction, percent, cwd)
+    }
+
+    fn break_pane(&self, pane: &str) -> Result<String> {
+        TmuxClient::break_pane(self, pane)
+    }
+
+    fn link_window(&self, window: &str, session: &str) -> Result<()> {
+        TmuxClient::link_window(self, window, session)
+    }
+
+    fn unlink_window(&self, session: &str, window: &str) -> Result<()> {
+        TmuxClient::unlink_window(self, session, window)
+    }
+
+    fn swap_pane(&self, source: &str, target: &str) -> Result<()> {
+        TmuxClient::swap_pane(self, source, target)
+    }
+
+    fn rotate_window(&self, target: &str, reverse: bool) -> Result<()> {
+        TmuxClient::rotate_window(self, target, reverse)
+    }
+
+    fn join_pane(&self, pane: &str, window: &str, direction: SplitDirection) -> Result<()> {
+        TmuxClient::join_pane(self, pane, window, direction)
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String> {
+        TmuxClient::capture_pane(self, target)
+    }
+
+    fn send_keys(&self, target: &str, keys: &[Key], enter: bool) -> Result<()> {
+        TmuxClient::send_keys(self, target, keys, enter)
+    }
+
+    fn list_buffers(&self) -> Result<Vec<TmuxBuffer>> {
+        TmuxClient::list_buffers(self)
+    }
+
+    fn show_buffer(&self, name: &str) -> Result<String> {
+        TmuxClient::show_buffer(self, name)
+    }
+
+    fn set_buffer(&self, name: Option<&str>, data: &str) -> Result<()> {
+        TmuxClient::set_buffer(self, name, data)
+    }
+
+    fn paste_buffer(&self, name: &str, target: &str) -> Result<()> {
+        TmuxClient::paste_buffer(self, name, target)
+    }
+
+    fn delete_buffer(&self, name: &str) -> Result<()> {
+        TmuxClient::delete_buffer(self, name)
+    }
+
+    fn show_options(&self, scope: &OptionScope) -> Result<Vec<TmuxOption>> {
+        TmuxClient::show_options(self, scope)
+    }
+
+    fn set_option(&self, scope: &OptionScope, name: &str, value: &str) -> Result<()> {
+        TmuxClient::set_option(self, scope, name, value)
+    }
+
+    fn unset_option(&self, scope: &OptionScope, name: &str) -> Result<()> {
+        TmuxClient::unset_option(self, scope, name)
+    }
+
+    fn show_environment(&self, session: Option<&str>) -> Result<Vec<TmuxVariable>> {
+        TmuxClient::show_environment(self, session)
+    }
+
+    fn set_environment(&self, session: Option<&str>, name: &str, value: &str) -> Result<()> {
+        TmuxClient::set_environment(self, session, name, value)
+    }
+
+    fn unset_environment(&self, session: Option<&str>, name: &str) -> Result<()> {
+        TmuxClient::unset_environment(self, session, name)
+    }
+
+    fn show_hooks(&self, scope: &HookScope) -> Result<Vec<TmuxHook>> {
+        TmuxClient::show_hooks(self, scope)
+    }
+
+    fn set_hook(&self, scope: &HookScope, hook: &str, command: &str, append: bool) -> Result<()> {
+        TmuxClient::set_hook(self, scope, hook, command, append)
+    }
+
+    fn unset_hook(&self, scope: &HookScope, hook: &str) -> Result<()> {
+        TmuxClient::unset_hook(self, scope, hook)
+    }
+
+    fn display_message(&self, target: Option<&str>, format: &str) -> Result<String> {
+        TmuxClient::display_message(self, target, format)
+    }
+
+    fn run_raw(&self, args: &[String]) -> Result<RawOutput> {
+        TmuxClient::run_raw(self, args)
+    }
+
+    fn capture_snapshot(&self) -> anyhow::Result<Snapshot> {
+        Snapshot::capture(self)
+    }
+
+    fn restore_snapshot(&self, snapshot: &Snapshot) -> anyhow::Result<RestoreReport> {
+        snapshot.restore(self)
+    }
+
+    fn snapshot_session(&self, session: &str) -> anyhow::Result<SessionSnapshot> {
+        TmuxClient::snapshot_session(self, session)
+    }
+
+    fn snapshot_window(&self, window: &str) -> anyhow::Result<SessionSnapshot> {
+        TmuxClient::snapshot_window(self, window)
+    }
+
+    fn restore_session(&self, session: &SessionSnapshot) -> anyhow::Result<()> {
+        TmuxClient::restore_session(self, session)
+    }
+
+    fn restore_window(&self, session: &str, window: &WindowSnapshot) -> anyhow::Result<()> {
+        TmuxClient::restore_window(self, session, window)
+    }
+
+    fn start_template(&self, template: &Template) -> anyhow::Result<String> {
+        template.start(self)
+    }
+}
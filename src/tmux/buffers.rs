@@ -0,0 +1,117 @@
+//! Paste buffers: the text tmux keeps from copy mode, `set-buffer` and `load-buffer`
+
+use super::{is_no_server_error, Error, Result, TmuxClient, COLUMN_SEPARATOR};
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TmuxBuffer {
+    /// Buffer name, e.g. `buffer0` for buffers tmux named itself
+    pub name: String,
+    /// Size of the content in bytes
+    pub size: usize,
+    /// Start of the content, with line breaks and other control characters escaped
+    pub sample: String,
+}
+
+impl TmuxClient {
+    /// List the paste buffers, most recent first
+    pub fn list_buffers(&self) -> Result<Vec<TmuxBuffer>> {
+        let format = ["#{buffer_name}", "#{buffer_size}", "#{buffer_sample}"]
+            .join(&COLUMN_SEPARATOR.to_string());
+        let output = self
+            .command()
+            .args(["list-buffers", "-F", &format])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if is_no_server_error(&stderr) {
+                return Ok(Vec::new());
+            }
+            return Err(Error::from_stderr("Failed to list buffers", &stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, COLUMN_SEPARATOR);
+                Some(TmuxBuffer {
+                    name: parts.next()?.to_string(),
+                    size: parts.next()?.parse().unwrap_or(0),
+                    sample: parts.next().unwrap_or_default().to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Full content of a buffer
+    pub fn show_buffer(&self, name: &str) -> Result<String> {
+        let output = self.command().args(["show-buffer", "-b", name]).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to show buffer {}", name),
+                &stderr,
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Store text in a buffer, replacing its content, or in a new buffer that tmux
+    /// names when `name` is `None`
+    pub fn set_buffer(&self, name: Option<&str>, data: &str) -> Result<()> {
+        let mut args = vec!["set-buffer"];
+        if let Some(name) = name {
+            args.extend(["-b", name]);
+        }
+        args.extend(["--", data]);
+
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("Failed to set buffer", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Paste a buffer into a pane (or the active pane of a session or window).
+    /// Programs that asked for bracketed paste get the text as one paste.
+    pub fn paste_buffer(&self, name: &str, target: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["paste-buffer", "-p", "-b", name, "-t", target])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to paste buffer {} into {}", name, target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn delete_buffer(&self, name: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["delete-buffer", "-b", name])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to delete buffer {}", name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+}
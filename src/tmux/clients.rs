@@ -0,0 +1,102 @@
+//! Clients attached to the server: terminals showing a session, and control-mode clients
+
+use super::{is_no_server_error, Error, Result, TmuxClient, COLUMN_SEPARATOR};
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AttachedClient {
+    /// Client name, which is its tty for terminals, e.g. `/dev/pts/3`
+    pub name: String,
+    pub tty: String,
+    /// Process id of the `tmux attach` process
+    pub pid: u32,
+    pub width: u16,
+    pub height: u16,
+    /// Id and name of the session the client shows
+    pub session_id: String,
+    pub session: String,
+    /// Unix time of the last key press or other input from the client
+    pub activity: u64,
+    /// `TERM` of the terminal the client runs in
+    pub terminal: String,
+    pub control_mode: bool,
+    pub readonly: bool,
+}
+
+const CLIENT_FORMAT: [&str; 11] = [
+    "#{client_name}",
+    "#{client_tty}",
+    "#{client_pid}",
+    "#{client_width}",
+    "#{client_height}",
+    "#{session_id}",
+    "#{session_name}",
+    "#{client_activity}",
+    "#{client_termname}",
+    "#{client_control_mode}",
+    "#{client_readonly}",
+];
+
+/// Parse a line of `list-clients` printed with [`CLIENT_FORMAT`]
+fn parse_client_line(line: &str) -> Option<AttachedClient> {
+    let fields: Vec<&str> = line.split(COLUMN_SEPARATOR).collect();
+    let [name, tty, pid, width, height, session_id, session, activity, terminal, control, readonly] =
+        fields[..]
+    else {
+        return None;
+    };
+    Some(AttachedClient {
+        name: name.to_string(),
+        tty: tty.to_string(),
+        pid: pid.parse().unwrap_or(0),
+        width: width.parse().unwrap_or(0),
+        height: height.parse().unwrap_or(0),
+        session_id: session_id.to_string(),
+        session: session.to_string(),
+        activity: activity.parse().unwrap_or(0),
+        terminal: terminal.to_string(),
+        control_mode: control == "1",
+        readonly: readonly == "1",
+    })
+}
+
+impl TmuxClient {
+    /// All clients attached to the server; none when the server is not running
+    pub fn list_clients(&self) -> Result<Vec<AttachedClient>> {
+        let format = CLIENT_FORMAT.join(&COLUMN_SEPARATOR.to_string());
+        let output = self
+            .command()
+            .args(["list-clients", "-F", &format])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if is_no_server_error(&stderr) {
+                return Ok(Vec::new());
+            }
+            return Err(Error::from_stderr("Failed to list clients", &stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().filter_map(parse_client_line).collect())
+    }
+
+    /// Detach one client, given by name (e.g. `/dev/pts/3`), leaving the other
+    /// clients of its session attached
+    pub fn detach_client(&self, client: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["detach-client", "-t", client])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to detach client {}", client),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+}
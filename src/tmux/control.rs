@@ -0,0 +1,189 @@
+//! Long-lived control-mode client (`tmux -C`) that reports changes on the server
+//! as they happen, so the UI does not have to poll for them.
+
+use super::{Error, Result, TmuxClient};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Child;
+use tokio::sync::mpsc::{self, error::TryRecvError, UnboundedReceiver};
+
+/// A change reported by tmux in control mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    /// A session was created or destroyed
+    SessionsChanged,
+    /// The control client itself was moved to another session
+    SessionChanged {
+        session_id: String,
+        name: String,
+    },
+    SessionRenamed {
+        session_id: String,
+        name: String,
+    },
+    /// The current window of a session changed
+    SessionWindowChanged {
+        session_id: String,
+        window_id: String,
+    },
+    /// A window was added, in the control client's session or elsewhere
+    WindowAdd(String),
+    /// A window was closed, in the control client's session or elsewhere
+    WindowClose(String),
+    WindowRenamed {
+        window_id: String,
+        name: String,
+    },
+    /// The active pane of a window changed
+    WindowPaneChanged {
+        window_id: String,
+        pane_id: String,
+    },
+    /// Panes of a window were split, resized or exited
+    LayoutChange(String),
+    /// The control client exited, e.g. because its last session was killed
+    Exit,
+}
+
+impl Notification {
+    /// Parse a notification line; command output and unrelated notifications
+    /// (such as `%output`) yield `None`
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(3, ' ');
+        let kind = parts.next()?;
+        let mut arg = || parts.next().map(|s| s.to_string());
+
+        Some(match kind {
+            "%sessions-changed" => Notification::SessionsChanged,
+            "%session-changed" => Notification::SessionChanged {
+                session_id: arg()?,
+                name: arg()?,
+            },
+            "%session-renamed" => Notification::SessionRenamed {
+                session_id: arg()?,
+                name: arg()?,
+            },
+            "%session-window-changed" => Notification::SessionWindowChanged {
+                session_id: arg()?,
+                window_id: arg()?,
+            },
+            "%window-add" | "%unlinked-window-add" => Notification::WindowAdd(arg()?),
+            "%window-close" | "%unlinked-window-close" => Notification::WindowClose(arg()?),
+            "%window-renamed" | "%unlinked-window-renamed" => Notification::WindowRenamed {
+                window_id: arg()?,
+                name: arg()?,
+            },
+            "%window-pane-changed" => Notification::WindowPaneChanged {
+                window_id: arg()?,
+                pane_id: arg()?,
+            },
+            "%layout-change" => Notification::LayoutChange(arg()?),
+            "%exit" => Notification::Exit,
+            _ => return None,
+        })
+    }
+}
+
+/// A running `tmux -C` client attached to a session of the server.
+///
+/// The client is attached read-only, without output and without affecting the
+/// session size. It does count as an attached client of its session, see
+/// [`ControlMode::session_id`]. The subprocess is killed when this is dropped.
+pub struct ControlMode {
+    child: Child,
+    notifications: UnboundedReceiver<Notification>,
+    session_id: Option<String>,
+}
+
+impl ControlMode {
+    /// Start a control-mode client attached to `session` on the client's server
+    pub fn spawn(client: &TmuxClient, session: &str) -> Result<Self> {
+        let mut command = tokio::process::Command::from(
+            client
+                .command()
+                .args([
+                    "-C",
+                    "attach-session",
+                    "-t",
+                    session,
+                    "-f",
+                    "read-only,ignore-size,no-output",
+                ])
+                .build(),
+        );
+        command
+            // tmux exits when stdin is closed, so keep it open for the client's lifetime
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| Error::spawn(e, client.host().is_some()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| Error::Tmux("Failed to read tmux control mode output".to_string()))?;
+
+        let (tx, notifications) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Some(notification) = Notification::parse(&line) {
+                    if tx.send(notification).is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = tx.send(Notification::Exit);
+        });
+
+        Ok(Self {
+            child,
+            notifications,
+            session_id: None,
+        })
+    }
+
+    /// Id of the session the control client is attached to, once tmux reported it.
+    /// That session's attached client count includes the control client.
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// Process id of the control client, which `list-clients` reports as its `client_pid`
+    pub fn pid(&self) -> Option<u32> {
+        self.child.id()
+    }
+
+    /// Take the next pending notification without waiting.
+    /// Returns `Notification::Exit` once the control client is gone.
+    pub fn try_next(&mut self) -> Option<Notification> {
+        match self.notifications.try_recv() {
+            Ok(notification) => Some(self.observe(notification)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Notification::Exit),
+        }
+    }
+
+    /// Wait for the next notification; `None` once the control client is gone
+    pub async fn next(&mut self) -> Option<Notification> {
+        let notification = self.notifications.recv().await?;
+        Some(self.observe(notification))
+    }
+
+    /// Keep track of the session the control client is attached to
+    fn observe(&mut self, notification: Notification) -> Notification {
+        if let Notification::SessionChanged { session_id, .. } = &notification {
+            self.session_id = Some(session_id.clone());
+        }
+        notification
+    }
+}
+
+impl Drop for ControlMode {
+    fn drop(&mut self) {
+        let _ = self.child.start_kill();
+    }
+}
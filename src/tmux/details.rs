@@ -0,0 +1,67 @@
+//! Everything about one session that the session list has no room for
+
+use super::{session_target, Error, Result, TmuxClient, COLUMN_SEPARATOR};
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionDetails {
+    /// Size of the session's active window
+    pub width: u16,
+    pub height: u16,
+    /// Name of the group of sessions sharing windows with this one, if any
+    pub group: Option<String>,
+    /// Number of sessions in that group, this one included
+    pub group_size: usize,
+    pub window_index: usize,
+    pub window_name: String,
+    /// Index of the active pane of the active window
+    pub pane_index: usize,
+    pub pane_command: String,
+}
+
+const DETAILS_FORMAT: [&str; 9] = [
+    "#{session_id}",
+    "#{window_width}",
+    "#{window_height}",
+    "#{session_group}",
+    "#{session_group_size}",
+    "#{window_index}",
+    "#{window_name}",
+    "#{pane_index}",
+    "#{pane_current_command}",
+];
+
+/// Parse the output of `display-message` printed with [`DETAILS_FORMAT`]
+fn parse_details_line(line: &str) -> Option<SessionDetails> {
+    let fields: Vec<&str> = line.split(COLUMN_SEPARATOR).collect();
+    let [id, width, height, group, group_size, window_index, window_name, pane_index, pane_command] =
+        fields[..]
+    else {
+        return None;
+    };
+    // tmux prints empty fields rather than failing when the target does not exist
+    if id.is_empty() {
+        return None;
+    }
+    Some(SessionDetails {
+        width: width.parse().unwrap_or(0),
+        height: height.parse().unwrap_or(0),
+        group: (!group.is_empty()).then(|| group.to_string()),
+        group_size: group_size.parse().unwrap_or(0),
+        window_index: window_index.parse().unwrap_or(0),
+        window_name: window_name.to_string(),
+        pane_index: pane_index.parse().unwrap_or(0),
+        pane_command: pane_command.to_string(),
+    })
+}
+
+impl TmuxClient {
+    /// Size, group and active window and pane of a session
+    pub fn session_details(&self, session: &str) -> Result<SessionDetails> {
+        let format = DETAILS_FORMAT.join(&COLUMN_SEPARATOR.to_string());
+        // A bare `=name` is not a pane target, so name the session's active window
+        let target = format!("{}:", session_target(session));
+        let line = self.display_message(Some(&target), &format)?;
+        parse_details_line(&line).ok_or_else(|| Error::SessionNotFound(session.to_string()))
+    }
+}
@@ -0,0 +1,98 @@
+//! Environment variables of a session, and the global environment every session inherits
+
+use super::{Error, Result, TmuxClient};
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TmuxVariable {
+    pub name: String,
+    /// `None` when the variable is removed from the environment of new panes,
+    /// which `show-environment` lists as `-NAME`
+    pub value: Option<String>,
+}
+
+/// Parse a line of `show-environment`, e.g. `SSH_AUTH_SOCK=/tmp/ssh-x/agent.1` or `-DISPLAY`
+pub fn parse_environment_line(line: &str) -> Option<TmuxVariable> {
+    if let Some(name) = line.strip_prefix('-') {
+        return (!name.is_empty()).then(|| TmuxVariable {
+            name: name.to_string(),
+            value: None,
+        });
+    }
+    let (name, value) = line.split_once('=')?;
+    (!name.is_empty()).then(|| TmuxVariable {
+        name: name.to_string(),
+        value: Some(value.to_string()),
+    })
+}
+
+/// Flags selecting a session's environment, or the global one when `session` is `None`
+fn scope_args(session: Option<&str>) -> Vec<&str> {
+    match session {
+        Some(target) => vec!["-t", target],
+        None => vec!["-g"],
+    }
+}
+
+fn scope_name(session: Option<&str>) -> String {
+    match session {
+        Some(target) => format!("session {}", target),
+        None => "the global environment".to_string(),
+    }
+}
+
+impl TmuxClient {
+    /// Variables of a session's environment, or of the global one when `session` is `None`
+    pub fn show_environment(&self, session: Option<&str>) -> Result<Vec<TmuxVariable>> {
+        let mut args = vec!["show-environment"];
+        args.extend(scope_args(session));
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to show environment of {}", scope_name(session)),
+                &stderr,
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().filter_map(parse_environment_line).collect())
+    }
+
+    /// Set a variable; panes created afterwards get the new value, running ones keep theirs
+    pub fn set_environment(&self, session: Option<&str>, name: &str, value: &str) -> Result<()> {
+        let mut args = vec!["set-environment"];
+        args.extend(scope_args(session));
+        args.extend(["--", name, value]);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to set {}", name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a variable, so a session's panes see the global value again
+    pub fn unset_environment(&self, session: Option<&str>, name: &str) -> Result<()> {
+        let mut args = vec!["set-environment", "-u"];
+        args.extend(scope_args(session));
+        args.extend(["--", name]);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to unset {}", name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+}
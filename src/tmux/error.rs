@@ -0,0 +1,109 @@
+//! Errors of tmux calls, with the failures callers act on as their own variants
+
+use super::TmuxVersion;
+use std::fmt;
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The session a command targets does not exist (any more)
+    #[error("session not found: {0}")]
+    SessionNotFound(String),
+    /// A session with the name already exists
+    #[error("duplicate session: {0}")]
+    DuplicateSession(String),
+    #[error("tmux is not installed or not on the PATH; install it with your package manager")]
+    TmuxNotInstalled,
+    /// The tmux binary is older than `min_tmux_version` of the config file
+    #[error("tmux {found} is too old; tmux-ui needs tmux {required} or newer")]
+    TmuxTooOld {
+        found: TmuxVersion,
+        required: TmuxVersion,
+    },
+    #[error("no tmux server is running")]
+    ServerNotRunning,
+    /// Input tmux-ui could not make sense of, e.g. a key sequence or tmux output
+    #[error("{0}")]
+    Parse(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Any other failure of a tmux command, with tmux's message
+    #[error("{0}")]
+    Tmux(String),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    /// The error of a tmux command that failed with `stderr`. Failures with their own
+    /// variant are recognized from tmux's message; others keep it after `context`,
+    /// e.g. `Failed to kill window @3: can't find window: @3`.
+    pub(crate) fn from_stderr(context: impl fmt::Display, stderr: &str) -> Self {
+        let stderr = stderr.trim();
+        for line in stderr.lines() {
+            if let Some(name) = line
+                .strip_prefix("can't find session: ")
+                .or_else(|| line.strip_prefix("session not found: "))
+            {
+                return Error::SessionNotFound(name.to_string());
+            }
+            if let Some(name) = line.strip_prefix("duplicate session: ") {
+                return Error::DuplicateSession(name.to_string());
+            }
+        }
+        if is_no_server_error(stderr) {
+            return Error::ServerNotRunning;
+        }
+        let context = context.to_string();
+        if context.is_empty() {
+            Error::Tmux(stderr.to_string())
+        } else {
+            Error::Tmux(format!("{}: {}", context, stderr))
+        }
+    }
+
+    /// Exit code of the CLI when a subcommand fails with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::SessionNotFound(_) => 2,
+            Error::DuplicateSession(_) => 3,
+            Error::ServerNotRunning => 4,
+            Error::TmuxTooOld { .. } => 5,
+            // EX_DATAERR and EX_IOERR of sysexits.h
+            Error::Parse(_) => 65,
+            Error::Io(_) => 74,
+            // What shells return for a command that is not found
+            Error::TmuxNotInstalled => 127,
+            Error::Tmux(_) => 1,
+        }
+    }
+
+    /// Short name of the kind of error, for machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::SessionNotFound(_) => "session_not_found",
+            Error::DuplicateSession(_) => "duplicate_session",
+            Error::ServerNotRunning => "server_not_running",
+            Error::TmuxTooOld { .. } => "tmux_too_old",
+            Error::Parse(_) => "invalid_input",
+            Error::Io(_) => "io",
+            Error::TmuxNotInstalled => "tmux_not_installed",
+            Error::Tmux(_) => "tmux",
+        }
+    }
+
+    /// The error of a tmux (or ssh) process that could not be started
+    pub(crate) fn spawn(error: io::Error, remote: bool) -> Self {
+        if error.kind() == io::ErrorKind::NotFound && !remote {
+            Error::TmuxNotInstalled
+        } else {
+            Error::Io(error)
+        }
+    }
+}
+
+/// Whether tmux failed only because no server is running, which simply means no sessions
+pub(crate) fn is_no_server_error(stderr: &str) -> bool {
+    stderr.contains("no server running") || stderr.contains("error connecting to")
+}
@@ -0,0 +1,167 @@
+//! tmux hooks: commands run when something happens, e.g. `session-created` or
+//! `alert-activity`. Each hook is an array, so several commands can run for one event.
+
+use super::{Error, Result, TmuxClient};
+use serde::Serialize;
+use std::fmt;
+
+/// Where a hook is set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookScope {
+    /// Global hooks, which apply to every session and window
+    Global,
+    /// Hooks of one session, run in addition to the global ones
+    Session(String),
+}
+
+impl HookScope {
+    fn args(&self) -> Vec<&str> {
+        match self {
+            HookScope::Global => vec!["-g"],
+            HookScope::Session(target) => vec!["-t", target],
+        }
+    }
+}
+
+impl fmt::Display for HookScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookScope::Global => write!(f, "global"),
+            HookScope::Session(target) => write!(f, "session {}", target),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TmuxHook {
+    /// Hook name, e.g. `pane-exited`
+    pub name: String,
+    /// Position in the hook's array of commands
+    pub index: usize,
+    /// Command as tmux prints it, e.g. `display-message "hi there"`
+    pub command: String,
+}
+
+impl TmuxHook {
+    /// Name with index, e.g. `pane-exited[1]`, which selects this command for `set-hook`
+    pub fn target(&self) -> String {
+        format!("{}[{}]", self.name, self.index)
+    }
+}
+
+/// A ready-made hook that can be added without writing the command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HookTemplate {
+    pub label: &'static str,
+    pub hook: &'static str,
+    pub command: &'static str,
+}
+
+/// Hooks people commonly set up, offered when adding a hook
+pub const HOOK_TEMPLATES: &[HookTemplate] = &[
+    HookTemplate {
+        label: "Message on activity (needs monitor-activity)",
+        hook: "alert-activity",
+        command: "display-message \"Activity in #{session_name}:#{window_index} #{window_name}\"",
+    },
+    HookTemplate {
+        label: "Message on silence (needs monitor-silence)",
+        hook: "alert-silence",
+        command: "display-message \"Silence in #{session_name}:#{window_index} #{window_name}\"",
+    },
+    HookTemplate {
+        label: "Message on bell",
+        hook: "alert-bell",
+        command: "display-message \"Bell in #{session_name}:#{window_index} #{window_name}\"",
+    },
+    HookTemplate {
+        label: "Desktop notification on bell (notify-send)",
+        hook: "alert-bell",
+        command: "run-shell -b \"notify-send tmux 'Bell in #{session_name}:#{window_name}'\"",
+    },
+    HookTemplate {
+        label: "Log created sessions to ~/.tmux-sessions.log",
+        hook: "session-created",
+        command:
+            "run-shell -b \"echo \\\"$(date '+%F %T') #{session_name}\\\" >> ~/.tmux-sessions.log\"",
+    },
+];
+
+/// Parse a line of `show-hooks`, e.g. `session-created[0] display-message hi`.
+/// Hooks without commands are listed by name only and give `None`.
+pub fn parse_hook_line(line: &str) -> Option<TmuxHook> {
+    let (target, command) = line.split_once(' ')?;
+    let (name, index) = target.strip_suffix(']')?.split_once('[')?;
+    Some(TmuxHook {
+        name: name.to_string(),
+        index: index.parse().ok()?,
+        command: command.to_string(),
+    })
+}
+
+impl TmuxClient {
+    /// Hooks with commands in a scope; the global scope includes window and pane
+    /// hooks such as `pane-exited`
+    pub fn show_hooks(&self, scope: &HookScope) -> Result<Vec<TmuxHook>> {
+        let mut hooks = self.show_hooks_with(scope.args())?;
+        if *scope == HookScope::Global {
+            hooks.extend(self.show_hooks_with(vec!["-g", "-w"])?);
+        }
+        Ok(hooks)
+    }
+
+    fn show_hooks_with(&self, scope_args: Vec<&str>) -> Result<Vec<TmuxHook>> {
+        let mut args = vec!["show-hooks"];
+        args.extend(scope_args);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("Failed to show hooks", &stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().filter_map(parse_hook_line).collect())
+    }
+
+    /// Set a hook's command. `hook` is a name such as `pane-exited`, replacing all of
+    /// its commands, or a name with index such as `pane-exited[1]`, replacing one.
+    /// With `append` the command is added after the existing ones instead.
+    pub fn set_hook(
+        &self,
+        scope: &HookScope,
+        hook: &str,
+        command: &str,
+        append: bool,
+    ) -> Result<()> {
+        let mut args = vec!["set-hook"];
+        if append {
+            args.push("-a");
+        }
+        args.extend(scope.args());
+        args.extend(["--", hook, command]);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a hook's commands, or a single one when `hook` includes an index
+    pub fn unset_hook(&self, scope: &HookScope, hook: &str) -> Result<()> {
+        let mut args = vec!["set-hook", "-u"];
+        args.extend(scope.args());
+        args.extend(["--", hook]);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("", &stderr));
+        }
+
+        Ok(())
+    }
+}
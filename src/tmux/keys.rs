@@ -0,0 +1,172 @@
+use super::{Error, Result};
+use std::fmt;
+
+/// Named keys understood by `tmux send-keys`, along with accepted aliases
+const KEY_NAMES: &[(&str, &str)] = &[
+    ("enter", "Enter"),
+    ("return", "Enter"),
+    ("escape", "Escape"),
+    ("esc", "Escape"),
+    ("tab", "Tab"),
+    ("btab", "BTab"),
+    ("space", "Space"),
+    ("bspace", "BSpace"),
+    ("backspace", "BSpace"),
+    ("dc", "DC"),
+    ("delete", "DC"),
+    ("ic", "IC"),
+    ("insert", "IC"),
+    ("up", "Up"),
+    ("down", "Down"),
+    ("left", "Left"),
+    ("right", "Right"),
+    ("home", "Home"),
+    ("end", "End"),
+    ("ppage", "PPage"),
+    ("pageup", "PPage"),
+    ("pgup", "PPage"),
+    ("npage", "NPage"),
+    ("pagedown", "NPage"),
+    ("pgdn", "NPage"),
+];
+
+/// A single element of a key sequence passed to `tmux send-keys`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Key {
+    /// A tmux key name such as `Enter`, `C-c` or `M-Left`
+    Named(String),
+    /// Text typed verbatim (sent with `send-keys -l`)
+    Literal(String),
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Named(name) => write!(f, "{}", name),
+            Key::Literal(text) => write!(f, "'{}'", text),
+        }
+    }
+}
+
+/// Parse a key specification into a sequence of keys.
+///
+/// Tokens are separated by whitespace. Quoted tokens (`'...'` or `"..."`)
+/// are sent as literal text, while bare tokens must be key names
+/// (`Enter`, `Escape`, `Up`, `F5`, ...) or chords (`C-c`, `M-x`, `C-M-Left`).
+/// For example `C-c 'make test' Enter` interrupts the running program,
+/// types `make test` and presses Enter.
+pub fn parse_keys(spec: &str) -> Result<Vec<Key>> {
+    let mut keys = Vec::new();
+    let mut chars = spec.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        if c == '\'' || c == '"' {
+            chars.next();
+            let mut text = String::new();
+            let mut closed = false;
+            while let Some(ch) = chars.next() {
+                if ch == c {
+                    closed = true;
+                    break;
+                }
+                // Only double-quoted strings support escapes, like a shell
+                if ch == '\\' && c == '"' {
+                    if let Some(escaped) = chars.next() {
+                        text.push(escaped);
+                        continue;
+                    }
+                }
+                text.push(ch);
+            }
+            if !closed {
+                return Err(Error::Parse(format!(
+                    "Unterminated quote in key sequence: {}",
+                    spec
+                )));
+            }
+            if !text.is_empty() {
+                keys.push(Key::Literal(text));
+            }
+            continue;
+        }
+
+        let mut token = String::new();
+        while let Some(&ch) = chars.peek() {
+            if ch.is_whitespace() {
+                break;
+            }
+            token.push(ch);
+            chars.next();
+        }
+        keys.push(Key::Named(parse_key_name(&token)?));
+    }
+
+    Ok(keys)
+}
+
+/// Normalize a single key name or chord to the spelling tmux expects
+pub fn parse_key_name(token: &str) -> Result<String> {
+    let mut modifiers = String::new();
+    let mut rest = token;
+
+    // Peel off modifier prefixes, keeping at least one character for the key
+    while rest.len() > 2 {
+        let prefix = match rest.get(..2) {
+            Some("C-" | "c-") => "C-",
+            Some("M-" | "m-") => "M-",
+            Some("S-" | "s-") => "S-",
+            _ => break,
+        };
+        if modifiers.contains(prefix) {
+            return Err(Error::Parse(format!(
+                "Duplicate modifier in key '{}'",
+                token
+            )));
+        }
+        modifiers.push_str(prefix);
+        rest = &rest[2..];
+    }
+
+    let base = base_key_name(rest).ok_or_else(|| {
+        Error::Parse(format!(
+            "Unknown key '{}' (quote literal text, e.g. '{}')",
+            token, token
+        ))
+    })?;
+
+    // A single character is only meaningful on its own when combined with a modifier
+    if modifiers.is_empty() && base.chars().count() == 1 {
+        return Err(Error::Parse(format!(
+            "Bare character '{}' is not a key name (quote literal text, e.g. '{}')",
+            token, token
+        )));
+    }
+
+    Ok(format!("{}{}", modifiers, base))
+}
+
+fn base_key_name(name: &str) -> Option<String> {
+    let lower = name.to_ascii_lowercase();
+    if let Some((_, canonical)) = KEY_NAMES.iter().find(|(alias, _)| *alias == lower) {
+        return Some(canonical.to_string());
+    }
+
+    if let Some(n) = lower.strip_prefix('f') {
+        if let Ok(n) = n.parse::<u8>() {
+            if (1..=12).contains(&n) {
+                return Some(format!("F{}", n));
+            }
+        }
+    }
+
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_whitespace() => Some(c.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,1532 @@
+//! An in-memory tmux server for testing the TUI without running tmux.
+//!
+//! Sessions, windows and panes behave roughly like tmux's: ids are never reused,
+//! killing the last pane of a window kills the window and killing the last window
+//! of a session kills the session. Commands that change something are recorded in
+//! tmux syntax so tests can check what the TUI asked for.
+
+use super::{
+    validate_session_name, Alerts, AttachedClient, ControlMode, Error, HookScope, Key, OptionScope,
+    PaneSnapshot, PopupSize, RawOutput, RestoreReport, Result, SessionDetails, SessionSnapshot,
+    SessionTree, Snapshot, SplitDirection, TmuxBackend, TmuxBuffer, TmuxHook, TmuxOption, TmuxPane,
+    TmuxSession, TmuxVariable, TmuxWindow, WindowSnapshot, WindowTree, LAYOUT_PRESETS,
+};
+use crate::config::ServerConfig;
+use crate::template::Template;
+use chrono::{TimeDelta, Utc};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A fake tmux server; clones share the same state, like clients of one server
+#[derive(Debug, Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    sessions: Vec<MockSession>,
+    next_session: usize,
+    next_window: usize,
+    next_pane: usize,
+    /// Paste buffers with their content, most recent first
+    buffers: Vec<(String, String)>,
+    next_buffer: usize,
+    /// Options set with `set_option` as (scope, name, value), where the scope is
+    /// `server` or a session or window id
+    options: Vec<(String, String, String)>,
+    /// Variables set with `set_environment` as (scope, name, value), where the scope
+    /// is `global` or a session id
+    environment: Vec<(String, String, String)>,
+    /// Hooks as (scope, hook), where the scope is `global` or a session id
+    hooks: Vec<(String, TmuxHook)>,
+    /// Clients attached with `with_client`
+    clients: Vec<AttachedClient>,
+    calls: Vec<String>,
+    /// Requests made through the backend so far, reads included
+    queries: usize,
+    /// Session tmux-ui runs in, if it runs inside this server
+    home: Option<String>,
+    /// Session shown by the client tmux-ui runs in, and the one it showed before
+    client_session: Option<String>,
+    last_session: Option<String>,
+}
+
+#[derive(Debug)]
+struct MockSession {
+    session: TmuxSession,
+    windows: Vec<MockWindow>,
+}
+
+/// A linked window is copied into each session, so changes to one link do not
+/// show in the others
+#[derive(Debug, Clone)]
+struct MockWindow {
+    window: TmuxWindow,
+    panes: Vec<MockPane>,
+}
+
+impl MockWindow {
+    fn snapshot(&self) -> WindowSnapshot {
+        WindowSnapshot {
+            index: self.window.index,
+            name: self.window.name.clone(),
+            layout: String::new(),
+            active: self.window.active,
+            automatic_rename: self.window.automatic_rename,
+            panes: self
+                .panes
+                .iter()
+                .map(|p| PaneSnapshot {
+                    index: p.pane.index,
+                    active: p.pane.active,
+                    cwd: p.pane.current_path.clone().into(),
+                    command: p.pane.current_command.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockPane {
+    pane: TmuxPane,
+    /// Everything typed into the pane, returned by `capture_pane`
+    screen: String,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a detached session with a single shell window
+    pub fn with_session(self, name: &str) -> Self {
+        self.create_session(name, None, None)
+            .expect("mock session names are valid");
+        self.forget_requests();
+        self
+    }
+
+    /// Add a detached session whose last activity was the given number of days ago
+    pub fn with_idle_session(self, name: &str, days: i64) -> Self {
+        let this = self.with_session(name);
+        {
+            let mut state = this.state();
+            let session = &mut state
+                .session(name)
+                .expect("the session was just created")
+                .session;
+            session.last_activity = Utc::now() - TimeDelta::days(days);
+        }
+        this
+    }
+
+    /// Add a paste buffer, named by the server like a copy from copy mode
+    pub fn with_buffer(self, data: &str) -> Self {
+        self.set_buffer(None, data)
+            .expect("mock buffers can always be set");
+        self.forget_requests();
+        self
+    }
+
+    /// Attach a terminal client with the given tty to a session
+    pub fn with_client(self, session: &str, tty: &str) -> Self {
+        {
+            let mut state = self.state();
+            let pid = 1000 + state.clients.len() as u32;
+            let session = &mut state
+                .session(session)
+                .expect("mock clients attach to existing sessions")
+                .session;
+            session.attached = true;
+            session.clients += 1;
+            let client = AttachedClient {
+                name: tty.to_string(),
+                tty: tty.to_string(),
+                pid,
+                width: 80,
+                height: 24,
+                session_id: session.id.clone(),
+                session: session.name.clone(),
+                activity: 0,
+                terminal: "xterm".to_string(),
+                control_mode: false,
+                readonly: false,
+            };
+            state.clients.push(client);
+        }
+        self
+    }
+
+    /// Pretend tmux-ui runs in a pane of this session
+    pub fn inside_session(self, name: &str) -> Self {
+        {
+            let mut state = self.state();
+            state.home = Some(name.to_string());
+            state.client_session = Some(name.to_string());
+        }
+        self
+    }
+
+    /// Let the program of a pane exit as with `remain-on-exit` on, leaving the pane dead
+    pub fn exit_pane(&self, pane: &str) {
+        self.state()
+            .pane(pane)
+            .expect("mock panes exit only when they exist")
+            .pane
+            .dead = true;
+    }
+
+    /// Session the client tmux-ui runs in currently shows
+    pub fn client_session(&self) -> Option<String> {
+        self.state().client_session.clone()
+    }
+
+    /// Names of the sessions, in creation order
+    pub fn session_names(&self) -> Vec<String> {
+        self.state()
+            .sessions
+            .iter()
+            .map(|s| s.session.name.clone())
+            .collect()
+    }
+
+    /// Commands that changed the server so far, e.g. `kill-session $1`
+    pub fn calls(&self) -> Vec<String> {
+        self.state().calls.clone()
+    }
+
+    /// Leave the requests a builder made out of `calls` and `queries`
+    fn forget_requests(&self) {
+        let mut state = self.state();
+        state.calls.clear();
+        state.queries = 0;
+    }
+
+    /// Requests made through the backend so far, including ones that only read
+    pub fn queries(&self) -> usize {
+        self.state().queries
+    }
+
+    /// State for a request made through the backend, counted in `queries`
+    fn query(&self) -> MutexGuard<'_, MockState> {
+        let mut state = self.state();
+        state.queries += 1;
+        state
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        // A test that panicked while holding the lock fails anyway
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a command and apply it to the state
+    fn apply<T, E>(
+        &self,
+        call: String,
+        f: impl FnOnce(&mut MockState) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut state = self.query();
+        state.calls.push(call);
+        f(&mut state)
+    }
+}
+
+/// Global options every session or window inherits, and the server options
+const DEFAULT_SESSION_OPTIONS: &[(&str, &str)] = &[("history-limit", "2000"), ("status", "on")];
+const DEFAULT_WINDOW_OPTIONS: &[(&str, &str)] = &[("mode-keys", "emacs")];
+const DEFAULT_SERVER_OPTIONS: &[(&str, &str)] = &[("buffer-limit", "50"), ("escape-time", "500")];
+
+/// The global environment of the server before anything is set
+const DEFAULT_GLOBAL_ENVIRONMENT: &[(&str, &str)] = &[("HOME", "/home/mock"), ("TERM", "xterm")];
+
+impl MockState {
+    /// Key of a scope in `options`, with defaults for options not set yet
+    fn option_scope(
+        &self,
+        scope: &OptionScope,
+    ) -> Result<(String, &'static [(&'static str, &'static str)])> {
+        Ok(match scope {
+            OptionScope::Server => ("server".to_string(), DEFAULT_SERVER_OPTIONS),
+            OptionScope::Session(target) => (
+                self.sessions[self.session_position(target)?]
+                    .session
+                    .id
+                    .clone(),
+                DEFAULT_SESSION_OPTIONS,
+            ),
+            OptionScope::Window(target) => {
+                let (s, w) = self.window_position(target)?;
+                (
+                    self.sessions[s].windows[w].window.id.clone(),
+                    DEFAULT_WINDOW_OPTIONS,
+                )
+            }
+        })
+    }
+
+    /// Index of a session given by id, `=name` or name
+    fn session_position(&self, target: &str) -> Result<usize> {
+        let name = target.strip_prefix('=').unwrap_or(target);
+        self.sessions
+            .iter()
+            .position(|s| s.session.id == target || s.session.name == name)
+            .ok_or_else(|| Error::SessionNotFound(target.to_string()))
+    }
+
+    fn buffer_position(&self, name: &str) -> Result<usize> {
+        self.buffers
+            .iter()
+            .position(|(n, _)| n == name)
+            .ok_or_else(|| Error::Tmux(format!("no buffer {}", name)))
+    }
+
+    /// Key of an environment in `environment`
+    fn environment_scope(&self, session: Option<&str>) -> Result<String> {
+        Ok(match session {
+            Some(target) => self.sessions[self.session_position(target)?]
+                .session
+                .id
+                .clone(),
+            None => "global".to_string(),
+        })
+    }
+
+    /// Key of a hook scope in `hooks`
+    fn hook_scope(&self, scope: &HookScope) -> Result<String> {
+        match scope {
+            HookScope::Global => self.environment_scope(None),
+            HookScope::Session(target) => self.environment_scope(Some(target)),
+        }
+    }
+
+    fn session(&mut self, target: &str) -> Result<&mut MockSession> {
+        let s = self.session_position(target)?;
+        Ok(&mut self.sessions[s])
+    }
+
+    /// Indexes of a window given by id, or of the active window of a session
+    fn window_position(&self, target: &str) -> Result<(usize, usize)> {
+        let target = target.trim_end_matches(':');
+        for (s, session) in self.sessions.iter().enumerate() {
+            if let Some(w) = session.windows.iter().position(|w| w.window.id == target) {
+                return Ok((s, w));
+            }
+        }
+        if target.starts_with('@') {
+            return Err(Error::Tmux(format!("can't find window: {}", target)));
+        }
+        let s = self.session_position(target)?;
+        let w = self.sessions[s]
+            .windows
+            .iter()
+            .position(|w| w.window.active)
+            .unwrap_or(0);
+        Ok((s, w))
+    }
+
+    fn window(&mut self, target: &str) -> Result<&mut MockWindow> {
+        let (s, w) = self.window_position(target)?;
+        Ok(&mut self.sessions[s].windows[w])
+    }
+
+    /// Indexes of a pane given by id, or of the active pane of a window or session
+    fn pane_position(&self, target: &str) -> Result<(usize, usize, usize)> {
+        for (s, session) in self.sessions.iter().enumerate() {
+            for (w, window) in session.windows.iter().enumerate() {
+                if let Some(p) = window.panes.iter().position(|p| p.pane.id == target) {
+                    return Ok((s, w, p));
+                }
+            }
+        }
+        if target.starts_with('%') {
+            return Err(Error::Tmux(format!("can't find pane: {}", target)));
+        }
+        let (s, w) = self.window_position(target)?;
+        let p = self.sessions[s].windows[w]
+            .panes
+            .iter()
+            .position(|p| p.pane.active)
+            .unwrap_or(0);
+        Ok((s, w, p))
+    }
+
+    fn pane(&mut self, target: &str) -> Result<&mut MockPane> {
+        let (s, w, p) = self.pane_position(target)?;
+        Ok(&mut self.sessions[s].windows[w].panes[p])
+    }
+
+    fn new_pane(&mut self) -> MockPane {
+        self.next_pane += 1;
+        MockPane {
+            pane: TmuxPane {
+                id: format!("%{}", self.next_pane),
+                index: 0,
+                active: true,
+                width: 80,
+                height: 24,
+                current_command: "sh".to_string(),
+                current_path: "/".to_string(),
+                title: String::new(),
+                dead: false,
+                start_command: String::new(),
+                piped: false,
+                pid: 0,
+            },
+            screen: String::new(),
+        }
+    }
+
+    fn new_window(&mut self, index: usize, name: &str) -> MockWindow {
+        self.next_window += 1;
+        let pane = self.new_pane();
+        MockWindow {
+            window: TmuxWindow {
+                id: format!("@{}", self.next_window),
+                index,
+                name: name.to_string(),
+                panes: 1,
+                active: true,
+                automatic_rename: true,
+                synchronized: false,
+                alerts: Alerts::default(),
+                zoomed: false,
+                linked: false,
+            },
+            panes: vec![pane],
+        }
+    }
+
+    /// A recorded window with fresh ids, running shells in the recorded directories
+    fn restore_window(&mut self, index: usize, recorded: &WindowSnapshot) -> MockWindow {
+        let mut window = self.new_window(index, &recorded.name);
+        window.window.active = recorded.active;
+        window.window.automatic_rename = recorded.automatic_rename;
+        window.panes.clear();
+        for recorded in &recorded.panes {
+            let mut pane = self.new_pane();
+            pane.pane.index = recorded.index;
+            pane.pane.active = recorded.active;
+            pane.pane.current_path = recorded.cwd.display().to_string();
+            window.panes.push(pane);
+        }
+        window.window.panes = window.panes.len();
+        window
+    }
+
+    /// Drop empty windows and sessions and renumber what is left, as tmux does
+    fn tidy(&mut self) {
+        for session in &mut self.sessions {
+            session.windows.retain(|w| !w.panes.is_empty());
+            for window in &mut session.windows {
+                // Adding or removing a pane unzooms the window
+                window.window.zoomed &= window.window.panes == window.panes.len();
+                window.window.panes = window.panes.len();
+                for (index, pane) in window.panes.iter_mut().enumerate() {
+                    pane.pane.index = index;
+                }
+                if !window.panes.iter().any(|p| p.pane.active) {
+                    window.panes[0].pane.active = true;
+                }
+            }
+            if !session.windows.is_empty() && !session.windows.iter().any(|w| w.window.active) {
+                session.windows[0].window.active = true;
+            }
+            session.session.windows = session.windows.len();
+        }
+        self.sessions.retain(|s| !s.windows.is_empty());
+    }
+}
+
+impl TmuxBackend for MockBackend {
+    /// Every configured server is a separate, empty mock server
+    fn for_server(&self, _server: &ServerConfig) -> anyhow::Result<Self> {
+        Ok(Self::new())
+    }
+
+    fn host(&self) -> Option<&str> {
+        None
+    }
+
+    // The client tells these from `$TMUX` without running tmux, so they are not counted
+    fn is_inside_tmux(&self) -> bool {
+        self.state().home.is_some()
+    }
+
+    fn is_inside_this_server(&self) -> bool {
+        self.state().home.is_some()
+    }
+
+    fn get_current_session(&self) -> Result<Option<String>> {
+        Ok(self.query().home.clone())
+    }
+
+    fn control_mode(&self, _session: &str) -> Result<ControlMode> {
+        drop(self.query());
+        Err(Error::Tmux(
+            "The mock backend has no control mode".to_string(),
+        ))
+    }
+
+    fn list_sessions_with_columns(&self, columns: &[String]) -> Result<Vec<TmuxSession>> {
+        Ok(self
+            .query()
+            .sessions
+            .iter()
+            .map(|s| {
+                let pane = s
+                    .windows
+                    .iter()
+                    .find(|w| w.window.active)
+                    .and_then(|w| w.panes.iter().find(|p| p.pane.active))
+                    .map(|p| p.pane.clone());
+                TmuxSession {
+                    columns: vec![String::new(); columns.len()],
+                    current_command: pane
+                        .as_ref()
+                        .map(|p| p.current_command.clone())
+                        .unwrap_or_default(),
+                    current_path: pane.map(|p| p.current_path).unwrap_or_default(),
+                    panes: s.windows.iter().map(|w| w.panes.len()).sum(),
+                    ..s.session.clone()
+                }
+            })
+            .collect())
+    }
+
+    fn list_tree(&self) -> Result<Vec<SessionTree>> {
+        Ok(self
+            .query()
+            .sessions
+            .iter()
+            .map(|s| SessionTree {
+                id: s.session.id.clone(),
+                name: s.session.name.clone(),
+                clients: s.session.clients,
+                windows: s
+                    .windows
+                    .iter()
+                    .map(|w| WindowTree {
+                        window: w.window.clone(),
+                        panes: w.panes.iter().map(|p| p.pane.clone()).collect(),
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    fn create_session(
+        &self,
+        name: &str,
+        start_directory: Option<&Path>,
+        command: Option<&str>,
+    ) -> Result<()> {
+        let name = validate_session_name(name)?;
+        let mut call = format!("new-session {}", name);
+        if let Some(dir) = start_directory {
+            call.push_str(&format!(" -c {}", dir.display()));
+        }
+        if let Some(command) = command {
+            call.push_str(&format!(" {}", command));
+        }
+        self.apply(call, |state| {
+            if state.sessions.iter().any(|s| s.session.name == name) {
+                return Err(Error::DuplicateSession(name.clone()));
+            }
+            state.next_session += 1;
+            let mut window = state.new_window(0, "sh");
+            let pane = &mut window.panes[0].pane;
+            if let Some(dir) = start_directory {
+                pane.current_path = dir.display().to_string();
+            }
+            if let Some(program) = command.and_then(|c| c.split_whitespace().next()) {
+                pane.current_command = program.to_string();
+                pane.start_command = command.unwrap_or_default().to_string();
+            }
+            state.sessions.push(MockSession {
+                session: TmuxSession {
+                    id: format!("${}", state.next_session),
+                    name,
+                    windows: 1,
+                    panes: 1,
+                    attached: false,
+                    clients: 0,
+                    created_at: Utc::now(),
+                    last_activity: Utc::now(),
+                    columns: Vec::new(),
+                    server: None,
+                    alerts: Alerts::default(),
+                    tags: Vec::new(),
+                    current_command: String::new(),
+                    current_path: String::new(),
+                },
+                windows: vec![window],
+            });
+            Ok(())
+        })
+    }
+
+    fn kill_session(&self, name: &str) -> Result<()> {
+        self.apply(format!("kill-session {}", name), |state| {
+            let id = state.session(name)?.session.id.clone();
+            state.sessions.retain(|s| s.session.id != id);
+            Ok(())
+        })
+    }
+
+    /// Copies windows and panes with fresh ids; without `commands` every pane runs `sh`
+    fn clone_session(&self, source: &str, new_name: &str, commands: bool) -> anyhow::Result<()> {
+        let new_name = validate_session_name(new_name)?;
+        let call = match commands {
+            true => format!("clone-session {} {}", source, new_name),
+            false => format!("clone-session {} {} -n", source, new_name),
+        };
+        self.apply(call, |state| {
+            if state.sessions.iter().any(|s| s.session.name == new_name) {
+                return Err(Error::DuplicateSession(new_name.clone()).into());
+            }
+            let s = state.session_position(source)?;
+            let originals: Vec<(TmuxWindow, Vec<TmuxPane>)> = state.sessions[s]
+                .windows
+                .iter()
+                .map(|w| {
+                    (
+                        w.window.clone(),
+                        w.panes.iter().map(|p| p.pane.clone()).collect(),
+                    )
+                })
+                .collect();
+            let mut windows = Vec::new();
+            for (original, panes) in originals {
+                let mut window = state.new_window(original.index, &original.name);
+                window.window.active = original.active;
+                window.panes.clear();
+                for pane in panes {
+                    let mut copy = state.new_pane();
+                    copy.pane.index = pane.index;
+                    copy.pane.active = pane.active;
+                    copy.pane.current_path = pane.current_path;
+                    if commands {
+                        copy.pane.current_command = pane.current_command;
+                    }
+                    window.panes.push(copy);
+                }
+                window.window.panes = window.panes.len();
+                windows.push(window);
+            }
+            state.next_session += 1;
+            let session = TmuxSession {
+                id: format!("${}", state.next_session),
+                name: new_name,
+                attached: false,
+                clients: 0,
+                created_at: Utc::now(),
+                ..state.sessions[s].session.clone()
+            };
+            state.sessions.push(MockSession { session, windows });
+            Ok(())
+        })
+    }
+
+    fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let new_name = validate_session_name(new_name)?;
+        self.apply(
+            format!("rename-session {} {}", old_name, new_name),
+            |state| {
+                if state.sessions.iter().any(|s| s.session.name == new_name) {
+                    return Err(Error::DuplicateSession(new_name.clone()));
+                }
+                state.session(old_name)?.session.name = new_name;
+                Ok(())
+            },
+        )
+    }
+
+    fn set_session_tags(&self, name: &str, tags: &[String]) -> Result<()> {
+        self.apply(
+            format!("set-option @tmux-ui-tags {}", tags.join(",")),
+            |state| {
+                let mut tags = tags.to_vec();
+                tags.sort();
+                state.session(name)?.session.tags = tags;
+                Ok(())
+            },
+        )
+    }
+
+    fn idle_sessions(&self) -> Result<Vec<TmuxSession>> {
+        Ok(self
+            .query()
+            .sessions
+            .iter()
+            .filter(|s| !s.session.attached)
+            .filter(|s| {
+                s.windows
+                    .iter()
+                    .flat_map(|w| &w.panes)
+                    .all(|p| p.pane.current_command == "sh")
+            })
+            .map(|s| s.session.clone())
+            .collect())
+    }
+
+    /// The default shell of the mock server is `sh`
+    fn running_commands(&self, session: &str) -> Result<Vec<String>> {
+        let mut state = self.query();
+        let session = state.session(session)?;
+        let mut commands: Vec<String> = Vec::new();
+        for pane in session.windows.iter().flat_map(|w| &w.panes) {
+            let command = &pane.pane.current_command;
+            if command != "sh" && !commands.contains(command) {
+                commands.push(command.clone());
+            }
+        }
+        Ok(commands)
+    }
+
+    /// Every mock window is 80x24 and no session is grouped
+    fn session_details(&self, session: &str) -> Result<SessionDetails> {
+        let mut state = self.query();
+        let session = state.session(session)?;
+        let window = session
+            .windows
+            .iter()
+            .find(|w| w.window.active)
+            .ok_or_else(|| Error::Tmux("no active window".to_string()))?;
+        let pane = window
+            .panes
+            .iter()
+            .find(|p| p.pane.active)
+            .ok_or_else(|| Error::Tmux("no active pane".to_string()))?;
+        Ok(SessionDetails {
+            width: 80,
+            height: 24,
+            group: None,
+            group_size: 0,
+            window_index: window.window.index,
+            window_name: window.window.name.clone(),
+            pane_index: pane.pane.index,
+            pane_command: pane.pane.current_command.clone(),
+        })
+    }
+
+    fn switch_client(&self, name: &str) -> Result<()> {
+        self.apply(format!("switch-client {}", name), |state| {
+            let name = state.session(name)?.session.name.clone();
+            state.last_session = state.client_session.replace(name);
+            Ok(())
+        })
+    }
+
+    fn switch_client_last(&self) -> Result<()> {
+        self.apply("switch-client -l".to_string(), |state| {
+            let last = state
+                .last_session
+                .take()
+                .ok_or_else(|| Error::Tmux("no last session".to_string()))?;
+            state.last_session = state.client_session.replace(last);
+            Ok(())
+        })
+    }
+
+    fn attach_session(&self, name: &str) -> Result<()> {
+        self.apply(format!("attach-session {}", name), |state| {
+            state.session(name).map(|_| ())
+        })
+    }
+
+    fn attach_session_exclusive(&self, name: &str) -> Result<()> {
+        self.apply(format!("attach-session -d {}", name), |state| {
+            state.session(name).map(|_| ())
+        })
+    }
+
+    fn attach_in_new_terminal(&self, session: &str, terminal_cmd: &str) -> Result<()> {
+        self.apply(
+            format!("{} tmux attach-session {}", terminal_cmd, session),
+            |state| state.session(session).map(|_| ()),
+        )
+    }
+
+    fn run_in_window(&self, session: &str, command: &str) -> Result<()> {
+        self.apply(format!("new-window {} {}", session, command), |state| {
+            let index = state
+                .session(session)?
+                .windows
+                .iter()
+                .map(|w| w.window.index + 1)
+                .max()
+                .unwrap_or(0);
+            let program = command.split_whitespace().next().unwrap_or("sh");
+            let mut window = state.new_window(index, program);
+            let pane = &mut window.panes[0].pane;
+            pane.current_command = program.to_string();
+            pane.start_command = command.to_string();
+            let session = state.session(session)?;
+            for other in &mut session.windows {
+                other.window.active = false;
+            }
+            session.windows.push(window);
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn display_popup(&self, target: &str, command: Option<&str>, size: &PopupSize) -> Result<()> {
+        let call = match command {
+            Some(command) => format!("display-popup {} {} {}", target, size, command),
+            None => format!("display-popup {} {}", target, size),
+        };
+        self.apply(call, |state| state.pane(target).map(|_| ()))
+    }
+
+    fn attach_in_popup(&self, session: &str) -> Result<()> {
+        self.apply(format!("display-popup {}", session), |state| {
+            state.session(session).map(|_| ())
+        })
+    }
+
+    fn detach_current_client(&self) -> Result<()> {
+        self.apply("detach-client".to_string(), |_| Ok(()))
+    }
+
+    fn detach_session(&self, name: &str) -> Result<()> {
+        self.apply(format!("detach-client -s {}", name), |state| {
+            let session = &mut state.session(name)?.session;
+            session.attached = false;
+            session.clients = 0;
+            let id = session.id.clone();
+            state.clients.retain(|c| c.session_id != id);
+            Ok(())
+        })
+    }
+
+    fn detach_other_clients(&self, name: &str) -> Result<usize> {
+        self.apply(format!("detach-client -a {}", name), |state| {
+            state.session(name).map(|_| 0)
+        })
+    }
+
+    fn list_clients(&self) -> Result<Vec<AttachedClient>> {
+        Ok(self.query().clients.clone())
+    }
+
+    fn detach_client(&self, client: &str) -> Result<()> {
+        self.apply(format!("detach-client -t {}", client), |state| {
+            let index = state
+                .clients
+                .iter()
+                .position(|c| c.name == client)
+                .ok_or_else(|| Error::Tmux(format!("can't find client: {}", client)))?;
+            let client = state.clients.remove(index);
+            let session = &mut state.session(&client.session_id)?.session;
+            session.clients = session.clients.saturating_sub(1);
+            session.attached = session.clients > 0;
+            Ok(())
+        })
+    }
+
+    fn list_windows(&self, session: &str) -> Result<Vec<TmuxWindow>> {
+        let mut state = self.query();
+        let session = state.session(session)?;
+        Ok(session.windows.iter().map(|w| w.window.clone()).collect())
+    }
+
+    fn create_window(&self, session: &str, name: Option<&str>) -> Result<()> {
+        self.apply(format!("new-window {}", session), |state| {
+            let index = state
+                .session(session)?
+                .windows
+                .iter()
+                .map(|w| w.window.index + 1)
+                .max()
+                .unwrap_or(0);
+            let mut window = state.new_window(index, name.unwrap_or("sh"));
+            window.window.automatic_rename = name.is_none();
+            let session = state.session(session)?;
+            for other in &mut session.windows {
+                other.window.active = false;
+            }
+            session.windows.push(window);
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn kill_window(&self, target: &str) -> Result<()> {
+        self.apply(format!("kill-window {}", target), |state| {
+            state.window(target)?.panes.clear();
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn rename_window(&self, target: &str, new_name: &str) -> Result<()> {
+        self.apply(format!("rename-window {} {}", target, new_name), |state| {
+            state.window(target)?.window.name = new_name.to_string();
+            Ok(())
+        })
+    }
+
+    fn disable_automatic_rename(&self, target: &str) -> Result<()> {
+        self.set_window_option(target, "automatic-rename", "off")
+    }
+
+    /// Options the window list shows are applied; others are only recorded
+    fn set_window_option(&self, target: &str, option: &str, value: &str) -> Result<()> {
+        self.apply(
+            format!("set-option -w {} {} {}", option, value, target),
+            |state| {
+                let window = &mut state.window(target)?.window;
+                let on = value == "on";
+                match option {
+                    "automatic-rename" => window.automatic_rename = on,
+                    "synchronize-panes" => window.synchronized = on,
+                    _ => {}
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn select_layout(&self, target: &str, layout: &str) -> Result<()> {
+        self.apply(format!("select-layout {} {}", target, layout), |state| {
+            state.window(target)?;
+            // Custom layouts start with a checksum, e.g. `b25d,80x24,0,0,1`
+            if LAYOUT_PRESETS.contains(&layout) || layout.contains(',') {
+                Ok(())
+            } else {
+                Err(Error::Tmux(format!("invalid layout: {}", layout)))
+            }
+        })
+    }
+
+    fn select_window(&self, target: &str) -> Result<()> {
+        self.apply(format!("select-window {}", target), |state| {
+            let (s, w) = state.window_position(target)?;
+            for (i, window) in state.sessions[s].windows.iter_mut().enumerate() {
+                window.window.active = i == w;
+            }
+            Ok(())
+        })
+    }
+
+    fn swap_window(&self, source: &str, target: &str) -> Result<()> {
+        self.apply(format!("swap-window {} {}", source, target), |state| {
+            let (s1, w1) = state.window_position(source)?;
+            let (s2, w2) = state.window_position(target)?;
+            if s1 != s2 {
+                return Err(Error::Tmux(
+                    "the mock backend only swaps windows within a session".to_string(),
+                ));
+            }
+            let windows = &mut state.sessions[s1].windows;
+            windows.swap(w1, w2);
+            let (i1, i2) = (windows[w1].window.index, windows[w2].window.index);
+            windows[w1].window.index = i2;
+            windows[w2].window.index = i1;
+            Ok(())
+        })
+    }
+
+    fn move_window(&self, window: &str, session: &str) -> Result<()> {
+        self.apply(format!("move-window {} {}", window, session), |state| {
+            let (s, w) = state.window_position(window)?;
+            let index = state
+                .session(session)?
+                .windows
+                .iter()
+                .map(|w| w.window.index + 1)
+                .max()
+                .unwrap_or(0);
+            let mut moved = state.sessions[s].windows.remove(w);
+            moved.window.index = index;
+            moved.window.active = false;
+            state.session(session)?.windows.push(moved);
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn link_window(&self, window: &str, session: &str) -> Result<()> {
+        self.apply(format!("link-window {} {}", window, session), |state| {
+            let (s, w) = state.window_position(window)?;
+            let id = state.sessions[s].windows[w].window.id.clone();
+            let target = state.session(session)?;
+            if target.windows.iter().any(|w| w.window.id == id) {
+                return Err(Error::Tmux(format!("{} is already linked", id)));
+            }
+            let index = target
+                .windows
+                .iter()
+                .map(|w| w.window.index + 1)
+                .max()
+                .unwrap_or(0);
+            let mut linked = state.sessions[s].windows[w].clone();
+            linked.window.index = index;
+            linked.window.active = false;
+            state.session(session)?.windows.push(linked);
+            for window in state.sessions.iter_mut().flat_map(|s| &mut s.windows) {
+                if window.window.id == id {
+                    window.window.linked = true;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn unlink_window(&self, session: &str, window: &str) -> Result<()> {
+        self.apply(format!("unlink-window {} {}", session, window), |state| {
+            let links = state
+                .sessions
+                .iter()
+                .filter(|s| s.windows.iter().any(|w| w.window.id == window))
+                .count();
+            if links == 1 {
+                return Err(Error::Tmux("window only linked to one session".to_string()));
+            }
+            let target = state.session(session)?;
+            let w = target
+                .windows
+                .iter()
+                .position(|w| w.window.id == window)
+                .ok_or_else(|| Error::Tmux(format!("can't find window: {}", window)))?;
+            target.windows.remove(w);
+            if links == 2 {
+                for other in state.sessions.iter_mut().flat_map(|s| &mut s.windows) {
+                    if other.window.id == window {
+                        other.window.linked = false;
+                    }
+                }
+            }
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn list_panes(&self, target: &str) -> Result<Vec<TmuxPane>> {
+        let mut state = self.query();
+        let window = state.window(target)?;
+        Ok(window.panes.iter().map(|p| p.pane.clone()).collect())
+    }
+
+    fn select_pane(&self, target: &str) -> Result<()> {
+        self.apply(format!("select-pane {}", target), |state| {
+            let (s, w, p) = state.pane_position(target)?;
+            for (i, pane) in state.sessions[s].windows[w].panes.iter_mut().enumerate() {
+                pane.pane.active = i == p;
+            }
+            Ok(())
+        })
+    }
+
+    /// Zooming also makes the pane active
+    fn toggle_zoom(&self, pane: &str) -> Result<()> {
+        self.apply(format!("resize-pane -Z {}", pane), |state| {
+            let (s, w, p) = state.pane_position(pane)?;
+            let window = &mut state.sessions[s].windows[w];
+            if window.panes.len() > 1 {
+                window.window.zoomed = !window.window.zoomed;
+                for (i, pane) in window.panes.iter_mut().enumerate() {
+                    pane.pane.active = i == p;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn pipe_pane(&self, target: &str, file: Option<&Path>) -> Result<()> {
+        let mut call = format!("pipe-pane {}", target);
+        if let Some(file) = file {
+            call.push_str(&format!(" {}", file.display()));
+        }
+        self.apply(call, |state| {
+            state.pane(target)?.pane.piped = file.is_some();
+            Ok(())
+        })
+    }
+
+    fn respawn_pane(&self, target: &str, command: Option<&str>) -> Result<()> {
+        let mut call = format!("respawn-pane {}", target);
+        if let Some(command) = command {
+            call.push_str(&format!(" {}", command));
+        }
+        self.apply(call, |state| {
+            let pane = &mut state.pane(target)?.pane;
+            if !pane.dead {
+                return Err(Error::Tmux(format!("pane {} still active", target)));
+            }
+            if let Some(command) = command {
+                pane.start_command = command.to_string();
+            }
+            pane.current_command = pane
+                .start_command
+                .split_whitespace()
+                .next()
+                .unwrap_or("sh")
+                .to_string();
+            pane.dead = false;
+            Ok(())
+        })
+    }
+
+    fn kill_pane(&self, target: &str) -> Result<()> {
+        self.apply(format!("kill-pane {}", target), |state| {
+            let (s, w, p) = state.pane_position(target)?;
+            state.sessions[s].windows[w].panes.remove(p);
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn split_window(
+        &self,
+        target: &str,
+        direction: SplitDirection,
+        _percent: Option<u8>,
+        _cwd: Option<&Path>,
+    ) -> Result<String> {
+        let flag = match direction {
+            SplitDirection::Vertical => "-v",
+            SplitDirection::Horizontal => "-h",
+        };
+        self.apply(format!("split-window {} {}", flag, target), |state| {
+            let (s, w, _) = state.pane_position(target)?;
+            let mut pane = state.new_pane();
+            pane.pane.active = false;
+            let id = pane.pane.id.clone();
+            state.sessions[s].windows[w].panes.push(pane);
+            state.tidy();
+            Ok(id)
+        })
+    }
+
+    fn break_pane(&self, pane: &str) -> Result<String> {
+        self.apply(format!("break-pane {}", pane), |state| {
+            let (s, w, p) = state.pane_position(pane)?;
+            let index = state.sessions[s]
+                .windows
+                .iter()
+                .map(|w| w.window.index + 1)
+                .max()
+                .unwrap_or(0);
+            let mut window = state.new_window(index, "sh");
+            window.window.active = false;
+            let mut moved = state.sessions[s].windows[w].panes.remove(p);
+            moved.pane.active = true;
+            window.panes = vec![moved];
+            let id = window.window.id.clone();
+            state.sessions[s].windows.push(window);
+            state.tidy();
+            Ok(id)
+        })
+    }
+
+    fn join_pane(&self, pane: &str, window: &str, _direction: SplitDirection) -> Result<()> {
+        self.apply(format!("join-pane {} {}", pane, window), |state| {
+            let (s, w, p) = state.pane_position(pane)?;
+            let mut moved = state.sessions[s].windows[w].panes.remove(p);
+            moved.pane.active = false;
+            state.window(window)?.panes.push(moved);
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn swap_pane(&self, source: &str, target: &str) -> Result<()> {
+        self.apply(format!("swap-pane {} {}", source, target), |state| {
+            let (s1, w1, p1) = state.pane_position(source)?;
+            let (s2, w2, p2) = state.pane_position(target)?;
+            if (s1, w1) != (s2, w2) {
+                return Err(Error::Tmux(
+                    "the mock backend only swaps panes within a window".to_string(),
+                ));
+            }
+            let panes = &mut state.sessions[s1].windows[w1].panes;
+            panes.swap(p1, p2);
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn rotate_window(&self, target: &str, reverse: bool) -> Result<()> {
+        let direction = if reverse { "-U" } else { "-D" };
+        self.apply(format!("rotate-window {} {}", direction, target), |state| {
+            let panes = &mut state.window(target)?.panes;
+            if reverse {
+                panes.rotate_left(1);
+            } else {
+                panes.rotate_right(1);
+            }
+            state.tidy();
+            Ok(())
+        })
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String> {
+        Ok(self.query().pane(target)?.screen.clone())
+    }
+
+    fn send_keys(&self, target: &str, keys: &[Key], enter: bool) -> Result<()> {
+        let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        self.apply(
+            format!("send-keys {} {}", target, keys.join(" ")),
+            |state| {
+                let pane = state.pane(target)?;
+                for key in &keys {
+                    pane.screen.push_str(key);
+                }
+                if enter {
+                    pane.screen.push('\n');
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn list_buffers(&self) -> Result<Vec<TmuxBuffer>> {
+        Ok(self
+            .query()
+            .buffers
+            .iter()
+            .map(|(name, data)| TmuxBuffer {
+                name: name.clone(),
+                size: data.len(),
+                sample: data
+                    .chars()
+                    .take(50)
+                    .flat_map(char::escape_default)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    fn show_buffer(&self, name: &str) -> Result<String> {
+        let state = self.query();
+        let index = state.buffer_position(name)?;
+        Ok(state.buffers[index].1.clone())
+    }
+
+    fn set_buffer(&self, name: Option<&str>, data: &str) -> Result<()> {
+        let call = match name {
+            Some(name) => format!("set-buffer -b {}", name),
+            None => "set-buffer".to_string(),
+        };
+        self.apply(call, |state| {
+            let name = match name {
+                Some(name) => name.to_string(),
+                None => {
+                    state.next_buffer += 1;
+                    format!("buffer{}", state.next_buffer - 1)
+                }
+            };
+            // Setting a buffer makes it the most recent one
+            state.buffers.retain(|(n, _)| *n != name);
+            state.buffers.insert(0, (name, data.to_string()));
+            Ok(())
+        })
+    }
+
+    fn paste_buffer(&self, name: &str, target: &str) -> Result<()> {
+        self.apply(format!("paste-buffer -b {} -t {}", name, target), |state| {
+            let index = state.buffer_position(name)?;
+            let data = state.buffers[index].1.clone();
+            state.pane(target)?.screen.push_str(&data);
+            Ok(())
+        })
+    }
+
+    fn delete_buffer(&self, name: &str) -> Result<()> {
+        self.apply(format!("delete-buffer -b {}", name), |state| {
+            let index = state.buffer_position(name)?;
+            state.buffers.remove(index);
+            Ok(())
+        })
+    }
+
+    fn show_options(&self, scope: &OptionScope) -> Result<Vec<TmuxOption>> {
+        let state = self.query();
+        let (key, defaults) = state.option_scope(scope)?;
+        let mut options: Vec<TmuxOption> = defaults
+            .iter()
+            .map(|(name, value)| TmuxOption {
+                name: name.to_string(),
+                value: value.to_string(),
+                inherited: *scope != OptionScope::Server,
+            })
+            .collect();
+        for (_, name, value) in state.options.iter().filter(|(k, _, _)| *k == key) {
+            options.retain(|o| o.name != *name);
+            options.push(TmuxOption {
+                name: name.clone(),
+                value: value.clone(),
+                inherited: false,
+            });
+        }
+        options.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(options)
+    }
+
+    fn set_option(&self, scope: &OptionScope, name: &str, value: &str) -> Result<()> {
+        self.apply(
+            format!("set-option {} {} {}", scope, name, value),
+            |state| {
+                let (key, _) = state.option_scope(scope)?;
+                state.options.retain(|(k, n, _)| !(*k == key && n == name));
+                state
+                    .options
+                    .push((key, name.to_string(), value.to_string()));
+                Ok(())
+            },
+        )
+    }
+
+    fn unset_option(&self, scope: &OptionScope, name: &str) -> Result<()> {
+        self.apply(format!("set-option -u {} {}", scope, name), |state| {
+            let (key, _) = state.option_scope(scope)?;
+            state.options.retain(|(k, n, _)| !(*k == key && n == name));
+            Ok(())
+        })
+    }
+
+    fn show_environment(&self, session: Option<&str>) -> Result<Vec<TmuxVariable>> {
+        let state = self.query();
+        let key = state.environment_scope(session)?;
+        let mut variables: Vec<TmuxVariable> = match session {
+            Some(_) => Vec::new(),
+            None => DEFAULT_GLOBAL_ENVIRONMENT
+                .iter()
+                .map(|(name, value)| TmuxVariable {
+                    name: name.to_string(),
+                    value: Some(value.to_string()),
+                })
+                .collect(),
+        };
+        for (_, name, value) in state.environment.iter().filter(|(k, _, _)| *k == key) {
+            variables.retain(|v| v.name != *name);
+            variables.push(TmuxVariable {
+                name: name.clone(),
+                value: Some(value.clone()),
+            });
+        }
+        variables.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(variables)
+    }
+
+    fn set_environment(&self, session: Option<&str>, name: &str, value: &str) -> Result<()> {
+        let scope = session.unwrap_or("-g");
+        self.apply(
+            format!("set-environment {} {} {}", scope, name, value),
+            |state| {
+                let key = state.environment_scope(session)?;
+                state
+                    .environment
+                    .retain(|(k, n, _)| !(*k == key && n == name));
+                state
+                    .environment
+                    .push((key, name.to_string(), value.to_string()));
+                Ok(())
+            },
+        )
+    }
+
+    fn unset_environment(&self, session: Option<&str>, name: &str) -> Result<()> {
+        let scope = session.unwrap_or("-g");
+        self.apply(format!("set-environment -u {} {}", scope, name), |state| {
+            let key = state.environment_scope(session)?;
+            state
+                .environment
+                .retain(|(k, n, _)| !(*k == key && n == name));
+            Ok(())
+        })
+    }
+
+    fn show_hooks(&self, scope: &HookScope) -> Result<Vec<TmuxHook>> {
+        let state = self.query();
+        let key = state.hook_scope(scope)?;
+        let mut hooks: Vec<TmuxHook> = state
+            .hooks
+            .iter()
+            .filter(|(k, _)| *k == key)
+            .map(|(_, hook)| hook.clone())
+            .collect();
+        hooks.sort_by(|a, b| (&a.name, a.index).cmp(&(&b.name, b.index)));
+        Ok(hooks)
+    }
+
+    fn set_hook(&self, scope: &HookScope, hook: &str, command: &str, append: bool) -> Result<()> {
+        let flag = if append { "-a " } else { "" };
+        self.apply(
+            format!("set-hook {}{} {} {}", flag, scope, hook, command),
+            |state| {
+                let key = state.hook_scope(scope)?;
+                let (name, index) = split_hook(hook)?;
+                let index = match index {
+                    Some(index) => index,
+                    None if append => state
+                        .hooks
+                        .iter()
+                        .filter(|(k, h)| *k == key && h.name == name)
+                        .map(|(_, h)| h.index + 1)
+                        .max()
+                        .unwrap_or(0),
+                    None => {
+                        state.hooks.retain(|(k, h)| !(*k == key && h.name == name));
+                        0
+                    }
+                };
+                state
+                    .hooks
+                    .retain(|(k, h)| !(*k == key && h.name == name && h.index == index));
+                state.hooks.push((
+                    key,
+                    TmuxHook {
+                        name: name.to_string(),
+                        index,
+                        command: command.to_string(),
+                    },
+                ));
+                Ok(())
+            },
+        )
+    }
+
+    fn unset_hook(&self, scope: &HookScope, hook: &str) -> Result<()> {
+        self.apply(format!("set-hook -u {} {}", scope, hook), |state| {
+            let key = state.hook_scope(scope)?;
+            let (name, index) = split_hook(hook)?;
+            state.hooks.retain(|(k, h)| {
+                !(*k == key && h.name == name && index.is_none_or(|i| i == h.index))
+            });
+            Ok(())
+        })
+    }
+
+    /// Formats are returned as they are
+    fn display_message(&self, _target: Option<&str>, format: &str) -> Result<String> {
+        drop(self.query());
+        Ok(format.to_string())
+    }
+
+    fn run_raw(&self, args: &[String]) -> Result<RawOutput> {
+        self.apply(args.join(" "), |_| {
+            Ok(RawOutput {
+                code: Some(0),
+                stdout: String::new(),
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn capture_snapshot(&self) -> anyhow::Result<Snapshot> {
+        anyhow::bail!("The mock backend cannot save snapshots")
+    }
+
+    fn restore_snapshot(&self, _snapshot: &Snapshot) -> anyhow::Result<RestoreReport> {
+        anyhow::bail!("The mock backend cannot restore snapshots")
+    }
+
+    /// Mock windows have no layout, so the recorded layouts are empty
+    fn snapshot_session(&self, session: &str) -> anyhow::Result<SessionSnapshot> {
+        let mut state = self.query();
+        let session = state.session(session)?;
+        Ok(SessionSnapshot {
+            name: session.session.name.clone(),
+            tags: session.session.tags.clone(),
+            windows: session.windows.iter().map(MockWindow::snapshot).collect(),
+        })
+    }
+
+    fn snapshot_window(&self, window: &str) -> anyhow::Result<SessionSnapshot> {
+        let state = self.query();
+        let (s, w) = state.window_position(window)?;
+        let session = &state.sessions[s];
+        Ok(SessionSnapshot {
+            name: session.session.name.clone(),
+            tags: session.session.tags.clone(),
+            windows: vec![session.windows[w].snapshot()],
+        })
+    }
+
+    fn restore_session(&self, session: &SessionSnapshot) -> anyhow::Result<()> {
+        self.apply(format!("restore-session {}", session.name), |state| {
+            if state
+                .sessions
+                .iter()
+                .any(|s| s.session.name == session.name)
+            {
+                return Err(Error::DuplicateSession(session.name.clone()).into());
+            }
+            let windows = session
+                .windows
+                .iter()
+                .map(|w| state.restore_window(w.index, w))
+                .collect();
+            state.next_session += 1;
+            state.sessions.push(MockSession {
+                session: TmuxSession {
+                    id: format!("${}", state.next_session),
+                    name: session.name.clone(),
+                    windows: session.windows.len(),
+                    panes: session.windows.iter().map(|w| w.panes.len()).sum(),
+                    attached: false,
+                    clients: 0,
+                    created_at: Utc::now(),
+                    last_activity: Utc::now(),
+                    columns: Vec::new(),
+                    server: None,
+                    alerts: Alerts::default(),
+                    tags: session.tags.clone(),
+                    current_command: String::new(),
+                    current_path: String::new(),
+                },
+                windows,
+            });
+            Ok(())
+        })
+    }
+
+    fn restore_window(&self, session: &str, window: &WindowSnapshot) -> anyhow::Result<()> {
+        self.apply(
+            format!("restore-window {} {}", session, window.name),
+            |state| {
+                let index = state
+                    .session(session)?
+                    .windows
+                    .iter()
+                    .map(|w| w.window.index + 1)
+                    .max()
+                    .unwrap_or(0);
+                let restored = state.restore_window(index, window);
+                let session = state.session(session)?;
+                session.windows.push(restored);
+                session.session.windows = session.windows.len();
+                Ok(())
+            },
+        )
+    }
+
+    fn start_template(&self, _template: &Template) -> anyhow::Result<String> {
+        anyhow::bail!("The mock backend cannot start templates")
+    }
+}
+
+/// Split `pane-exited[1]` into the hook name and index
+fn split_hook(hook: &str) -> Result<(&str, Option<usize>)> {
+    let (name, index) = match hook.strip_suffix(']').and_then(|h| h.split_once('[')) {
+        Some((name, index)) => (
+            name,
+            Some(
+                index
+                    .parse()
+                    .map_err(|_| Error::Tmux(format!("invalid hook index: {}", hook)))?,
+            ),
+        ),
+        None => (hook, None),
+    };
+    if name.is_empty() {
+        return Err(Error::Tmux(format!("invalid option: {}", hook)));
+    }
+    Ok((name, index))
+}
@@ -0,0 +1,1867 @@
+use chrono::{DateTime, Utc};
+use error::is_no_server_error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
+
+pub mod backend;
+pub mod buffers;
+pub mod clients;
+pub mod control;
+pub mod details;
+pub mod environment;
+pub mod error;
+pub mod hooks;
+pub mod keys;
+pub mod mock;
+pub mod options;
+pub mod popup;
+pub mod prefetch;
+pub mod raw;
+pub mod remote;
+pub mod snapshot;
+pub mod tree;
+pub mod version;
+
+pub use backend::TmuxBackend;
+pub use buffers::TmuxBuffer;
+pub use clients::AttachedClient;
+pub use control::{ControlMode, Notification};
+pub use details::SessionDetails;
+pub use environment::TmuxVariable;
+pub use error::{Error, Result};
+pub use hooks::{HookScope, TmuxHook, HOOK_TEMPLATES};
+pub use keys::{parse_keys, Key};
+pub use options::{OptionScope, TmuxOption};
+pub use popup::PopupSize;
+pub use prefetch::{prefetch_panes, DEFAULT_PREFETCH_CONCURRENCY};
+pub use raw::{complete_command, split_args, RawOutput};
+pub use snapshot::{PaneSnapshot, RestoreReport, SessionSnapshot, Snapshot, WindowSnapshot};
+pub use tree::{SessionTree, WindowTree};
+pub use version::{Capabilities, ServerInfo, TmuxVersion, MIN_TMUX_VERSION};
+
+/// Parse a unix time printed by tmux, e.g. `#{session_created}`; unparsable values
+/// are the epoch, so they sort first
+fn parse_timestamp(value: &str) -> DateTime<Utc> {
+    value
+        .parse()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .unwrap_or(DateTime::UNIX_EPOCH)
+}
+
+/// Separator between user-defined column values in list output.
+/// The ASCII unit separator is used because it cannot appear in typical format output.
+const COLUMN_SEPARATOR: char = '\x1f';
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TmuxSession {
+    /// Stable session id (e.g. `$3`) that survives renames
+    pub id: String,
+    pub name: String,
+    pub windows: usize,
+    /// Number of panes across all windows of the session
+    pub panes: usize,
+    pub attached: bool,
+    /// Number of attached clients, including control-mode clients
+    pub clients: usize,
+    /// Serialized as unix time, like tmux prints it
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: DateTime<Utc>,
+    /// Last input or output in any pane of the session
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub last_activity: DateTime<Utc>,
+    /// Values of user-defined format columns, in the order they were requested
+    pub columns: Vec<String>,
+    /// Label of the configured server this session was listed from, if not the default one
+    pub server: Option<String>,
+    /// Alerts raised in any window of the session
+    pub alerts: Alerts,
+    /// User-defined tags, sorted (see [`TAGS_OPTION`])
+    pub tags: Vec<String>,
+    /// Command running in the active pane of the session's active window
+    pub current_command: String,
+    /// Working directory of that pane
+    pub current_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TmuxWindow {
+    pub id: String,
+    /// Position in the session's window list (`#{window_index}`)
+    pub index: usize,
+    pub name: String,
+    pub panes: usize,
+    pub active: bool,
+    /// Whether tmux renames the window after its running command,
+    /// which would overwrite a manual name
+    pub automatic_rename: bool,
+    /// Whether keys typed into one pane go to every pane of the window
+    /// (the `synchronize-panes` option)
+    pub synchronized: bool,
+    pub alerts: Alerts,
+    /// Whether one pane is zoomed to fill the window
+    pub zoomed: bool,
+    /// Whether the window is linked into more than one session (`link-window`)
+    pub linked: bool,
+}
+
+/// Monitoring alerts (`monitor-bell`, `monitor-activity`, `monitor-silence`)
+/// raised in a window that has not been looked at since
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Alerts {
+    pub bell: bool,
+    pub activity: bool,
+    pub silence: bool,
+}
+
+impl Alerts {
+    /// Parse `#{session_alerts}`, e.g. `1!,3#` (`!` bell, `#` activity, `~` silence)
+    pub fn from_session_alerts(alerts: &str) -> Self {
+        Self {
+            bell: alerts.contains('!'),
+            activity: alerts.contains('#'),
+            silence: alerts.contains('~'),
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        self.bell || self.activity || self.silence
+    }
+
+    /// Badges for the list views: `!` bell, `~` activity, `_` silence
+    pub fn badges(&self) -> String {
+        [(self.bell, '!'), (self.activity, '~'), (self.silence, '_')]
+            .iter()
+            .filter(|(raised, _)| *raised)
+            .map(|(_, badge)| *badge)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TmuxPane {
+    pub id: String,
+    pub index: usize,
+    pub active: bool,
+    pub width: u16,
+    pub height: u16,
+    pub current_command: String,
+    /// Working directory of the pane's foreground process
+    pub current_path: String,
+    pub title: String,
+    /// The program exited and the pane was kept (`remain-on-exit`), so it can be respawned
+    pub dead: bool,
+    /// Command the pane was started with; empty for the default shell
+    pub start_command: String,
+    /// The pane's output is piped to a program (`pipe-pane`), e.g. logged to a file
+    pub piped: bool,
+    /// Process id of the program the pane was started with, usually a shell
+    pub pid: u32,
+}
+
+/// Layouts built into tmux, in the order `next-layout` cycles through them
+pub const LAYOUT_PRESETS: [&str; 5] = [
+    "even-horizontal",
+    "even-vertical",
+    "main-horizontal",
+    "main-vertical",
+    "tiled",
+];
+
+/// Where a new pane goes when splitting a pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    /// Below the split pane (`split-window -v`)
+    #[default]
+    Vertical,
+    /// To the right of the split pane (`split-window -h`)
+    Horizontal,
+}
+
+/// Clean up a session name before it is passed to tmux.
+///
+/// Newlines and other control characters are removed and surrounding whitespace
+/// is trimmed. `:` and `.` separate windows and panes in tmux targets, so they are
+/// replaced with `_`, as tmux does itself. Fails if nothing is left.
+pub fn validate_session_name(name: &str) -> Result<String> {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| if c == ':' || c == '.' { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        return Err(Error::Parse("Session name cannot be empty".to_string()));
+    }
+    Ok(cleaned.to_string())
+}
+
+/// Clean up a window name before it is passed to tmux.
+///
+/// Control characters and surrounding whitespace are removed as for session names,
+/// but `:` and `.` are rejected: tmux keeps them in window names, where they would
+/// make targets like `session:name` ambiguous. Fails if nothing is left.
+pub fn validate_window_name(name: &str) -> Result<String> {
+    let cleaned: String = name.chars().filter(|c| !c.is_control()).collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        return Err(Error::Parse("Window name cannot be empty".to_string()));
+    }
+    if cleaned.contains([':', '.']) {
+        return Err(Error::Parse(format!(
+            "Window name '{}' cannot contain ':' or '.'",
+            cleaned
+        )));
+    }
+    Ok(cleaned.to_string())
+}
+
+/// Session user option holding the comma-separated tags of a session.
+/// Tags live as long as the session; snapshots save and restore them.
+pub const TAGS_OPTION: &str = "@tmux-ui-tags";
+
+/// Parse a list of tags separated by commas or whitespace, e.g. `work, #client`.
+///
+/// A leading `#` is dropped. Tags may contain letters, digits, `-` and `_`.
+/// The result is sorted and free of duplicates; an empty list clears the tags.
+pub fn parse_tags(input: &str) -> Result<Vec<String>> {
+    let mut tags = Vec::new();
+    for tag in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        let tag = tag.trim_start_matches('#');
+        if tag.is_empty() {
+            continue;
+        }
+        if !tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(Error::Parse(format!(
+                "Invalid tag '{}': use letters, digits, '-' and '_'",
+                tag
+            )));
+        }
+        tags.push(tag.to_string());
+    }
+    tags.sort();
+    tags.dedup();
+    Ok(tags)
+}
+
+/// Target for a session-level command. Names are matched exactly (`=name`),
+/// since tmux otherwise falls back to the first session starting with the name;
+/// session ids (`$3`) are passed through.
+fn session_target(session: &str) -> String {
+    if session.starts_with('$') {
+        session.to_string()
+    } else {
+        format!("={}", session)
+    }
+}
+
+/// Which tmux server socket commands are sent to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Socket {
+    /// The default server, as chosen by tmux itself
+    #[default]
+    Default,
+    /// A named socket in the tmux socket directory (`tmux -L <name>`)
+    Name(String),
+    /// An explicit socket path (`tmux -S <path>`)
+    Path(PathBuf),
+}
+
+/// tmux commands that only read from the server, which still run in dry-run mode
+/// so lookups keep working
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "-V",
+    "capture-pane",
+    "display-message",
+    "has-session",
+    "list-buffers",
+    "list-clients",
+    "list-commands",
+    "list-keys",
+    "list-panes",
+    "list-sessions",
+    "list-windows",
+    "show-buffer",
+    "show-environment",
+    "show-hooks",
+    "show-messages",
+    "show-options",
+];
+
+/// A tmux command line being built, run locally or over ssh when it is executed
+pub(crate) struct TmuxCommand {
+    host: Option<String>,
+    args: Vec<OsString>,
+    /// Number of leading `args` selecting the server; the tmux command follows them
+    socket_args: usize,
+    interactive: bool,
+    verbose: bool,
+    dry_run: bool,
+}
+
+impl TmuxCommand {
+    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    /// The command takes over the terminal (attaching), so ssh has to allocate one
+    pub fn interactive(&mut self) -> &mut Self {
+        self.interactive = true;
+        self
+    }
+
+    pub fn output(&mut self) -> Result<Output> {
+        if self.skip() {
+            return Ok(Output {
+                status: ExitStatus::default(),
+                stdout: self.placeholder_output().into_bytes(),
+                stderr: Vec::new(),
+            });
+        }
+        tracing::debug!("running {}", self.command_line());
+        let output = self.build().output();
+        match &output {
+            Ok(output) if output.status.success() => tracing::debug!("{}", output.status),
+            Ok(output) => tracing::warn!(
+                "{} failed with {}: {}",
+                self.command_line(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Err(e) => tracing::error!("{} could not run: {}", self.command_line(), e),
+        }
+        output.map_err(|e| Error::spawn(e, self.host.is_some()))
+    }
+
+    /// Run the command on the terminal, for commands that take it over (attaching).
+    /// Everything else uses [`output`](Self::output), so tmux's error text ends up
+    /// in the error instead of on the screen.
+    pub fn status(&mut self) -> Result<ExitStatus> {
+        if self.skip() {
+            return Ok(ExitStatus::default());
+        }
+        tracing::debug!("running {}", self.command_line());
+        let status = self.build().status();
+        match &status {
+            Ok(status) if status.success() => tracing::debug!("{}", status),
+            Ok(status) => tracing::warn!("{} failed with {}", self.command_line(), status),
+            Err(e) => tracing::error!("{} could not run: {}", self.command_line(), e),
+        }
+        status.map_err(|e| Error::spawn(e, self.host.is_some()))
+    }
+
+    /// Print the command line when verbose, or instead of running it in dry-run mode.
+    /// Returns true when the command must not run; it then counts as succeeded.
+    pub fn skip(&self) -> bool {
+        let skip = self.dry_run && !self.is_read_only();
+        if skip {
+            tracing::info!("dry run, skipped {}", self.command_line());
+            eprintln!("[dry-run] {}", self.command_line());
+        } else if self.verbose {
+            eprintln!("+ {}", self.command_line());
+        }
+        skip
+    }
+
+    /// What a skipped command that prints what it created (`-P -F <format>`) would
+    /// print, with names in place of the values, e.g. `<session_id> <window_id>`.
+    /// Later commands of a dry run then refer to those names.
+    fn placeholder_output(&self) -> String {
+        let args: Vec<&OsStr> = self.args.iter().map(OsString::as_os_str).collect();
+        let Some(p) = args.iter().position(|a| *a == "-P") else {
+            return String::new();
+        };
+        let format = args[p..]
+            .windows(2)
+            .find(|pair| pair[0] == "-F")
+            .map(|pair| pair[1].to_string_lossy())
+            .unwrap_or_default();
+        let mut output = String::new();
+        let mut rest = format.as_ref();
+        while let Some(start) = rest.find("#{") {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            output.push_str(&rest[..start]);
+            output.push_str(&format!("<{}>", &rest[start + 2..start + end]));
+            rest = &rest[start + end + 1..];
+        }
+        output.push_str(rest);
+        output
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.args
+            .get(self.socket_args)
+            .and_then(|command| command.to_str())
+            .is_some_and(|command| READ_ONLY_COMMANDS.contains(&command))
+    }
+
+    /// The command as it would be typed into a shell, e.g. `tmux -u list-sessions -F '#{session_name}'`
+    pub fn command_line(&self) -> String {
+        let command = self.build();
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| remote::shell_quote(&arg.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The process running the command
+    pub fn build(&self) -> Command {
+        // Without a UTF-8 locale tmux escapes control characters in its output,
+        // including the separator of multi-field formats. Only commands that do
+        // not draw on the terminal may claim UTF-8 support.
+        let mut args: Vec<OsString> = Vec::new();
+        if !self.interactive {
+            args.push("-u".into());
+        }
+        args.extend(self.args.iter().cloned());
+        match &self.host {
+            None => {
+                let mut cmd = Command::new("tmux");
+                cmd.args(&args);
+                cmd
+            }
+            Some(host) => {
+                let mut cmd = Command::new("ssh");
+                cmd.args(remote::ssh_args(host, &args, self.interactive));
+                if !self.interactive {
+                    // ssh would otherwise forward the keys typed into the TUI
+                    cmd.stdin(Stdio::null());
+                }
+                cmd
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TmuxClient {
+    socket: Socket,
+    /// Machine tmux runs on, reached over ssh; `None` is this machine
+    host: Option<String>,
+    /// Print every tmux command line to stderr before running it
+    verbose: bool,
+    /// Print tmux commands that would change something instead of running them
+    dry_run: bool,
+}
+
+impl TmuxClient {
+    pub fn new() -> Self {
+        Self {
+            socket: Socket::Default,
+            host: None,
+            verbose: false,
+            dry_run: false,
+        }
+    }
+
+    /// Send all commands to the given server socket instead of the default one
+    pub fn with_socket(mut self, socket: Socket) -> Self {
+        self.socket = socket;
+        self
+    }
+
+    /// Run tmux on another machine over ssh, e.g. `user@devbox` or a `~/.ssh/config` alias.
+    /// The socket then refers to a server on that machine.
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Print each tmux command line to stderr before running it
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Print the tmux commands that would change the server instead of running them.
+    /// Commands that only read, such as `list-sessions`, still run.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// The server socket this client talks to
+    pub fn socket(&self) -> &Socket {
+        &self.socket
+    }
+
+    /// The ssh host tmux runs on, if it is not this machine
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Build a tmux command addressed to this client's server.
+    ///
+    /// Every tmux invocation goes through here so socket and host selection apply everywhere.
+    fn command(&self) -> TmuxCommand {
+        let args = self.socket_args();
+        TmuxCommand {
+            host: self.host.clone(),
+            socket_args: args.len(),
+            args,
+            interactive: false,
+            verbose: self.verbose,
+            dry_run: self.dry_run,
+        }
+    }
+
+    /// Run client calls on tokio's blocking thread pool.
+    ///
+    /// Every method of the client waits for a tmux process; async callers such as
+    /// the TUI go through here so a slow server (e.g. behind an SSH-forwarded
+    /// socket) does not stall the runtime.
+    pub async fn blocking<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&TmuxClient) -> Result<T, E> + Send + 'static,
+        T: Send + 'static,
+        E: From<Error> + Send + 'static,
+    {
+        TmuxBackend::blocking(self, f).await
+    }
+
+    /// tmux options selecting this client's server
+    fn socket_args(&self) -> Vec<OsString> {
+        match &self.socket {
+            Socket::Default => Vec::new(),
+            Socket::Name(name) => vec!["-L".into(), name.into()],
+            Socket::Path(path) => vec!["-S".into(), path.into()],
+        }
+    }
+
+    /// List all tmux sessions
+    pub fn list_sessions(&self) -> Result<Vec<TmuxSession>> {
+        self.list_sessions_with_columns(&[])
+    }
+
+    /// List all tmux sessions, also expanding extra format strings for each session
+    ///
+    /// The extra formats are fetched in the same `list-sessions` call and stored
+    /// in `TmuxSession::columns`. Pane and window formats refer to the active
+    /// pane of the session's active window.
+    pub fn list_sessions_with_columns(&self, columns: &[String]) -> Result<Vec<TmuxSession>> {
+        let mut format =
+            "#{session_id}|#{session_windows}|#{session_attached}|#{session_created}|#{session_activity}|#{session_alerts}|#{@tmux-ui-tags}|#{session_name}"
+                .to_string();
+        // The path may contain '|', so it gets a field of its own
+        for field in ["#{pane_current_command}", "#{pane_current_path}"]
+            .into_iter()
+            .chain(columns.iter().map(String::as_str))
+        {
+            format.push(COLUMN_SEPARATOR);
+            format.push_str(field);
+        }
+
+        let output = self
+            .command()
+            .args(["list-sessions", "-F", &format])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if is_no_server_error(&stderr) {
+                // No sessions running
+                return Ok(Vec::new());
+            }
+            return Err(Error::from_stderr("Failed to list sessions", &stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let pane_counts = self.pane_counts().unwrap_or_default();
+        let mut sessions = Vec::new();
+
+        for line in stdout.lines() {
+            let mut fields = line.split(COLUMN_SEPARATOR);
+            let builtin = fields.next().unwrap_or_default();
+            // The name is last since it may itself contain '|'
+            let parts: Vec<&str> = builtin.splitn(8, '|').collect();
+            if parts.len() >= 8 {
+                // Parse window count, defaulting to 1 if parsing fails
+                // This maintains backwards compatibility if tmux format changes
+                let windows = parts[1].parse().unwrap_or_else(|e| {
+                    eprintln!(
+                        "Warning: Failed to parse window count '{}': {}",
+                        parts[1], e
+                    );
+                    1
+                });
+
+                let current_command = fields.next().unwrap_or_default().to_string();
+                let current_path = fields.next().unwrap_or_default().to_string();
+                let mut values: Vec<String> = fields.map(|v| v.to_string()).collect();
+                values.resize(columns.len(), String::new());
+                let clients = parts[2].parse().unwrap_or(0);
+
+                sessions.push(TmuxSession {
+                    id: parts[0].to_string(),
+                    name: parts[7].to_string(),
+                    windows,
+                    panes: pane_counts.get(parts[0]).copied().unwrap_or(0),
+                    attached: clients > 0,
+                    clients,
+                    created_at: parse_timestamp(parts[3]),
+                    last_activity: parse_timestamp(parts[4]),
+                    columns: values,
+                    server: None,
+                    alerts: Alerts::from_session_alerts(parts[5]),
+                    tags: parse_tags(parts[6]).unwrap_or_default(),
+                    current_command,
+                    current_path,
+                });
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    /// Number of panes of every session by session id; tmux has no format for it
+    fn pane_counts(&self) -> Result<HashMap<String, usize>> {
+        let output = self
+            .command()
+            .args(["list-panes", "-a", "-F", "#{session_id}"])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("Failed to list panes", &stderr));
+        }
+
+        let mut counts = HashMap::new();
+        for id in String::from_utf8_lossy(&output.stdout).lines() {
+            *counts.entry(id.to_string()).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
+    /// List `(session id, session name, has alerts)` for every session in one call
+    pub fn list_session_alerts(&self) -> Result<Vec<(String, String, bool)>> {
+        let output = self
+            .command()
+            .args([
+                "list-sessions",
+                "-F",
+                "#{session_id}|#{session_alerts}|#{session_name}",
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if is_no_server_error(&stderr) {
+                return Ok(Vec::new());
+            }
+            return Err(Error::from_stderr("Failed to list sessions", &stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, '|');
+                let id = parts.next()?.to_string();
+                let alerts = !parts.next()?.is_empty();
+                let name = parts.next()?.to_string();
+                Some((id, name, alerts))
+            })
+            .collect())
+    }
+
+    /// Create a new tmux session; the name is cleaned up with [`validate_session_name`]
+    pub fn create_session(
+        &self,
+        name: &str,
+        start_directory: Option<&Path>,
+        command: Option<&str>,
+    ) -> Result<()> {
+        let name = validate_session_name(name)?;
+        let mut cmd = self.command();
+        cmd.args(["new-session", "-d", "-s", &name]);
+        if let Some(dir) = start_directory {
+            cmd.arg("-c").arg(dir);
+        }
+        // tmux runs the command with the default shell, so it may use pipes and quotes
+        if let Some(command) = command {
+            cmd.arg(command);
+        }
+        let output = cmd.output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to create session {}", name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Kill a tmux session
+    pub fn kill_session(&self, name: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["kill-session", "-t", &session_target(name)])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to kill session {}", name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Kill the tmux server with all of its sessions
+    pub fn kill_server(&self) -> Result<()> {
+        let output = self.command().arg("kill-server").output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // Nothing to kill is the outcome we wanted anyway
+            if is_no_server_error(&stderr) {
+                return Ok(());
+            }
+            return Err(Error::from_stderr("Failed to kill server", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Sessions that are safe to clean up: no client is attached and every pane
+    /// runs nothing but the default shell
+    pub fn idle_sessions(&self) -> Result<Vec<TmuxSession>> {
+        let sessions = self.list_sessions()?;
+        if sessions.iter().all(|s| s.attached) {
+            return Ok(Vec::new());
+        }
+        let shell = self.default_shell()?;
+
+        let output = self
+            .command()
+            .args([
+                "list-panes",
+                "-a",
+                "-F",
+                "#{session_id}|#{pane_current_command}",
+            ])
+            .output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("Failed to list panes", &stderr));
+        }
+
+        // Sessions with a pane running anything else, e.g. an editor or a build
+        let busy: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('|'))
+            .filter(|(_, command)| command.trim_start_matches('-') != shell)
+            .map(|(id, _)| id.to_string())
+            .collect();
+
+        Ok(sessions
+            .into_iter()
+            .filter(|s| !s.attached && !busy.contains(&s.id))
+            .collect())
+    }
+
+    /// Program name of the server's `default-shell`, e.g. `zsh`, as
+    /// `#{pane_current_command}` shows it
+    fn default_shell(&self) -> Result<String> {
+        let shell = self
+            .command()
+            .args(["show-options", "-gv", "default-shell"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|shell| !shell.is_empty())
+            .or_else(|| env::var("SHELL").ok())
+            .ok_or_else(|| Error::Tmux("Cannot determine the default shell".to_string()))?;
+        Ok(Path::new(&shell)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or(shell))
+    }
+
+    /// Commands other than the default shell in the foreground of a session's panes,
+    /// e.g. an editor or a build, in pane order and without repeats
+    pub fn running_commands(&self, session: &str) -> Result<Vec<String>> {
+        let shell = self.default_shell()?;
+        let output = self
+            .command()
+            .args([
+                "list-panes",
+                "-s",
+                "-t",
+                &session_target(session),
+                "-F",
+                "#{pane_current_command}",
+            ])
+            .output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to list panes of session {}", session),
+                &stderr,
+            ));
+        }
+
+        let mut commands: Vec<String> = Vec::new();
+        for command in String::from_utf8_lossy(&output.stdout).lines() {
+            // Login shells show as e.g. `-zsh`
+            if command.trim_start_matches('-') != shell && !commands.iter().any(|c| c == command) {
+                commands.push(command.to_string());
+            }
+        }
+        Ok(commands)
+    }
+
+    /// Kill every session returned by [`idle_sessions`](Self::idle_sessions),
+    /// returning their names
+    pub fn prune_sessions(&self) -> Result<Vec<String>> {
+        let mut pruned = Vec::new();
+        for session in self.idle_sessions()? {
+            self.kill_session(&session.id)?;
+            pruned.push(session.name);
+        }
+        Ok(pruned)
+    }
+
+    /// Run an arbitrary tmux command on this client's server, capturing its output.
+    ///
+    /// A failing tmux command is not an error here; check `RawOutput::success`.
+    pub fn run_raw<S: AsRef<std::ffi::OsStr>>(&self, args: &[S]) -> Result<RawOutput> {
+        let output = self.command().args(args).output()?;
+
+        Ok(RawOutput {
+            code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+
+    /// Check if currently running inside a tmux session
+    pub fn is_inside_tmux(&self) -> bool {
+        env::var("TMUX").is_ok()
+    }
+
+    /// Check if currently running inside a session of this client's server,
+    /// which is required for `switch-client` to work
+    pub fn is_inside_this_server(&self) -> bool {
+        if self.host.is_some() {
+            return false;
+        }
+        // $TMUX is "<socket path>,<server pid>,<session id>"
+        let Ok(tmux) = env::var("TMUX") else {
+            return false;
+        };
+        let socket_path = tmux.split(',').next().unwrap_or_default();
+
+        match &self.socket {
+            Socket::Default => socket_path.ends_with("/default"),
+            Socket::Name(name) => socket_path.ends_with(&format!("/{}", name)),
+            Socket::Path(path) => std::path::Path::new(socket_path) == path,
+        }
+    }
+
+    /// Get the current tmux session name (when inside tmux)
+    pub fn get_current_session(&self) -> Result<Option<String>> {
+        // $TMUX does not travel over ssh
+        if !self.is_inside_tmux() || self.host.is_some() {
+            return Ok(None);
+        }
+
+        let output = self
+            .command()
+            .args(["display-message", "-p", "#S"])
+            .output()?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let session_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(session_name))
+    }
+
+    /// Expand a tmux format string (e.g. `#{pane_current_command}`) with `display-message -p`
+    ///
+    /// When a target is given the format is evaluated in the context of that
+    /// session, window or pane; otherwise tmux uses the current client.
+    pub fn display_message(&self, target: Option<&str>, format: &str) -> Result<String> {
+        let mut args = vec!["display-message", "-p"];
+        if let Some(t) = target {
+            args.push("-t");
+            args.push(t);
+        }
+        args.push("--");
+        args.push(format);
+
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("Failed to display message", &stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.trim_end_matches('\n').to_string())
+    }
+
+    /// Switch to a different tmux session (when already inside tmux)
+    pub fn switch_client(&self, name: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["switch-client", "-t", &session_target(name)])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to switch to session {}", name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Switch the current client back to the session it used before (`switch-client -l`)
+    pub fn switch_client_last(&self) -> Result<()> {
+        let output = self.command().args(["switch-client", "-l"]).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                "Failed to switch to the last session",
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Attach to a tmux session
+    pub fn attach_session(&self, name: &str) -> Result<()> {
+        let status = self
+            .command()
+            .args(["attach-session", "-t", &session_target(name)])
+            .interactive()
+            .status()?;
+
+        if !status.success() {
+            return Err(self.attach_error(name));
+        }
+
+        Ok(())
+    }
+
+    /// Attach to a tmux session, detaching every other client from it (`attach-session -d`)
+    pub fn attach_session_exclusive(&self, name: &str) -> Result<()> {
+        let status = self
+            .command()
+            .args(["attach-session", "-d", "-t", &session_target(name)])
+            .interactive()
+            .status()?;
+
+        if !status.success() {
+            return Err(self.attach_error(name));
+        }
+
+        Ok(())
+    }
+
+    /// Why attaching failed. tmux printed its message on the terminal, so the session
+    /// is looked up again to tell a missing session from other failures.
+    fn attach_error(&self, name: &str) -> Error {
+        match self
+            .command()
+            .args(["has-session", "-t", &session_target(name)])
+            .output()
+        {
+            Ok(output) if !output.status.success() => Error::from_stderr(
+                format!("Failed to attach to session {}", name),
+                &String::from_utf8_lossy(&output.stderr),
+            ),
+            Ok(_) => Error::Tmux(format!("Failed to attach to session {}", name)),
+            Err(e) => e,
+        }
+    }
+
+    /// Detach the clients of a session except the current one, returning how many
+    /// were detached. Control-mode clients (such as tmux-ui's own) are left alone.
+    pub fn detach_other_clients(&self, name: &str) -> Result<usize> {
+        let current = self
+            .display_message(None, "#{client_name}")
+            .unwrap_or_default();
+        let output = self
+            .command()
+            .args([
+                "list-clients",
+                "-t",
+                &session_target(name),
+                "-F",
+                "#{client_control_mode}|#{client_name}",
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to list clients of {}", name),
+                &stderr,
+            ));
+        }
+
+        let mut detached = 0;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Some((control, client)) = line.split_once('|') else {
+                continue;
+            };
+            if control == "1" || client == current {
+                continue;
+            }
+            let output = self
+                .command()
+                .args(["detach-client", "-t", client])
+                .output()?;
+            if output.status.success() {
+                detached += 1;
+            }
+        }
+        Ok(detached)
+    }
+
+    /// Open a session in a new terminal emulator window, without waiting for it
+    ///
+    /// `terminal_cmd` runs a program in a new window, e.g. `alacritty -e` or
+    /// `wezterm start --`; the tmux attach command is appended to it.
+    pub fn attach_in_new_terminal(&self, session: &str, terminal_cmd: &str) -> Result<()> {
+        let mut words = split_args(terminal_cmd)?.into_iter();
+        let program = words
+            .next()
+            .ok_or_else(|| Error::Parse("Terminal command is empty".to_string()))?;
+
+        let mut attach = self.command();
+        attach
+            .args(["attach-session", "-t", &session_target(session)])
+            .interactive();
+        if attach.skip() {
+            return Ok(());
+        }
+        let attach = attach.build();
+
+        let mut child = Command::new(&program)
+            .args(words)
+            .arg(attach.get_program())
+            .args(attach.get_args())
+            // Attaching from inside tmux is refused while $TMUX is set
+            .env_remove("TMUX")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| {
+                Error::Io(io::Error::new(
+                    e.kind(),
+                    format!("Failed to start terminal '{}': {}", program, e),
+                ))
+            })?;
+
+        // Reap the terminal once it is closed so it does not linger as a zombie
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+
+    /// Open a session in a popup over the current client, which waits until the
+    /// popup is closed. Needs tmux 3.2 and tmux-ui running inside this server.
+    pub fn attach_in_popup(&self, session: &str) -> Result<()> {
+        let mut attach = self.command();
+        attach
+            .args(["attach-session", "-t", &session_target(session)])
+            .interactive();
+        // Attaching from inside tmux is refused while $TMUX is set
+        let shell_command = format!("TMUX= {}", attach.command_line());
+        let output = self
+            .command()
+            .args([
+                "display-popup",
+                "-E",
+                "-w",
+                "90%",
+                "-h",
+                "90%",
+                &shell_command,
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to open session {} in a popup", session),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// List windows in a session
+    pub fn list_windows(&self, session: &str) -> Result<Vec<TmuxWindow>> {
+        let output = self
+            .command()
+            .args([
+                "list-windows",
+                "-t",
+                &session_target(session),
+                "-F",
+                "#{window_id}|#{window_index}|#{window_panes}|#{window_active}|#{automatic-rename}|#{synchronize-panes}|#{window_bell_flag}#{window_activity_flag}#{window_silence_flag}#{window_zoomed_flag}#{window_linked}|#{window_name}",
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("Failed to list windows", &stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut windows = Vec::new();
+
+        for line in stdout.lines() {
+            // The name is last since it may itself contain '|'
+            let parts: Vec<&str> = line.splitn(8, '|').collect();
+            if parts.len() >= 8 {
+                // Parse pane count, defaulting to 1 if parsing fails
+                // This maintains backwards compatibility if tmux format changes
+                let panes = parts[2].parse().unwrap_or_else(|e| {
+                    eprintln!("Warning: Failed to parse pane count '{}': {}", parts[2], e);
+                    1
+                });
+
+                let flags: Vec<char> = parts[6].chars().collect();
+                windows.push(TmuxWindow {
+                    id: parts[0].to_string(),
+                    index: parts[1].parse().unwrap_or_default(),
+                    name: parts[7].to_string(),
+                    panes,
+                    active: parts[3] == "1",
+                    automatic_rename: parts[4] == "1",
+                    synchronized: parts[5] == "1",
+                    alerts: Alerts {
+                        bell: flags.first() == Some(&'1'),
+                        activity: flags.get(1) == Some(&'1'),
+                        silence: flags.get(2) == Some(&'1'),
+                    },
+                    zoomed: flags.get(3) == Some(&'1'),
+                    linked: flags.get(4) == Some(&'1'),
+                });
+            }
+        }
+
+        Ok(windows)
+    }
+
+    /// List panes in a window (or the current window of a session)
+    pub fn list_panes(&self, target: &str) -> Result<Vec<TmuxPane>> {
+        let format = [
+            "#{pane_id}",
+            "#{pane_index}",
+            "#{pane_active}",
+            "#{pane_width}",
+            "#{pane_height}",
+            "#{pane_current_command}",
+            "#{pane_current_path}",
+            "#{pane_dead}",
+            "#{pane_pipe}",
+            "#{pane_start_command}",
+            "#{pane_pid}",
+            "#{pane_title}",
+        ]
+        .join(&COLUMN_SEPARATOR.to_string());
+        let output = self
+            .command()
+            .args(["list-panes", "-t", target, "-F", &format])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // tmux looks a bare name up as a session, but reports it as a missing window
+            let bare = !target.contains([':', '.']) && !target.starts_with(['@', '%', '$']);
+            if bare && stderr.trim() == format!("can't find window: {}", target) {
+                return Err(Error::SessionNotFound(target.to_string()));
+            }
+            return Err(Error::from_stderr("Failed to list panes", &stderr));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut panes = Vec::new();
+
+        for line in stdout.lines() {
+            // The title is last since it may contain anything
+            let parts: Vec<&str> = line.splitn(12, COLUMN_SEPARATOR).collect();
+            if parts.len() >= 12 {
+                panes.push(TmuxPane {
+                    id: parts[0].to_string(),
+                    index: parts[1].parse().unwrap_or(0),
+                    active: parts[2] == "1",
+                    width: parts[3].parse().unwrap_or(0),
+                    height: parts[4].parse().unwrap_or(0),
+                    current_command: parts[5].to_string(),
+                    current_path: parts[6].to_string(),
+                    dead: parts[7] == "1",
+                    piped: parts[8] == "1",
+                    start_command: options::unquote(parts[9]),
+                    pid: parts[10].parse().unwrap_or(0),
+                    title: parts[11].to_string(),
+                });
+            }
+        }
+
+        Ok(panes)
+    }
+
+    /// Start a dead pane again, with `command` or else the command it was started with
+    pub fn respawn_pane(&self, target: &str, command: Option<&str>) -> Result<()> {
+        let mut args = vec!["respawn-pane", "-t", target];
+        args.extend(command);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to respawn pane {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Start a window whose panes are all dead again, as a single pane running
+    /// `command` or else the command of its first pane
+    pub fn respawn_window(&self, target: &str, command: Option<&str>) -> Result<()> {
+        let mut args = vec!["respawn-window", "-t", target];
+        args.extend(command);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to respawn window {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Capture the visible content of a pane (or the active pane of a session or window)
+    pub fn capture_pane(&self, target: &str) -> Result<String> {
+        let output = self
+            .command()
+            .args(["capture-pane", "-p", "-t", target])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to capture pane {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Create a new window in a session
+    pub fn create_window(&self, session: &str, name: Option<&str>) -> Result<()> {
+        // The trailing ':' picks the next free index in the session
+        let target = format!("{}:", session_target(session));
+        let mut args = vec!["new-window", "-t", &target];
+        if let Some(n) = name {
+            args.push("-n");
+            args.push(n);
+        }
+
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to create window in session {}", session),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Run a shell command in a new window of a session. The window stays open
+    /// when the command exits, so its output can be read and it can be respawned.
+    pub fn run_in_window(&self, session: &str, command: &str) -> Result<()> {
+        // Both run as one command sequence, before the command can exit
+        let target = format!("{}:", session_target(session));
+        let output = self
+            .command()
+            .args(["new-window", "-t", &target, command])
+            .args([";", "set-option", "-w", "remain-on-exit", "on"])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to run '{}' in session {}", command, session),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Kill a window
+    pub fn kill_window(&self, target: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["kill-window", "-t", target])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to kill window {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Make a window the current window of its session
+    pub fn select_window(&self, target: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["select-window", "-t", target])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to select window {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Exchange the positions of two windows, which may be in different sessions;
+    /// the current window of each session stays selected
+    pub fn swap_window(&self, source: &str, target: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["swap-window", "-d", "-s", source, "-t", target])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to swap windows {} and {}", source, target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Move a window to the first free index of another session
+    pub fn move_window(&self, window: &str, session: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args([
+                "move-window",
+                "-d",
+                "-s",
+                window,
+                "-t",
+                &format!("{}:", session_target(session)),
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to move window {} to session {}", window, session),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Make a pane the active pane of its window
+    pub fn select_pane(&self, target: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["select-pane", "-t", target])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to select pane {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Append everything a pane prints from now on to `file`, or stop piping its
+    /// output when `file` is `None`. The file is written by the tmux server.
+    pub fn pipe_pane(&self, target: &str, file: Option<&Path>) -> Result<()> {
+        let command =
+            file.map(|file| format!("cat >> {}", remote::shell_quote(&file.to_string_lossy())));
+        let mut args = vec!["pipe-pane", "-t", target];
+        args.extend(command.as_deref());
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to pipe pane {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Zoom a pane to fill its window, or unzoom the window if a pane is zoomed.
+    /// A window with a single pane is left as it is.
+    pub fn toggle_zoom(&self, pane: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["resize-pane", "-Z", "-t", pane])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to zoom pane {}", pane),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Split a pane (or the active pane of a window) and return the new pane's id
+    ///
+    /// `percent` is the size of the new pane; without it tmux splits in half.
+    /// The new pane starts in `cwd`, or in the session's directory if not given.
+    /// It does not become the active pane.
+    pub fn split_window(
+        &self,
+        target: &str,
+        direction: SplitDirection,
+        percent: Option<u8>,
+        cwd: Option<&Path>,
+    ) -> Result<String> {
+        let mut cmd = self.command();
+        cmd.args(["split-window", "-d", "-t", target]);
+        cmd.arg(match direction {
+            SplitDirection::Vertical => "-v",
+            SplitDirection::Horizontal => "-h",
+        });
+        if let Some(percent) = percent {
+            if !(1..=99).contains(&percent) {
+                return Err(Error::Parse(format!(
+                    "Split size must be between 1 and 99 percent, got {}",
+                    percent
+                )));
+            }
+            cmd.args(["-l", &format!("{}%", percent)]);
+        }
+        if let Some(cwd) = cwd {
+            cmd.arg("-c").arg(cwd);
+        }
+        let output = cmd.args(["-P", "-F", "#{pane_id}"]).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to split pane {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Move a pane out of its window into a new window of the same session,
+    /// returning the new window's id. The current window does not change.
+    pub fn break_pane(&self, pane: &str) -> Result<String> {
+        let output = self
+            .command()
+            .args(["break-pane", "-d", "-s", pane, "-P", "-F", "#{window_id}"])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to break pane {}", pane),
+                &stderr,
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Link a window into another session at its first free index, so the same
+    /// window shows in both
+    pub fn link_window(&self, window: &str, session: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args([
+                "link-window",
+                "-d",
+                "-s",
+                window,
+                "-t",
+                &format!("{}:", session_target(session)),
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to link window {} into session {}", window, session),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a window from one session it is linked into; tmux refuses when it
+    /// is not linked into any other session
+    pub fn unlink_window(&self, session: &str, window: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args([
+                "unlink-window",
+                "-t",
+                &format!("{}:{}", session_target(session), window),
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!(
+                    "Failed to unlink window {} from session {}",
+                    window, session
+                ),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Exchange the positions of two panes, which may be in different windows;
+    /// the active pane stays the same
+    pub fn swap_pane(&self, source: &str, target: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["swap-pane", "-d", "-s", source, "-t", target])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to swap panes {} and {}", source, target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Move every pane of a window one position on, the last pane becoming the
+    /// first, or one position back with `reverse`; the layout stays the same
+    pub fn rotate_window(&self, target: &str, reverse: bool) -> Result<()> {
+        let direction = if reverse { "-U" } else { "-D" };
+        let output = self
+            .command()
+            .args(["rotate-window", direction, "-t", target])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to rotate the panes of window {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Move a pane into another window, splitting that window's active pane
+    /// in `direction`. A window left without panes is closed.
+    pub fn join_pane(&self, pane: &str, window: &str, direction: SplitDirection) -> Result<()> {
+        let output = self
+            .command()
+            .args(["join-pane", "-d", "-s", pane, "-t", window])
+            .arg(match direction {
+                SplitDirection::Vertical => "-v",
+                SplitDirection::Horizontal => "-h",
+            })
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to join pane {} to window {}", pane, window),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Kill a pane
+    pub fn kill_pane(&self, target: &str) -> Result<()> {
+        let output = self.command().args(["kill-pane", "-t", target]).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to kill pane {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Rename a window
+    pub fn rename_window(&self, target: &str, new_name: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["rename-window", "-t", target, "--", new_name])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to rename window {} to {}", target, new_name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the value of a window option, or `None` if it is not set
+    pub fn show_window_option(&self, target: &str, option: &str) -> Result<Option<String>> {
+        let output = self
+            .command()
+            .args(["show-options", "-w", "-v", "-t", target, option])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to show window option {}", option),
+                &stderr,
+            ));
+        }
+
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(if value.is_empty() { None } else { Some(value) })
+    }
+
+    /// Set a window option
+    pub fn set_window_option(&self, target: &str, option: &str, value: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["set-option", "-w", "-t", target, option, value])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to set window option {} on {}", option, target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Arrange the panes of a window with one of [`LAYOUT_PRESETS`], or a layout
+    /// string as `#{window_layout}` prints it, e.g. `b25d,80x24,0,0,1`
+    pub fn select_layout(&self, target: &str, layout: &str) -> Result<()> {
+        let output = self
+            .command()
+            .args(["select-layout", "-t", target, layout])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to apply layout to window {}", target),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Stop tmux from renaming a window after its running command, so a manual name sticks
+    pub fn disable_automatic_rename(&self, target: &str) -> Result<()> {
+        self.set_window_option(target, "automatic-rename", "off")
+    }
+
+    /// Replace the tags of a session; an empty list removes them
+    pub fn set_session_tags(&self, name: &str, tags: &[String]) -> Result<()> {
+        // set-option takes a pane target, which needs the trailing ':'
+        let target = format!("{}:", session_target(name));
+        let mut cmd = self.command();
+        if tags.is_empty() {
+            cmd.args(["set-option", "-u", "-t", &target, TAGS_OPTION]);
+        } else {
+            cmd.args(["set-option", "-t", &target, TAGS_OPTION, &tags.join(",")]);
+        }
+        let output = cmd.output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to tag session {}", name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Rename a session; the new name is cleaned up with [`validate_session_name`]
+    pub fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let new_name = validate_session_name(new_name)?;
+        let output = self
+            .command()
+            .args([
+                "rename-session",
+                "-t",
+                &session_target(old_name),
+                "--",
+                &new_name,
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to rename session from {} to {}", old_name, new_name),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Send a sequence of keys to a pane, followed by Enter if `enter` is set
+    ///
+    /// Named keys are passed to tmux as key names, while literal text is sent
+    /// with `-l` so it is never interpreted as a key binding.
+    pub fn send_keys(&self, target: &str, keys: &[Key], enter: bool) -> Result<()> {
+        let enter_key = Key::Named("Enter".to_string());
+        for key in keys.iter().chain(enter.then_some(&enter_key)) {
+            let args = match key {
+                Key::Named(name) => vec!["send-keys", "-t", target, name.as_str()],
+                Key::Literal(text) => vec!["send-keys", "-t", target, "-l", "--", text.as_str()],
+            };
+
+            let output = self.command().args(&args).output()?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(Error::from_stderr(
+                    format!("Failed to send key {} to {}", key, target),
+                    &stderr,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a key specification (e.g. `C-c 'make test' Enter`) and send it to a pane
+    pub fn send_key_spec(&self, target: &str, spec: &str) -> Result<()> {
+        let keys = parse_keys(spec)?;
+        self.send_keys(target, &keys, false)
+    }
+
+    /// Detach the current client (when inside tmux)
+    pub fn detach_current_client(&self) -> Result<()> {
+        let output = self.command().args(["detach-client"]).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                "Failed to detach current client",
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Detach all clients from a session
+    pub fn detach_session(&self, name: &str) -> Result<()> {
+        // Detach all clients from the session
+        // This may fail if no clients are attached, which is not an error
+        let _result = self
+            .command()
+            .args(["detach-client", "-s", &session_target(name)])
+            .output();
+
+        // Always return Ok since detaching from a session with no attached clients
+        // is not an error condition
+        Ok(())
+    }
+}
+
+impl Default for TmuxClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,135 @@
+//! Reading and changing tmux options of the server, a session or a window
+
+use super::{Error, Result, TmuxClient};
+use serde::Serialize;
+use std::fmt;
+
+/// Where an option is set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionScope {
+    /// Server options, e.g. `buffer-limit` or `escape-time`
+    Server,
+    /// Options of a session, e.g. `status-left` or `history-limit`
+    Session(String),
+    /// Options of a window (or the current window of a session), e.g. `mode-keys`
+    Window(String),
+}
+
+impl OptionScope {
+    /// Flags selecting the scope for `show-options` and `set-option`
+    fn args(&self) -> Vec<&str> {
+        match self {
+            OptionScope::Server => vec!["-s"],
+            OptionScope::Session(target) => vec!["-t", target],
+            OptionScope::Window(target) => vec!["-w", "-t", target],
+        }
+    }
+}
+
+impl fmt::Display for OptionScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionScope::Server => write!(f, "server"),
+            OptionScope::Session(target) => write!(f, "session {}", target),
+            OptionScope::Window(target) => write!(f, "window {}", target),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TmuxOption {
+    /// Option name; array items include their index, e.g. `status-format[0]`
+    pub name: String,
+    pub value: String,
+    /// The value comes from the global options rather than the session or window itself
+    pub inherited: bool,
+}
+
+/// Parse a line of `show-options`, e.g. `status-left* "[#S] "`
+pub fn parse_option_line(line: &str) -> Option<TmuxOption> {
+    let (name, value) = line.split_once(' ').unwrap_or((line, ""));
+    if name.is_empty() {
+        return None;
+    }
+    let (name, inherited) = match name.strip_suffix('*') {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    Some(TmuxOption {
+        name: name.to_string(),
+        value: unquote(value),
+        inherited,
+    })
+}
+
+/// Undo the quoting tmux applies to values with spaces or special characters
+pub(crate) fn unquote(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.to_string();
+    }
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+impl TmuxClient {
+    /// All options of a scope, including those a session or window inherits
+    pub fn show_options(&self, scope: &OptionScope) -> Result<Vec<TmuxOption>> {
+        let mut args = vec!["show-options"];
+        if *scope != OptionScope::Server {
+            args.push("-A");
+        }
+        args.extend(scope.args());
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to show options of {}", scope),
+                &stderr,
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().filter_map(parse_option_line).collect())
+    }
+
+    /// Set an option of a scope; tmux checks the name and value
+    pub fn set_option(&self, scope: &OptionScope, name: &str, value: &str) -> Result<()> {
+        let mut args = vec!["set-option"];
+        args.extend(scope.args());
+        args.extend(["--", name, value]);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("", &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Remove an option from a session or window, so the global value applies again
+    pub fn unset_option(&self, scope: &OptionScope, name: &str) -> Result<()> {
+        let mut args = vec!["set-option", "-u"];
+        args.extend(scope.args());
+        args.extend(["--", name]);
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("", &stderr));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+//! Popups over the current client (`display-popup`, tmux 3.2): scratch terminals
+//! and commands run without a window of their own
+
+use super::{remote, Error, Result, TmuxClient};
+use std::fmt;
+use std::str::FromStr;
+
+/// Width and height of a popup, each a number of cells or a percentage of the
+/// client, e.g. `80%x60%` or `120x40`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PopupSize {
+    pub width: String,
+    pub height: String,
+}
+
+impl Default for PopupSize {
+    fn default() -> Self {
+        Self {
+            width: "80%".to_string(),
+            height: "80%".to_string(),
+        }
+    }
+}
+
+impl FromStr for PopupSize {
+    type Err = Error;
+
+    /// Parse `WIDTHxHEIGHT`, or a single size used for both, e.g. `90%`
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (width, height) = s.split_once('x').unwrap_or((s, s));
+        for size in [width, height] {
+            let number = size.strip_suffix('%').unwrap_or(size);
+            let valid = number
+                .parse::<u32>()
+                .is_ok_and(|n| n > 0 && (n <= 100 || !size.ends_with('%')));
+            if !valid {
+                return Err(Error::Parse(format!(
+                    "Invalid popup size '{}' (expected e.g. 80%x60% or 120x40)",
+                    s
+                )));
+            }
+        }
+        Ok(PopupSize {
+            width: width.to_string(),
+            height: height.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for PopupSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+impl TmuxClient {
+    /// Show a popup over the current client, in the working directory of `target`'s
+    /// active pane, and wait until it is closed. Without a command the popup runs the
+    /// default shell and closes when it exits; a command's popup stays open after it
+    /// exits so its output can be read. Needs tmux 3.2.
+    pub fn display_popup(
+        &self,
+        target: &str,
+        command: Option<&str>,
+        size: &PopupSize,
+    ) -> Result<()> {
+        let mut args = vec!["display-popup", "-t", target, "-d", "#{pane_current_path}"];
+        args.extend(["-w", &size.width, "-h", &size.height]);
+        match command {
+            Some(command) => args.push(command),
+            None => args.push("-E"),
+        }
+        let output = self.command().args(&args).output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let context = match command {
+                Some(command) => format!("Failed to run '{}' in a popup", command),
+                None => format!("Failed to open a popup in {}", target),
+            };
+            return Err(Error::from_stderr(context, &stderr));
+        }
+
+        Ok(())
+    }
+
+    /// Run a program in a popup over the current client that closes when the program
+    /// exits, and wait for that; `argv` is the program and its arguments, e.g.
+    /// tmux-ui itself for `--popup`. Needs tmux 3.2.
+    pub fn run_in_popup(&self, argv: &[String], size: &PopupSize) -> Result<()> {
+        let command = argv
+            .iter()
+            .map(|arg| remote::shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let output = self
+            .command()
+            .args(["display-popup", "-E", "-w", &size.width, "-h", &size.height])
+            .arg(&command)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr(
+                format!("Failed to run '{}' in a popup", command),
+                &stderr,
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use super::{TmuxBackend, TmuxPane, TmuxWindow};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Default number of tmux processes allowed to run at once while prefetching
+pub const DEFAULT_PREFETCH_CONCURRENCY: usize = 4;
+
+/// Fetch the panes of every window concurrently, keyed by window id.
+///
+/// At most `max_concurrency` `tmux list-panes` processes run at the same time,
+/// so large sessions populate quickly without flooding the tmux server.
+pub async fn prefetch_panes<B: TmuxBackend>(
+    client: &B,
+    windows: &[TmuxWindow],
+    max_concurrency: usize,
+) -> HashMap<String, Vec<TmuxPane>> {
+    let semaphore = Arc::new(Semaphore::new(max_concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for window in windows {
+        let client = client.clone();
+        let semaphore = Arc::clone(&semaphore);
+        let window_id = window.id.clone();
+
+        tasks.spawn(async move {
+            // The semaphore is never closed, so acquiring only waits for a free slot
+            let _permit = semaphore.acquire_owned().await.ok()?;
+            let target = window_id.clone();
+            // A window closed in the meantime has no panes
+            let panes = client
+                .blocking(move |c| c.list_panes(&target))
+                .await
+                .unwrap_or_default();
+            Some((window_id, panes))
+        });
+    }
+
+    let mut panes = HashMap::new();
+    while let Some(result) = tasks.join_next().await {
+        if let Ok(Some((window_id, window_panes))) = result {
+            panes.insert(window_id, window_panes);
+        }
+    }
+
+    panes
+}
@@ -0,0 +1,33 @@
+//! Running tmux on another machine over ssh.
+//!
+//! ssh joins the remote command into a single line for the remote shell, so every
+//! tmux argument is quoted; format strings are full of characters (`#{}`, `|`) the
+//! shell would otherwise interpret.
+
+use std::ffi::OsString;
+
+/// Quote an argument for a POSIX shell, leaving plain words as they are
+pub fn shell_quote(arg: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./=:@%,+".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Arguments of `ssh` running tmux with `args` on `host`.
+///
+/// Interactive commands (attaching) get a terminal allocated; everything else runs
+/// in batch mode so ssh never stops to ask for a password in the middle of the TUI.
+pub fn ssh_args(host: &str, args: &[OsString], interactive: bool) -> Vec<OsString> {
+    let mut ssh: Vec<OsString> = if interactive {
+        vec!["-t".into()]
+    } else {
+        vec!["-o".into(), "BatchMode=yes".into()]
+    };
+    let command: Vec<String> = std::iter::once("tmux".to_string())
+        .chain(args.iter().map(|arg| shell_quote(&arg.to_string_lossy())))
+        .collect();
+    ssh.extend(["--".into(), host.into(), command.join(" ").into()]);
+    ssh
+}
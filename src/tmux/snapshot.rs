@@ -0,0 +1,381 @@
+//! Save and restore the full session layout (`tmux-ui save` / `tmux-ui restore`)
+//!
+//! A snapshot records every session, window and pane with its working directory,
+//! layout and running command, so the layout can be recreated after a reboot.
+//! The same recording is used to copy a single session under a new name, and to
+//! bring back a session or window that was just killed.
+
+use super::{
+    parse_tags, session_target, validate_session_name, Error, TmuxClient, COLUMN_SEPARATOR,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version of the snapshot file format
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Commands started again in restored panes. Anything else (shells, builds,
+/// servers) is not restarted, since running it again may not be safe.
+const RESTORABLE_COMMANDS: &[&str] = &[
+    "vi", "vim", "nvim", "emacs", "nano", "less", "more", "man", "top", "htop", "btop",
+];
+
+/// All sessions of a server at one point in time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    /// Unix time the snapshot was taken
+    pub created: u64,
+    pub sessions: Vec<SessionSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub windows: Vec<WindowSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowSnapshot {
+    pub index: usize,
+    pub name: String,
+    /// tmux layout string, e.g. `b25f,80x24,0,0,1`
+    pub layout: String,
+    pub active: bool,
+    pub automatic_rename: bool,
+    pub panes: Vec<PaneSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaneSnapshot {
+    pub index: usize,
+    pub active: bool,
+    pub cwd: PathBuf,
+    pub command: String,
+}
+
+/// What a restore did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RestoreReport {
+    pub restored: Vec<String>,
+    /// Sessions left alone because a session with the same name exists
+    pub skipped: Vec<String>,
+}
+
+impl Snapshot {
+    /// Record all sessions of the client's server with a single `list-panes -a`
+    pub fn capture(client: &TmuxClient) -> Result<Self> {
+        Ok(Self::new(capture_panes(client, &["-a"])?))
+    }
+
+    fn new(sessions: Vec<SessionSnapshot>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            created: crate::usage::now(),
+            sessions,
+        }
+    }
+
+    /// Default snapshot location, honoring `XDG_DATA_HOME`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?)
+                .join(".local")
+                .join("share"),
+        };
+        Some(base.join("tmux-ui").join("snapshot.json"))
+    }
+
+    /// Write the snapshot as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))
+    }
+
+    /// Read a snapshot written by `save`
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+        let snapshot: Snapshot = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid snapshot {}", path.display()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            anyhow::bail!(
+                "Unsupported snapshot version {} in {}",
+                snapshot.version,
+                path.display()
+            );
+        }
+        Ok(snapshot)
+    }
+
+    /// Recreate every session that does not exist on the server yet
+    pub fn restore(&self, client: &TmuxClient) -> Result<RestoreReport> {
+        let existing: Vec<String> = client
+            .list_sessions()?
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+
+        let mut report = RestoreReport::default();
+        for session in &self.sessions {
+            if existing.contains(&session.name) {
+                report.skipped.push(session.name.clone());
+                continue;
+            }
+            restore_session(client, session, None, true)
+                .with_context(|| format!("Failed to restore session '{}'", session.name))?;
+            report.restored.push(session.name.clone());
+        }
+        Ok(report)
+    }
+}
+
+/// Sessions, windows and panes listed by `list-panes` in the given scope,
+/// e.g. `-a` for the whole server
+fn capture_panes(client: &TmuxClient, scope: &[&str]) -> super::Result<Vec<SessionSnapshot>> {
+    let format = [
+        "#{session_name}",
+        "#{window_index}",
+        "#{window_active}",
+        "#{automatic-rename}",
+        "#{window_layout}",
+        "#{pane_index}",
+        "#{pane_active}",
+        "#{pane_current_command}",
+        "#{pane_current_path}",
+        "#{@tmux-ui-tags}",
+        "#{window_name}",
+    ]
+    .join(&COLUMN_SEPARATOR.to_string());
+
+    let output = client
+        .command()
+        .arg("list-panes")
+        .args(scope)
+        .args(["-F", &format])
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if super::is_no_server_error(&stderr) {
+            return Ok(Vec::new());
+        }
+        return Err(Error::from_stderr("Failed to list panes", &stderr));
+    }
+
+    let mut sessions: Vec<SessionSnapshot> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        // The window name is last since it may contain anything
+        let fields: Vec<&str> = line.splitn(11, COLUMN_SEPARATOR).collect();
+        if fields.len() < 11 {
+            continue;
+        }
+        let pane = PaneSnapshot {
+            index: fields[5].parse().unwrap_or(0),
+            active: fields[6] == "1",
+            command: fields[7].to_string(),
+            cwd: PathBuf::from(fields[8]),
+        };
+        let window_index: usize = fields[1].parse().unwrap_or(0);
+
+        if sessions.last().map(|s| s.name.as_str()) != Some(fields[0]) {
+            sessions.push(SessionSnapshot {
+                name: fields[0].to_string(),
+                tags: parse_tags(fields[9]).unwrap_or_default(),
+                windows: Vec::new(),
+            });
+        }
+        let session = sessions.last_mut().expect("session was just pushed");
+        if session.windows.last().map(|w| w.index) != Some(window_index) {
+            session.windows.push(WindowSnapshot {
+                index: window_index,
+                name: fields[10].to_string(),
+                layout: fields[4].to_string(),
+                active: fields[2] == "1",
+                automatic_rename: fields[3] == "1",
+                panes: Vec::new(),
+            });
+        }
+        let window = session.windows.last_mut().expect("window was just pushed");
+        window.panes.push(pane);
+    }
+
+    Ok(sessions)
+}
+
+impl TmuxClient {
+    /// Create a copy of a session under a new name, with the same windows, splits
+    /// and working directories. With `commands`, editors and pagers running in
+    /// the original are started in the copy too (see [`RESTORABLE_COMMANDS`]).
+    pub fn clone_session(&self, source: &str, new_name: &str, commands: bool) -> Result<()> {
+        let new_name = validate_session_name(new_name)?;
+        let mut session = self.snapshot_session(source)?;
+        session.name = new_name;
+        restore_session(self, &session, None, commands)
+            .with_context(|| format!("Failed to create session '{}'", session.name))
+    }
+
+    /// Record the windows and panes of one session
+    pub fn snapshot_session(&self, session: &str) -> Result<SessionSnapshot> {
+        capture_panes(self, &["-s", "-t", &session_target(session)])?
+            .pop()
+            .with_context(|| format!("Session '{}' not found", session))
+    }
+
+    /// Record one window, as the only window of its session
+    pub fn snapshot_window(&self, window: &str) -> Result<SessionSnapshot> {
+        capture_panes(self, &["-t", window])?
+            .pop()
+            .with_context(|| format!("Window '{}' not found", window))
+    }
+
+    /// Recreate a recorded session with its working directories; no command is
+    /// started again, so the panes run plain shells
+    pub fn restore_session(&self, session: &SessionSnapshot) -> Result<()> {
+        restore_session(self, session, None, false)
+            .with_context(|| format!("Failed to restore session '{}'", session.name))
+    }
+
+    /// Add a recorded window to an existing session, at the next free index
+    pub fn restore_window(&self, session: &str, window: &WindowSnapshot) -> Result<()> {
+        let recorded = SessionSnapshot {
+            name: session.to_string(),
+            tags: Vec::new(),
+            windows: vec![window.clone()],
+        };
+        restore_session(self, &recorded, Some(session_target(session)), false)
+            .with_context(|| format!("Failed to restore window '{}'", window.name))
+    }
+}
+
+/// Recreate a session, or with `into` add its windows to that existing session;
+/// `commands` restarts the restorable commands in its panes
+fn restore_session(
+    client: &TmuxClient,
+    session: &SessionSnapshot,
+    into: Option<String>,
+    commands: bool,
+) -> Result<()> {
+    let created_session = into.is_none();
+    let mut session_id = into;
+    let mut active_window = None;
+
+    for window in &session.windows {
+        let Some(first) = window.panes.first() else {
+            continue;
+        };
+        let cwd = first.cwd.to_string_lossy();
+
+        let created = match &session_id {
+            None => run(
+                client,
+                &[
+                    "new-session",
+                    "-d",
+                    "-s",
+                    &session.name,
+                    "-n",
+                    &window.name,
+                    "-c",
+                    &cwd,
+                    "-P",
+                    "-F",
+                    "#{session_id} #{window_id} #{pane_id}",
+                ],
+            )?,
+            Some(id) => run(
+                client,
+                &[
+                    "new-window",
+                    "-d",
+                    "-t",
+                    &format!("{}:", id),
+                    "-n",
+                    &window.name,
+                    "-c",
+                    &cwd,
+                    "-P",
+                    "-F",
+                    "#{session_id} #{window_id} #{pane_id}",
+                ],
+            )?,
+        };
+        let mut ids = created.split_whitespace();
+        let (Some(id), Some(window_id), Some(first_pane)) = (ids.next(), ids.next(), ids.next())
+        else {
+            anyhow::bail!("Unexpected tmux output '{}'", created);
+        };
+        session_id.get_or_insert_with(|| id.to_string());
+        let window_id = window_id.to_string();
+
+        let mut pane_ids = vec![first_pane.to_string()];
+        for pane in &window.panes[1..] {
+            let cwd = pane.cwd.to_string_lossy();
+            let pane_id = run(
+                client,
+                &[
+                    "split-window",
+                    "-d",
+                    "-t",
+                    &window_id,
+                    "-c",
+                    &cwd,
+                    "-P",
+                    "-F",
+                    "#{pane_id}",
+                ],
+            )?;
+            pane_ids.push(pane_id);
+            // Keep room for the next split; the saved layout is applied at the end
+            let _ = run(client, &["select-layout", "-t", &window_id, "tiled"]);
+        }
+        // Best effort: the layout may not fit if the client is much smaller
+        let _ = run(client, &["select-layout", "-t", &window_id, &window.layout]);
+
+        if !window.automatic_rename {
+            client.disable_automatic_rename(&window_id)?;
+        }
+        for (pane, pane_id) in window.panes.iter().zip(&pane_ids) {
+            if commands && RESTORABLE_COMMANDS.contains(&pane.command.as_str()) {
+                client.send_keys(pane_id, &[super::Key::Literal(pane.command.clone())], true)?;
+            }
+            if pane.active {
+                client.select_pane(pane_id)?;
+            }
+        }
+        if window.active {
+            active_window = Some(window_id);
+        }
+    }
+
+    if let Some(window_id) = active_window {
+        client.select_window(&window_id)?;
+    }
+    if let (Some(id), true, false) = (&session_id, created_session, session.tags.is_empty()) {
+        client.set_session_tags(id, &session.tags)?;
+    }
+    Ok(())
+}
+
+/// Run a tmux command and return its trimmed stdout
+fn run(client: &TmuxClient, args: &[&str]) -> super::Result<String> {
+    let output = client.command().args(args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::from_stderr(
+            format!("tmux {} failed", args[0]),
+            &stderr,
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,126 @@
+//! The whole session → window → pane hierarchy, fetched with a single
+//! `list-panes -a` instead of one `list-windows` and `list-panes` per node.
+
+use super::{Alerts, Error, Result, TmuxClient, TmuxPane, TmuxWindow, COLUMN_SEPARATOR};
+use serde::Serialize;
+
+/// A session with all of its windows and panes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionTree {
+    pub id: String,
+    pub name: String,
+    /// Number of attached clients, including control-mode clients
+    pub clients: usize,
+    pub windows: Vec<WindowTree>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WindowTree {
+    pub window: TmuxWindow,
+    pub panes: Vec<TmuxPane>,
+}
+
+impl TmuxClient {
+    /// List every session with its windows and panes, in tmux's order
+    pub fn list_tree(&self) -> Result<Vec<SessionTree>> {
+        let format = [
+            "#{session_id}",
+            "#{session_attached}",
+            "#{window_id}",
+            "#{window_index}",
+            "#{window_panes}",
+            "#{window_active}",
+            "#{automatic-rename}",
+            "#{synchronize-panes}",
+            "#{window_bell_flag}#{window_activity_flag}#{window_silence_flag}#{window_zoomed_flag}#{window_linked}",
+            "#{pane_id}",
+            "#{pane_index}",
+            "#{pane_active}",
+            "#{pane_width}",
+            "#{pane_height}",
+            "#{pane_current_command}",
+            "#{pane_current_path}",
+            "#{pane_dead}",
+            "#{pane_pipe}",
+            "#{session_name}",
+            "#{pane_start_command}",
+            "#{window_name}",
+            "#{pane_pid}",
+            "#{pane_title}",
+        ]
+        .join(&COLUMN_SEPARATOR.to_string());
+
+        let output = self
+            .command()
+            .args(["list-panes", "-a", "-F", &format])
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if super::is_no_server_error(&stderr) {
+                return Ok(Vec::new());
+            }
+            return Err(Error::from_stderr("Failed to list panes", &stderr));
+        }
+
+        let mut sessions: Vec<SessionTree> = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            // The pane title is last since it may contain anything
+            let fields: Vec<&str> = line.splitn(23, COLUMN_SEPARATOR).collect();
+            if fields.len() < 23 {
+                continue;
+            }
+
+            if sessions.last().map(|s| s.id.as_str()) != Some(fields[0]) {
+                sessions.push(SessionTree {
+                    id: fields[0].to_string(),
+                    name: fields[18].to_string(),
+                    clients: fields[1].parse().unwrap_or(0),
+                    windows: Vec::new(),
+                });
+            }
+            let session = sessions.last_mut().expect("session was just pushed");
+
+            if session.windows.last().map(|w| w.window.id.as_str()) != Some(fields[2]) {
+                let flags: Vec<char> = fields[8].chars().collect();
+                session.windows.push(WindowTree {
+                    window: TmuxWindow {
+                        id: fields[2].to_string(),
+                        index: fields[3].parse().unwrap_or(0),
+                        name: fields[20].to_string(),
+                        panes: fields[4].parse().unwrap_or(1),
+                        active: fields[5] == "1",
+                        automatic_rename: fields[6] == "1",
+                        synchronized: fields[7] == "1",
+                        alerts: Alerts {
+                            bell: flags.first() == Some(&'1'),
+                            activity: flags.get(1) == Some(&'1'),
+                            silence: flags.get(2) == Some(&'1'),
+                        },
+                        zoomed: flags.get(3) == Some(&'1'),
+                        linked: flags.get(4) == Some(&'1'),
+                    },
+                    panes: Vec::new(),
+                });
+            }
+            let window = session.windows.last_mut().expect("window was just pushed");
+
+            window.panes.push(TmuxPane {
+                id: fields[9].to_string(),
+                index: fields[10].parse().unwrap_or(0),
+                active: fields[11] == "1",
+                width: fields[12].parse().unwrap_or(0),
+                height: fields[13].parse().unwrap_or(0),
+                current_command: fields[14].to_string(),
+                current_path: fields[15].to_string(),
+                title: fields[22].to_string(),
+                dead: fields[16] == "1",
+                piped: fields[17] == "1",
+                start_command: super::options::unquote(fields[19]),
+                pid: fields[21].parse().unwrap_or(0),
+            });
+        }
+
+        Ok(sessions)
+    }
+}
@@ -0,0 +1,154 @@
+//! The tmux version, checked at startup so a missing or old tmux fails with one clear
+//! message instead of every operation failing on its own
+
+use super::{Error, Result, TmuxClient};
+use std::fmt;
+use std::str::FromStr;
+
+/// Oldest tmux tmux-ui is tested with; control mode needs the `attach-session -f`
+/// flags of 3.2. The `min_tmux_version` config setting overrides it.
+pub const MIN_TMUX_VERSION: &str = "3.2";
+
+/// A tmux release, e.g. `3.3a`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TmuxVersion {
+    pub major: u32,
+    pub minor: u32,
+    /// Letter of a bug-fix release, e.g. `a` in `3.3a`
+    pub patch: Option<char>,
+}
+
+impl FromStr for TmuxVersion {
+    type Err = Error;
+
+    /// Parse `3.3a`, or what `tmux -V` prints, e.g. `tmux 3.3a` or `tmux next-3.4`
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Parse(format!("Invalid tmux version '{}'", s));
+        let version = s.trim();
+        let version = version.strip_prefix("tmux ").unwrap_or(version);
+        // Development builds are named after the release they lead to
+        let version = version.strip_prefix("next-").unwrap_or(version);
+        let (major, rest) = version.split_once('.').ok_or_else(invalid)?;
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (minor, patch) = rest.split_at(digits);
+        let mut patch = patch.chars();
+        let letter = patch.next();
+        if patch.next().is_some() || letter.is_some_and(|c| !c.is_ascii_lowercase()) {
+            return Err(invalid());
+        }
+        Ok(TmuxVersion {
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+            patch: letter,
+        })
+    }
+}
+
+impl fmt::Display for TmuxVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if let Some(patch) = self.patch {
+            write!(f, "{}", patch)?;
+        }
+        Ok(())
+    }
+}
+
+/// What `tmux -V` reports about the tmux binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerInfo {
+    /// The output of `tmux -V`, e.g. `tmux 3.3a`
+    pub description: String,
+    /// `None` for builds without a release number, e.g. `tmux master` or `tmux openbsd-7.4`
+    pub version: Option<TmuxVersion>,
+}
+
+impl ServerInfo {
+    /// Fail unless tmux is at least `minimum`; builds without a release number pass
+    pub fn require(&self, minimum: TmuxVersion) -> Result<()> {
+        match self.version {
+            Some(version) if version < minimum => Err(Error::TmuxTooOld {
+                found: version,
+                required: minimum,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl TmuxClient {
+    /// Check that tmux can be run and find out its version
+    pub fn server_info(&self) -> Result<ServerInfo> {
+        let output = self.command().arg("-V").output()?;
+
+        if !output.status.success() {
+            // The shell on the other side of ssh did not find tmux
+            if self.host().is_some() && output.status.code() == Some(127) {
+                return Err(Error::TmuxNotInstalled);
+            }
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_stderr("Failed to run tmux -V", &stderr));
+        }
+
+        let description = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(ServerInfo {
+            version: description.parse().ok(),
+            description,
+        })
+    }
+}
+
+/// What the installed tmux supports, for features that need a newer tmux than
+/// `min_tmux_version` may allow. The TUI hides or works around what is missing
+/// instead of failing when it is used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `None` when the version is unknown, which is assumed to support everything
+    version: Option<TmuxVersion>,
+}
+
+impl Capabilities {
+    pub fn new(version: Option<TmuxVersion>) -> Self {
+        Self { version }
+    }
+
+    fn since(&self, major: u32, minor: u32) -> bool {
+        self.version.is_none_or(|version| {
+            version
+                >= TmuxVersion {
+                    major,
+                    minor,
+                    patch: None,
+                }
+        })
+    }
+
+    /// `display-popup`, added in 3.2
+    pub fn has_popup(&self) -> bool {
+        self.since(3, 2)
+    }
+
+    /// Pane titles that can be set (`select-pane -T`, added in 2.6); older tmux shows
+    /// little more than the host name as `#{pane_title}`
+    pub fn has_pane_titles(&self) -> bool {
+        self.since(2, 6)
+    }
+
+    /// Keys such as `S-Enter` reaching programs running inside tmux, added in 3.2
+    pub fn has_extended_keys(&self) -> bool {
+        self.since(3, 2)
+    }
+
+    /// The `attach-session -f` flags control mode attaches with, added in 3.2
+    pub fn has_client_flags(&self) -> bool {
+        self.since(3, 2)
+    }
+}
+
+impl From<&ServerInfo> for Capabilities {
+    fn from(info: &ServerInfo) -> Self {
+        Self::new(info.version)
+    }
+}
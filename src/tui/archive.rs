@@ -0,0 +1,309 @@
+//! Archiving sessions instead of killing them, and the browser to restore them from
+
+use super::theme::Theme;
+use super::{elapsed_since, App, InputMode, SessionRef, DEFAULT_SERVER_LABEL};
+use crate::archive::{Archive, ArchivedSession};
+use crate::tmux::TmuxBackend;
+use crate::usage::EventKind;
+use crate::Result;
+use anyhow::Context;
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Popup listing the archived sessions next to the windows of the selected one
+pub(crate) struct ArchiveBrowser {
+    archive: Archive,
+    sessions: Vec<ArchivedSession>,
+    selected: ListState,
+}
+
+impl ArchiveBrowser {
+    fn new(archive: Archive) -> Self {
+        Self {
+            archive,
+            sessions: Vec::new(),
+            selected: ListState::default(),
+        }
+    }
+
+    /// Replace the session list, keeping the selection at the same position
+    fn set_sessions(&mut self, sessions: Vec<ArchivedSession>) {
+        let index = self.selected.selected().unwrap_or(0);
+        self.selected
+            .select((!sessions.is_empty()).then(|| index.min(sessions.len() - 1)));
+        self.sessions = sessions;
+    }
+
+    fn selected_session(&self) -> Option<&ArchivedSession> {
+        self.selected.selected().and_then(|i| self.sessions.get(i))
+    }
+
+    fn select_next(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            self.selected.select(Some((i + 1) % self.sessions.len()));
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            let len = self.sessions.len();
+            self.selected.select(Some((i + len - 1) % len));
+        }
+    }
+
+    /// Draw the popup centered over `area`
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let width = area.width.saturating_sub(4).max(1);
+        let height = area.height.saturating_sub(4).max(1);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(popup);
+
+        let items: Vec<ListItem> = self
+            .sessions
+            .iter()
+            .map(|archived| {
+                ListItem::new(format!(
+                    "{} (archived {} ago)",
+                    archived.session.name,
+                    elapsed_since(archived.archived_at)
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.accent))
+                    .title("Archived sessions (Enter restores)"),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        let (title, lines) = match self.selected_session() {
+            Some(archived) => (archived.session.name.clone(), contents(archived, theme)),
+            None => (String::new(), Vec::new()),
+        };
+        let contents = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .title(title),
+        );
+
+        f.render_widget(Clear, popup);
+        f.render_stateful_widget(list, halves[0], &mut self.selected);
+        f.render_widget(contents, halves[1]);
+    }
+}
+
+/// Server, tags, and the windows of an archived session with the directories of their panes
+fn contents(archived: &ArchivedSession, theme: &Theme) -> Vec<Line<'static>> {
+    let label = Style::default()
+        .fg(theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Server  ", label),
+            Span::raw(
+                archived
+                    .server
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_SERVER_LABEL.to_string()),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Tags    ", label),
+            Span::raw(match archived.session.tags.is_empty() {
+                true => "none".to_string(),
+                false => archived.session.tags.join(", "),
+            }),
+        ]),
+        Line::default(),
+    ];
+    for window in &archived.session.windows {
+        lines.push(Line::styled(
+            format!("{}: {}", window.index, window.name),
+            Style::default().fg(theme.text),
+        ));
+        for pane in &window.panes {
+            lines.push(Line::styled(
+                format!("   {}", pane.cwd.display()),
+                Style::default().fg(theme.secondary),
+            ));
+        }
+    }
+    lines
+}
+
+impl<C: TmuxBackend> App<C> {
+    fn archive(&self) -> Result<Archive> {
+        self.config
+            .archive_dir()
+            .map(Archive::new)
+            .context("No directory for archived sessions; set `archive_dir` in the config")
+    }
+
+    /// Record a session in the archive and kill it; it is only killed once recorded
+    pub(crate) async fn archive_session(&mut self, target: SessionRef) -> Result<()> {
+        let archive = match self.archive() {
+            Ok(archive) => archive,
+            Err(e) => {
+                self.report_error(format!("{:#}", e));
+                return Ok(());
+            }
+        };
+        let client = self.client_for(target.server.as_deref()).clone();
+        let (session, server, into) = (
+            target.tmux_target().to_string(),
+            target.server.clone(),
+            archive.clone(),
+        );
+        let archived = match client
+            .blocking(move |c| {
+                c.snapshot_session(&session)
+                    .and_then(|snapshot| into.add(server.as_deref(), snapshot))
+            })
+            .await
+        {
+            Ok(archived) => archived,
+            Err(e) => {
+                self.report_error(format!("Error archiving session '{}': {:#}", target, e));
+                return Ok(());
+            }
+        };
+        let session = target.tmux_target().to_string();
+        match client.blocking(move |c| c.kill_session(&session)).await {
+            Ok(_) => {
+                self.track(EventKind::Killed, &target.name);
+                self.success(format!(
+                    "Archived session '{}' ({} to restore it)",
+                    target,
+                    self.keymap
+                        .first_key(super::Action::Archived, super::Scope::Sessions)
+                ));
+                self.refresh_sessions().await?;
+            }
+            Err(e) => {
+                // Still running, so it must not show up in the archive too
+                let _ = archive.remove(&archived);
+                self.report_error(format!("Error killing session '{}': {}", target, e));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn open_archive(&mut self) {
+        match self.archive() {
+            Ok(archive) => {
+                self.archived = Some(ArchiveBrowser::new(archive));
+                if self.reload_archive() {
+                    self.input_mode = InputMode::BrowsingArchive;
+                    self.info(
+                        "Choose an archived session (Enter to restore, d to delete, ESC to close)",
+                    );
+                }
+            }
+            Err(e) => self.report_error(format!("{:#}", e)),
+        }
+    }
+
+    /// Read the archive again. Closes the browser and returns false when it is empty.
+    fn reload_archive(&mut self) -> bool {
+        let Some(browser) = &mut self.archived else {
+            return false;
+        };
+        match browser.archive.list() {
+            Ok(sessions) if sessions.is_empty() => {
+                self.close_archive();
+                self.warn("No archived sessions");
+                false
+            }
+            Ok(sessions) => {
+                browser.set_sessions(sessions);
+                true
+            }
+            Err(e) => {
+                self.close_archive();
+                self.report_error(format!("Error reading archived sessions: {:#}", e));
+                false
+            }
+        }
+    }
+
+    fn close_archive(&mut self) {
+        self.archived = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub(crate) async fn handle_browsing_archive_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(browser) = self.archived.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let Some(archived) = browser.selected_session().cloned() else {
+            self.close_archive();
+            return Ok(());
+        };
+        let archive = browser.archive.clone();
+        let name = archived.session.name.clone();
+        match key {
+            KeyCode::Down => browser.select_next(),
+            KeyCode::Up => browser.select_previous(),
+            KeyCode::Enter => {
+                // Sessions of servers no longer configured come back on the primary one
+                let session = archived.session.clone();
+                match self
+                    .client_for(archived.server.as_deref())
+                    .blocking(move |c| c.restore_session(&session))
+                    .await
+                {
+                    Ok(()) => {
+                        if let Err(e) = archive.remove(&archived) {
+                            self.report_error(format!("{:#}", e));
+                        } else {
+                            self.success(format!(
+                                "Restored session '{}'; programs that ran in it were not restarted",
+                                name
+                            ));
+                        }
+                        self.close_archive();
+                        self.refresh_sessions().await?;
+                    }
+                    Err(e) => self.report_error(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => match archive.remove(&archived) {
+                Ok(()) => {
+                    if self.reload_archive() {
+                        self.success(format!("Deleted archived session '{}'", name));
+                    }
+                }
+                Err(e) => self.error(format!("{:#}", e)),
+            },
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.close_archive();
+                self.info("Closed");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
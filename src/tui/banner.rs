@@ -0,0 +1,118 @@
+use super::icons::IconSet;
+use super::keymap::{Action, Keymap, Scope};
+use super::SessionRef;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Delay before the first automatic retry; doubled after every failed attempt
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound for the automatic retry delay
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// An operation that failed and can be attempted again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RetryAction {
+    Refresh,
+    CreateSession {
+        name: String,
+        dir: Option<PathBuf>,
+        command: Option<String>,
+    },
+    KillSession(SessionRef),
+    RenameSession {
+        session: SessionRef,
+        new_name: String,
+    },
+    CreateWindow(SessionRef),
+    SwitchClient(SessionRef),
+}
+
+impl RetryAction {
+    /// Short description used in error and success messages
+    pub fn describe(&self) -> String {
+        match self {
+            RetryAction::Refresh => "refreshing sessions".to_string(),
+            RetryAction::CreateSession { name, .. } => format!("creating session '{}'", name),
+            RetryAction::KillSession(name) => format!("deleting session '{}'", name),
+            RetryAction::RenameSession { session, new_name } => {
+                format!("renaming session '{}' to '{}'", session, new_name)
+            }
+            RetryAction::CreateWindow(session) => format!("creating window in '{}'", session),
+            RetryAction::SwitchClient(name) => format!("switching to session '{}'", name),
+        }
+    }
+
+    /// Only refreshes are retried automatically; commands that change tmux state
+    /// are retried when the user asks for it
+    fn is_automatic(&self) -> bool {
+        matches!(self, RetryAction::Refresh)
+    }
+}
+
+/// A persistent error that stays visible until it is retried successfully or dismissed,
+/// so it is not overwritten by the next status message
+#[derive(Debug, Clone)]
+pub(crate) struct ErrorBanner {
+    pub message: String,
+    pub retry: Option<RetryAction>,
+    pub attempts: u32,
+    next_retry: Option<Instant>,
+}
+
+impl ErrorBanner {
+    pub fn new(message: String, retry: Option<RetryAction>) -> Self {
+        let next_retry = retry
+            .as_ref()
+            .filter(|action| action.is_automatic())
+            .map(|_| Instant::now() + INITIAL_BACKOFF);
+
+        Self {
+            message,
+            retry,
+            attempts: 0,
+            next_retry,
+        }
+    }
+
+    /// Record another failure of the same action, backing off the next automatic retry
+    pub fn failed_again(&mut self, message: String) {
+        self.message = message;
+        self.attempts += 1;
+        if self.next_retry.is_some() {
+            let backoff = INITIAL_BACKOFF
+                .saturating_mul(2u32.saturating_pow(self.attempts))
+                .min(MAX_BACKOFF);
+            self.next_retry = Some(Instant::now() + backoff);
+        }
+    }
+
+    /// Whether an automatic retry is due
+    pub fn retry_due(&self) -> bool {
+        self.next_retry.is_some_and(|at| Instant::now() >= at)
+    }
+
+    /// Whether the banner counts down to an automatic retry, and so changes every second
+    pub fn counting_down(&self) -> bool {
+        self.next_retry.is_some()
+    }
+
+    /// Text shown in the banner, including hints for the keys bound to retry and
+    /// dismiss in the list shown
+    pub fn text(&self, keymap: &Keymap, scope: Scope, icons: IconSet) -> String {
+        let mut text = format!("{} {}", icons.warning(), self.message);
+        if let Some(at) = self.next_retry {
+            let secs = at.saturating_duration_since(Instant::now()).as_secs() + 1;
+            text.push_str(&format!(" {} retrying in {}s", icons.dash(), secs));
+        }
+        let mut hint = |action: Action, label: &str| {
+            if let Some(key) = keymap.bound_key(action, scope) {
+                text.push_str(&format!("  [{}] {}", key, label));
+            }
+        };
+        if self.retry.is_some() {
+            hint(Action::Refresh, "retry");
+        }
+        hint(Action::DismissError, "dismiss");
+        text
+    }
+}
@@ -0,0 +1,266 @@
+//! Browser for the paste buffers of a server: shows their content, pastes one into
+//! the selected session, window or pane, or copies it to the system clipboard
+
+use super::theme::Theme;
+use super::{App, InputMode, PaneTarget, SessionRef, View};
+use crate::clipboard;
+use crate::tmux::{TmuxBackend, TmuxBuffer};
+use crate::Result;
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Popup listing the buffers next to the content of the selected one
+pub(crate) struct BufferBrowser {
+    /// Server the buffers belong to, `None` for the primary server
+    server: Option<String>,
+    /// Pane a buffer is pasted into
+    target: Option<PaneTarget>,
+    buffers: Vec<TmuxBuffer>,
+    selected: ListState,
+    /// Full content of the selected buffer
+    content: String,
+}
+
+impl BufferBrowser {
+    fn new(server: Option<String>, target: Option<PaneTarget>) -> Self {
+        Self {
+            server,
+            target,
+            buffers: Vec::new(),
+            selected: ListState::default(),
+            content: String::new(),
+        }
+    }
+
+    /// Replace the buffer list, keeping the selection at the same position
+    fn set_buffers(&mut self, buffers: Vec<TmuxBuffer>) {
+        let index = self.selected.selected().unwrap_or(0);
+        self.selected
+            .select((!buffers.is_empty()).then(|| index.min(buffers.len() - 1)));
+        self.buffers = buffers;
+    }
+
+    fn selected_buffer(&self) -> Option<&TmuxBuffer> {
+        self.selected.selected().and_then(|i| self.buffers.get(i))
+    }
+
+    fn select_next(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            self.selected.select(Some((i + 1) % self.buffers.len()));
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            let len = self.buffers.len();
+            self.selected.select(Some((i + len - 1) % len));
+        }
+    }
+
+    /// Draw the popup centered over `area`
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let width = area.width.saturating_sub(4).max(1);
+        let height = area.height.saturating_sub(4).max(1);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(popup);
+
+        let items: Vec<ListItem> = self
+            .buffers
+            .iter()
+            .map(|buffer| ListItem::new(format!("{} ({} bytes)", buffer.name, buffer.size)))
+            .collect();
+        let title = match &self.target {
+            Some(target) => format!("Paste buffers (Enter pastes into {})", target.label),
+            None => "Paste buffers".to_string(),
+        };
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.accent))
+                    .title(title),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        let title = self
+            .selected_buffer()
+            .map_or(String::new(), |buffer| buffer.name.clone());
+        let content = Paragraph::new(self.content.as_str()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .title(title),
+        );
+
+        f.render_widget(Clear, popup);
+        f.render_stateful_widget(list, halves[0], &mut self.selected);
+        f.render_widget(content, halves[1]);
+    }
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Open the buffer browser for the server of the selected session, window or pane
+    pub(crate) async fn open_buffers(&mut self) -> Result<()> {
+        let target = match &self.view {
+            View::Sessions => self.selected_session().map(|session| PaneTarget {
+                server: session.server.clone(),
+                target: SessionRef::of(session).tmux_target().to_string(),
+                label: format!("session '{}'", session.name),
+            }),
+            View::Windows(view) => view.selected_window().map(|window| PaneTarget {
+                server: view.session.server.clone(),
+                target: window.id.clone(),
+                label: format!("window '{}'", window.name),
+            }),
+            View::Panes(view) => view.selected_pane().map(|pane| PaneTarget {
+                server: view.parent.session.server.clone(),
+                target: pane.id.clone(),
+                label: format!("pane {} of '{}'", pane.index, view.window.name),
+            }),
+            View::Tree(_) | View::Clients(_) => None,
+        };
+        let server = match &target {
+            Some(target) => target.server.clone(),
+            None => self.shown_server.clone(),
+        };
+        self.buffers = Some(BufferBrowser::new(server, target));
+        if self.reload_buffers().await {
+            self.input_mode = InputMode::BrowsingBuffers;
+            self.info("Choose a buffer (Enter to paste, y to copy to the clipboard, ESC to close)");
+        }
+        Ok(())
+    }
+
+    /// List the buffers again and load the content of the selected one. Closes the
+    /// browser and returns false when there is nothing to show.
+    async fn reload_buffers(&mut self) -> bool {
+        let Some(browser) = &self.buffers else {
+            return false;
+        };
+        let client = self.client_for(browser.server.as_deref()).clone();
+        match client.blocking(|c| c.list_buffers()).await {
+            Ok(buffers) if buffers.is_empty() => {
+                self.close_buffers();
+                self.warn("No paste buffers");
+                false
+            }
+            Ok(buffers) => {
+                if let Some(browser) = self.buffers.as_mut() {
+                    browser.set_buffers(buffers);
+                }
+                self.load_buffer_content().await;
+                true
+            }
+            Err(e) => {
+                self.close_buffers();
+                self.report_error(format!("Error listing buffers: {:#}", e));
+                false
+            }
+        }
+    }
+
+    async fn load_buffer_content(&mut self) {
+        let Some(browser) = &self.buffers else {
+            return;
+        };
+        let Some(name) = browser.selected_buffer().map(|b| b.name.clone()) else {
+            return;
+        };
+        let client = self.client_for(browser.server.as_deref()).clone();
+        let content = client
+            .blocking(move |c| c.show_buffer(&name))
+            .await
+            .unwrap_or_else(|e| format!("{:#}", e));
+        if let Some(browser) = self.buffers.as_mut() {
+            browser.content = content;
+        }
+    }
+
+    fn close_buffers(&mut self) {
+        self.buffers = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub(crate) async fn handle_browsing_buffers_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(browser) = self.buffers.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let Some(buffer) = browser.selected_buffer().map(|b| b.name.clone()) else {
+            self.close_buffers();
+            return Ok(());
+        };
+        let server = browser.server.clone();
+        match key {
+            KeyCode::Down => {
+                browser.select_next();
+                self.load_buffer_content().await;
+            }
+            KeyCode::Up => {
+                browser.select_previous();
+                self.load_buffer_content().await;
+            }
+            KeyCode::Enter | KeyCode::Char('p') => {
+                let Some(target) = browser.target.clone() else {
+                    self.warn("Select a session, window or pane to paste into");
+                    return Ok(());
+                };
+                self.close_buffers();
+                let (name, into) = (buffer.clone(), target.target.clone());
+                match self
+                    .client_for(target.server.as_deref())
+                    .blocking(move |c| c.paste_buffer(&name, &into))
+                    .await
+                {
+                    Ok(()) => {
+                        self.success(format!("Pasted buffer '{}' into {}", buffer, target.label))
+                    }
+                    Err(e) => self.report_error(format!("Error pasting buffer: {:#}", e)),
+                }
+            }
+            KeyCode::Char('y') => match clipboard::copy(&browser.content) {
+                Ok(()) => self.success(format!("Copied buffer '{}' to the clipboard", buffer)),
+                Err(e) => self.error(format!("Error copying to the clipboard: {:#}", e)),
+            },
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let name = buffer.clone();
+                match self
+                    .client_for(server.as_deref())
+                    .blocking(move |c| c.delete_buffer(&name))
+                    .await
+                {
+                    Ok(()) => {
+                        if self.reload_buffers().await {
+                            self.success(format!("Deleted buffer '{}'", buffer));
+                        }
+                    }
+                    Err(e) => self.error(format!("Error deleting buffer: {:#}", e)),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.close_buffers();
+                self.info("Closed");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+//! The clients attached to the server, to see which terminals show which session
+//! and detach a single one, e.g. a client left behind on another machine
+
+use super::icons::IconSet;
+use super::picker::Picker;
+use super::theme::Theme;
+use super::widgets::{render_scrollbar, Jump};
+use super::{App, InputMode, SessionRef, View};
+use crate::tmux::{self, AttachedClient, TmuxBackend};
+use crate::usage::{format_duration, EventKind};
+use crate::Result;
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) struct ClientView {
+    pub clients: Vec<AttachedClient>,
+    pub selected: ListState,
+}
+
+impl ClientView {
+    pub fn new() -> Self {
+        Self {
+            clients: Vec::new(),
+            selected: ListState::default(),
+        }
+    }
+
+    /// Replace the clients, keeping the selection on the same client where possible
+    pub fn set_clients(&mut self, clients: Vec<AttachedClient>) {
+        let previous = self.selected_client().map(|c| c.name.clone());
+        let index = previous
+            .and_then(|name| clients.iter().position(|c| c.name == name))
+            .unwrap_or(self.selected.selected().unwrap_or(0));
+        self.selected
+            .select((!clients.is_empty()).then(|| index.min(clients.len() - 1)));
+        self.clients = clients;
+    }
+
+    pub fn selected_client(&self) -> Option<&AttachedClient> {
+        self.selected.selected().and_then(|i| self.clients.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            self.selected.select(Some((i + 1) % self.clients.len()));
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            let len = self.clients.len();
+            self.selected.select(Some((i + len - 1) % len));
+        }
+    }
+
+    pub fn jump(&mut self, jump: Jump) {
+        self.selected
+            .select(jump.apply(self.selected.selected(), self.clients.len()));
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme, icons: IconSet) {
+        let now = now();
+        let name_width = self
+            .clients
+            .iter()
+            .map(|c| c.name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let session_width = self
+            .clients
+            .iter()
+            .map(|c| c.session.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let items: Vec<ListItem> = self
+            .clients
+            .iter()
+            .map(|client| {
+                let mut spans = vec![
+                    Span::raw(format!("{:name_width$}  ", client.name)),
+                    Span::styled(
+                        format!("{:session_width$}  ", client.session),
+                        Style::default().fg(theme.attached),
+                    ),
+                    Span::raw(format!(
+                        "{:>9}  idle {:>7}",
+                        format!("{}x{}", client.width, client.height),
+                        format_duration(now.saturating_sub(client.activity))
+                    )),
+                    Span::styled(
+                        format!("  {}", client.terminal),
+                        Style::default().fg(theme.secondary),
+                    ),
+                ];
+                if client.control_mode {
+                    spans.push(Span::styled(
+                        "  control mode",
+                        Style::default().fg(theme.dim),
+                    ));
+                }
+                if client.readonly {
+                    spans.push(Span::styled("  read-only", Style::default().fg(theme.dim)));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let title = match self.clients.len() {
+            0 => "Clients (none attached)".to_string(),
+            n => format!("Clients ({} attached)", n),
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        f.render_stateful_widget(list, area, &mut self.selected);
+        render_scrollbar(f, area, icons, self.clients.len(), self.selected.selected());
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// One line describing a client, e.g. `/dev/pts/3  120x40  idle 5m  xterm-256color`
+fn client_label(client: &AttachedClient, now: u64) -> String {
+    format!(
+        "{}  {}x{}  idle {}  {}",
+        client.name,
+        client.width,
+        client.height,
+        format_duration(now.saturating_sub(client.activity)),
+        client.terminal
+    )
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Reload the clients of the primary server
+    pub(crate) async fn refresh_clients(&mut self) {
+        let clients = self.client.blocking(|c| c.list_clients()).await;
+        self.apply_clients(clients);
+    }
+
+    /// Show freshly listed clients; returns whether anything shown changed
+    pub(crate) fn apply_clients(&mut self, clients: tmux::Result<Vec<AttachedClient>>) -> bool {
+        match clients {
+            Ok(mut clients) => {
+                clients.retain(|c| !self.is_own_client(c));
+                match &mut self.view {
+                    View::Clients(view) if view.clients != clients => {
+                        view.set_clients(clients);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Err(e) => {
+                self.report_error(format!("Error listing clients: {:#}", e));
+                true
+            }
+        }
+    }
+
+    /// Detach the selected client, leaving the other clients of its session attached
+    pub(crate) async fn detach_selected_client(&mut self) {
+        let View::Clients(view) = &self.view else {
+            return;
+        };
+        let Some(client) = view.selected_client().cloned() else {
+            self.warn("No client selected");
+            return;
+        };
+        let name = client.name.clone();
+        match self.client.blocking(move |c| c.detach_client(&name)).await {
+            Ok(()) => {
+                self.success(format!(
+                    "Detached client {} from '{}'",
+                    client.name, client.session
+                ));
+                self.refresh_clients().await;
+            }
+            Err(e) => self.report_error(format!("Error detaching client: {:#}", e)),
+        }
+    }
+
+    /// Whether this is tmux-ui's own control-mode client rather than one the user attached
+    fn is_own_client(&self, client: &AttachedClient) -> bool {
+        self.control
+            .as_ref()
+            .and_then(|c| c.pid())
+            .is_some_and(|pid| pid == client.pid)
+    }
+
+    /// Detach a session from the terminals showing it: the only client directly,
+    /// or the one picked when several are attached, so a stale client left on
+    /// another machine can go without taking the others along
+    pub(crate) async fn detach_from_session(&mut self, target: SessionRef) -> Result<()> {
+        let backend = self.client_for(target.server.as_deref()).clone();
+        let clients = match backend.blocking(|c| c.list_clients()).await {
+            Ok(clients) => clients,
+            Err(e) => {
+                self.report_error(format!("Error listing clients: {:#}", e));
+                return Ok(());
+            }
+        };
+        let mut clients: Vec<AttachedClient> = clients
+            .into_iter()
+            .filter(|c| {
+                c.session_id == target.id || (target.id.is_empty() && c.session == target.name)
+            })
+            .filter(|c| !self.is_own_client(c))
+            .collect();
+
+        match clients.len() {
+            0 => self.warn(format!("No clients attached to '{}'", target)),
+            1 => {
+                let client = clients.remove(0);
+                self.detach_clients(target, Some(client)).await?;
+            }
+            _ => {
+                let now = now();
+                let mut items: Vec<String> = clients.iter().map(|c| client_label(c, now)).collect();
+                items.push(format!("All {} clients", clients.len()));
+                self.client_picker = Some(Picker::new(
+                    format!("Detach which client of '{}'?", target.name),
+                    items,
+                ));
+                self.detaching = Some((target, clients));
+                self.input_mode = InputMode::PickingClient;
+            }
+        }
+        Ok(())
+    }
+
+    /// Detach one client of a session, or all of them when `client` is `None`
+    async fn detach_clients(
+        &mut self,
+        target: SessionRef,
+        client: Option<AttachedClient>,
+    ) -> Result<()> {
+        let (name, session) = (client.as_ref().map(|c| c.name.clone()), target.name.clone());
+        let result = self
+            .client_for(target.server.as_deref())
+            .blocking(move |c| match name {
+                Some(name) => c.detach_client(&name),
+                None => c.detach_session(&session),
+            })
+            .await;
+        match result {
+            Ok(()) => {
+                self.track(EventKind::Detached, &target.name);
+                match client {
+                    Some(client) => self.success(format!(
+                        "Detached client {} from session '{}'",
+                        client.name, target
+                    )),
+                    None => self.success(format!("Detached from session '{}'", target)),
+                }
+                self.refresh_sessions().await?;
+            }
+            Err(e) => self.report_error(format!("Error detaching: {:#}", e)),
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn handle_picking_client_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(picker) = self.client_picker.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                let index = picker.selected_index();
+                self.client_picker = None;
+                self.input_mode = InputMode::Normal;
+                let (Some(index), Some((target, mut clients))) = (index, self.detaching.take())
+                else {
+                    return Ok(());
+                };
+                // The entry after the clients detaches all of them
+                let client = (index < clients.len()).then(|| clients.remove(index));
+                self.detach_clients(target, client).await?;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.client_picker = None;
+                self.detaching = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Session shown by the selected client, to switch to it
+    pub(crate) fn selected_client_session(&self) -> Option<SessionRef> {
+        let View::Clients(view) = &self.view else {
+            return None;
+        };
+        view.selected_client().map(|client| SessionRef {
+            server: None,
+            id: client.session_id.clone(),
+            name: client.session.clone(),
+        })
+    }
+}
@@ -0,0 +1,143 @@
+//! Detail panel of the selected session: creation time, clients, size, group,
+//! environment and active window and pane
+
+use super::elapsed_since;
+use super::theme::Theme;
+use crate::tmux::{self, SessionDetails, TmuxSession, TmuxVariable};
+use chrono::Local;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Server label and tmux target of the session the details are of
+pub(crate) type DetailsTarget = (Option<String>, String);
+
+/// The details and environment of a session, as read from tmux
+pub(crate) type DetailsResult = tmux::Result<(SessionDetails, Vec<TmuxVariable>)>;
+
+#[derive(Default)]
+pub(crate) struct SessionDetailsPanel {
+    target: Option<DetailsTarget>,
+    details: Option<SessionDetails>,
+    environment: Vec<TmuxVariable>,
+    error: Option<String>,
+}
+
+impl SessionDetailsPanel {
+    /// Whether the panel shows another session
+    pub fn needs_update(&self, target: Option<&DetailsTarget>) -> bool {
+        self.target.as_ref() != target
+    }
+
+    /// Have the details read again on the next update, e.g. after the panel was hidden
+    pub fn invalidate(&mut self) {
+        self.target = None;
+    }
+
+    /// Store the details of a session; returns whether the panel looks any different
+    pub fn update(&mut self, target: Option<DetailsTarget>, result: DetailsResult) -> bool {
+        let (details, environment, error) = match result {
+            Ok((details, environment)) => (Some(details), environment, None),
+            Err(e) => (None, Vec::new(), Some(e.to_string())),
+        };
+        let changed = self.target != target
+            || self.details != details
+            || self.environment != environment
+            || self.error != error;
+        self.target = target;
+        self.details = details;
+        self.environment = environment;
+        self.error = error;
+        changed
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme, session: Option<&TmuxSession>) {
+        let title = match session {
+            Some(session) => format!("Details of '{}'", session.name),
+            None => "Details".to_string(),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let (Some(session), Some(details)) = (session, &self.details) else {
+            let (text, color) = match &self.error {
+                Some(error) if session.is_some() => (error.as_str(), theme.error),
+                _ => ("Nothing selected", theme.dim),
+            };
+            let placeholder = Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .block(block);
+            f.render_widget(placeholder, area);
+            return;
+        };
+
+        let created = format!(
+            "{} ({} ago)",
+            session
+                .created_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            elapsed_since(session.created_at)
+        );
+        let clients = match session.clients {
+            0 => "none attached".to_string(),
+            n => format!("{} attached", n),
+        };
+        let group = match &details.group {
+            Some(group) => format!("{} ({} sessions)", group, details.group_size),
+            None => "none".to_string(),
+        };
+        let set: Vec<&str> = self
+            .environment
+            .iter()
+            .filter(|v| v.value.is_some())
+            .map(|v| v.name.as_str())
+            .collect();
+        let removed = self.environment.len() - set.len();
+        let mut environment = format!("{} set, {} removed", set.len(), removed);
+        if !set.is_empty() {
+            environment.push_str(&format!(": {}", set.join(", ")));
+        }
+
+        let label = Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD);
+        let value = Style::default().fg(theme.text);
+        let rows = [
+            ("Id", session.id.clone()),
+            ("Created", created),
+            (
+                "Activity",
+                format!("{} ago", elapsed_since(session.last_activity)),
+            ),
+            ("Clients", clients),
+            ("Size", format!("{}x{}", details.width, details.height)),
+            ("Group", group),
+            (
+                "Window",
+                format!("{}: {}", details.window_index, details.window_name),
+            ),
+            (
+                "Pane",
+                format!("{} ({})", details.pane_index, details.pane_command),
+            ),
+            ("Environment", environment),
+        ];
+        let lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(name, text)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<12}", name), label),
+                    Span::styled(text, value),
+                ])
+            })
+            .collect();
+
+        let panel = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block);
+        f.render_widget(panel, area);
+    }
+}
@@ -0,0 +1,306 @@
+//! Inspector for the environment of a session and the global environment, e.g. to
+//! find a stale `SSH_AUTH_SOCK` in a session that outlived its ssh connection
+
+use super::theme::Theme;
+use super::{App, InputMode, SessionRef, View};
+use crate::tmux::{TmuxBackend, TmuxVariable};
+use crate::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+/// Popup listing the variables of one environment
+pub(crate) struct EnvironmentBrowser {
+    /// Server the environment belongs to, `None` for the primary server
+    server: Option<String>,
+    /// Environments Tab switches between: a session target, or `None` for the
+    /// global one, with labels such as `session 'work'`
+    scopes: Vec<(Option<String>, String)>,
+    scope: usize,
+    variables: Vec<TmuxVariable>,
+    selected: ListState,
+    /// Variable whose value is being edited, `None` while adding a new one
+    editing: Option<String>,
+}
+
+impl EnvironmentBrowser {
+    fn new(server: Option<String>, scopes: Vec<(Option<String>, String)>) -> Self {
+        Self {
+            server,
+            scopes,
+            scope: 0,
+            variables: Vec::new(),
+            selected: ListState::default(),
+            editing: None,
+        }
+    }
+
+    fn scope(&self) -> &(Option<String>, String) {
+        &self.scopes[self.scope]
+    }
+
+    /// Replace the variables, selecting `name` if given or else staying at the same position
+    fn set_variables(&mut self, variables: Vec<TmuxVariable>, name: Option<&str>) {
+        let index = name
+            .and_then(|name| variables.iter().position(|v| v.name == name))
+            .unwrap_or(self.selected.selected().unwrap_or(0));
+        self.selected
+            .select((!variables.is_empty()).then(|| index.min(variables.len() - 1)));
+        self.variables = variables;
+    }
+
+    fn selected_variable(&self) -> Option<&TmuxVariable> {
+        self.selected.selected().and_then(|i| self.variables.get(i))
+    }
+
+    fn select_next(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            self.selected.select(Some((i + 1) % self.variables.len()));
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            let len = self.variables.len();
+            self.selected.select(Some((i + len - 1) % len));
+        }
+    }
+
+    /// Draw the popup centered over `area`
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let width = area.width.saturating_sub(4).max(1);
+        let height = area.height.saturating_sub(4).max(1);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let items: Vec<ListItem> = self
+            .variables
+            .iter()
+            .map(|variable| match &variable.value {
+                Some(value) => ListItem::new(Line::from(vec![
+                    Span::raw(format!("{}=", variable.name)),
+                    Span::styled(value.clone(), Style::default().fg(theme.secondary)),
+                ])),
+                None => ListItem::new(Line::styled(
+                    format!("-{} (removed from new panes)", variable.name),
+                    Style::default().fg(theme.dim),
+                )),
+            })
+            .collect();
+
+        let mut title = format!("Environment of {}", self.scope().1);
+        let others: Vec<&str> = self
+            .scopes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.scope)
+            .map(|(_, (_, label))| label.as_str())
+            .collect();
+        if !others.is_empty() {
+            title.push_str(&format!(" (Tab: {})", others.join(", ")));
+        }
+        if self.variables.is_empty() {
+            title.push_str(" - empty, n adds a variable");
+        }
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.accent))
+                    .title(title),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        f.render_widget(Clear, popup);
+        f.render_stateful_widget(list, popup, &mut self.selected);
+    }
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Open the environment of the selected session, with the global one a Tab away
+    pub(crate) async fn open_environment(&mut self) -> Result<()> {
+        let session = match &self.view {
+            View::Sessions => self.selected_session().map(SessionRef::of),
+            View::Windows(view) => Some(view.session.clone()),
+            View::Panes(view) => Some(view.parent.session.clone()),
+            View::Tree(_) | View::Clients(_) => None,
+        };
+        let (server, mut scopes) = match session {
+            Some(session) => (
+                session.server.clone(),
+                vec![(
+                    Some(session.tmux_target().to_string()),
+                    format!("session '{}'", session.name),
+                )],
+            ),
+            None => (self.shown_server.clone(), Vec::new()),
+        };
+        scopes.push((None, "the global environment".to_string()));
+
+        self.environment = Some(EnvironmentBrowser::new(server, scopes));
+        if self.reload_environment(None).await {
+            self.input_mode = InputMode::BrowsingEnvironment;
+            self.info(
+                "Enter to edit, n to add, d to unset, Tab for the global environment, ESC to close",
+            );
+        }
+        Ok(())
+    }
+
+    /// Load the variables of the current environment, selecting `name` if given;
+    /// closes the browser if they cannot be listed
+    async fn reload_environment(&mut self, name: Option<String>) -> bool {
+        let Some(browser) = &self.environment else {
+            return false;
+        };
+        let client = self.client_for(browser.server.as_deref()).clone();
+        let session = browser.scope().0.clone();
+        match client
+            .blocking(move |c| c.show_environment(session.as_deref()))
+            .await
+        {
+            Ok(variables) => {
+                if let Some(browser) = self.environment.as_mut() {
+                    browser.set_variables(variables, name.as_deref());
+                }
+                true
+            }
+            Err(e) => {
+                self.environment = None;
+                self.input_mode = InputMode::Normal;
+                self.report_error(format!("Error listing the environment: {:#}", e));
+                false
+            }
+        }
+    }
+
+    pub(crate) async fn handle_browsing_environment_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(browser) = self.environment.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Down => browser.select_next(),
+            KeyCode::Up => browser.select_previous(),
+            KeyCode::Tab | KeyCode::BackTab => {
+                browser.scope = (browser.scope + 1) % browser.scopes.len();
+                browser.selected.select(None);
+                self.reload_environment(None).await;
+            }
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if let Some(variable) = browser.selected_variable().cloned() {
+                    browser.editing = Some(variable.name);
+                    self.input.set(variable.value.unwrap_or_default());
+                    self.input_mode = InputMode::EditingEnvironment;
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('a') => {
+                browser.editing = None;
+                self.input.clear();
+                self.input_mode = InputMode::EditingEnvironment;
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let Some(name) = browser.selected_variable().map(|v| v.name.clone()) else {
+                    return Ok(());
+                };
+                let (session, label) = browser.scope().clone();
+                let server = browser.server.clone();
+                let variable = name.clone();
+                match self
+                    .client_for(server.as_deref())
+                    .blocking(move |c| c.unset_environment(session.as_deref(), &variable))
+                    .await
+                {
+                    Ok(()) => {
+                        self.success(format!("Unset {} in {}", name, label));
+                        self.reload_environment(None).await;
+                    }
+                    Err(e) => self.error(format!("Error unsetting {}: {:#}", name, e)),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.environment = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Closed");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Prompt for a value, e.g. `DISPLAY in session 'work': `, or for a new variable
+    pub(crate) fn environment_prompt(&self) -> String {
+        match &self.environment {
+            Some(browser) => match &browser.editing {
+                Some(name) => format!("{} in {}: ", name, browser.scope().1),
+                None => format!("New variable in {} (NAME=value): ", browser.scope().1),
+            },
+            None => "Value: ".to_string(),
+        }
+    }
+
+    pub(crate) async fn handle_editing_environment_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter => {
+                let Some(browser) = &self.environment else {
+                    self.input_mode = InputMode::Normal;
+                    return Ok(());
+                };
+                let (name, value) = match &browser.editing {
+                    Some(name) => (name.clone(), self.input.to_string()),
+                    None => match self.input.split_once('=') {
+                        Some((name, value)) if !name.trim().is_empty() => {
+                            (name.trim().to_string(), value.to_string())
+                        }
+                        _ => {
+                            self.input_error = Some("expected NAME=value".to_string());
+                            return Ok(());
+                        }
+                    },
+                };
+                let (session, label) = browser.scope().clone();
+                let variable = name.clone();
+                match self
+                    .client_for(browser.server.as_deref())
+                    .blocking(move |c| c.set_environment(session.as_deref(), &variable, &value))
+                    .await
+                {
+                    Ok(()) => {
+                        self.success(format!(
+                            "Set {} in {}; running panes keep their old value",
+                            name, label
+                        ));
+                        self.input.clear();
+                        self.input_mode = InputMode::BrowsingEnvironment;
+                        self.reload_environment(Some(name)).await;
+                    }
+                    // Stay in the prompt so the value can be fixed
+                    Err(e) => self.input_error = Some(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.input_mode = InputMode::BrowsingEnvironment;
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+}
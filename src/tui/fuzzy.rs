@@ -0,0 +1,104 @@
+//! Fuzzy matching for the session search, scored in the spirit of skim/fzf:
+//! every pattern character must appear in order, and matches that are
+//! consecutive or start at word boundaries score higher.
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_CONSECUTIVE: i64 = 12;
+const BONUS_BOUNDARY: i64 = 10;
+const BONUS_FIRST_CHAR: i64 = 8;
+const PENALTY_GAP: i64 = 1;
+
+/// A successful match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Character indices of `text` that matched the pattern
+    pub positions: Vec<usize>,
+}
+
+/// Match `pattern` against `text`.
+///
+/// Matching is case-insensitive unless the pattern contains an upper-case
+/// character (smart case). An empty pattern matches everything with score 0.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let case_sensitive = pattern.chars().any(char::is_uppercase);
+    let normalize = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(normalize)
+        .collect();
+    let text: Vec<char> = text.chars().collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    // best[i][j]: best score matching pattern[..=i] with pattern[i] at text[j]
+    let mut best: Vec<Vec<Option<i64>>> = vec![vec![None; text.len()]; pattern.len()];
+    let mut from: Vec<Vec<usize>> = vec![vec![0; text.len()]; pattern.len()];
+
+    for (i, &p) in pattern.iter().enumerate() {
+        for (j, &t) in text.iter().enumerate() {
+            if normalize(t) != p {
+                continue;
+            }
+            let bonus = char_bonus(&text, j);
+            if i == 0 {
+                best[i][j] = Some(SCORE_MATCH + bonus - j as i64 * PENALTY_GAP / 4);
+                continue;
+            }
+            for k in 0..j {
+                let Some(previous) = best[i - 1][k] else {
+                    continue;
+                };
+                let score = if k + 1 == j {
+                    previous + SCORE_MATCH + bonus.max(BONUS_CONSECUTIVE)
+                } else {
+                    previous + SCORE_MATCH + bonus - (j - k - 1) as i64 * PENALTY_GAP
+                };
+                if !matches!(best[i][j], Some(s) if s >= score) {
+                    best[i][j] = Some(score);
+                    from[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let last = pattern.len() - 1;
+    let (mut j, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, s)| s.map(|s| (j, s)))
+        .max_by_key(|&(j, s)| (s, std::cmp::Reverse(j)))?;
+
+    let mut positions = vec![0; pattern.len()];
+    for i in (0..pattern.len()).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Bonus for matching at the start of the text or of a word
+fn char_bonus(text: &[char], index: usize) -> i64 {
+    if index == 0 {
+        return BONUS_FIRST_CHAR + BONUS_BOUNDARY;
+    }
+    let previous = text[index - 1];
+    let current = text[index];
+    if !previous.is_alphanumeric() || (previous.is_lowercase() && current.is_uppercase()) {
+        BONUS_BOUNDARY
+    } else {
+        0
+    }
+}
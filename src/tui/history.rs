@@ -0,0 +1,161 @@
+//! Sessions switched to from the TUI, for jumping back to the previous one
+
+use super::picker::Picker;
+use super::{App, InputMode, SessionRef};
+use crate::tmux::TmuxBackend;
+use crate::usage::{recent_sessions, EventKind};
+use crate::Result;
+use crossterm::event::KeyCode;
+
+/// How many sessions the history keeps
+const HISTORY_LEN: usize = 10;
+
+/// Visited sessions, most recent first
+#[derive(Debug, Default)]
+pub(crate) struct SessionHistory {
+    entries: Vec<SessionRef>,
+}
+
+impl SessionHistory {
+    /// Move a session to the front
+    pub fn visit(&mut self, session: SessionRef) {
+        self.entries.retain(|s| !s.same_session(&session));
+        self.entries.insert(0, session);
+        self.entries.truncate(HISTORY_LEN);
+    }
+
+    /// Add a session visited before all sessions in the history
+    fn push_older(&mut self, session: SessionRef) {
+        if self.entries.len() < HISTORY_LEN
+            && !self.entries.iter().any(|s| s.same_session(&session))
+        {
+            self.entries.push(session);
+        }
+    }
+
+    /// The two most recent sessions trade places, as after `switch-client -l`
+    fn swap_last(&mut self) {
+        if self.entries.len() >= 2 {
+            self.entries.swap(0, 1);
+        }
+    }
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Start the history with the session tmux-ui runs in, followed by the
+    /// sessions last attached to according to the usage log
+    pub(crate) fn load_history(&mut self) {
+        let mut names: Vec<String> = self.original_session.iter().cloned().collect();
+        if let Some(events) = self.usage_log.as_ref().and_then(|log| log.read().ok()) {
+            names.extend(recent_sessions(&events, HISTORY_LEN));
+        }
+        for name in names {
+            // The log does not name servers, so only the primary server is searched
+            if let Some(session) = self
+                .sessions
+                .iter()
+                .find(|s| s.server.is_none() && s.name == name)
+            {
+                self.history.push_older(SessionRef::of(session));
+            }
+        }
+    }
+
+    /// Sessions of the history that still exist, under their current names
+    fn recent_sessions(&self) -> Vec<SessionRef> {
+        self.history
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                self.sessions
+                    .iter()
+                    .map(SessionRef::of)
+                    .find(|s| s.same_session(entry))
+            })
+            .collect()
+    }
+
+    /// Go back to the session used before the current one. Returns whether the
+    /// TUI should exit to attach.
+    pub(crate) async fn switch_to_last_session(&mut self) -> Result<bool> {
+        if self.client.is_inside_this_server() {
+            // tmux knows the client's previous session best, including switches made
+            // outside tmux-ui
+            match self.client.blocking(|c| c.switch_client_last()).await {
+                Ok(()) => {
+                    self.history.swap_last();
+                    let current = self.recent_sessions().into_iter().next();
+                    self.success(match &current {
+                        Some(session) => {
+                            self.track(EventKind::Attached, &session.name);
+                            format!("Switched to session '{}'", session)
+                        }
+                        None => "Switched to the last session".to_string(),
+                    });
+                    self.refresh_sessions().await?;
+                }
+                Err(e) => self.report_error(e.to_string()),
+            }
+            return Ok(false);
+        }
+
+        // Outside tmux no session is current, so the latest one is the previous one
+        match self.recent_sessions().into_iter().next() {
+            Some(target) => {
+                let exclusive = self.config.exclusive_attach;
+                self.attach_or_switch(target, exclusive).await
+            }
+            None => {
+                self.warn("No previous session");
+                Ok(false)
+            }
+        }
+    }
+
+    /// Show the recently visited sessions in a popup
+    pub(crate) fn open_history(&mut self) {
+        let sessions = self.recent_sessions();
+        if sessions.is_empty() {
+            self.warn("No sessions visited yet");
+            return;
+        }
+        let items = sessions
+            .iter()
+            .enumerate()
+            .map(|(i, session)| format!("{}. {}", i + 1, session))
+            .collect();
+        self.history_picker = Some(Picker::new("Recent sessions".to_string(), items));
+        self.history_choices = sessions;
+        self.input_mode = InputMode::PickingHistory;
+        self.info("Choose a session (Enter to switch to it, ESC to cancel)");
+    }
+
+    pub(crate) async fn handle_picking_history_input(&mut self, key: KeyCode) -> Result<bool> {
+        let Some(picker) = self.history_picker.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(false);
+        };
+        match key {
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                let index = picker.selected_index();
+                self.history_picker = None;
+                self.input_mode = InputMode::Normal;
+                let choices = std::mem::take(&mut self.history_choices);
+                if let Some(target) = index.and_then(|i| choices.into_iter().nth(i)) {
+                    let exclusive = self.config.exclusive_attach;
+                    return self.attach_or_switch(target, exclusive).await;
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.history_picker = None;
+                self.history_choices.clear();
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+}
@@ -0,0 +1,363 @@
+//! Manager for tmux hooks: lists the commands run on events such as `session-created`
+//! or `alert-activity`, edits them and adds common ones from templates
+
+use super::picker::Picker;
+use super::theme::Theme;
+use super::{App, InputMode, SessionRef, View};
+use crate::tmux::{HookScope, TmuxBackend, TmuxHook, HOOK_TEMPLATES};
+use crate::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+/// Widest hook column; longer names push their command to the right
+const HOOK_WIDTH: usize = 24;
+
+/// Popup listing the hooks of one scope
+pub(crate) struct HooksBrowser {
+    /// Server the hooks belong to, `None` for the primary server
+    server: Option<String>,
+    /// Scopes Tab switches between, with labels such as `session 'work'`
+    scopes: Vec<(HookScope, String)>,
+    scope: usize,
+    hooks: Vec<TmuxHook>,
+    selected: ListState,
+    /// Hook whose command is being edited, e.g. `pane-exited[1]`, or `None` while
+    /// adding a new one
+    editing: Option<String>,
+    /// Templates to add a hook from, open in `InputMode::PickingHookTemplate`
+    templates: Option<Picker>,
+}
+
+impl HooksBrowser {
+    fn new(server: Option<String>, scopes: Vec<(HookScope, String)>) -> Self {
+        Self {
+            server,
+            scopes,
+            scope: 0,
+            hooks: Vec::new(),
+            selected: ListState::default(),
+            editing: None,
+            templates: None,
+        }
+    }
+
+    fn scope(&self) -> &(HookScope, String) {
+        &self.scopes[self.scope]
+    }
+
+    /// Replace the hooks, keeping the selection at the same position
+    fn set_hooks(&mut self, hooks: Vec<TmuxHook>) {
+        let index = self.selected.selected().unwrap_or(0);
+        self.selected
+            .select((!hooks.is_empty()).then(|| index.min(hooks.len() - 1)));
+        self.hooks = hooks;
+    }
+
+    fn selected_hook(&self) -> Option<&TmuxHook> {
+        self.selected.selected().and_then(|i| self.hooks.get(i))
+    }
+
+    fn select_next(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            self.selected.select(Some((i + 1) % self.hooks.len()));
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            let len = self.hooks.len();
+            self.selected.select(Some((i + len - 1) % len));
+        }
+    }
+
+    /// Draw the popup centered over `area`, with the template picker on top
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let width = area.width.saturating_sub(4).max(1);
+        let height = area.height.saturating_sub(4).max(1);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let items: Vec<ListItem> = self
+            .hooks
+            .iter()
+            .map(|hook| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:width$} ", hook.target(), width = HOOK_WIDTH)),
+                    Span::styled(hook.command.clone(), Style::default().fg(theme.secondary)),
+                ]))
+            })
+            .collect();
+
+        let mut title = format!("Hooks of {}", self.scope().1);
+        let others: Vec<&str> = self
+            .scopes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.scope)
+            .map(|(_, (_, label))| label.as_str())
+            .collect();
+        if !others.is_empty() {
+            title.push_str(&format!(" (Tab: {})", others.join(", ")));
+        }
+        if self.hooks.is_empty() {
+            title.push_str(" - none, n adds one, t from a template");
+        }
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.accent))
+                    .title(title),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        f.render_widget(Clear, popup);
+        f.render_stateful_widget(list, popup, &mut self.selected);
+        if let Some(picker) = &mut self.templates {
+            picker.render(f, area, theme);
+        }
+    }
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Open the hooks of the selected session, with the global hooks a Tab away
+    pub(crate) async fn open_hooks(&mut self) -> Result<()> {
+        let session = match &self.view {
+            View::Sessions => self.selected_session().map(SessionRef::of),
+            View::Windows(view) => Some(view.session.clone()),
+            View::Panes(view) => Some(view.parent.session.clone()),
+            View::Tree(_) | View::Clients(_) => None,
+        };
+        let (server, mut scopes) = match session {
+            Some(session) => (
+                session.server.clone(),
+                vec![(
+                    HookScope::Session(session.tmux_target().to_string()),
+                    format!("session '{}'", session.name),
+                )],
+            ),
+            None => (self.shown_server.clone(), Vec::new()),
+        };
+        scopes.push((HookScope::Global, "all sessions".to_string()));
+
+        self.hooks = Some(HooksBrowser::new(server, scopes));
+        if self.reload_hooks().await {
+            self.input_mode = InputMode::BrowsingHooks;
+            self.info("Enter to edit, n to add, t for templates, d to remove, Tab for global hooks, ESC to close");
+        }
+        Ok(())
+    }
+
+    /// Load the hooks of the current scope; closes the manager if they cannot be listed
+    async fn reload_hooks(&mut self) -> bool {
+        let Some(browser) = &self.hooks else {
+            return false;
+        };
+        let client = self.client_for(browser.server.as_deref()).clone();
+        let scope = browser.scope().0.clone();
+        match client.blocking(move |c| c.show_hooks(&scope)).await {
+            Ok(hooks) => {
+                if let Some(browser) = self.hooks.as_mut() {
+                    browser.set_hooks(hooks);
+                }
+                true
+            }
+            Err(e) => {
+                self.hooks = None;
+                self.input_mode = InputMode::Normal;
+                self.report_error(format!("Error listing hooks: {:#}", e));
+                false
+            }
+        }
+    }
+
+    pub(crate) async fn handle_browsing_hooks_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(browser) = self.hooks.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Down => browser.select_next(),
+            KeyCode::Up => browser.select_previous(),
+            KeyCode::Tab | KeyCode::BackTab => {
+                browser.scope = (browser.scope + 1) % browser.scopes.len();
+                browser.selected.select(None);
+                self.reload_hooks().await;
+            }
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if let Some(hook) = browser.selected_hook().cloned() {
+                    browser.editing = Some(hook.target());
+                    self.input.set(hook.command);
+                    self.input_mode = InputMode::EditingHook;
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('a') => {
+                browser.editing = None;
+                self.input.clear();
+                self.input_mode = InputMode::EditingHook;
+            }
+            KeyCode::Char('t') => {
+                let labels = HOOK_TEMPLATES
+                    .iter()
+                    .map(|t| format!("{} ({})", t.label, t.hook))
+                    .collect();
+                browser.templates = Some(Picker::new("Add a hook".to_string(), labels));
+                self.input_mode = InputMode::PickingHookTemplate;
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let Some(hook) = browser.selected_hook().map(TmuxHook::target) else {
+                    return Ok(());
+                };
+                let (scope, label) = browser.scope().clone();
+                let server = browser.server.clone();
+                let target = hook.clone();
+                match self
+                    .client_for(server.as_deref())
+                    .blocking(move |c| c.unset_hook(&scope, &target))
+                    .await
+                {
+                    Ok(()) => {
+                        self.success(format!("Removed {} from {}", hook, label));
+                        self.reload_hooks().await;
+                    }
+                    Err(e) => self.error(format!("Error removing {}: {:#}", hook, e)),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.hooks = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Closed");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn handle_picking_hook_template_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(browser) = self.hooks.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let Some(picker) = browser.templates.as_mut() else {
+            self.input_mode = InputMode::BrowsingHooks;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                let template = picker.selected_index().and_then(|i| HOOK_TEMPLATES.get(i));
+                browser.templates = None;
+                self.input_mode = InputMode::BrowsingHooks;
+                let Some(template) = template else {
+                    return Ok(());
+                };
+                if let Err(e) = self.add_hook(template.hook, template.command).await {
+                    self.error(format!("Error adding {}: {:#}", template.hook, e));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                browser.templates = None;
+                self.input_mode = InputMode::BrowsingHooks;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Append a command to a hook of the current scope, keeping its other commands
+    async fn add_hook(&mut self, hook: &str, command: &str) -> Result<()> {
+        let Some(browser) = &self.hooks else {
+            return Ok(());
+        };
+        let (scope, label) = browser.scope().clone();
+        let (hook_name, command) = (hook.to_string(), command.to_string());
+        self.client_for(browser.server.as_deref())
+            .blocking(move |c| c.set_hook(&scope, &hook_name, &command, true))
+            .await?;
+        self.success(format!("Added {} to {}", hook, label));
+        self.reload_hooks().await;
+        Ok(())
+    }
+
+    /// Prompt for a command, e.g. `pane-exited[0] in session 'work': `, or for a new hook
+    pub(crate) fn hook_prompt(&self) -> String {
+        match &self.hooks {
+            Some(browser) => match &browser.editing {
+                Some(hook) => format!("{} in {}: ", hook, browser.scope().1),
+                None => format!("New hook in {} (hook command): ", browser.scope().1),
+            },
+            None => "Command: ".to_string(),
+        }
+    }
+
+    pub(crate) async fn handle_editing_hook_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter => {
+                let Some(browser) = &self.hooks else {
+                    self.input_mode = InputMode::Normal;
+                    return Ok(());
+                };
+                let Some(hook) = browser.editing.clone() else {
+                    let Some((hook, command)) = self
+                        .input
+                        .trim()
+                        .split_once(char::is_whitespace)
+                        .map(|(hook, command)| (hook.to_string(), command.trim().to_string()))
+                    else {
+                        self.input_error = Some("expected a hook and a command".to_string());
+                        return Ok(());
+                    };
+                    match self.add_hook(&hook, &command).await {
+                        Ok(()) => {
+                            self.input.clear();
+                            self.input_mode = InputMode::BrowsingHooks;
+                        }
+                        Err(e) => self.input_error = Some(format!("{:#}", e)),
+                    }
+                    return Ok(());
+                };
+                let (scope, label) = browser.scope().clone();
+                let (target, command) = (hook.clone(), self.input.to_string());
+                match self
+                    .client_for(browser.server.as_deref())
+                    .blocking(move |c| c.set_hook(&scope, &target, &command, false))
+                    .await
+                {
+                    Ok(()) => {
+                        self.success(format!("Set {} in {}", hook, label));
+                        self.input.clear();
+                        self.input_mode = InputMode::BrowsingHooks;
+                        self.reload_hooks().await;
+                    }
+                    // Stay in the prompt so the command can be fixed
+                    Err(e) => self.input_error = Some(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.input_mode = InputMode::BrowsingHooks;
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+//! Session icons, selected with `icons = "<set>"` in the config file
+
+use anyhow::Result;
+use std::fmt;
+use std::str::FromStr;
+
+/// The characters icons are drawn with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IconSet {
+    /// Glyphs of a patched Nerd Font
+    NerdFont,
+    /// Symbols most terminal fonts have
+    #[default]
+    Unicode,
+    /// Plain ASCII, for dumb terminals and serial consoles
+    Ascii,
+}
+
+/// Direction of an arrow key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// What a session's active pane is running, as far as its icon is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Program {
+    Editor,
+    Node,
+    Docker,
+    Ssh,
+    Shell,
+    Other,
+}
+
+impl Program {
+    fn of(command: &str) -> Self {
+        let name = command.rsplit('/').next().unwrap_or(command);
+        match name.to_ascii_lowercase().as_str() {
+            "vim" | "nvim" | "vi" | "view" | "nano" | "emacs" | "hx" | "helix" | "kak" => {
+                Program::Editor
+            }
+            "node" | "npm" | "npx" | "yarn" | "pnpm" | "deno" | "bun" => Program::Node,
+            "docker" | "docker-compose" | "podman" | "lazydocker" => Program::Docker,
+            "ssh" | "mosh" | "mosh-client" | "autossh" => Program::Ssh,
+            "" | "bash" | "zsh" | "fish" | "sh" | "dash" | "ksh" | "tcsh" | "nu" => Program::Shell,
+            _ => Program::Other,
+        }
+    }
+}
+
+impl IconSet {
+    /// Names of the icon sets, as written in the config file
+    pub const NAMES: [&'static str; 3] = ["nerd-font", "unicode", "ascii"];
+
+    /// Icon for the command running in a session's active pane
+    pub fn command(self, command: &str) -> &'static str {
+        let program = Program::of(command);
+        match self {
+            IconSet::NerdFont => match program {
+                Program::Editor => "\u{e62b}",
+                Program::Node => "\u{e718}",
+                Program::Docker => "\u{f308}",
+                Program::Ssh => "\u{f233}",
+                Program::Shell => "\u{e795}",
+                Program::Other => "\u{f013}",
+            },
+            IconSet::Unicode => match program {
+                Program::Editor => "✎",
+                Program::Node => "⬢",
+                Program::Docker => "▣",
+                Program::Ssh => "⇄",
+                Program::Shell => "›",
+                Program::Other => "•",
+            },
+            IconSet::Ascii => match program {
+                Program::Editor => "E",
+                Program::Node => "N",
+                Program::Docker => "D",
+                Program::Ssh => "@",
+                Program::Shell => "$",
+                Program::Other => ">",
+            },
+        }
+    }
+
+    /// Marker of attached sessions, active windows and active panes
+    pub fn attached(self, attached: bool) -> &'static str {
+        match (self, attached) {
+            (IconSet::NerdFont, true) => "\u{f111}",
+            (IconSet::NerdFont, false) => "\u{f10c}",
+            (IconSet::Unicode, true) => "●",
+            (IconSet::Unicode, false) => "○",
+            (IconSet::Ascii, true) => "*",
+            (IconSet::Ascii, false) => ".",
+        }
+    }
+
+    /// Branch in front of a grouped session: the last of its group or another one
+    pub fn branch(self, last: bool) -> &'static str {
+        match (self, last) {
+            (IconSet::Ascii, true) => "`-",
+            (IconSet::Ascii, false) => "|-",
+            (_, true) => "└─",
+            (_, false) => "├─",
+        }
+    }
+
+    /// Marker of the column the session table is sorted by
+    pub fn sort_arrow(self, descending: bool) -> &'static str {
+        match (self, descending) {
+            (IconSet::Ascii, true) => "v",
+            (IconSet::Ascii, false) => "^",
+            (_, true) => "▼",
+            (_, false) => "▲",
+        }
+    }
+
+    /// Prefix of error messages
+    pub fn warning(self) -> &'static str {
+        match self {
+            IconSet::Ascii => "!",
+            _ => "⚠",
+        }
+    }
+
+    /// Sign of text that is still to come
+    pub fn ellipsis(self) -> &'static str {
+        match self {
+            IconSet::Ascii => "...",
+            _ => "…",
+        }
+    }
+
+    /// Dash between a message and a remark on it
+    pub fn dash(self) -> &'static str {
+        match self {
+            IconSet::Ascii => "-",
+            _ => "—",
+        }
+    }
+
+    /// Arrow between a thing and what it leads to
+    pub fn arrow(self) -> &'static str {
+        match self {
+            IconSet::Ascii => "->",
+            _ => "→",
+        }
+    }
+
+    /// Name of an arrow key in key hints
+    pub fn arrow_key(self, direction: Direction) -> &'static str {
+        match (self, direction) {
+            (IconSet::Ascii, Direction::Up) => "Up",
+            (IconSet::Ascii, Direction::Down) => "Down",
+            (IconSet::Ascii, Direction::Left) => "Left",
+            (IconSet::Ascii, Direction::Right) => "Right",
+            (_, Direction::Up) => "↑",
+            (_, Direction::Down) => "↓",
+            (_, Direction::Left) => "←",
+            (_, Direction::Right) => "→",
+        }
+    }
+
+    /// The up and down arrow keys together, as in "scroll with ↑↓"
+    pub fn up_down(self) -> &'static str {
+        match self {
+            IconSet::Ascii => "Up/Down",
+            _ => "↑↓",
+        }
+    }
+
+    /// Marker of an expanded or collapsed tree node
+    pub fn expander(self, expanded: bool) -> &'static str {
+        match (self, expanded) {
+            (IconSet::Ascii, true) => "-",
+            (IconSet::Ascii, false) => "+",
+            (_, true) => "▾",
+            (_, false) => "▸",
+        }
+    }
+}
+
+impl FromStr for IconSet {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "nerd-font" | "nerdfont" | "nerd" => Ok(IconSet::NerdFont),
+            "unicode" => Ok(IconSet::Unicode),
+            "ascii" => Ok(IconSet::Ascii),
+            other => anyhow::bail!(
+                "Unknown icon set '{}' (expected one of: {})",
+                other,
+                Self::NAMES.join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for IconSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            IconSet::NerdFont => "nerd-font",
+            IconSet::Unicode => "unicode",
+            IconSet::Ascii => "ascii",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,657 @@
+//! Mapping of keys to TUI actions, with user overrides from the config file
+
+use super::icons::{Direction, IconSet};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// List a key is pressed in, for keys whose action depends on what is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Sessions,
+    Windows,
+    Panes,
+    Tree,
+    Clients,
+}
+
+/// Something the user can trigger from the session, window or pane list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    Up,
+    Down,
+    /// Select the first entry of the list
+    Top,
+    /// Select the last entry of the list
+    Bottom,
+    /// Move the selection up by the rows the list shows
+    PageUp,
+    /// Move the selection down by the rows the list shows
+    PageDown,
+    /// Move the selection up by half the rows the list shows
+    HalfPageUp,
+    /// Move the selection down by half the rows the list shows
+    HalfPageDown,
+    /// New session, or new window in the window view
+    New,
+    /// Delete the selected session, window or pane
+    Kill,
+    /// Recreate the session or window killed last, within a few minutes of the kill
+    Undo,
+    /// Save the selected session to the archive on disk, then kill it
+    Archive,
+    /// Browse the archived sessions, to restore or delete one
+    Archived,
+    /// Rename the selected session or window, or respawn the selected dead pane
+    Rename,
+    /// Attach to (or switch to) the selected session, window or pane
+    Attach,
+    /// Back to the management session, or up one level from windows and panes
+    Back,
+    Detach,
+    NewWindow,
+    /// Drill down from sessions to windows and from windows to panes
+    DrillDown,
+    /// Make the selected pane active without switching to it
+    SelectPane,
+    /// Zoom the selected pane to fill its window, or unzoom it
+    ToggleZoom,
+    TogglePreview,
+    /// Show or hide the detail panel of the selected session, or the processes of the selected pane
+    ToggleDetails,
+    TypeIntoPane,
+    FormatQuery,
+    TmuxCommand,
+    ToggleServers,
+    Refresh,
+    DismissError,
+    /// Filter the session list
+    Search,
+    SaveSnapshot,
+    RestoreSnapshot,
+    /// Create a session from a project template
+    StartTemplate,
+    /// Type a command into the selected session, window or pane and press Enter
+    SendCommand,
+    /// Run a shell command in a new window or a popup
+    RunCommand,
+    /// Open a shell in a popup, in the directory of the selected session, window or pane
+    ScratchTerminal,
+    /// Arrange the panes of the selected window with a preset or a layout string
+    Layout,
+    /// Split the selected pane, putting the new pane to its right
+    SplitHorizontal,
+    /// Split the selected pane, putting the new pane below it
+    SplitVertical,
+    /// Open the selected session in a new terminal window
+    AttachNewTerminal,
+    /// Attach and detach every other client from the session
+    AttachExclusive,
+    /// Kill all idle sessions, after confirmation
+    Prune,
+    /// Kill all detached sessions without activity for `stale_after_days`, after confirmation
+    PruneStale,
+    /// Swap the selected window, or pane in the pane list, with the one before it
+    MoveWindowUp,
+    /// Swap the selected window, or pane in the pane list, with the one after it
+    MoveWindowDown,
+    /// Move the selected window to another session
+    MoveWindow,
+    /// Link the selected window into another session as well
+    LinkWindow,
+    /// Remove the selected window from this session, keeping it in the others it is linked into
+    UnlinkWindow,
+    /// Send keys typed into one pane of the selected window to all of its panes
+    ToggleSync,
+    /// Move every pane of the selected window one position on, keeping the layout
+    RotatePanes,
+    /// Move the selected pane into a window of its own
+    BreakPane,
+    /// Move the selected pane into another window
+    JoinPane,
+    /// Start or stop logging the output of the selected pane to a file
+    ToggleLog,
+    /// Set the tags of the selected session
+    EditTags,
+    /// Show only sessions with a tag, cycling through the tags in use
+    FilterTag,
+    /// Group the session list by tag
+    ToggleGroups,
+    /// Sort the session table by the next column, cycling back to the configured order
+    SortColumn,
+    /// Reverse the order of the column the session table is sorted by
+    ReverseSort,
+    /// Switch between the session list and the session tree
+    ToggleTree,
+    /// Expand the selected tree node, or show the windows or panes of the selection
+    Expand,
+    /// Collapse the selected tree node or go to its parent, or up one level from windows and panes
+    Collapse,
+    /// Choose the server whose sessions are shown, or all of them
+    SwitchServer,
+    /// Switch back to the session used before the current one
+    LastSession,
+    /// Choose from the recently visited sessions
+    History,
+    /// Copy the selected session under a new name
+    CloneSession,
+    /// Browse the paste buffers, to paste one or copy it to the clipboard
+    Buffers,
+    /// Browse and edit the options of the selected session or window, or the server
+    Options,
+    /// Inspect and edit the environment of the selected session, or the global one
+    Environment,
+    /// Manage the hooks of the selected session and the global ones
+    Hooks,
+    /// Show the clients attached to the server, to detach a single one
+    Clients,
+}
+
+impl Action {
+    /// All actions with their config names, in the order they are listed in help
+    pub const ALL: [(Action, &'static str); 72] = [
+        (Action::Quit, "quit"),
+        (Action::Help, "help"),
+        (Action::Up, "up"),
+        (Action::Down, "down"),
+        (Action::Top, "top"),
+        (Action::Bottom, "bottom"),
+        (Action::PageUp, "page_up"),
+        (Action::PageDown, "page_down"),
+        (Action::HalfPageUp, "half_page_up"),
+        (Action::HalfPageDown, "half_page_down"),
+        (Action::New, "new"),
+        (Action::Kill, "kill"),
+        (Action::Undo, "undo"),
+        (Action::Archive, "archive"),
+        (Action::Archived, "archived"),
+        (Action::Rename, "rename"),
+        (Action::Attach, "attach"),
+        (Action::Back, "back"),
+        (Action::LastSession, "last_session"),
+        (Action::History, "history"),
+        (Action::CloneSession, "clone_session"),
+        (Action::Detach, "detach"),
+        (Action::NewWindow, "new_window"),
+        (Action::DrillDown, "drill_down"),
+        (Action::SelectPane, "select_pane"),
+        (Action::ToggleZoom, "toggle_zoom"),
+        (Action::TogglePreview, "toggle_preview"),
+        (Action::ToggleDetails, "toggle_details"),
+        (Action::TypeIntoPane, "type_into_pane"),
+        (Action::FormatQuery, "format_query"),
+        (Action::TmuxCommand, "tmux_command"),
+        (Action::ToggleServers, "toggle_servers"),
+        (Action::SwitchServer, "switch_server"),
+        (Action::Refresh, "refresh"),
+        (Action::DismissError, "dismiss_error"),
+        (Action::Search, "search"),
+        (Action::SaveSnapshot, "save_snapshot"),
+        (Action::RestoreSnapshot, "restore_snapshot"),
+        (Action::StartTemplate, "start_template"),
+        (Action::SendCommand, "send_command"),
+        (Action::RunCommand, "run_command"),
+        (Action::ScratchTerminal, "scratch_terminal"),
+        (Action::Buffers, "buffers"),
+        (Action::Options, "options"),
+        (Action::Environment, "environment"),
+        (Action::Hooks, "hooks"),
+        (Action::Clients, "clients"),
+        (Action::SplitHorizontal, "split_horizontal"),
+        (Action::SplitVertical, "split_vertical"),
+        (Action::Layout, "layout"),
+        (Action::AttachNewTerminal, "attach_new_terminal"),
+        (Action::AttachExclusive, "attach_exclusive"),
+        (Action::Prune, "prune"),
+        (Action::PruneStale, "prune_stale"),
+        (Action::MoveWindowUp, "move_window_up"),
+        (Action::MoveWindowDown, "move_window_down"),
+        (Action::MoveWindow, "move_window"),
+        (Action::LinkWindow, "link_window"),
+        (Action::UnlinkWindow, "unlink_window"),
+        (Action::ToggleSync, "toggle_sync"),
+        (Action::RotatePanes, "rotate_panes"),
+        (Action::BreakPane, "break_pane"),
+        (Action::JoinPane, "join_pane"),
+        (Action::ToggleLog, "toggle_log"),
+        (Action::EditTags, "edit_tags"),
+        (Action::FilterTag, "filter_tag"),
+        (Action::ToggleGroups, "toggle_groups"),
+        (Action::SortColumn, "sort_column"),
+        (Action::ReverseSort, "reverse_sort"),
+        (Action::ToggleTree, "toggle_tree"),
+        (Action::Expand, "expand"),
+        (Action::Collapse, "collapse"),
+    ];
+
+    /// Name used for this action in the `[keys]` config table
+    pub fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(action, _)| *action == self)
+            .map(|(_, name)| *name)
+            .unwrap_or("")
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(action, _)| *action)
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Help => &["h"],
+            Action::Up => &["Up"],
+            Action::Down => &["Down"],
+            Action::Top => &["Home"],
+            Action::Bottom => &["End"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::HalfPageUp | Action::HalfPageDown => &[],
+            Action::New => &["n"],
+            Action::Kill => &["d"],
+            Action::Undo => &["u"],
+            Action::Archive => &["v"],
+            Action::Archived => &["F"],
+            Action::Rename => &["r"],
+            Action::Attach => &["a", "Enter"],
+            Action::Back => &["Esc", "b"],
+            Action::LastSession => &["l"],
+            Action::History => &["H"],
+            Action::CloneSession => &[],
+            Action::Detach => &["x"],
+            Action::NewWindow => &["w"],
+            Action::DrillDown => &["Tab"],
+            Action::SelectPane => &["s"],
+            Action::ToggleZoom => &["z"],
+            Action::TogglePreview => &["p"],
+            Action::ToggleDetails => &["i"],
+            Action::TypeIntoPane => &["T"],
+            Action::FormatQuery => &["?"],
+            Action::TmuxCommand => &[":"],
+            Action::ToggleServers => &["M"],
+            Action::SwitchServer => &["V"],
+            Action::Refresh => &["R"],
+            Action::DismissError => &["C"],
+            Action::Search => &["/"],
+            Action::SaveSnapshot => &["S"],
+            Action::RestoreSnapshot => &["L"],
+            Action::StartTemplate => &["N"],
+            Action::SendCommand => &["c", "e"],
+            Action::RunCommand => &["!"],
+            Action::ScratchTerminal => &["`"],
+            Action::Buffers => &["B"],
+            Action::Options => &["O"],
+            Action::Environment => &["E"],
+            Action::Hooks => &["K"],
+            Action::Clients => &["X"],
+            Action::SplitHorizontal => &["|"],
+            Action::SplitVertical => &["-"],
+            Action::Layout => &["Space"],
+            Action::AttachNewTerminal => &["A"],
+            Action::AttachExclusive => &["S-Enter"],
+            Action::Prune => &["P"],
+            Action::PruneStale => &["Z"],
+            Action::MoveWindowUp => &["S-Up"],
+            Action::MoveWindowDown => &["S-Down"],
+            Action::RotatePanes => &["C-o"],
+            Action::MoveWindow => &["m"],
+            Action::LinkWindow => &["I"],
+            Action::UnlinkWindow => &["U"],
+            Action::ToggleSync => &[],
+            Action::BreakPane => &["W"],
+            Action::JoinPane => &["J"],
+            Action::ToggleLog => &["o"],
+            Action::EditTags => &["g"],
+            Action::FilterTag => &["f"],
+            Action::ToggleGroups => &["G"],
+            Action::SortColumn => &[">"],
+            Action::ReverseSort => &["<"],
+            Action::ToggleTree => &["t"],
+            Action::Expand => &["Right"],
+            Action::Collapse => &["Left"],
+        }
+    }
+
+    /// Keys bound in one list only, where they take precedence over the keys
+    /// other actions have in every list
+    fn view_keys(self) -> &'static [(Scope, &'static str)] {
+        match self {
+            // c sends a command in the other lists, and e in this one
+            Action::CloneSession => &[(Scope::Sessions, "c")],
+            // S saves a snapshot from the session list
+            Action::ToggleSync => &[(Scope::Windows, "S")],
+            _ => &[],
+        }
+    }
+
+    /// Keys replacing the default ones in vim mode; `g` and `h`/`l` move elsewhere
+    /// since `gg`, `G`, `h` and `l` navigate there
+    fn vim_keys(self) -> Option<&'static [&'static str]> {
+        match self {
+            Action::Up => Some(&["k", "Up"]),
+            Action::Down => Some(&["j", "Down"]),
+            Action::Collapse => Some(&["h", "Left"]),
+            Action::Expand => Some(&["l", "Right"]),
+            Action::Bottom => Some(&["G", "End"]),
+            Action::HalfPageUp => Some(&["C-u"]),
+            Action::HalfPageDown => Some(&["C-d"]),
+            Action::Help => Some(&["F1"]),
+            Action::LastSession => Some(&["'"]),
+            Action::EditTags => Some(&["#"]),
+            Action::ToggleGroups => Some(&["="]),
+            _ => None,
+        }
+    }
+}
+
+/// A key with modifiers, e.g. `C-n` or `Enter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Parse `[C-][M-][S-]<key>`, where key is a single character or a name
+    /// such as `Enter`, `Esc`, `Tab`, `Space`, `Up` or `F5`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = spec;
+        while rest.chars().count() > 2 {
+            let modifier = match rest.get(..2) {
+                Some("C-") => KeyModifiers::CONTROL,
+                Some("M-") => KeyModifiers::ALT,
+                Some("S-") => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            if modifiers.contains(modifier) {
+                anyhow::bail!("Duplicate modifier in key '{}'", spec);
+            }
+            modifiers |= modifier;
+            rest = &rest[2..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" | "bspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "ppage" => KeyCode::PageUp,
+                "pagedown" | "npage" => KeyCode::PageDown,
+                "delete" | "dc" => KeyCode::Delete,
+                "insert" | "ic" => KeyCode::Insert,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => anyhow::bail!("Unknown key '{}'", spec),
+                },
+            },
+        };
+
+        Ok(Self::normalized(code, modifiers))
+    }
+
+    /// Whether the terminal can only send the key with extended keys (`S-Enter`,
+    /// `C-Tab`, `C-1`, ...), which tmux before 3.2 does not pass on
+    pub fn needs_extended_keys(&self) -> bool {
+        if self.modifiers.is_empty() {
+            return false;
+        }
+        match self.code {
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Backspace | KeyCode::Esc => true,
+            KeyCode::Char(c) => {
+                self.modifiers.contains(KeyModifiers::CONTROL)
+                    && !(c.is_ascii_alphabetic() || " @[\\]^_".contains(c))
+            }
+            _ => false,
+        }
+    }
+
+    fn from_event(key: &KeyEvent) -> Self {
+        Self::normalized(key.code, key.modifiers)
+    }
+
+    /// Shift is part of the character itself (`R` vs `r`), so it is ignored for characters
+    fn normalized(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(code, KeyCode::Char(_)) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+}
+
+impl KeyBinding {
+    /// Name of the key in hints, with the arrow keys drawn from `icons`
+    pub fn name(&self, icons: IconSet) -> String {
+        let mut name = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            name.push_str("C-");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            name.push_str("M-");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            name.push_str("S-");
+        }
+        match self.code {
+            KeyCode::Char(' ') => name.push_str("Space"),
+            KeyCode::Char(c) => name.push(c),
+            KeyCode::Enter => name.push_str("Enter"),
+            KeyCode::Esc => name.push_str("Esc"),
+            KeyCode::Tab => name.push_str("Tab"),
+            KeyCode::BackTab => name.push_str("BackTab"),
+            KeyCode::Backspace => name.push_str("Backspace"),
+            KeyCode::Up => name.push_str(icons.arrow_key(Direction::Up)),
+            KeyCode::Down => name.push_str(icons.arrow_key(Direction::Down)),
+            KeyCode::Left => name.push_str(icons.arrow_key(Direction::Left)),
+            KeyCode::Right => name.push_str(icons.arrow_key(Direction::Right)),
+            KeyCode::Home => name.push_str("Home"),
+            KeyCode::End => name.push_str("End"),
+            KeyCode::PageUp => name.push_str("PageUp"),
+            KeyCode::PageDown => name.push_str("PageDown"),
+            KeyCode::Delete => name.push_str("Delete"),
+            KeyCode::Insert => name.push_str("Insert"),
+            KeyCode::F(n) => name.push_str(&format!("F{}", n)),
+            other => name.push_str(&format!("{:?}", other)),
+        }
+        name
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name(IconSet::default()))
+    }
+}
+
+/// Keys of every action.
+///
+/// Actions listed in the config replace all of their default keys, including
+/// the ones bound in a single list; a key taken over by another action is
+/// removed from the action it was bound to by default.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyBinding, Action>,
+    /// Keys bound in one list only, ahead of `bindings` there
+    view_bindings: HashMap<(Scope, KeyBinding), Action>,
+    /// Whether counts (`5j`) and `gg` are read in the lists
+    vim: bool,
+    /// Characters the arrow keys are shown with
+    icons: IconSet,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::with_keys(false, |action| action.default_keys())
+    }
+}
+
+impl Keymap {
+    /// Keymap binding the keys each action gets from `keys`
+    fn with_keys(vim: bool, keys: impl Fn(Action) -> &'static [&'static str]) -> Self {
+        let mut bindings = HashMap::new();
+        let mut view_bindings = HashMap::new();
+        for (action, _) in Action::ALL {
+            for key in keys(action) {
+                let key = KeyBinding::parse(key).expect("default key bindings are valid");
+                bindings.insert(key, action);
+            }
+            for (scope, key) in action.view_keys() {
+                let key = KeyBinding::parse(key).expect("default key bindings are valid");
+                view_bindings.insert((*scope, key), action);
+            }
+        }
+        Self {
+            bindings,
+            view_bindings,
+            vim,
+            icons: IconSet::default(),
+        }
+    }
+
+    /// Keymap of vim mode: `hjkl`, `gg`/`G`, `Ctrl+U`/`Ctrl+D` and counts
+    pub fn vim() -> Self {
+        Self::with_keys(true, |action| {
+            action.vim_keys().unwrap_or(action.default_keys())
+        })
+    }
+
+    /// Show the arrow keys with the characters of `icons`
+    pub fn with_icons(mut self, icons: IconSet) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// Whether this is the keymap of vim mode
+    pub fn is_vim(&self) -> bool {
+        self.vim
+    }
+
+    /// Default keymap with the `[keys]` table from the config applied
+    pub fn with_overrides(overrides: &BTreeMap<String, Vec<String>>) -> Result<Self> {
+        Self::default().overridden(overrides)
+    }
+
+    /// This keymap with the `[keys]` table from the config applied
+    pub fn overridden(self, overrides: &BTreeMap<String, Vec<String>>) -> Result<Self> {
+        let mut keymap = self;
+        let mut bound: HashMap<KeyBinding, Action> = HashMap::new();
+
+        for (name, keys) in overrides {
+            let action = Action::from_name(name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown action '{}' in [keys]", name))?;
+            keymap.bindings.retain(|_, a| *a != action);
+            keymap.view_bindings.retain(|_, a| *a != action);
+
+            for key in keys {
+                let binding = KeyBinding::parse(key)?;
+                if let Some(other) = bound.insert(binding, action) {
+                    if other != action {
+                        anyhow::bail!(
+                            "Key '{}' is bound to both '{}' and '{}'",
+                            key,
+                            other.name(),
+                            action.name()
+                        );
+                    }
+                }
+            }
+        }
+
+        // Keys from the config mean the same in every list
+        keymap
+            .view_bindings
+            .retain(|(_, key), _| !bound.contains_key(key));
+        keymap.bindings.extend(bound);
+        Ok(keymap)
+    }
+
+    /// Unbind the keys that need extended keys, so the help does not offer keys
+    /// that never arrive
+    pub fn remove_extended_keys(&mut self) {
+        self.bindings.retain(|key, _| !key.needs_extended_keys());
+        self.view_bindings
+            .retain(|(_, key), _| !key.needs_extended_keys());
+    }
+
+    /// The action bound to a key press in a list, if any
+    pub fn action(&self, key: &KeyEvent, scope: Scope) -> Option<Action> {
+        let key = KeyBinding::from_event(key);
+        self.view_bindings
+            .get(&(scope, key))
+            .or_else(|| self.bindings.get(&key))
+            .copied()
+    }
+
+    /// Keys bound to an action in a list, shortest first
+    pub fn keys(&self, action: Action, scope: Scope) -> Vec<KeyBinding> {
+        let view_keys = self
+            .view_bindings
+            .iter()
+            .filter(|((s, _), a)| *s == scope && **a == action)
+            .map(|((_, k), _)| *k);
+        let mut keys: Vec<KeyBinding> = self
+            .bindings
+            .iter()
+            .filter(|(k, a)| **a == action && !self.view_bindings.contains_key(&(scope, **k)))
+            .map(|(k, _)| *k)
+            .chain(view_keys)
+            .collect();
+        keys.sort_by_key(|k| {
+            let name = k.to_string();
+            (name.chars().count(), name)
+        });
+        keys
+    }
+
+    /// First key of an action in a list, if it has any
+    pub fn bound_key(&self, action: Action, scope: Scope) -> Option<String> {
+        self.keys(action, scope).first().map(|k| k.name(self.icons))
+    }
+
+    /// First key of an action for the status bar hints; `-` if unbound
+    pub fn first_key(&self, action: Action, scope: Scope) -> String {
+        self.bound_key(action, scope)
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Keys of an action joined for display, e.g. `a/Enter`; `-` if unbound
+    pub fn label(&self, action: Action, scope: Scope) -> String {
+        let keys = self.keys(action, scope);
+        if keys.is_empty() {
+            return "-".to_string();
+        }
+        keys.iter()
+            .map(|k| k.name(self.icons))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Help line listing the keys the given actions have in a list
+    pub fn help(&self, scope: Scope, actions: &[(Action, &str)]) -> String {
+        let entries: Vec<String> = actions
+            .iter()
+            .map(|(action, description)| format!("{}={}", self.label(*action, scope), description))
+            .collect();
+        format!("Commands: {}", entries.join(", "))
+    }
+}
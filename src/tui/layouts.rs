@@ -0,0 +1,331 @@
+//! The layout menu: arranges the panes of the selected window with a tmux preset,
+//! a layout saved by name, or a layout string copied from another window
+
+use super::picker::Picker;
+use super::{App, InputMode, View};
+use crate::clipboard;
+use crate::layouts::SavedLayouts;
+use crate::tmux::{TmuxBackend, LAYOUT_PRESETS};
+use crate::Result;
+use anyhow::Context;
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Window whose panes the layout menu arranges
+#[derive(Debug, Clone)]
+pub(crate) struct LayoutTarget {
+    server: Option<String>,
+    /// Window id, e.g. `@3`
+    window: String,
+    name: String,
+}
+
+/// An entry of the layout menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LayoutEntry {
+    Preset(&'static str),
+    /// A layout saved by name
+    Saved(String),
+    /// Type a layout string
+    Custom,
+    /// Copy the window's layout string to the clipboard
+    Copy,
+    /// Save the window's layout under a name
+    Save,
+}
+
+impl LayoutEntry {
+    fn label(&self) -> String {
+        match self {
+            LayoutEntry::Preset(name) => name.to_string(),
+            LayoutEntry::Saved(name) => format!("{} (saved)", name),
+            LayoutEntry::Custom => "custom layout...".to_string(),
+            LayoutEntry::Copy => "copy current layout".to_string(),
+            LayoutEntry::Save => "save current layout as...".to_string(),
+        }
+    }
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Offer the layout presets and saved layouts for the selected window, or the
+    /// window of the pane list
+    pub(crate) fn open_layout_menu(&mut self) {
+        let target = match &self.view {
+            View::Windows(view) => view.selected_window().map(|window| LayoutTarget {
+                server: view.session.server.clone(),
+                window: window.id.clone(),
+                name: window.name.clone(),
+            }),
+            View::Panes(view) => Some(LayoutTarget {
+                server: view.parent.session.server.clone(),
+                window: view.window.id.clone(),
+                name: view.window.name.clone(),
+            }),
+            View::Sessions | View::Tree(_) | View::Clients(_) => None,
+        };
+        let Some(target) = target else {
+            return;
+        };
+        // Read on every open, so edits to the file show up without a restart
+        let saved = match self.load_saved_layouts() {
+            Ok(saved) => saved,
+            Err(e) => {
+                self.report_error(format!("Error reading saved layouts: {:#}", e));
+                SavedLayouts::default()
+            }
+        };
+
+        let mut entries: Vec<LayoutEntry> = LAYOUT_PRESETS
+            .iter()
+            .map(|preset| LayoutEntry::Preset(preset))
+            .collect();
+        entries.extend(
+            saved
+                .names()
+                .map(|name| LayoutEntry::Saved(name.to_string())),
+        );
+        entries.extend([LayoutEntry::Custom, LayoutEntry::Copy, LayoutEntry::Save]);
+        let items = entries.iter().map(LayoutEntry::label).collect();
+        self.layout_picker = Some(Picker::new(format!("Layout of '{}'", target.name), items));
+        self.layout_entries = entries;
+        self.layout_target = Some(target);
+        self.input_mode = InputMode::PickingLayout;
+        if saved.is_empty() {
+            self.info("Choose a layout (Enter to apply, ESC to cancel)");
+        } else {
+            self.info("Choose a layout (Enter to apply, d to delete a saved one, ESC to cancel)");
+        }
+    }
+
+    fn load_saved_layouts(&self) -> Result<SavedLayouts> {
+        let path = self
+            .config
+            .layouts_file()
+            .context("No file for saved layouts; set `layouts_file` in the config")?;
+        SavedLayouts::load(&path)
+    }
+
+    /// Change the saved layouts and write them back
+    fn update_saved_layouts(
+        &self,
+        change: impl FnOnce(&mut SavedLayouts) -> Result<()>,
+    ) -> Result<()> {
+        let path = self
+            .config
+            .layouts_file()
+            .context("No file for saved layouts; set `layouts_file` in the config")?;
+        let mut saved = SavedLayouts::load(&path)?;
+        change(&mut saved)?;
+        saved.save(&path)
+    }
+
+    /// The layout string of the target window, as `#{window_layout}` prints it
+    async fn current_layout(&self, target: &LayoutTarget) -> Result<String> {
+        let window = target.window.clone();
+        Ok(self
+            .client_for(target.server.as_deref())
+            .blocking(move |c| c.display_message(Some(&window), "#{window_layout}"))
+            .await?)
+    }
+
+    /// Apply a preset or layout string to the target window and show the new pane sizes
+    async fn apply_layout(
+        &mut self,
+        target: &LayoutTarget,
+        label: &str,
+        layout: &str,
+    ) -> Result<()> {
+        let (window, layout) = (target.window.clone(), layout.to_string());
+        self.client_for(target.server.as_deref())
+            .blocking(move |c| c.select_layout(&window, &layout))
+            .await?;
+        self.success(format!("Applied layout {} to '{}'", label, target.name));
+        if matches!(self.view, View::Panes(_)) {
+            self.refresh_panes().await
+        } else {
+            self.refresh_windows().await
+        }
+    }
+
+    fn close_layout_menu(&mut self) {
+        self.layout_picker = None;
+        self.layout_entries.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub(crate) async fn handle_picking_layout_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(picker) = self.layout_picker.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let entry = picker
+            .selected_index()
+            .and_then(|i| self.layout_entries.get(i))
+            .cloned();
+        match key {
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                self.close_layout_menu();
+                let (Some(entry), Some(target)) = (entry, self.layout_target.clone()) else {
+                    return Ok(());
+                };
+                self.choose_layout_entry(entry, target).await;
+            }
+            KeyCode::Char('d') => {
+                let Some(LayoutEntry::Saved(name)) = entry else {
+                    return Ok(());
+                };
+                match self.update_saved_layouts(|saved| {
+                    saved.remove(&name);
+                    Ok(())
+                }) {
+                    Ok(()) => {
+                        self.open_layout_menu();
+                        self.success(format!("Deleted saved layout '{}'", name));
+                    }
+                    Err(e) => self.report_error(format!("Error deleting layout: {:#}", e)),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.close_layout_menu();
+                self.layout_target = None;
+                self.info("Cancelled");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn choose_layout_entry(&mut self, entry: LayoutEntry, target: LayoutTarget) {
+        match entry {
+            LayoutEntry::Preset(preset) => {
+                self.layout_target = None;
+                if let Err(e) = self.apply_layout(&target, preset, preset).await {
+                    self.report_error(format!("Error applying layout: {:#}", e));
+                }
+            }
+            LayoutEntry::Saved(name) => {
+                self.layout_target = None;
+                let layout = self.load_saved_layouts().and_then(|saved| {
+                    saved
+                        .get(&name)
+                        .map(str::to_string)
+                        .with_context(|| format!("'{}' is no longer saved", name))
+                });
+                let label = format!("'{}'", name);
+                let result = match layout {
+                    Ok(layout) => self.apply_layout(&target, &label, &layout).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    self.report_error(format!("Error applying layout: {:#}", e));
+                }
+            }
+            LayoutEntry::Custom => match self.current_layout(&target).await {
+                // Start from the current layout, to edit it or paste over it
+                Ok(layout) => {
+                    self.input.set(layout);
+                    self.input_mode = InputMode::EnteringLayout;
+                }
+                Err(e) => self.report_error(format!("Error reading layout: {:#}", e)),
+            },
+            LayoutEntry::Copy => {
+                self.layout_target = None;
+                match self
+                    .current_layout(&target)
+                    .await
+                    .and_then(|layout| clipboard::copy(&layout))
+                {
+                    Ok(()) => self.success(format!(
+                        "Copied the layout of '{}' to the clipboard",
+                        target.name
+                    )),
+                    Err(e) => self.report_error(format!("Error copying layout: {:#}", e)),
+                }
+            }
+            LayoutEntry::Save => {
+                self.input.clear();
+                self.input_mode = InputMode::NamingLayout;
+            }
+        }
+    }
+
+    pub(crate) fn layout_prompt(&self) -> String {
+        match (&self.input_mode, &self.layout_target) {
+            (InputMode::NamingLayout, Some(target)) => {
+                format!("Save the layout of '{}' as: ", target.name)
+            }
+            (InputMode::NamingLayout, None) => "Save layout as: ".to_string(),
+            (_, Some(target)) => format!("Layout of '{}': ", target.name),
+            (_, None) => "Layout: ".to_string(),
+        }
+    }
+
+    pub(crate) async fn handle_entering_layout_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter if !self.input.is_empty() => {
+                let Some(target) = self.layout_target.clone() else {
+                    self.input_mode = InputMode::Normal;
+                    return Ok(());
+                };
+                let layout = self.input.trim().to_string();
+                match self.apply_layout(&target, &layout, &layout).await {
+                    Ok(()) => {
+                        self.input.clear();
+                        self.layout_target = None;
+                        self.input_mode = InputMode::Normal;
+                    }
+                    // Stay in the prompt so the layout can be fixed
+                    Err(e) => self.input_error = Some(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.layout_target = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn handle_naming_layout_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter if !self.input.is_empty() => {
+                let Some(target) = self.layout_target.clone() else {
+                    self.input_mode = InputMode::Normal;
+                    return;
+                };
+                let name = self.input.trim().to_string();
+                let result = self.current_layout(&target).await.and_then(|layout| {
+                    self.update_saved_layouts(|saved| saved.insert(&name, &layout))
+                });
+                match result {
+                    Ok(()) => {
+                        self.input.clear();
+                        self.layout_target = None;
+                        self.input_mode = InputMode::Normal;
+                        self.success(format!(
+                            "Saved the layout of '{}' as '{}'",
+                            target.name, name
+                        ));
+                    }
+                    // Stay in the prompt so the name can be fixed
+                    Err(e) => self.input_error = Some(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.layout_target = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+    }
+}
@@ -1,31 +1,413 @@
-use crate::tmux::{TmuxClient, TmuxSession};
+use crate::config::Config;
+use crate::sort::{SortKey, SortSpec};
+use crate::template::{collapse_home, Template};
+use crate::tmux::{
+    self, complete_command, parse_tags, split_args, validate_session_name, Alerts, AttachedClient,
+    Capabilities, ControlMode, Key, Notification, SessionSnapshot, SessionTree, Snapshot,
+    SplitDirection, TmuxBackend, TmuxClient, TmuxPane, TmuxSession, TmuxWindow,
+};
+use crate::usage::{format_duration, EventKind, UsageLog};
 use crate::Result;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
     Frame, Terminal,
 };
+use std::fmt;
+use std::fs;
 use std::io;
-use tokio::time::Duration;
+use std::path::PathBuf;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+
+mod archive;
+mod banner;
+mod buffers;
+mod clients;
+mod details;
+mod environment;
+pub mod fuzzy;
+mod history;
+mod hooks;
+pub mod icons;
+pub mod keymap;
+mod layouts;
+mod new_session;
+mod options;
+mod panes;
+mod picker;
+mod preview;
+mod processes;
+mod reload;
+pub mod remote;
+mod run_command;
+mod status;
+pub mod switcher;
+pub mod table;
+pub mod terminal;
+pub mod theme;
+mod tree;
+mod viewer;
+pub mod widgets;
+mod windows;
+
+use archive::ArchiveBrowser;
+use banner::{ErrorBanner, RetryAction};
+use buffers::BufferBrowser;
+use clients::ClientView;
+use details::{DetailsResult, DetailsTarget, SessionDetailsPanel};
+use environment::EnvironmentBrowser;
+use fuzzy::fuzzy_match;
+use history::SessionHistory;
+use hooks::HooksBrowser;
+use icons::IconSet;
+use keymap::{Action, Keymap, Scope};
+use layouts::{LayoutEntry, LayoutTarget};
+use new_session::NewSessionForm;
+use options::OptionsBrowser;
+use panes::PaneView;
+use picker::Picker;
+use preview::PanePreview;
+use processes::ProcessPanel;
+use reload::{fetch_windows, Reload, SessionSource, WindowList};
+use run_command::RunTarget;
+use status::{Severity, StatusMessage};
+use table::{next_sort, SessionColumn};
+use terminal::TerminalSupport;
+use theme::Theme;
+use tree::{NodeKind, TreeView};
+use viewer::OutputView;
+use widgets::{render_scrollbar, Jump, TextInput};
+use windows::WindowView;
+
+/// Identifies a session on one of the servers shown by the TUI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SessionRef {
+    /// Label of the configured server, `None` for the primary server
+    pub server: Option<String>,
+    /// tmux session id, which stays the same when the session is renamed
+    pub id: String,
+    pub name: String,
+}
+
+impl SessionRef {
+    fn of(session: &TmuxSession) -> Self {
+        Self {
+            server: session.server.clone(),
+            id: session.id.clone(),
+            name: session.name.clone(),
+        }
+    }
+
+    /// Whether both refer to the same session, even if it was renamed since
+    fn same_session(&self, other: &SessionRef) -> bool {
+        self.server == other.server && self.id == other.id
+    }
+
+    /// Target to pass to tmux; the id cannot be confused with another session
+    /// that took over the name
+    fn tmux_target(&self) -> &str {
+        if self.id.is_empty() {
+            &self.name
+        } else {
+            &self.id
+        }
+    }
+}
+
+/// Pane a command is sent to or previewed: a session or window id selects its active pane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PaneTarget {
+    server: Option<String>,
+    target: String,
+    /// Shown in status messages, e.g. `window 'editor'`
+    label: String,
+}
+
+/// What choosing a session in the session picker does with `moving_window`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowTransfer {
+    Move,
+    Link,
+}
+
+/// Which sessions the prune confirmation kills
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PruneKind {
+    /// Detached and running only the shell
+    Idle,
+    /// Detached and without activity for `stale_after_days`
+    Stale,
+}
+
+/// How long a killed session or window can be brought back with [`Action::Undo`]
+const UNDO_GRACE: Duration = Duration::from_secs(300);
+
+/// Recording of a session or window taken just before it was killed
+#[derive(Debug, Clone)]
+struct Killed {
+    server: Option<String>,
+    /// A killed window is recorded as the only window of its session
+    snapshot: SessionSnapshot,
+    window: bool,
+    at: Instant,
+}
+
+/// Session, window or pane waiting in the kill confirmation
+#[derive(Debug, Clone)]
+enum PendingKill {
+    /// Session with programs other than the shell running in its panes
+    Session(SessionRef),
+    Window(PaneTarget),
+    Pane(PaneTarget),
+}
+
+impl fmt::Display for SessionRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.server {
+            Some(server) => write!(f, "{}:{}", server, self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Outer margin around the main layout
+const LAYOUT_MARGIN: u16 = 1;
+/// Minimum usable width of the main layout
+const MIN_WIDTH: u16 = 58;
+/// Session list rows including borders, so at least one session is visible
+const MIN_LIST_HEIGHT: u16 = 3;
+
+/// Time from `then` until now, e.g. `2h 5m`
+fn elapsed_since(then: DateTime<Utc>) -> String {
+    format_duration((Utc::now() - then).num_seconds().max(0) as u64)
+}
+
+/// List width from which the pane preview and the detail panels are shown next to
+/// the list; narrower terminals, such as a small tmux pane, only get the list
+const SIDE_PANEL_MIN_WIDTH: u16 = 100;
+/// Terminal height from which the title bar is shown
+const TITLE_MIN_HEIGHT: u16 = 20;
+
+/// Width of a user-defined column without a configured width
+const USER_COLUMN_WIDTH: usize = 12;
+
+/// Server column label for sessions on the primary server
+const DEFAULT_SERVER_LABEL: &str = "default";
+
+/// Badges for the alerts of a session or window, followed by a space
+fn alert_badges(alerts: &Alerts, theme: &Theme) -> Option<Span<'static>> {
+    alerts.any().then(|| {
+        Span::styled(
+            format!("{} ", alerts.badges()),
+            Style::default()
+                .fg(theme.alert)
+                .add_modifier(Modifier::BOLD),
+        )
+    })
+}
+
+/// Group label of a session in the grouped session list: its first tag
+const UNTAGGED_GROUP: &str = "untagged";
+
+fn tag_group(session: &TmuxSession) -> &str {
+    session.tags.first().map_or(UNTAGGED_GROUP, String::as_str)
+}
+
+/// Sort key putting groups in tag order, with untagged sessions last
+fn tag_group_order(session: &TmuxSession) -> (bool, &str) {
+    (session.tags.is_empty(), tag_group(session))
+}
+
+/// Position of a session's server in the combined view: the default server first,
+/// then the configured servers in config order
+fn server_order<C>(servers: &[Server<C>], session: &TmuxSession) -> usize {
+    match &session.server {
+        None => 0,
+        Some(name) => servers
+            .iter()
+            .position(|s| &s.name == name)
+            .map_or(servers.len() + 1, |i| i + 1),
+    }
+}
+
+/// A session shown in the (possibly filtered) session list
+struct SessionMatch {
+    /// Index into `App::sessions`
+    index: usize,
+    /// Character positions of the session name that matched the search
+    positions: Vec<usize>,
+}
+
+/// Which level of the tmux hierarchy is shown in the main list
+enum View {
+    Sessions,
+    Windows(WindowView),
+    Panes(PaneView),
+    Tree(TreeView),
+    Clients(ClientView),
+}
+
+/// An additional tmux server from the config file
+struct Server<C> {
+    name: String,
+    client: C,
+}
 
-/// Application state
-pub struct App {
-    client: TmuxClient,
+/// Application state, generic over the tmux backend so tests can use a mock server
+pub struct App<C: TmuxBackend = TmuxClient> {
+    client: C,
+    servers: Vec<Server<C>>,
+    /// Show the sessions of all servers, grouped by server
+    combined: bool,
+    /// Server shown on its own outside the combined view; `None` is the default server
+    shown_server: Option<String>,
+    config: Config,
+    usage_log: Option<UsageLog>,
     sessions: Vec<TmuxSession>,
-    selected: ListState,
-    input: String,
+    /// Sessions passing the search filter, best match first; `selected` indexes into this
+    visible: Vec<SessionMatch>,
+    /// Active search pattern, empty when the list is not filtered
+    search: String,
+    /// Only sessions with this tag are visible
+    tag_filter: Option<String>,
+    /// Group the session list by first tag
+    group_by_tag: bool,
+    /// Built-in columns of the session table, in display order
+    table_columns: Vec<SessionColumn>,
+    /// Column the session table is sorted by, ahead of the configured order
+    sort_column: Option<SortKey>,
+    /// Session whose tags are being edited
+    pending_tags: Option<SessionRef>,
+    /// Fields of the new-session prompt
+    new_session: NewSessionForm,
+    /// Session being copied, and whether its commands are started in the copy
+    pending_clone: Option<(SessionRef, bool)>,
+    selected: TableState,
+    input: TextInput,
+    /// Why the text entered in the current prompt was rejected
+    input_error: Option<String>,
     input_mode: InputMode,
-    status_message: String,
-    attach_on_exit: Option<String>,
+    /// Message on the left of the status bar, also the text of some prompts
+    status: Option<StatusMessage>,
+    attach_on_exit: Option<SessionRef>,
+    /// Detach other clients when attaching to `attach_on_exit`
+    attach_exclusive: bool,
     original_session: Option<String>,
+    /// Sessions switched to, for jumping back to the last one
+    history: SessionHistory,
+    /// Recently visited sessions listed in `history_picker`
+    history_picker: Option<Picker>,
+    history_choices: Vec<SessionRef>,
+    remote_target: Option<SessionRef>,
+    /// Last `send-keys` of `InputMode::RemoteTyping` that may still be running,
+    /// with its session
+    remote_send: Option<(SessionRef, JoinHandle<tmux::Result<()>>)>,
+    send_target: Option<PaneTarget>,
+    /// Where the command typed in `InputMode::RunCommand` runs
+    run_target: Option<RunTarget>,
+    /// Dead pane restarted with the command typed in `InputMode::RespawningPane`
+    respawn_target: Option<PaneTarget>,
+    /// Idle sessions shown in the prune confirmation
+    prune_candidates: Vec<TmuxSession>,
+    prune_kind: PruneKind,
+    /// Window or pane shown in the kill confirmation
+    pending_kill: Option<PendingKill>,
+    /// Sessions and windows killed within [`UNDO_GRACE`], most recent last
+    undo: Vec<Killed>,
+    /// Session being renamed, tracked by id so external changes can be detected
+    pending_rename: Option<SessionRef>,
+    loading: bool,
+    error_banner: Option<ErrorBanner>,
+    output_view: Option<OutputView>,
+    /// Paste buffer browser, open in `InputMode::BrowsingBuffers`
+    buffers: Option<BufferBrowser>,
+    /// Archived session browser, open in `InputMode::BrowsingArchive`
+    archived: Option<ArchiveBrowser>,
+    /// Option browser, open in `InputMode::BrowsingOptions` and `EditingOption`
+    options: Option<OptionsBrowser>,
+    /// Environment browser, open in `InputMode::BrowsingEnvironment` and `EditingEnvironment`
+    environment: Option<EnvironmentBrowser>,
+    /// Hook manager, open in `InputMode::BrowsingHooks`, `EditingHook` and `PickingHookTemplate`
+    hooks: Option<HooksBrowser>,
+    template_picker: Option<Picker>,
+    /// Sessions the window in `moving_window` can be moved or linked to
+    session_picker: Option<Picker>,
+    /// "All servers", the default server and the configured servers
+    server_picker: Option<Picker>,
+    moving_window: Option<(TmuxWindow, WindowTransfer)>,
+    /// Windows the pane in `joining_pane` can be joined to, with their ids and names
+    window_picker: Option<Picker>,
+    joining_pane: Option<(TmuxPane, Vec<(String, String)>)>,
+    /// Clients of the session in `detaching`, and an entry to detach all of them
+    client_picker: Option<Picker>,
+    /// Presets, saved layouts and other entries of the layout menu for `layout_target`
+    layout_picker: Option<Picker>,
+    layout_entries: Vec<LayoutEntry>,
+    /// Window arranged from the layout menu, `InputMode::EnteringLayout` or `NamingLayout`
+    layout_target: Option<LayoutTarget>,
+    detaching: Option<(SessionRef, Vec<AttachedClient>)>,
+    theme: Theme,
+    icons: IconSet,
+    /// What the terminal displays; the theme and icons are reduced to it
+    terminal: TerminalSupport,
+    /// Running in a tmux popup (`--popup`): no title bar or margin, and switching to
+    /// a session closes the popup
+    popup: bool,
+    /// When the lists were last reloaded in the background
+    last_poll: Instant,
+    /// How often the lists are reloaded without a change notification. tmux does not
+    /// report bell, activity or silence alerts over control mode, so they are polled.
+    refresh_interval: Option<Duration>,
+    /// Something shown changed since the screen was last drawn
+    needs_redraw: bool,
+    /// Background reload started by the poll or a control-mode notification
+    reload: Option<JoinHandle<Reload>>,
+    /// Something changed while a reload was running, so another one is needed
+    reload_again: bool,
+    /// Bumped whenever a new session list is stored, to discard outdated reloads
+    sessions_generation: u64,
+    /// Capture of the previewed pane that is still running, with its target
+    preview_task: Option<(Option<PaneTarget>, JoinHandle<tmux::Result<String>>)>,
+    /// Read of the selected session's details that is still running, with its target
+    details_task: Option<(Option<DetailsTarget>, JoinHandle<DetailsResult>)>,
+    view: View,
+    preview: PanePreview,
+    show_preview: bool,
+    /// Detail panel of the selected session, shown instead of the preview
+    details: SessionDetailsPanel,
+    /// Process tree of the selected pane, shown next to the pane list
+    processes: ProcessPanel,
+    /// Whether the session details and pane processes are shown
+    show_details: bool,
+    /// Size of the terminal as of the last draw or resize; `None` before the first
+    screen: Option<Rect>,
+    /// Entries the list showed in the last draw, which is how far a page moves
+    list_rows: usize,
+    /// Count typed before a motion in vim mode, e.g. the 5 of `5j`
+    count: Option<usize>,
+    /// Set after the first `g` of `gg` in vim mode
+    pending_g: bool,
+    /// Control-mode client reporting changes on the primary server, if running
+    control: Option<ControlMode>,
+    /// Set when control mode could not attach, so the TUI stays on manual refreshes
+    control_unavailable: bool,
+    /// What the tmux of the primary server supports
+    capabilities: Capabilities,
+    keymap: Keymap,
 }
 
 #[derive(Debug, Clone)]
@@ -33,33 +415,245 @@ pub enum InputMode {
     Normal,
     CreatingSession,
     RenamingSession,
+    EditingTags,
+    RemoteTyping,
+    DisplayMessage,
+    RawCommand,
+    ViewingOutput,
+    CreatingWindow,
+    RenamingWindow,
+    Searching,
+    PickingTemplate,
+    SendingCommand,
+    RunCommand,
+    RespawningPane,
+    ConfirmingPrune,
+    ConfirmingKill,
+    PickingSession,
+    PickingWindow,
+    PickingServer,
+    PickingHistory,
+    BrowsingBuffers,
+    BrowsingArchive,
+    BrowsingOptions,
+    EditingOption,
+    BrowsingEnvironment,
+    EditingEnvironment,
+    BrowsingHooks,
+    EditingHook,
+    PickingHookTemplate,
+    PickingClient,
+    PickingLayout,
+    EnteringLayout,
+    NamingLayout,
+    CloningSession,
 }
 
-impl App {
-    pub fn new(client: TmuxClient) -> Self {
-        let mut selected = ListState::default();
+impl<C: TmuxBackend> App<C> {
+    pub fn new(client: C) -> Self {
+        let mut selected = TableState::default();
         selected.select(Some(0));
 
-        // Store the current session name if inside tmux
-        let original_session = client.get_current_session().ok().flatten();
+        // Nothing here talks to tmux, so the first frame can be drawn immediately;
+        // the session list and current session are loaded in `load_initial_state`.
 
         Self {
             client,
+            servers: Vec::new(),
+            combined: false,
+            shown_server: None,
+            config: Config::default(),
+            usage_log: None,
             sessions: Vec::new(),
+            visible: Vec::new(),
+            search: String::new(),
+            tag_filter: None,
+            group_by_tag: false,
+            table_columns: SessionColumn::ALL.iter().map(|(c, _)| *c).collect(),
+            sort_column: None,
+            pending_tags: None,
+            new_session: NewSessionForm::default(),
+            pending_clone: None,
             selected,
-            input: String::new(),
+            input: TextInput::new(),
+            input_error: None,
             input_mode: InputMode::Normal,
-            status_message: "Welcome to tmux-ui! Press 'h' for help.".to_string(),
+            status: Some(StatusMessage::new(
+                Severity::Info,
+                "Welcome to tmux-ui! Press 'h' for help.".to_string(),
+            )),
             attach_on_exit: None,
-            original_session,
+            attach_exclusive: false,
+            original_session: None,
+            history: SessionHistory::default(),
+            history_picker: None,
+            buffers: None,
+            archived: None,
+            options: None,
+            environment: None,
+            hooks: None,
+            history_choices: Vec::new(),
+            remote_target: None,
+            remote_send: None,
+            send_target: None,
+            run_target: None,
+            respawn_target: None,
+            prune_candidates: Vec::new(),
+            prune_kind: PruneKind::Idle,
+            pending_kill: None,
+            undo: Vec::new(),
+            pending_rename: None,
+            loading: true,
+            error_banner: None,
+            output_view: None,
+            template_picker: None,
+            session_picker: None,
+            server_picker: None,
+            moving_window: None,
+            client_picker: None,
+            layout_picker: None,
+            layout_entries: Vec::new(),
+            layout_target: None,
+            detaching: None,
+            window_picker: None,
+            joining_pane: None,
+            theme: Theme::default(),
+            icons: IconSet::default(),
+            terminal: TerminalSupport::default(),
+            popup: false,
+            last_poll: Instant::now(),
+            refresh_interval: Config::default().refresh_interval(),
+            needs_redraw: true,
+            reload: None,
+            reload_again: false,
+            sessions_generation: 0,
+            preview_task: None,
+            details_task: None,
+            view: View::Sessions,
+            preview: PanePreview::default(),
+            show_preview: true,
+            details: SessionDetailsPanel::default(),
+            processes: ProcessPanel::default(),
+            show_details: false,
+            screen: None,
+            list_rows: 1,
+            count: None,
+            pending_g: false,
+            control: None,
+            control_unavailable: false,
+            capabilities: Capabilities::default(),
+            keymap: Keymap::default(),
+        }
+    }
+
+    /// Use the given user configuration (custom columns, extra servers, ...)
+    pub fn with_config(mut self, config: Config) -> Self {
+        // Sockets and hosts were validated when the config was parsed
+        self.servers = config
+            .servers
+            .iter()
+            .filter_map(|server| {
+                Some(Server {
+                    name: server.name.clone(),
+                    client: self.client.for_server(server).ok()?,
+                })
+            })
+            .collect();
+        self.combined = !self.servers.is_empty();
+        self.usage_log = config.usage_log();
+        // Key overrides were validated when the config was parsed
+        self.keymap = config.keymap().unwrap_or_default();
+        self.status = Some(StatusMessage::new(
+            Severity::Info,
+            format!(
+                "Welcome to tmux-ui! Press '{}' for help.",
+                self.keymap.first_key(Action::Help, Scope::Sessions)
+            ),
+        ));
+        // The theme name was validated when the config was parsed
+        self.theme = config.theme().unwrap_or_default();
+        // So was the icon set
+        self.icons = config.icons().unwrap_or_default();
+        self.adapt_to_terminal();
+        self.group_by_tag = config.group_by_tag;
+        // Column names were validated when the config was parsed
+        self.table_columns = config.table_columns().unwrap_or_default();
+        self.refresh_interval = config.refresh_interval();
+        self.config = config;
+        self
+    }
+
+    /// Draw only the characters and colors the terminal can display
+    pub fn with_terminal(mut self, terminal: TerminalSupport) -> Self {
+        self.terminal = terminal;
+        self.adapt_to_terminal();
+        self
+    }
+
+    fn adapt_to_terminal(&mut self) {
+        self.theme = self.theme.for_terminal(self.terminal.color);
+        if !self.terminal.unicode {
+            self.icons = IconSet::Ascii;
+        }
+        self.keymap = std::mem::take(&mut self.keymap).with_icons(self.icons);
+    }
+
+    /// Use the compact layout of a popup switcher, which closes after switching
+    pub fn with_popup(mut self, popup: bool) -> Self {
+        self.popup = popup;
+        self
+    }
+
+    /// Hide or work around the features the tmux of the primary server lacks
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Client for the server a session lives on
+    fn client_for(&self, server: Option<&str>) -> &C {
+        server
+            .and_then(|name| self.servers.iter().find(|s| s.name == name))
+            .map(|s| &s.client)
+            .unwrap_or(&self.client)
+    }
+
+    /// Record an action in the usage log, if tracking is enabled
+    fn track(&self, kind: EventKind, session: &str) {
+        if let Some(log) = &self.usage_log {
+            log.record(kind, session);
         }
     }
 
+    /// Which list's keys apply
+    fn scope(&self) -> Scope {
+        match self.view {
+            View::Sessions => Scope::Sessions,
+            View::Windows(_) => Scope::Windows,
+            View::Panes(_) => Scope::Panes,
+            View::Tree(_) => Scope::Tree,
+            View::Clients(_) => Scope::Clients,
+        }
+    }
+
+    /// The currently selected session, if any
+    fn selected_session(&self) -> Option<&TmuxSession> {
+        self.selected
+            .selected()
+            .and_then(|i| self.visible.get(i))
+            .and_then(|m| self.sessions.get(m.index))
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         // Setup terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        execute!(
+            stdout,
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableBracketedPaste
+        )?;
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
@@ -70,7 +664,8 @@ impl App {
         execute!(
             terminal.backend_mut(),
             LeaveAlternateScreen,
-            DisableMouseCapture
+            DisableMouseCapture,
+            DisableBracketedPaste
         )?;
         terminal.show_cursor()?;
 
@@ -79,39 +674,94 @@ impl App {
         // which requires that we've fully released our terminal handling first.
         // Attempting to attach while still in alternate screen or raw mode
         // would cause terminal corruption and keyboard input issues.
-        if let Some(session_name) = &self.attach_on_exit {
-            self.client.attach_session(session_name)?;
+        if let Some(target) = &self.attach_on_exit {
+            self.track(EventKind::Attached, &target.name);
+            let client = self.client_for(target.server.as_deref());
+            let attached = if self.attach_exclusive {
+                client.attach_session_exclusive(target.tmux_target())
+            } else {
+                client.attach_session(target.tmux_target())
+            };
+            // attach-session blocks until the client detaches
+            self.track(EventKind::Detached, &target.name);
+            attached?;
         }
 
         result
     }
 
-    async fn run_app<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+    /// Render a single frame, e.g. the loading placeholder before any tmux query finishes
+    pub fn draw<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        terminal.draw(|f| self.ui(f))?;
+        Ok(())
+    }
+
+    /// Query tmux for the state shown by the TUI, clearing the loading placeholder
+    pub async fn load_initial_state(&mut self) -> Result<()> {
+        // Store the current session name if inside tmux
+        self.original_session = self.client.get_current_session().ok().flatten();
+        // Without extended keys tmux never passes keys such as S-Enter on to the TUI
+        if self.client.is_inside_tmux() && !self.capabilities.has_extended_keys() {
+            self.keymap.remove_extended_keys();
+        }
         self.refresh_sessions().await?;
+        self.load_history();
+        self.loading = false;
+        Ok(())
+    }
+
+    async fn run_app<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        // Show the UI before spawning any tmux processes so startup feels instant
+        self.draw(terminal)?;
+        self.load_initial_state().await?;
+        self.start_control_mode();
 
         loop {
-            terminal.draw(|f| self.ui(f))?;
+            self.process_notifications();
+            if matches!(self.input_mode, InputMode::Normal)
+                && self
+                    .refresh_interval
+                    .is_some_and(|interval| self.last_poll.elapsed() >= interval)
+            {
+                self.start_reload();
+            }
+            self.finish_reload().await?;
+            self.finish_remote_keys().await;
+
+            if self.error_banner.as_ref().is_some_and(|b| b.retry_due()) {
+                self.retry_failed_action().await?;
+                self.needs_redraw = true;
+            }
+
+            if self.expire_status() {
+                self.needs_redraw = true;
+            }
+            self.update_preview().await;
+            self.update_details().await;
+            // Polls that found nothing new leave the screen alone
+            if self.needs_redraw
+                || self
+                    .error_banner
+                    .as_ref()
+                    .is_some_and(ErrorBanner::counting_down)
+            {
+                terminal.draw(|f| self.ui(f))?;
+                self.needs_redraw = false;
+            }
 
             if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
-                    if key.kind == KeyEventKind::Press {
-                        match self.input_mode {
-                            InputMode::Normal => {
-                                if self.handle_normal_input(key.code).await? {
-                                    break;
-                                }
-                            }
-                            InputMode::CreatingSession => {
-                                if self.handle_creating_input(key.code).await? {
-                                    break;
-                                }
-                            }
-                            InputMode::RenamingSession => {
-                                if self.handle_renaming_input(key.code).await? {
-                                    break;
-                                }
-                            }
-                        }
+                // Keys, resizes and mouse events all may change what is shown
+                self.needs_redraw = true;
+                let event = event::read()?;
+                if let Event::Paste(text) = &event {
+                    self.handle_paste(text);
+                }
+                if let Event::Resize(width, height) = event {
+                    self.handle_resize(width, height);
+                }
+                if let Event::Key(key) = event {
+                    if key.kind == KeyEventKind::Press && self.handle_key(key).await? {
+                        break;
                     }
                 }
             }
@@ -120,129 +770,659 @@ impl App {
         Ok(())
     }
 
-    async fn handle_normal_input(&mut self, key: KeyCode) -> Result<bool> {
-        match key {
-            KeyCode::Char('q') => return Ok(true),
-            KeyCode::Char('h') => {
-                self.status_message = "Commands: q=quit, n=new, d=delete, a/Enter=attach/switch, Esc/b=back to UI, r=rename, w=new window, x=detach, R=refresh, ↑↓=navigate".to_string();
+    /// The jump an action makes in the list shown, if it is a jump; a count
+    /// repeats moves and makes `Top` and `Bottom` go to that entry, like in vim
+    fn jump_for(&self, action: Action, count: Option<usize>) -> Option<Jump> {
+        let times = count.unwrap_or(1);
+        let half_page = (self.list_rows / 2).max(1);
+        match action {
+            Action::Top | Action::Bottom if count.is_some() => {
+                Some(Jump::To(times.saturating_sub(1)))
             }
-            KeyCode::Char('n') => {
-                self.input_mode = InputMode::CreatingSession;
-                self.input.clear();
-                self.status_message =
-                    "Enter session name (ESC to cancel, Enter to create):".to_string();
+            Action::Top => Some(Jump::First),
+            Action::Bottom => Some(Jump::Last),
+            Action::PageUp => Some(Jump::Up(self.list_rows.saturating_mul(times))),
+            Action::PageDown => Some(Jump::Down(self.list_rows.saturating_mul(times))),
+            Action::HalfPageUp => Some(Jump::Up(half_page.saturating_mul(times))),
+            Action::HalfPageDown => Some(Jump::Down(half_page.saturating_mul(times))),
+            // Single moves keep wrapping around at the ends
+            Action::Up if count.is_some() => Some(Jump::Up(times)),
+            Action::Down if count.is_some() => Some(Jump::Down(times)),
+            _ => None,
+        }
+    }
+
+    /// Take a digit of a count or the first `g` of `gg` in vim mode; returns
+    /// whether the key was one of them
+    fn vim_prefix(&mut self, key: &KeyEvent) -> bool {
+        if !self.keymap.is_vim()
+            || !key.modifiers.is_empty()
+            || self.keymap.action(key, self.scope()).is_some()
+        {
+            return false;
+        }
+        match key.code {
+            // A leading 0 is not a count
+            KeyCode::Char(c @ '0'..='9') if c != '0' || self.count.is_some() => {
+                let digit = c.to_digit(10).unwrap_or(0) as usize;
+                let count = self.count.unwrap_or(0).saturating_mul(10);
+                self.count = Some(count.saturating_add(digit));
+                true
             }
-            KeyCode::Char('r') => {
-                if let Some(index) = self.selected.selected() {
-                    if index < self.sessions.len() {
-                        self.input_mode = InputMode::RenamingSession;
-                        self.input.clear();
-                        self.status_message =
-                            "Enter new session name (ESC to cancel, Enter to rename):".to_string();
-                    }
+            KeyCode::Char('g') if !self.pending_g => {
+                self.pending_g = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Move the selection of the list shown by more than one entry
+    fn jump_selection(&mut self, jump: Jump) {
+        match &mut self.view {
+            View::Sessions => {
+                let selected = jump.apply(self.selected.selected(), self.visible.len());
+                self.selected.select(selected);
+            }
+            View::Windows(view) => view.jump(jump),
+            View::Panes(view) => view.jump(jump),
+            View::Tree(view) => view.jump(jump),
+            View::Clients(view) => view.jump(jump),
+        }
+    }
+
+    /// Adapt the layout to a new terminal size
+    pub fn handle_resize(&mut self, width: u16, height: u16) {
+        let fitted = self.side_panels_fit();
+        self.screen = Some(Rect::new(0, 0, width, height));
+        if !fitted && self.side_panels_fit() {
+            // The panels were not kept up to date while hidden
+            self.details.invalidate();
+        }
+    }
+
+    /// Whether the terminal is wide enough for the preview and detail panels
+    fn side_panels_fit(&self) -> bool {
+        self.screen.is_none_or(|screen| {
+            screen.width.saturating_sub(2 * self.margin()) >= SIDE_PANEL_MIN_WIDTH
+        })
+    }
+
+    /// Whether the terminal is tall enough for the title bar, which popups do without
+    fn title_shown(&self) -> bool {
+        !self.popup
+            && self
+                .screen
+                .is_none_or(|screen| screen.height >= TITLE_MIN_HEIGHT)
+    }
+
+    /// Outer margin around the main layout; the border of a popup is margin enough
+    fn margin(&self) -> u16 {
+        if self.popup {
+            0
+        } else {
+            LAYOUT_MARGIN
+        }
+    }
+
+    /// Insert pasted text into the prompt being typed into, if any
+    pub fn handle_paste(&mut self, text: &str) {
+        match self.input_mode {
+            InputMode::CreatingSession
+            | InputMode::RenamingSession
+            | InputMode::EditingTags
+            | InputMode::CloningSession
+            | InputMode::DisplayMessage
+            | InputMode::RawCommand
+            | InputMode::CreatingWindow
+            | InputMode::RenamingWindow
+            | InputMode::SendingCommand
+            | InputMode::RunCommand
+            | InputMode::RespawningPane
+            | InputMode::EditingOption
+            | InputMode::EditingEnvironment
+            | InputMode::EditingHook
+            | InputMode::EnteringLayout
+            | InputMode::NamingLayout => self.input.insert_str(text),
+            InputMode::Searching => {
+                self.input.insert_str(text);
+                self.update_search();
+            }
+            // Sent as one literal, so tmux does not read e.g. "Enter" in it as a key name
+            InputMode::RemoteTyping => {
+                if let Some(target) = self.remote_target.clone() {
+                    self.send_remote_keys(target, Key::Literal(text.to_string()));
                 }
             }
-            KeyCode::Char('d') => {
-                if let Some(index) = self.selected.selected() {
-                    if index < self.sessions.len() {
-                        let session = &self.sessions[index];
-                        match self.client.kill_session(&session.name) {
-                            Ok(_) => {
-                                self.status_message =
-                                    format!("Session '{}' deleted!", session.name);
-                                self.refresh_sessions().await?;
-                            }
-                            Err(e) => {
-                                self.status_message = format!("Error deleting session: {}", e);
-                            }
-                        }
+            InputMode::Normal
+            | InputMode::ViewingOutput
+            | InputMode::PickingTemplate
+            | InputMode::ConfirmingPrune
+            | InputMode::ConfirmingKill
+            | InputMode::PickingSession
+            | InputMode::PickingWindow
+            | InputMode::PickingServer
+            | InputMode::PickingHistory
+            | InputMode::BrowsingBuffers
+            | InputMode::BrowsingArchive
+            | InputMode::BrowsingOptions
+            | InputMode::BrowsingEnvironment
+            | InputMode::BrowsingHooks
+            | InputMode::PickingHookTemplate
+            | InputMode::PickingClient
+            | InputMode::PickingLayout => {}
+        }
+    }
+
+    /// Handle a key press in the current view and input mode; returns whether
+    /// the TUI should exit (quit, or attach after leaving the terminal)
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
+        // A rejected input stays flagged until the next keystroke
+        self.input_error = None;
+        tracing::debug!(
+            "key {:?} {:?} in {:?} mode",
+            key.modifiers,
+            key.code,
+            self.input_mode
+        );
+        match self.input_mode {
+            InputMode::Normal => {
+                if self.vim_prefix(&key) {
+                    return Ok(false);
+                }
+                let count = self.count.take();
+                let action = if std::mem::take(&mut self.pending_g)
+                    && key.code == KeyCode::Char('g')
+                    && key.modifiers.is_empty()
+                {
+                    Some(Action::Top)
+                } else {
+                    self.keymap.action(&key, self.scope())
+                };
+                if let Some(action) = action {
+                    tracing::info!("action {:?}", action);
+                    if let Some(jump) = self.jump_for(action, count) {
+                        self.jump_selection(jump);
+                        return Ok(false);
                     }
                 }
+                return match self.view {
+                    View::Sessions => self.handle_normal_input(key).await,
+                    View::Windows(_) => self.handle_window_input(key).await,
+                    View::Panes(_) => self.handle_pane_input(key).await,
+                    View::Tree(_) => self.handle_tree_input(key).await,
+                    View::Clients(_) => self.handle_clients_input(key).await,
+                };
             }
-            KeyCode::Char('a') | KeyCode::Enter => {
-                if let Some(index) = self.selected.selected() {
-                    if index < self.sessions.len() {
-                        let session = &self.sessions[index];
-                        
-                        // Check if we're already inside a tmux session
-                        if self.client.is_inside_tmux() {
-                            // Use switch-client to change to the selected session
-                            // This works within tmux and doesn't require exiting the TUI
-                            match self.client.switch_client(&session.name) {
-                                Ok(_) => {
-                                    self.status_message = format!("Switched to session '{}'", session.name);
-                                    self.refresh_sessions().await?;
-                                }
-                                Err(e) => {
-                                    self.status_message = format!("Error switching to session: {}", e);
-                                }
-                            }
-                        } else {
-                            // Not inside tmux, use attach-session
-                            // Store the session to attach to after TUI exits
-                            self.attach_on_exit = Some(session.name.clone());
-                            self.status_message = format!("Attaching to session '{}'...", session.name);
-                            // Return true to exit TUI, then attach
-                            return Ok(true);
-                        }
-                    }
+            InputMode::ConfirmingPrune => {
+                self.handle_confirming_prune_input(key.code).await?;
+            }
+            InputMode::ConfirmingKill => {
+                self.handle_confirming_kill_input(key.code).await?;
+            }
+            InputMode::SendingCommand => {
+                self.handle_sending_command_input(key).await;
+            }
+            InputMode::RespawningPane => {
+                self.handle_respawning_pane_input(key).await?;
+            }
+            InputMode::RunCommand => {
+                self.handle_run_command_input(key).await;
+            }
+            InputMode::PickingWindow => {
+                self.handle_picking_window_input(key.code).await?;
+            }
+            InputMode::PickingSession => {
+                self.handle_picking_session_input(key.code).await?;
+            }
+            InputMode::PickingServer => {
+                self.handle_picking_server_input(key.code).await?;
+            }
+            InputMode::PickingHistory => {
+                return self.handle_picking_history_input(key.code).await;
+            }
+            InputMode::PickingTemplate => {
+                self.handle_picking_template_input(key.code).await?;
+            }
+            InputMode::BrowsingBuffers => {
+                self.handle_browsing_buffers_input(key.code).await?;
+            }
+            InputMode::BrowsingArchive => {
+                self.handle_browsing_archive_input(key.code).await?;
+            }
+            InputMode::BrowsingOptions => {
+                self.handle_browsing_options_input(key).await?;
+            }
+            InputMode::EditingOption => {
+                self.handle_editing_option_input(key).await?;
+            }
+            InputMode::BrowsingEnvironment => {
+                self.handle_browsing_environment_input(key.code).await?;
+            }
+            InputMode::EditingEnvironment => {
+                self.handle_editing_environment_input(key).await?;
+            }
+            InputMode::BrowsingHooks => {
+                self.handle_browsing_hooks_input(key.code).await?;
+            }
+            InputMode::EditingHook => {
+                self.handle_editing_hook_input(key).await?;
+            }
+            InputMode::PickingHookTemplate => {
+                self.handle_picking_hook_template_input(key.code).await?;
+            }
+            InputMode::PickingClient => {
+                self.handle_picking_client_input(key.code).await?;
+            }
+            InputMode::PickingLayout => {
+                self.handle_picking_layout_input(key.code).await?;
+            }
+            InputMode::EnteringLayout => {
+                self.handle_entering_layout_input(key).await?;
+            }
+            InputMode::NamingLayout => {
+                self.handle_naming_layout_input(key).await;
+            }
+            InputMode::Searching => {
+                self.handle_searching_input(key);
+            }
+            InputMode::CreatingWindow => {
+                self.handle_creating_window_input(key).await?;
+            }
+            InputMode::RenamingWindow => {
+                self.handle_renaming_window_input(key).await?;
+            }
+            InputMode::CreatingSession => {
+                return self.handle_creating_input(key).await;
+            }
+            InputMode::CloningSession => {
+                self.handle_cloning_input(key).await?;
+            }
+            InputMode::RenamingSession => {
+                return self.handle_renaming_input(key).await;
+            }
+            InputMode::EditingTags => {
+                self.handle_editing_tags_input(key).await?;
+            }
+            InputMode::DisplayMessage => {
+                return self.handle_display_message_input(key).await;
+            }
+            InputMode::RawCommand => {
+                return self.handle_raw_command_input(key).await;
+            }
+            InputMode::ViewingOutput => {
+                self.handle_viewing_output_input(key.code);
+            }
+            InputMode::RemoteTyping => {
+                self.handle_remote_typing_input(key).await?;
+            }
+        }
+        Ok(false)
+    }
+
+    async fn handle_normal_input(&mut self, key: KeyEvent) -> Result<bool> {
+        let Some(action) = self.keymap.action(&key, Scope::Sessions) else {
+            return Ok(false);
+        };
+        match action {
+            Action::Quit => return Ok(true),
+            Action::Help => {
+                self.show_help(&[
+                    (Action::Quit, "quit"),
+                    (Action::New, "new"),
+                    (Action::Kill, "delete"),
+                    (Action::Undo, "undo delete"),
+                    (Action::Archive, "archive"),
+                    (Action::Archived, "archived sessions"),
+                    (Action::Attach, "attach/switch"),
+                    (Action::AttachExclusive, "attach, detaching others"),
+                    (Action::AttachNewTerminal, "open in new terminal"),
+                    (Action::Back, "back to UI"),
+                    (Action::LastSession, "last session"),
+                    (Action::History, "recent sessions"),
+                    (Action::Rename, "rename"),
+                    (Action::CloneSession, "clone"),
+                    (Action::NewWindow, "new window"),
+                    (Action::DrillDown, "windows"),
+                    (Action::Detach, "detach"),
+                    (Action::TogglePreview, "preview"),
+                    (Action::ToggleDetails, "details"),
+                    (Action::TypeIntoPane, "type into pane"),
+                    (Action::SendCommand, "send command"),
+                    (Action::RunCommand, "run command"),
+                    (Action::ScratchTerminal, "scratch terminal"),
+                    (Action::Buffers, "paste buffers"),
+                    (Action::Options, "options"),
+                    (Action::Environment, "environment"),
+                    (Action::Hooks, "hooks"),
+                    (Action::FormatQuery, "format query"),
+                    (Action::TmuxCommand, "tmux command"),
+                    (Action::Search, "search"),
+                    (Action::ToggleTree, "tree"),
+                    (Action::Clients, "attached clients"),
+                    (Action::EditTags, "tags"),
+                    (Action::FilterTag, "filter by tag"),
+                    (Action::ToggleGroups, "group by tag"),
+                    (Action::SortColumn, "sort by next column"),
+                    (Action::ReverseSort, "reverse sort"),
+                    (Action::Prune, "prune idle sessions"),
+                    (Action::PruneStale, "prune stale sessions"),
+                    (Action::SaveSnapshot, "save snapshot"),
+                    (Action::RestoreSnapshot, "restore snapshot"),
+                    (Action::StartTemplate, "start template"),
+                    (Action::ToggleServers, "all servers"),
+                    (Action::SwitchServer, "switch server"),
+                    (Action::Refresh, "refresh"),
+                    (Action::Up, "up"),
+                    (Action::Down, "down"),
+                    (Action::Top, "first"),
+                    (Action::Bottom, "last"),
+                    (Action::PageUp, "page up"),
+                    (Action::PageDown, "page down"),
+                ]);
+            }
+            Action::TogglePreview => self.toggle_preview(),
+            Action::ToggleDetails => self.toggle_details().await,
+            Action::Undo => self.undo().await?,
+            Action::Archive => {
+                if let Some(session) = self.selected_session() {
+                    let selected = SessionRef::of(session);
+                    let Some(target) = self.revalidate(&selected).await? else {
+                        self.warn(format!(
+                            "Session '{}' no longer exists; nothing was archived",
+                            selected
+                        ));
+                        return Ok(false);
+                    };
+                    self.archive_session(target).await?;
                 }
             }
-            KeyCode::Char('x') => {
-                if let Some(index) = self.selected.selected() {
-                    if index < self.sessions.len() {
-                        let session = &self.sessions[index];
-                        
-                        // Check if we're inside a tmux session
-                        if self.client.is_inside_tmux() {
-                            // When inside tmux, detach the current client (exits the TUI and tmux)
-                            match self.client.detach_current_client() {
-                                Ok(_) => {
-                                    self.status_message = "Detaching from tmux...".to_string();
-                                    // Return true to exit TUI since we're detaching from tmux
-                                    return Ok(true);
-                                }
+            Action::Archived => self.open_archive(),
+            Action::Buffers => self.open_buffers().await?,
+            Action::Options => self.open_options().await?,
+            Action::Environment => self.open_environment().await?,
+            Action::Hooks => self.open_hooks().await?,
+            Action::DrillDown | Action::Expand => {
+                if let Some(session) = self.selected_session() {
+                    let target = SessionRef::of(session);
+                    self.view = View::Windows(WindowView::new(target));
+                    self.refresh_windows().await?;
+                }
+            }
+            Action::TmuxCommand => {
+                self.input_mode = InputMode::RawCommand;
+                self.input.clear();
+                self.info(
+                    "Enter tmux command, e.g. list-keys -T prefix (Tab completes, ESC to cancel, Enter to run):",
+                );
+            }
+            Action::FormatQuery => {
+                self.input_mode = InputMode::DisplayMessage;
+                self.input.clear();
+                self.info("Enter tmux format, e.g. #{pane_current_command} (ESC to cancel, Enter to run):");
+            }
+            Action::TypeIntoPane => {
+                if let Some(session) = self.selected_session() {
+                    // Targeting the session sends keys to its active pane
+                    let target = SessionRef::of(session);
+                    self.info(format!(
+                        "REMOTE TYPING {} '{}': keys are sent live",
+                        self.icons.arrow(),
+                        target
+                    ));
+                    self.remote_target = Some(target);
+                    self.input_mode = InputMode::RemoteTyping;
+                }
+            }
+            Action::Prune => match self.session_source().idle_async().await {
+                Ok(sessions) if sessions.is_empty() => {
+                    self.warn("No idle sessions (detached, running only the shell)");
+                }
+                Ok(sessions) => {
+                    let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+                    self.info(format!(
+                        "Kill {} idle session(s): {}? (y to confirm, any other key to cancel)",
+                        sessions.len(),
+                        names.join(", ")
+                    ));
+                    self.prune_candidates = sessions;
+                    self.prune_kind = PruneKind::Idle;
+                    self.input_mode = InputMode::ConfirmingPrune;
+                }
+                Err(e) => self.report_error(format!("Error finding idle sessions: {}", e)),
+            },
+            Action::PruneStale => {
+                let Some(threshold) = self.config.stale_after() else {
+                    self.warn("Stale sessions are turned off (stale_after_days = 0)");
+                    return Ok(false);
+                };
+                let stale: Vec<TmuxSession> = self
+                    .sessions
+                    .iter()
+                    .filter(|s| self.is_stale(s))
+                    .cloned()
+                    .collect();
+                if stale.is_empty() {
+                    self.warn(format!(
+                        "No sessions idle for more than {} days",
+                        threshold.num_days()
+                    ));
+                    return Ok(false);
+                }
+                let names: Vec<&str> = stale.iter().map(|s| s.name.as_str()).collect();
+                self.info(format!(
+                    "Kill {} session(s) idle for more than {} days: {}? (y to confirm, any other key to cancel)",
+                    stale.len(),
+                    threshold.num_days(),
+                    names.join(", ")
+                ));
+                self.prune_candidates = stale;
+                self.prune_kind = PruneKind::Stale;
+                self.input_mode = InputMode::ConfirmingPrune;
+            }
+            Action::AttachNewTerminal => {
+                if let Some(session) = self.selected_session() {
+                    let target = SessionRef::of(session);
+                    let client = self.client_for(target.server.as_deref()).clone();
+                    let session = target.tmux_target().to_string();
+                    match self.config.terminal_command() {
+                        Some(terminal) => {
+                            let opened = client
+                                .blocking(move |c| c.attach_in_new_terminal(&session, &terminal))
+                                .await;
+                            match opened {
+                                Ok(_) => self.success(format!(
+                                    "Opened session '{}' in a new terminal",
+                                    target
+                                )),
                                 Err(e) => {
-                                    self.status_message = format!("Error detaching: {}", e);
+                                    self.report_error(format!("Error opening terminal: {:#}", e))
                                 }
                             }
-                        } else {
-                            // When outside tmux, detach all clients from the selected session
-                            match self.client.detach_session(&session.name) {
+                        }
+                        // Inside tmux a popup does without a terminal emulator
+                        None if target.server.is_none()
+                            && self.client.is_inside_this_server()
+                            && self.capabilities.has_popup() =>
+                        {
+                            match client.blocking(move |c| c.attach_in_popup(&session)).await {
                                 Ok(_) => {
-                                    self.status_message =
-                                        format!("Detached from session '{}'", session.name);
-                                    self.refresh_sessions().await?;
+                                    self.info(format!("Closed the popup of session '{}'", target))
                                 }
                                 Err(e) => {
-                                    self.status_message = format!("Error detaching: {}", e);
+                                    self.report_error(format!("Error opening popup: {:#}", e))
                                 }
                             }
                         }
+                        None => self.report_error(
+                            "No terminal configured; set `terminal` in the config or $TERMINAL"
+                                .to_string(),
+                        ),
+                    }
+                }
+            }
+            Action::RunCommand => self.start_run_command(),
+            Action::ScratchTerminal => self.open_scratch_terminal().await,
+            Action::SendCommand => {
+                if let Some(session) = self.selected_session() {
+                    let target = PaneTarget {
+                        server: session.server.clone(),
+                        target: SessionRef::of(session).tmux_target().to_string(),
+                        label: format!("session '{}'", session.name),
+                    };
+                    self.start_sending_command(target);
+                }
+            }
+            Action::ToggleTree => {
+                // The tree shows the primary server; start at the selected session
+                let selected = self
+                    .selected_session()
+                    .filter(|s| s.server.is_none())
+                    .map(|s| s.id.clone());
+                self.view = View::Tree(TreeView::new());
+                self.info("Session tree");
+                self.refresh_tree().await;
+                if let (View::Tree(view), Some(id)) = (&mut self.view, selected) {
+                    view.reveal_session(&id);
+                }
+            }
+            Action::Clients => {
+                self.view = View::Clients(ClientView::new());
+                self.info("Attached clients");
+                self.refresh_clients().await;
+            }
+            Action::ToggleServers => {
+                if self.servers.is_empty() {
+                    self.warn("No additional servers configured");
+                } else {
+                    self.combined = !self.combined;
+                    self.info(if self.combined {
+                        "Showing sessions from all servers".to_string()
+                    } else {
+                        format!(
+                            "Showing sessions from the {} server",
+                            self.shown_server_label()
+                        )
+                    });
+                    self.refresh_sessions().await?;
+                }
+            }
+            Action::SwitchServer => {
+                if self.servers.is_empty() {
+                    self.warn("No additional servers configured");
+                } else {
+                    let items = ["All servers", DEFAULT_SERVER_LABEL]
+                        .into_iter()
+                        .map(str::to_string)
+                        .chain(self.servers.iter().map(|s| s.name.clone()))
+                        .collect();
+                    self.server_picker = Some(Picker::new("Show server".to_string(), items));
+                    self.input_mode = InputMode::PickingServer;
+                    self.info("Choose a server (Enter to show its sessions, ESC to cancel)");
+                }
+            }
+            Action::New => self.start_creating_session(),
+            Action::CloneSession => {
+                if let Some(session) = self.selected_session() {
+                    let source = SessionRef::of(session);
+                    self.start_cloning_session(source);
+                }
+            }
+            Action::Rename => {
+                if let Some(session) = self.selected_session() {
+                    let pending = SessionRef::of(session);
+                    self.input.set(pending.name.clone());
+                    self.pending_rename = Some(pending);
+                    self.input_mode = InputMode::RenamingSession;
+                    self.info("Edit the session name (ESC to cancel, Enter to rename):");
+                }
+            }
+            Action::Kill => {
+                if let Some(session) = self.selected_session() {
+                    let selected = SessionRef::of(session);
+                    // The list may be stale, so make sure we kill the session the user saw
+                    let Some(target) = self.revalidate(&selected).await? else {
+                        self.warn(format!(
+                            "Session '{}' no longer exists; nothing was deleted",
+                            selected
+                        ));
+                        return Ok(false);
+                    };
+                    // Ask first when killing would take e.g. an editor or a build with it;
+                    // if the panes cannot be listed, the kill itself reports the problem
+                    let session = target.tmux_target().to_string();
+                    let running = self
+                        .client_for(target.server.as_deref())
+                        .blocking(move |c| c.running_commands(&session))
+                        .await
+                        .unwrap_or_default();
+                    if running.is_empty() {
+                        self.kill_session(target).await?;
+                    } else {
+                        self.info(format!(
+                            "Kill session '{}'? {} running (y to confirm, any other key to cancel)",
+                            target,
+                            running.join(", ")
+                        ));
+                        self.pending_kill = Some(PendingKill::Session(target));
+                        self.input_mode = InputMode::ConfirmingKill;
                     }
                 }
             }
-            KeyCode::Char('w') => {
-                if let Some(index) = self.selected.selected() {
-                    if index < self.sessions.len() {
-                        let session = &self.sessions[index];
-                        match self.client.create_window(&session.name, None) {
+            Action::Attach | Action::AttachExclusive => {
+                if let Some(session) = self.selected_session() {
+                    let target = SessionRef::of(session);
+                    let exclusive = self.exclusive(action);
+                    return self.attach_or_switch(target, exclusive).await;
+                }
+            }
+            Action::Detach => {
+                if let Some(session) = self.selected_session() {
+                    let target = SessionRef::of(session);
+                    // Check if we're inside a tmux session (of this machine)
+                    if self.client.is_inside_tmux() && self.client.host().is_none() {
+                        // When inside tmux, detach the current client (exits the TUI and tmux)
+                        match self.client.blocking(|c| c.detach_current_client()).await {
                             Ok(_) => {
-                                self.status_message =
-                                    format!("New window created in session '{}'", session.name);
-                                self.refresh_sessions().await?;
+                                self.info("Detaching from tmux...");
+                                // Return true to exit TUI since we're detaching from tmux
+                                return Ok(true);
                             }
                             Err(e) => {
-                                self.status_message = format!("Error creating window: {}", e);
+                                self.report_error(format!("Error detaching: {}", e));
                             }
                         }
+                    } else {
+                        // When outside tmux, detach a client of the selected session,
+                        // asking which one when there are several
+                        self.detach_from_session(target).await?;
+                    }
+                }
+            }
+            Action::NewWindow => {
+                if let Some(session) = self.selected_session() {
+                    let target = SessionRef::of(session);
+                    let session = target.tmux_target().to_string();
+                    let created = self
+                        .client_for(target.server.as_deref())
+                        .blocking(move |c| c.create_window(&session, None))
+                        .await;
+                    match created {
+                        Ok(_) => {
+                            self.track(EventKind::WindowCreated, &target.name);
+                            self.success(format!("New window created in session '{}'", target));
+                            self.refresh_sessions().await?;
+                        }
+                        Err(e) => {
+                            self.report_failure(RetryAction::CreateWindow(target), e);
+                        }
                     }
                 }
             }
-            KeyCode::Down => {
+            Action::Down => {
                 let i = match self.selected.selected() {
                     Some(i) => {
-                        if i >= self.sessions.len().saturating_sub(1) {
+                        if i >= self.visible.len().saturating_sub(1) {
                             0
                         } else {
                             i + 1
@@ -252,11 +1432,11 @@ impl App {
                 };
                 self.selected.select(Some(i));
             }
-            KeyCode::Up => {
+            Action::Up => {
                 let i = match self.selected.selected() {
                     Some(i) => {
                         if i == 0 {
-                            self.sessions.len().saturating_sub(1)
+                            self.visible.len().saturating_sub(1)
                         } else {
                             i - 1
                         }
@@ -265,49 +1445,178 @@ impl App {
                 };
                 self.selected.select(Some(i));
             }
-            KeyCode::Char('R') => {
-                self.refresh_sessions().await?;
-                self.status_message = "Sessions refreshed!".to_string();
+            Action::Refresh => {
+                if self
+                    .error_banner
+                    .as_ref()
+                    .is_some_and(|b| b.retry.is_some())
+                {
+                    self.retry_failed_action().await?;
+                } else {
+                    self.refresh_sessions().await?;
+                    if self.error_banner.is_none() {
+                        self.success("Sessions refreshed!");
+                    }
+                }
             }
-            KeyCode::Char('b') => {
-                // Go back to the original session (tmux-ui management session)
-                if self.client.is_inside_tmux() {
-                    if let Some(ref session_name) = self.original_session {
-                        match self.client.switch_client(session_name) {
-                            Ok(_) => {
-                                self.status_message = format!("Switched back to tmux-ui session '{}'", session_name);
-                                self.refresh_sessions().await?;
-                            }
-                            Err(e) => {
-                                self.status_message = format!("Error switching back: {}", e);
-                            }
+            Action::DismissError if self.error_banner.is_some() => {
+                self.error_banner = None;
+                self.info("Error dismissed");
+            }
+            Action::Back if !self.search.is_empty() => {
+                self.search.clear();
+                self.apply_filter();
+                self.info("Search cleared");
+            }
+            Action::SaveSnapshot => {
+                let result = self
+                    .client
+                    .blocking(|c| {
+                        let path = Snapshot::default_path()
+                            .context("Cannot determine snapshot location")?;
+                        let snapshot = c.capture_snapshot()?;
+                        snapshot.save(&path)?;
+                        Ok::<_, anyhow::Error>((snapshot.sessions.len(), path))
+                    })
+                    .await;
+                match result {
+                    Ok((count, path)) => {
+                        self.success(format!("Saved {} session(s) to {}", count, path.display()));
+                    }
+                    Err(e) => self.report_error(format!("Error saving snapshot: {}", e)),
+                }
+            }
+            Action::RestoreSnapshot => {
+                let result = self
+                    .client
+                    .blocking(|c| {
+                        let path = Snapshot::default_path()
+                            .context("Cannot determine snapshot location")?;
+                        c.restore_snapshot(&Snapshot::load(&path)?)
+                    })
+                    .await;
+                match result {
+                    Ok(report) => {
+                        for name in &report.restored {
+                            self.track(EventKind::Created, name);
                         }
-                    } else {
-                        self.status_message = "Not running from a tmux session".to_string();
+                        self.success(format!(
+                            "Restored {} session(s), skipped {} existing",
+                            report.restored.len(),
+                            report.skipped.len()
+                        ));
                     }
+                    Err(e) => self.report_error(format!("Error restoring snapshot: {}", e)),
+                }
+                self.refresh_sessions().await?;
+            }
+            Action::StartTemplate => match Template::list() {
+                Ok(names) if names.is_empty() => {
+                    let dir = Template::dir()
+                        .map(|d| d.display().to_string())
+                        .unwrap_or_else(|| "~/.config/tmux-ui/templates".to_string());
+                    self.warn(format!("No templates found in {}", dir));
+                }
+                Ok(names) => {
+                    self.template_picker = Some(Picker::new("Start template".to_string(), names));
+                    self.input_mode = InputMode::PickingTemplate;
+                    self.info("Choose a template (Enter to start, ESC to cancel)");
+                }
+                Err(e) => self.report_error(format!("Error listing templates: {}", e)),
+            },
+            Action::EditTags => {
+                if let Some(session) = self.selected_session() {
+                    let tags = session.tags.join(", ");
+                    self.pending_tags = Some(SessionRef::of(session));
+                    self.input.set(tags);
+                    self.input_mode = InputMode::EditingTags;
+                    self.info("Enter tags separated by commas (empty to clear, ESC to cancel)");
+                }
+            }
+            Action::FilterTag => {
+                let mut tags: Vec<&String> = self.sessions.iter().flat_map(|s| &s.tags).collect();
+                tags.sort();
+                tags.dedup();
+                // Cycle through the tags in use, then back to all sessions
+                let next = match &self.tag_filter {
+                    None => tags.first(),
+                    Some(current) => tags.iter().find(|t| t.as_str() > current.as_str()),
+                };
+                self.tag_filter = next.map(|t| t.to_string());
+                self.info(match &self.tag_filter {
+                    Some(tag) => format!("Showing sessions tagged '{}'", tag),
+                    None if tags.is_empty() => "No session has tags yet".to_string(),
+                    None => "Showing all sessions".to_string(),
+                });
+                self.apply_filter();
+            }
+            Action::ToggleGroups => {
+                self.group_by_tag = !self.group_by_tag;
+                self.info(if self.group_by_tag {
+                    "Sessions grouped by tag".to_string()
                 } else {
-                    self.status_message = "Not inside tmux".to_string();
+                    "Sessions not grouped".to_string()
+                });
+                self.apply_filter();
+            }
+            Action::SortColumn => {
+                self.sort_column = next_sort(&self.table_columns, self.sort_column);
+                self.info(match self.sort_column {
+                    Some(key) => format!("Sorted by {}", key),
+                    None => "Sorted in the configured order".to_string(),
+                });
+                self.resort_sessions();
+            }
+            Action::ReverseSort => match &mut self.sort_column {
+                Some(key) => {
+                    key.descending = !key.descending;
+                    let message = format!("Sorted by {}", key);
+                    self.info(message);
+                    self.resort_sessions();
                 }
+                None => self.warn("Choose a column to sort by first"),
+            },
+            Action::Search => {
+                self.input.set(self.search.clone());
+                self.input_mode = InputMode::Searching;
+                self.info("Type to filter sessions (Enter to keep, ESC to clear)");
             }
-            KeyCode::Esc => {
+            Action::LastSession => return self.switch_to_last_session().await,
+            Action::History => self.open_history(),
+            Action::Back => {
                 // Go back to the original session (tmux-ui management session)
-                // Same as 'b' key
                 if self.client.is_inside_tmux() {
                     if let Some(ref session_name) = self.original_session {
-                        match self.client.switch_client(session_name) {
+                        let name = session_name.clone();
+                        match self.client.blocking(move |c| c.switch_client(&name)).await {
                             Ok(_) => {
-                                self.status_message = format!("Switched back to tmux-ui session '{}'", session_name);
+                                if let Some(session) = self
+                                    .sessions
+                                    .iter()
+                                    .find(|s| s.server.is_none() && &s.name == session_name)
+                                {
+                                    self.history.visit(SessionRef::of(session));
+                                }
+                                self.success(format!(
+                                    "Switched back to tmux-ui session '{}'",
+                                    session_name
+                                ));
                                 self.refresh_sessions().await?;
                             }
                             Err(e) => {
-                                self.status_message = format!("Error switching back: {}", e);
+                                let target = SessionRef {
+                                    server: None,
+                                    id: String::new(),
+                                    name: session_name.clone(),
+                                };
+                                self.report_failure(RetryAction::SwitchClient(target), e);
                             }
                         }
                     } else {
-                        self.status_message = "Not running from a tmux session".to_string();
+                        self.warn("Not running from a tmux session");
                     }
                 } else {
-                    self.status_message = "Not inside tmux".to_string();
+                    self.warn("Not inside tmux");
                 }
             }
             _ => {}
@@ -315,191 +1624,2833 @@ impl App {
         Ok(false)
     }
 
-    async fn handle_creating_input(&mut self, key: KeyCode) -> Result<bool> {
-        match key {
-            KeyCode::Enter => {
-                if !self.input.is_empty() {
-                    let session_name = self.input.trim().to_string();
-                    match self.client.create_session(&session_name) {
-                        Ok(_) => {
-                            self.status_message = format!("Session '{}' created!", session_name);
-                            self.input.clear();
-                            self.input_mode = InputMode::Normal;
-                            self.refresh_sessions().await?;
+    /// Whether an attach action should detach the session's other clients
+    fn exclusive(&self, action: Action) -> bool {
+        action == Action::AttachExclusive || self.config.exclusive_attach
+    }
+
+    /// Switch to the session when already inside its tmux server, otherwise exit the TUI
+    /// and attach to it. Returns whether the TUI should exit.
+    async fn attach_or_switch(&mut self, target: SessionRef, exclusive: bool) -> Result<bool> {
+        let client = self.client_for(target.server.as_deref());
+
+        // Check if we're already inside a tmux session
+        if client.is_inside_this_server() {
+            // Use switch-client to change to the selected session
+            // This works within tmux and doesn't require exiting the TUI
+            let session = target.tmux_target().to_string();
+            let switched = client
+                .blocking(move |c| {
+                    c.switch_client(&session)?;
+                    if exclusive {
+                        c.detach_other_clients(&session)
+                    } else {
+                        Ok(0)
+                    }
+                })
+                .await;
+            match switched {
+                Ok(detached) => {
+                    self.track(EventKind::Attached, &target.name);
+                    self.history.visit(target.clone());
+                    self.success(match detached {
+                        0 => format!("Switched to session '{}'", target),
+                        n => format!(
+                            "Switched to session '{}' and detached {} other client(s)",
+                            target, n
+                        ),
+                    });
+                    if self.popup {
+                        return Ok(true);
+                    }
+                    self.refresh_sessions().await?;
+                }
+                // New sessions are created on the primary server only
+                Err(tmux::Error::SessionNotFound(_)) if target.server.is_none() => {
+                    self.refresh_sessions().await?;
+                    self.offer_to_create_session(&target.name);
+                }
+                Err(e) => {
+                    self.report_failure(RetryAction::SwitchClient(target), e);
+                }
+            }
+        } else if client.is_inside_tmux() && client.host().is_none() {
+            // switch-client cannot cross servers and nesting attach is unsafe.
+            // Sessions on other machines are attached over ssh, nested in this one.
+            self.report_error(format!(
+                "Session '{}' is on another tmux server; detach first to attach to it",
+                target
+            ));
+        } else {
+            // Not inside tmux, use attach-session
+            // Store the session to attach to after TUI exits
+            self.info(format!("Attaching to session '{}'...", target));
+            self.attach_on_exit = Some(target);
+            self.attach_exclusive = exclusive;
+            // Return true to exit TUI, then attach
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    async fn handle_window_input(&mut self, key: KeyEvent) -> Result<bool> {
+        let Some(action) = self.keymap.action(&key, Scope::Windows) else {
+            return Ok(false);
+        };
+        let View::Windows(view) = &mut self.view else {
+            return Ok(false);
+        };
+        let session = view.session.clone();
+        let window = view.selected_window().cloned();
+
+        match action {
+            Action::Quit => return Ok(true),
+            Action::Help => {
+                self.show_help(&[
+                    (Action::Attach, "select window"),
+                    (Action::DrillDown, "panes"),
+                    (Action::SendCommand, "send command"),
+                    (Action::RunCommand, "run command"),
+                    (Action::ScratchTerminal, "scratch terminal"),
+                    (Action::Buffers, "paste buffers"),
+                    (Action::Options, "options"),
+                    (Action::Environment, "environment"),
+                    (Action::Hooks, "hooks"),
+                    (Action::Layout, "layout"),
+                    (Action::New, "new"),
+                    (Action::Rename, "rename"),
+                    (Action::Kill, "kill"),
+                    (Action::Undo, "undo kill"),
+                    (Action::MoveWindowUp, "move up"),
+                    (Action::MoveWindowDown, "move down"),
+                    (Action::MoveWindow, "move to session"),
+                    (Action::LinkWindow, "link into session"),
+                    (Action::UnlinkWindow, "unlink"),
+                    (Action::ToggleSync, "synchronize panes"),
+                    (Action::RotatePanes, "rotate panes"),
+                    (Action::TogglePreview, "preview"),
+                    (Action::Refresh, "refresh"),
+                    (Action::Back, "back to sessions"),
+                    (Action::Quit, "quit"),
+                    (Action::Up, "up"),
+                    (Action::Down, "down"),
+                    (Action::Top, "first"),
+                    (Action::Bottom, "last"),
+                    (Action::PageUp, "page up"),
+                    (Action::PageDown, "page down"),
+                ]);
+            }
+            Action::TogglePreview => self.toggle_preview(),
+            Action::Undo => self.undo().await?,
+            Action::Buffers => self.open_buffers().await?,
+            Action::Options => self.open_options().await?,
+            Action::Environment => self.open_environment().await?,
+            Action::Hooks => self.open_hooks().await?,
+            Action::DrillDown | Action::Expand => {
+                if let Some(window) = window {
+                    let View::Windows(parent) = std::mem::replace(&mut self.view, View::Sessions)
+                    else {
+                        return Ok(false);
+                    };
+                    self.view = View::Panes(PaneView::new(parent, window));
+                    self.refresh_panes().await?;
+                }
+            }
+            Action::Back | Action::Collapse => {
+                self.view = View::Sessions;
+                self.info("Back to sessions");
+                self.refresh_sessions().await?;
+            }
+            Action::Down => view.select_next(),
+            Action::Up => view.select_previous(),
+            Action::New => {
+                self.input_mode = InputMode::CreatingWindow;
+                self.input.clear();
+                self.info("Enter window name (empty for default, ESC to cancel, Enter to create):");
+            }
+            Action::RunCommand => self.start_run_command(),
+            Action::ScratchTerminal => self.open_scratch_terminal().await,
+            Action::Layout => self.open_layout_menu(),
+            Action::SendCommand => {
+                if let Some(window) = window {
+                    self.start_sending_command(PaneTarget {
+                        server: session.server.clone(),
+                        target: window.id.clone(),
+                        label: format!("window '{}'", window.name),
+                    });
+                }
+            }
+            Action::Rename if window.is_some() => {
+                self.input_mode = InputMode::RenamingWindow;
+                self.input.clear();
+                self.info("Enter new window name (ESC to cancel, Enter to rename):");
+            }
+            Action::Kill => {
+                if let Some(window) = window {
+                    self.info(format!(
+                        "Kill window '{}' and its {} pane(s)? (y to confirm, any other key to cancel)",
+                        window.name, window.panes
+                    ));
+                    self.pending_kill = Some(PendingKill::Window(PaneTarget {
+                        server: session.server.clone(),
+                        target: window.id.clone(),
+                        label: format!("window '{}'", window.name),
+                    }));
+                    self.input_mode = InputMode::ConfirmingKill;
+                }
+            }
+            Action::MoveWindowUp | Action::MoveWindowDown => {
+                let Some(i) = view.selected.selected() else {
+                    return Ok(false);
+                };
+                let neighbour = if action == Action::MoveWindowUp {
+                    i.checked_sub(1).and_then(|j| view.windows.get(j))
+                } else {
+                    view.windows.get(i + 1)
+                };
+                let (Some(window), Some(neighbour)) = (window, neighbour.cloned()) else {
+                    return Ok(false);
+                };
+                let (id, other) = (window.id.clone(), neighbour.id.clone());
+                match self
+                    .client_for(session.server.as_deref())
+                    .blocking(move |c| c.swap_window(&id, &other))
+                    .await
+                {
+                    Ok(_) => {
+                        self.success(format!(
+                            "Window '{}' moved to index {}",
+                            window.name, neighbour.index
+                        ));
+                    }
+                    Err(e) => self.report_error(format!("Error moving window: {}", e)),
+                }
+                self.refresh_windows().await?;
+            }
+            Action::RotatePanes => {
+                if let Some(window) = window {
+                    self.rotate_panes(session.server.as_deref(), &window)
+                        .await?;
+                }
+            }
+            Action::ToggleSync => {
+                if let Some(window) = window {
+                    let value = if window.synchronized { "off" } else { "on" };
+                    let id = window.id.clone();
+                    match self
+                        .client_for(session.server.as_deref())
+                        .blocking(move |c| c.set_window_option(&id, "synchronize-panes", value))
+                        .await
+                    {
+                        Ok(_) if window.synchronized => {
+                            self.success(format!(
+                                "Panes of '{}' no longer synchronized",
+                                window.name
+                            ));
                         }
-                        Err(e) => {
-                            self.status_message = format!("Error creating session: {}", e);
-                            self.input_mode = InputMode::Normal;
+                        Ok(_) => {
+                            self.warn(format!(
+                                "Keys typed into one pane of '{}' now go to all {} panes",
+                                window.name, window.panes
+                            ));
                         }
+                        Err(e) => self.report_error(format!("Error synchronizing panes: {}", e)),
                     }
+                    self.refresh_windows().await?;
                 }
             }
-            KeyCode::Char(c) => {
-                self.input.push(c);
+            Action::MoveWindow | Action::LinkWindow => {
+                let Some(window) = window else {
+                    return Ok(false);
+                };
+                let (transfer, verb) = if action == Action::LinkWindow {
+                    (WindowTransfer::Link, "link")
+                } else {
+                    (WindowTransfer::Move, "move")
+                };
+                let targets: Vec<String> = self
+                    .sessions
+                    .iter()
+                    .filter(|s| s.server == session.server && s.id != session.id)
+                    .map(|s| s.name.clone())
+                    .collect();
+                if targets.is_empty() {
+                    self.warn(format!("No other session to {} the window to", verb));
+                    return Ok(false);
+                }
+                let title = match transfer {
+                    WindowTransfer::Move => format!("Move window '{}' to", window.name),
+                    WindowTransfer::Link => format!("Link window '{}' into", window.name),
+                };
+                self.session_picker = Some(Picker::new(title, targets));
+                self.moving_window = Some((window, transfer));
+                self.input_mode = InputMode::PickingSession;
+                self.info(format!(
+                    "Choose a session (Enter to {} the window, ESC to cancel)",
+                    verb
+                ));
             }
-            KeyCode::Backspace => {
-                self.input.pop();
+            Action::UnlinkWindow => {
+                let Some(window) = window else {
+                    return Ok(false);
+                };
+                if !window.linked {
+                    self.warn(format!(
+                        "Window '{}' is only in this session; kill it instead",
+                        window.name
+                    ));
+                    return Ok(false);
+                }
+                let (name, id) = (session.name.clone(), window.id.clone());
+                match self
+                    .client_for(session.server.as_deref())
+                    .blocking(move |c| c.unlink_window(&name, &id))
+                    .await
+                {
+                    Ok(_) => self.success(format!(
+                        "Window '{}' unlinked from session '{}'",
+                        window.name, session.name
+                    )),
+                    Err(e) => self.report_error(format!("Error unlinking window: {}", e)),
+                }
+                self.refresh_windows().await?;
             }
-            KeyCode::Esc => {
-                self.input.clear();
-                self.input_mode = InputMode::Normal;
-                self.status_message = "Cancelled".to_string();
+            Action::Attach | Action::AttachExclusive => {
+                if let Some(window) = window {
+                    let id = window.id.clone();
+                    if let Err(e) = self
+                        .client_for(session.server.as_deref())
+                        .blocking(move |c| c.select_window(&id))
+                        .await
+                    {
+                        self.report_error(format!("Error selecting window: {}", e));
+                        return Ok(false);
+                    }
+                    let exclusive = self.exclusive(action);
+                    return self.attach_or_switch(session, exclusive).await;
+                }
+            }
+            Action::Refresh => {
+                self.refresh_windows().await?;
+                if self.error_banner.is_none() {
+                    self.success("Windows refreshed!");
+                }
+            }
+            Action::DismissError if self.error_banner.is_some() => {
+                self.error_banner = None;
+                self.info("Error dismissed");
             }
             _ => {}
         }
         Ok(false)
     }
 
-    async fn handle_renaming_input(&mut self, key: KeyCode) -> Result<bool> {
-        match key {
-            KeyCode::Enter => {
-                if !self.input.is_empty() {
-                    if let Some(index) = self.selected.selected() {
-                        if index < self.sessions.len() {
-                            let old_name = self.sessions[index].name.clone();
-                            let new_name = self.input.trim().to_string();
-                            match self.client.rename_session(&old_name, &new_name) {
-                                Ok(_) => {
-                                    self.status_message = format!(
-                                        "Session renamed from '{}' to '{}'!",
-                                        old_name, new_name
-                                    );
-                                    self.input.clear();
-                                    self.input_mode = InputMode::Normal;
-                                    self.refresh_sessions().await?;
-                                }
-                                Err(e) => {
-                                    self.status_message = format!("Error renaming session: {}", e);
-                                    self.input_mode = InputMode::Normal;
-                                }
-                            }
+    async fn handle_tree_input(&mut self, key: KeyEvent) -> Result<bool> {
+        let Some(action) = self.keymap.action(&key, Scope::Tree) else {
+            return Ok(false);
+        };
+        let View::Tree(view) = &mut self.view else {
+            return Ok(false);
+        };
+
+        match action {
+            Action::Quit => return Ok(true),
+            Action::Help => {
+                self.show_help(&[
+                    (Action::Attach, "switch to"),
+                    (Action::Expand, "expand"),
+                    (Action::Collapse, "collapse"),
+                    (Action::DrillDown, "expand/collapse"),
+                    (Action::Refresh, "refresh"),
+                    (Action::ToggleTree, "session list"),
+                    (Action::Quit, "quit"),
+                    (Action::Up, "up"),
+                    (Action::Down, "down"),
+                    (Action::Top, "first"),
+                    (Action::Bottom, "last"),
+                    (Action::PageUp, "page up"),
+                    (Action::PageDown, "page down"),
+                ]);
+            }
+            Action::Down => view.select_next(),
+            Action::Up => view.select_previous(),
+            Action::Expand => view.expand(),
+            Action::Collapse => view.collapse(),
+            Action::DrillDown => view.toggle(),
+            Action::ToggleTree | Action::Back => {
+                self.view = View::Sessions;
+                self.info("Back to sessions");
+                self.refresh_sessions().await?;
+            }
+            Action::Attach | Action::AttachExclusive => {
+                let Some(row) = view.selected_row().cloned() else {
+                    return Ok(false);
+                };
+                let session = &view.sessions[row.session];
+                let target = SessionRef {
+                    server: None,
+                    id: session.id.clone(),
+                    name: session.name.clone(),
+                };
+                let window = row.window.map(|w| session.windows[w].window.id.clone());
+                let kind = row.kind;
+                let pane = row.id.clone();
+                let result = self
+                    .client
+                    .blocking(move |c| match (kind, window) {
+                        (NodeKind::Session, _) | (_, None) => Ok(()),
+                        (NodeKind::Window, Some(window)) => c.select_window(&window),
+                        (NodeKind::Pane, Some(window)) => {
+                            c.select_window(&window).and_then(|_| c.select_pane(&pane))
                         }
-                    }
+                    })
+                    .await;
+                if let Err(e) = result {
+                    self.report_error(format!("Error selecting {}: {}", row.id, e));
+                    return Ok(false);
                 }
+                let exclusive = self.exclusive(action);
+                return self.attach_or_switch(target, exclusive).await;
             }
-            KeyCode::Char(c) => {
-                self.input.push(c);
-            }
-            KeyCode::Backspace => {
-                self.input.pop();
+            Action::Refresh => {
+                self.refresh_tree().await;
+                if self.error_banner.is_none() {
+                    self.success("Tree refreshed!");
+                }
             }
-            KeyCode::Esc => {
-                self.input.clear();
-                self.input_mode = InputMode::Normal;
-                self.status_message = "Cancelled".to_string();
+            Action::DismissError if self.error_banner.is_some() => {
+                self.error_banner = None;
+                self.info("Error dismissed");
             }
             _ => {}
         }
         Ok(false)
     }
 
-    async fn refresh_sessions(&mut self) -> Result<()> {
-        self.sessions = self.client.list_sessions()?;
+    async fn handle_clients_input(&mut self, key: KeyEvent) -> Result<bool> {
+        let Some(action) = self.keymap.action(&key, Scope::Clients) else {
+            return Ok(false);
+        };
+        let View::Clients(view) = &mut self.view else {
+            return Ok(false);
+        };
 
-        // Adjust selection if needed
-        if self.sessions.is_empty() {
-            self.selected.select(None);
-        } else if let Some(selected) = self.selected.selected() {
-            if selected >= self.sessions.len() {
-                self.selected.select(Some(self.sessions.len() - 1));
+        match action {
+            Action::Quit => return Ok(true),
+            Action::Help => {
+                self.show_help(&[
+                    (Action::Attach, "switch to the client's session"),
+                    (Action::Detach, "detach client"),
+                    (Action::Refresh, "refresh"),
+                    (Action::Clients, "session list"),
+                    (Action::Quit, "quit"),
+                    (Action::Up, "up"),
+                    (Action::Down, "down"),
+                    (Action::Top, "first"),
+                    (Action::Bottom, "last"),
+                    (Action::PageUp, "page up"),
+                    (Action::PageDown, "page down"),
+                ]);
             }
-        } else {
-            self.selected.select(Some(0));
+            Action::Down => view.select_next(),
+            Action::Up => view.select_previous(),
+            Action::Detach | Action::Kill => self.detach_selected_client().await,
+            Action::Attach | Action::AttachExclusive => {
+                let Some(target) = self.selected_client_session() else {
+                    return Ok(false);
+                };
+                let exclusive = self.exclusive(action);
+                return self.attach_or_switch(target, exclusive).await;
+            }
+            Action::Clients | Action::Back => {
+                self.view = View::Sessions;
+                self.info("Back to sessions");
+                self.refresh_sessions().await?;
+            }
+            Action::Refresh => {
+                self.refresh_clients().await;
+                if self.error_banner.is_none() {
+                    self.success("Clients refreshed!");
+                }
+            }
+            Action::DismissError if self.error_banner.is_some() => {
+                self.error_banner = None;
+                self.info("Error dismissed");
+            }
+            _ => {}
         }
+        Ok(false)
+    }
 
-        Ok(())
+    /// Reload the session tree of the primary server
+    async fn refresh_tree(&mut self) {
+        let tree = self.client.blocking(|c| c.list_tree()).await;
+        self.apply_tree(tree);
     }
 
-    fn ui(&mut self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .margin(1)
-            .constraints([
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Min(0),
-                Constraint::Length(3),
-            ])
-            .split(f.size());
-
-        // Title
-        let title = Paragraph::new("🖥️  tmux-ui - Session Manager")
-            .style(Style::default().fg(Color::Cyan))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(title, chunks[0]);
-
-        // Action buttons bar
-        let actions_line = if self.client.is_inside_tmux() {
-            Line::from(vec![
-                Span::styled("[a] Attach/Switch  ", Style::default().fg(Color::Yellow)),
-                Span::styled("[Esc/b] Back to UI  ", Style::default().fg(Color::Yellow)),
-                Span::styled("[x] Detach", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::styled("  [n] New  [d] Delete  [r] Rename  [q] Quit", Style::default().fg(Color::Yellow)),
-            ])
-        } else {
-            Line::from(vec![
-                Span::styled("[a] Attach  [x] Detach  [n] New  [d] Delete  [r] Rename  [w] New Window  [q] Quit", Style::default().fg(Color::Yellow)),
-            ])
+    /// Show a freshly listed tree; returns whether anything shown changed
+    fn apply_tree(&mut self, tree: tmux::Result<Vec<SessionTree>>) -> bool {
+        match tree {
+            Ok(mut sessions) => {
+                // The control-mode client counts as attached; only real clients should
+                if let Some(id) = self.control.as_ref().and_then(|c| c.session_id()) {
+                    if let Some(session) = sessions.iter_mut().find(|s| s.id == id) {
+                        session.clients = session.clients.saturating_sub(1);
+                    }
+                }
+                match &mut self.view {
+                    View::Tree(view) if view.sessions != sessions => {
+                        view.set_sessions(sessions);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Err(e) => {
+                self.report_error(format!("Error listing the session tree: {}", e));
+                true
+            }
+        }
+    }
+
+    async fn handle_pane_input(&mut self, key: KeyEvent) -> Result<bool> {
+        let Some(action) = self.keymap.action(&key, Scope::Panes) else {
+            return Ok(false);
         };
-        
-        let actions = Paragraph::new(actions_line)
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL).title("Actions"));
-        f.render_widget(actions, chunks[1]);
+        let View::Panes(view) = &mut self.view else {
+            return Ok(false);
+        };
+        let session = view.parent.session.clone();
+        let window = view.window.clone();
+        let pane = view.selected_pane().cloned();
 
-        // Session list
-        let sessions: Vec<ListItem> = self
-            .sessions
-            .iter()
-            .map(|session| {
-                let attached_indicator = if session.attached { "●" } else { "○" };
-                let style = if session.attached {
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD)
+        match action {
+            Action::Quit => return Ok(true),
+            Action::Help => {
+                self.show_help(&[
+                    (Action::Attach, "select pane and switch"),
+                    (Action::SelectPane, "make active"),
+                    (Action::ToggleZoom, "zoom"),
+                    (Action::SendCommand, "send command"),
+                    (Action::RunCommand, "run command"),
+                    (Action::ScratchTerminal, "scratch terminal"),
+                    (Action::Buffers, "paste buffers"),
+                    (Action::Options, "options"),
+                    (Action::Environment, "environment"),
+                    (Action::Hooks, "hooks"),
+                    (Action::SplitHorizontal, "split right"),
+                    (Action::SplitVertical, "split below"),
+                    (Action::Layout, "layout"),
+                    (Action::MoveWindowUp, "move up"),
+                    (Action::MoveWindowDown, "move down"),
+                    (Action::RotatePanes, "rotate panes"),
+                    (Action::BreakPane, "break into new window"),
+                    (Action::JoinPane, "join another window"),
+                    (Action::Rename, "respawn dead pane"),
+                    (Action::ToggleLog, "log output to a file"),
+                    (Action::ToggleDetails, "processes"),
+                    (Action::Kill, "kill"),
+                    (Action::Refresh, "refresh"),
+                    (Action::Back, "back to windows"),
+                    (Action::Quit, "quit"),
+                    (Action::Up, "up"),
+                    (Action::Down, "down"),
+                    (Action::Top, "first"),
+                    (Action::Bottom, "last"),
+                    (Action::PageUp, "page up"),
+                    (Action::PageDown, "page down"),
+                ]);
+            }
+            Action::DrillDown | Action::Back | Action::Collapse => {
+                self.close_pane_view();
+                self.info("Back to windows");
+                self.refresh_windows().await?;
+            }
+            Action::Down => view.select_next(),
+            Action::Up => view.select_previous(),
+            Action::ToggleDetails => self.toggle_details().await,
+            Action::Buffers => self.open_buffers().await?,
+            Action::Options => self.open_options().await?,
+            Action::Environment => self.open_environment().await?,
+            Action::Hooks => self.open_hooks().await?,
+            Action::ToggleZoom => {
+                if let Some(pane) = pane {
+                    if view.panes.len() < 2 {
+                        self.warn(format!(
+                            "'{}' has a single pane, nothing to zoom",
+                            window.name
+                        ));
+                        return Ok(false);
+                    }
+                    let client = self.client_for(session.server.as_deref()).clone();
+                    let id = pane.id.clone();
+                    match client.blocking(move |c| c.toggle_zoom(&id)).await {
+                        Ok(()) => {
+                            // Read the state back, since tmux may have unzoomed another pane
+                            let target = session.tmux_target().to_string();
+                            let zoomed = client
+                                .blocking(move |c| c.list_windows(&target))
+                                .await
+                                .ok()
+                                .and_then(|windows| windows.into_iter().find(|w| w.id == window.id))
+                                .is_some_and(|w| w.zoomed);
+                            if let View::Panes(view) = &mut self.view {
+                                view.window.zoomed = zoomed;
+                            }
+                            self.success(if zoomed {
+                                format!("Pane {} zoomed in '{}'", pane.index, window.name)
+                            } else {
+                                format!("'{}' unzoomed", window.name)
+                            });
+                        }
+                        Err(e) => self.report_error(format!("Error zooming pane: {}", e)),
+                    }
+                    self.refresh_panes().await?;
+                }
+            }
+            Action::SelectPane => {
+                if let Some(pane) = pane {
+                    let id = pane.id.clone();
+                    match self
+                        .client_for(session.server.as_deref())
+                        .blocking(move |c| c.select_pane(&id))
+                        .await
+                    {
+                        Ok(_) => {
+                            self.success(format!(
+                                "Pane {} is now active in '{}'",
+                                pane.index, window.name
+                            ));
+                        }
+                        Err(e) => {
+                            self.report_error(format!("Error selecting pane: {}", e));
+                        }
+                    }
+                    self.refresh_panes().await?;
+                }
+            }
+            Action::SplitHorizontal | Action::SplitVertical => {
+                if let Some(pane) = pane {
+                    let direction = if action == Action::SplitHorizontal {
+                        SplitDirection::Horizontal
+                    } else {
+                        SplitDirection::Vertical
+                    };
+                    let cwd = (self.config.split_inherit_cwd && !pane.current_path.is_empty())
+                        .then(|| PathBuf::from(&pane.current_path));
+                    let id = pane.id.clone();
+                    match self
+                        .client_for(session.server.as_deref())
+                        .blocking(move |c| c.split_window(&id, direction, None, cwd.as_deref()))
+                        .await
+                    {
+                        Ok(new_pane) => {
+                            self.success(format!("Split pane {}", pane.index));
+                            self.refresh_panes().await?;
+                            if let View::Panes(view) = &mut self.view {
+                                view.select_pane_id(&new_pane);
+                            }
+                        }
+                        Err(e) => {
+                            self.report_error(format!("Error splitting pane: {}", e));
+                            self.refresh_panes().await?;
+                        }
+                    }
+                }
+            }
+            Action::MoveWindowUp | Action::MoveWindowDown => {
+                let Some(i) = view.selected.selected() else {
+                    return Ok(false);
+                };
+                let neighbour = if action == Action::MoveWindowUp {
+                    i.checked_sub(1).and_then(|j| view.panes.get(j))
                 } else {
-                    Style::default().fg(Color::White)
+                    view.panes.get(i + 1)
+                };
+                let (Some(pane), Some(neighbour)) = (pane, neighbour.cloned()) else {
+                    return Ok(false);
+                };
+                // The selection follows the pane, which keeps its id
+                let (id, other) = (pane.id.clone(), neighbour.id.clone());
+                match self
+                    .client_for(session.server.as_deref())
+                    .blocking(move |c| c.swap_pane(&id, &other))
+                    .await
+                {
+                    Ok(_) => {
+                        self.success(format!(
+                            "Pane {} moved to index {}",
+                            pane.index, neighbour.index
+                        ));
+                    }
+                    Err(e) => self.report_error(format!("Error moving pane: {}", e)),
+                }
+                self.refresh_panes().await?;
+            }
+            Action::RotatePanes => {
+                self.rotate_panes(session.server.as_deref(), &window)
+                    .await?;
+            }
+            Action::BreakPane => {
+                if let Some(pane) = pane {
+                    let id = pane.id.clone();
+                    match self
+                        .client_for(session.server.as_deref())
+                        .blocking(move |c| c.break_pane(&id))
+                        .await
+                    {
+                        Ok(_) => {
+                            self.success(format!("Pane {} moved to a new window", pane.index));
+                        }
+                        Err(e) => self.report_error(format!("Error breaking pane: {}", e)),
+                    }
+                    self.refresh_panes().await?;
+                }
+            }
+            Action::JoinPane => {
+                let Some(pane) = pane else {
+                    return Ok(false);
+                };
+                // Listed afresh, since panes may have been broken out since
+                let target = session.tmux_target().to_string();
+                let windows = match self
+                    .client_for(session.server.as_deref())
+                    .blocking(move |c| c.list_windows(&target))
+                    .await
+                {
+                    Ok(windows) => windows,
+                    Err(e) => {
+                        self.report_error(format!("Error listing windows: {}", e));
+                        return Ok(false);
+                    }
                 };
+                let (labels, targets): (Vec<String>, Vec<(String, String)>) = windows
+                    .iter()
+                    .filter(|w| w.id != window.id)
+                    .map(|w| {
+                        (
+                            format!("{}: {}", w.index, w.name),
+                            (w.id.clone(), w.name.clone()),
+                        )
+                    })
+                    .unzip();
+                if targets.is_empty() {
+                    self.warn("No other window to join the pane to");
+                    return Ok(false);
+                }
+                self.window_picker =
+                    Some(Picker::new(format!("Join pane {} to", pane.index), labels));
+                self.joining_pane = Some((pane, targets));
+                self.input_mode = InputMode::PickingWindow;
+                self.info("Choose a window (Enter to join the pane, ESC to cancel)");
+            }
+            Action::RunCommand => self.start_run_command(),
+            Action::ScratchTerminal => self.open_scratch_terminal().await,
+            Action::Layout => self.open_layout_menu(),
+            Action::SendCommand => {
+                if let Some(pane) = pane {
+                    self.start_sending_command(PaneTarget {
+                        server: session.server.clone(),
+                        target: pane.id.clone(),
+                        label: format!("pane {} of '{}'", pane.index, window.name),
+                    });
+                }
+            }
+            Action::ToggleLog => {
+                if let Some(pane) = pane {
+                    let label = format!("pane {} of '{}'", pane.index, window.name);
+                    if pane.piped {
+                        let id = pane.id.clone();
+                        match self
+                            .client_for(session.server.as_deref())
+                            .blocking(move |c| c.pipe_pane(&id, None))
+                            .await
+                        {
+                            Ok(_) => self.success(format!("Stopped logging {}", label)),
+                            Err(e) => self.report_error(format!("Error stopping the log: {}", e)),
+                        }
+                    } else {
+                        match self
+                            .start_pane_log(session.server.as_deref(), &pane.id)
+                            .await
+                        {
+                            Ok(file) => self.success(format!(
+                                "Logging {} to {}",
+                                label,
+                                collapse_home(&file.to_string_lossy())
+                            )),
+                            Err(e) => self.report_error(format!("Error logging pane: {:#}", e)),
+                        }
+                    }
+                    self.refresh_panes().await?;
+                }
+            }
+            Action::Rename => {
+                if let Some(pane) = pane {
+                    let label = format!("pane {} of '{}'", pane.index, window.name);
+                    if !pane.dead {
+                        self.warn(format!(
+                            "{} is still running {}; only dead panes can be respawned",
+                            label, pane.current_command
+                        ));
+                        return Ok(false);
+                    }
+                    self.info(format!(
+                        "Command for {} (empty for the default shell, ESC to cancel, Enter to respawn):",
+                        label
+                    ));
+                    self.input.set(pane.start_command.clone());
+                    self.respawn_target = Some(PaneTarget {
+                        server: session.server.clone(),
+                        target: pane.id.clone(),
+                        label,
+                    });
+                    self.input_mode = InputMode::RespawningPane;
+                }
+            }
+            Action::Kill => {
+                if let Some(pane) = pane {
+                    let label = format!("pane {} of '{}'", pane.index, window.name);
+                    self.info(format!(
+                        "Kill {} running {}? (y to confirm, any other key to cancel)",
+                        label, pane.current_command
+                    ));
+                    self.pending_kill = Some(PendingKill::Pane(PaneTarget {
+                        server: session.server.clone(),
+                        target: pane.id.clone(),
+                        label,
+                    }));
+                    self.input_mode = InputMode::ConfirmingKill;
+                }
+            }
+            Action::Attach | Action::AttachExclusive => {
+                if let Some(pane) = pane {
+                    let (window_id, id) = (window.id.clone(), pane.id.clone());
+                    if let Err(e) = self
+                        .client_for(session.server.as_deref())
+                        .blocking(move |c| {
+                            c.select_window(&window_id).and_then(|_| c.select_pane(&id))
+                        })
+                        .await
+                    {
+                        self.report_error(format!("Error selecting pane: {}", e));
+                        return Ok(false);
+                    }
+                    let exclusive = self.exclusive(action);
+                    return self.attach_or_switch(session, exclusive).await;
+                }
+            }
+            Action::Refresh => {
+                self.refresh_panes().await?;
+                if self.error_banner.is_none() {
+                    self.success("Panes refreshed!");
+                }
+            }
+            Action::DismissError if self.error_banner.is_some() => {
+                self.error_banner = None;
+                self.info("Error dismissed");
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
 
-                let content = format!(
-                    "{} {} ({} windows)",
-                    attached_indicator, session.name, session.windows
-                );
-                ListItem::new(content).style(style)
-            })
-            .collect();
+    /// Rotate the panes of a window, from the window or the pane list
+    async fn rotate_panes(&mut self, server: Option<&str>, window: &TmuxWindow) -> Result<()> {
+        if window.panes < 2 {
+            self.warn(format!("Window '{}' has a single pane", window.name));
+            return Ok(());
+        }
+        let id = window.id.clone();
+        match self
+            .client_for(server)
+            .blocking(move |c| c.rotate_window(&id, false))
+            .await
+        {
+            Ok(()) => self.success(format!("Rotated the panes of '{}'", window.name)),
+            Err(e) => self.report_error(format!("Error rotating panes: {}", e)),
+        }
+        if matches!(self.view, View::Panes(_)) {
+            self.refresh_panes().await
+        } else {
+            self.refresh_windows().await
+        }
+    }
 
-        let sessions_list = List::new(sessions)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!("tmux Sessions ({})", self.sessions.len())),
-            )
-            .highlight_style(
-                Style::default()
-                    .bg(Color::DarkGray)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .highlight_symbol(">> ");
+    /// Go back from the pane view to the window view it was opened from
+    fn close_pane_view(&mut self) {
+        if let View::Panes(view) = std::mem::replace(&mut self.view, View::Sessions) {
+            self.view = View::Windows(view.parent);
+        }
+    }
+
+    /// Reload the panes of the window shown in the pane view,
+    /// returning to the window view if the window is gone
+    async fn refresh_panes(&mut self) -> Result<()> {
+        let View::Panes(view) = &self.view else {
+            return Ok(());
+        };
+        let client = self.client_for(view.parent.session.server.as_deref());
+        let target = view.window.id.clone();
 
-        f.render_stateful_widget(sessions_list, chunks[2], &mut self.selected);
+        let panes = client.blocking(move |c| c.list_panes(&target)).await;
+        if self.apply_panes(panes) {
+            self.refresh_windows().await?;
+        }
+        Ok(())
+    }
 
-        // Status/Input bar
-        let status_text = match self.input_mode {
-            InputMode::Normal => self.status_message.clone(),
-            InputMode::CreatingSession => format!("New session name: {}", self.input),
-            InputMode::RenamingSession => format!("Rename to: {}", self.input),
+    /// Show freshly listed panes in the pane view. Returns whether the window
+    /// was gone, in which case the view went back to the window list.
+    fn apply_panes(&mut self, panes: tmux::Result<Vec<TmuxPane>>) -> bool {
+        let View::Panes(view) = &mut self.view else {
+            return false;
         };
+        match panes {
+            // list-panes fails (and returns nothing) once the window was killed
+            Ok(panes) if panes.is_empty() => {
+                let name = view.window.name.clone();
+                self.close_pane_view();
+                self.warn(format!("Window '{}' no longer exists", name));
+                return true;
+            }
+            Ok(panes) => view.set_panes(panes),
+            Err(e) => self.report_error(format!("Error listing panes: {}", e)),
+        }
+        false
+    }
 
-        let status = Paragraph::new(status_text)
-            .style(match self.input_mode {
-                InputMode::Normal => Style::default(),
-                _ => Style::default().fg(Color::Yellow),
-            })
-            .wrap(Wrap { trim: true })
-            .block(Block::default().borders(Borders::ALL).title("Status"));
+    async fn handle_picking_template_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(picker) = self.template_picker.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                let name = picker.selected_item().map(str::to_string);
+                self.template_picker = None;
+                self.input_mode = InputMode::Normal;
+                let Some(name) = name else {
+                    return Ok(());
+                };
+                let template = name.clone();
+                let started = self
+                    .client
+                    .blocking(move |c| Template::load(&template).and_then(|t| c.start_template(&t)))
+                    .await;
+                match started {
+                    Ok(session) => {
+                        self.track(EventKind::Created, &session);
+                        self.success(format!(
+                            "Session '{}' started from template '{}'",
+                            session, name
+                        ));
+                        self.refresh_sessions().await?;
+                        self.select_session_named(&session);
+                    }
+                    Err(e) => self.report_error(format!("Error starting template: {:#}", e)),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.template_picker = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 
-        f.render_widget(status, chunks[3]);
+    async fn handle_picking_session_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(picker) = self.session_picker.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                let target = picker.selected_item().map(str::to_string);
+                self.session_picker = None;
+                self.input_mode = InputMode::Normal;
+                let (Some(target), Some((window, transfer))) = (target, self.moving_window.take())
+                else {
+                    return Ok(());
+                };
+                let View::Windows(view) = &self.view else {
+                    return Ok(());
+                };
+                let (id, session) = (window.id.clone(), target.clone());
+                let result = self
+                    .client_for(view.session.server.as_deref())
+                    .blocking(move |c| match transfer {
+                        WindowTransfer::Move => c.move_window(&id, &session),
+                        WindowTransfer::Link => c.link_window(&id, &session),
+                    })
+                    .await;
+                match (result, transfer) {
+                    (Ok(_), WindowTransfer::Move) => self.success(format!(
+                        "Window '{}' moved to session '{}'",
+                        window.name, target
+                    )),
+                    (Ok(_), WindowTransfer::Link) => self.success(format!(
+                        "Window '{}' linked into session '{}'",
+                        window.name, target
+                    )),
+                    (Err(e), WindowTransfer::Move) => {
+                        self.report_error(format!("Error moving window: {}", e))
+                    }
+                    (Err(e), WindowTransfer::Link) => {
+                        self.report_error(format!("Error linking window: {}", e))
+                    }
+                }
+                // Moving the last window away closes the session, which takes
+                // the view back to the session list
+                self.refresh_windows().await?;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.session_picker = None;
+                self.moving_window = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn handle_picking_server_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(picker) = self.server_picker.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                let index = picker.selected_index();
+                self.server_picker = None;
+                self.input_mode = InputMode::Normal;
+                // Items are "All servers", the default server, then the configured ones
+                let Some(index) = index else {
+                    return Ok(());
+                };
+                self.combined = index == 0;
+                self.shown_server = index
+                    .checked_sub(2)
+                    .and_then(|i| self.servers.get(i))
+                    .map(|s| s.name.clone());
+                self.info(if self.combined {
+                    "Showing sessions from all servers".to_string()
+                } else {
+                    format!(
+                        "Showing sessions from the {} server",
+                        self.shown_server_label()
+                    )
+                });
+                self.selected.select(Some(0));
+                self.refresh_sessions().await?;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.server_picker = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Name of the server shown outside the combined view
+    fn shown_server_label(&self) -> &str {
+        self.shown_server.as_deref().unwrap_or(DEFAULT_SERVER_LABEL)
+    }
+
+    async fn handle_picking_window_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(picker) = self.window_picker.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match key {
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Enter => {
+                let index = picker.selected_index();
+                self.window_picker = None;
+                self.input_mode = InputMode::Normal;
+                let Some((pane, targets)) = self.joining_pane.take() else {
+                    return Ok(());
+                };
+                let Some((target, name)) = index.and_then(|i| targets.get(i)) else {
+                    return Ok(());
+                };
+                let View::Panes(view) = &self.view else {
+                    return Ok(());
+                };
+                let server = view.parent.session.server.clone();
+                let (id, target) = (pane.id.clone(), target.clone());
+                match self
+                    .client_for(server.as_deref())
+                    .blocking(move |c| c.join_pane(&id, &target, SplitDirection::Vertical))
+                    .await
+                {
+                    Ok(_) => {
+                        self.success(format!("Pane {} joined to window '{}'", pane.index, name));
+                    }
+                    Err(e) => self.report_error(format!("Error joining pane: {}", e)),
+                }
+                // Joining the last pane away closes the window, which takes
+                // the view back to the window list
+                self.refresh_panes().await?;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.window_picker = None;
+                self.joining_pane = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Move the selection to a session of the primary server, if it is visible
+    fn select_session_named(&mut self, name: &str) {
+        if let Some(i) = self.visible.iter().position(|m| {
+            let session = &self.sessions[m.index];
+            session.server.is_none() && session.name == name
+        }) {
+            self.selected.select(Some(i));
+        }
+    }
+
+    fn handle_searching_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.info(if self.search.is_empty() {
+                    String::new()
+                } else {
+                    format!("{} matching sessions", self.visible.len())
+                });
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.search.clear();
+                self.input_mode = InputMode::Normal;
+                self.apply_filter();
+                self.info("Search cleared");
+            }
+            KeyCode::Down | KeyCode::Up => {
+                let len = self.visible.len();
+                if len > 0 {
+                    let i = self.selected.selected().unwrap_or(0);
+                    let i = if key.code == KeyCode::Down {
+                        (i + 1) % len
+                    } else {
+                        (i + len - 1) % len
+                    };
+                    self.selected.select(Some(i));
+                }
+            }
+            _ => {
+                if self.input.handle_key(&key) {
+                    self.update_search();
+                }
+            }
+        }
+    }
+
+    /// Filter the sessions by the search text typed so far
+    fn update_search(&mut self) {
+        if self.search != *self.input {
+            self.search = self.input.to_string();
+            self.apply_filter();
+            self.selected
+                .select((!self.visible.is_empty()).then_some(0));
+        }
+    }
+
+    /// Session order: the column chosen in the table, then the configured keys
+    fn sort_spec(&self) -> SortSpec {
+        // Sort keys were validated when the config was parsed
+        let mut spec = self.config.sort_spec().unwrap_or_default();
+        if let Some(key) = self.sort_column {
+            spec.keys.insert(0, key);
+        }
+        spec
+    }
+
+    /// Re-sort the sessions after the sort column changed, keeping the selection
+    fn resort_sessions(&mut self) {
+        let selected = self.selected_session().map(SessionRef::of);
+        let spec = self.sort_spec();
+        self.sessions.sort_by(|a, b| spec.compare(a, b));
+        self.apply_filter();
+        if let Some(selected) = selected {
+            if let Some(i) = self
+                .visible
+                .iter()
+                .position(|m| SessionRef::of(&self.sessions[m.index]).same_session(&selected))
+            {
+                self.selected.select(Some(i));
+            }
+        }
+    }
+
+    /// Recompute the visible sessions from the search pattern, best match first
+    fn apply_filter(&mut self) {
+        let mut visible: Vec<(i64, SessionMatch)> = self
+            .sessions
+            .iter()
+            .enumerate()
+            .filter(|(_, session)| match &self.tag_filter {
+                Some(tag) => session.tags.contains(tag),
+                None => true,
+            })
+            .filter_map(|(index, session)| {
+                let m = fuzzy_match(&self.search, &session.name)?;
+                Some((
+                    m.score,
+                    SessionMatch {
+                        index,
+                        positions: m.positions,
+                    },
+                ))
+            })
+            .collect();
+        if !self.search.is_empty() {
+            // Stable, so equally good matches keep the configured order
+            visible.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        }
+        if self.group_by_tag {
+            // Stable as well, so each group keeps the order from above
+            let sessions = &self.sessions;
+            visible.sort_by(|(_, a), (_, b)| {
+                tag_group_order(&sessions[a.index]).cmp(&tag_group_order(&sessions[b.index]))
+            });
+        }
+        if self.combined {
+            // Servers are the top-level groups, each keeping the order from above
+            let (sessions, servers) = (&self.sessions, &self.servers);
+            visible.sort_by_key(|(_, m)| server_order(servers, &sessions[m.index]));
+        }
+        self.visible = visible.into_iter().map(|(_, m)| m).collect();
+
+        // Keep the selection within the list
+        if self.visible.is_empty() {
+            self.selected.select(None);
+        } else if let Some(selected) = self.selected.selected() {
+            if selected >= self.visible.len() {
+                self.selected.select(Some(self.visible.len() - 1));
+            }
+        } else {
+            self.selected.select(Some(0));
+        }
+    }
+
+    async fn handle_creating_window_input(&mut self, key: KeyEvent) -> Result<()> {
+        let View::Windows(view) = &self.view else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let session = view.session.clone();
+
+        match key.code {
+            KeyCode::Enter => {
+                let name = self.input.trim().to_string();
+                let target = session.tmux_target().to_string();
+                match self
+                    .client_for(session.server.as_deref())
+                    .blocking(move |c| {
+                        c.create_window(&target, (!name.is_empty()).then_some(name.as_str()))
+                    })
+                    .await
+                {
+                    Ok(_) => {
+                        self.track(EventKind::WindowCreated, &session.name);
+                        self.success(format!("New window created in session '{}'", session));
+                    }
+                    Err(e) => {
+                        self.report_error(format!("Error creating window: {}", e));
+                    }
+                }
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.refresh_windows().await?;
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_renaming_window_input(&mut self, key: KeyEvent) -> Result<()> {
+        let View::Windows(view) = &self.view else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let session = view.session.clone();
+        let window = view.selected_window().cloned();
+
+        match key.code {
+            KeyCode::Enter if !self.input.trim().is_empty() => {
+                let new_name = self.input.trim().to_string();
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                let Some(window) = window else {
+                    return Ok(());
+                };
+
+                let client = self.client_for(session.server.as_deref()).clone();
+                let (id, name) = (window.id.clone(), new_name.clone());
+                match client.blocking(move |c| c.rename_window(&id, &name)).await {
+                    Ok(_) => {
+                        // Otherwise tmux may replace the name with the running command
+                        let note = if window.automatic_rename && self.config.lock_window_names {
+                            let id = window.id.clone();
+                            match client
+                                .blocking(move |c| c.disable_automatic_rename(&id))
+                                .await
+                            {
+                                Ok(_) => " (automatic rename turned off)",
+                                Err(_) => " (automatic rename may overwrite it)",
+                            }
+                        } else {
+                            ""
+                        };
+                        self.success(format!(
+                            "Window renamed from '{}' to '{}'!{}",
+                            window.name, new_name, note
+                        ));
+                    }
+                    Err(e) => {
+                        self.report_error(format!("Error renaming window: {}", e));
+                    }
+                }
+                self.refresh_windows().await?;
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reload the windows (and their panes) of the session shown in the window view,
+    /// returning to the session list if the session is gone
+    async fn refresh_windows(&mut self) -> Result<()> {
+        let View::Windows(view) = &self.view else {
+            return Ok(());
+        };
+        let session = view.session.clone();
+
+        if self.revalidate(&session).await?.is_none() {
+            self.view = View::Sessions;
+            self.warn(format!("Session '{}' no longer exists", session));
+            return Ok(());
+        }
+
+        let client = self.client_for(session.server.as_deref()).clone();
+        let windows = fetch_windows(client, session.tmux_target().to_string()).await;
+        self.apply_windows(windows);
+        Ok(())
+    }
+
+    /// Show freshly listed windows in the window view
+    fn apply_windows(&mut self, windows: Result<WindowList>) {
+        match windows {
+            Ok((windows, panes)) => {
+                if let View::Windows(view) = &mut self.view {
+                    view.set_windows(windows, panes);
+                }
+            }
+            Err(e) => self.report_error(format!("Error listing windows: {}", e)),
+        }
+    }
+
+    async fn handle_renaming_input(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter => {
+                let new_name = match validate_session_name(&self.input) {
+                    Ok(name) => name,
+                    Err(e) => {
+                        self.input_error = Some(e.to_string());
+                        return Ok(false);
+                    }
+                };
+                if let Some(pending) = self.pending_rename.take() {
+                    // Re-resolve the session: it may have been killed or renamed meanwhile
+                    let Some(target) = self.revalidate(&pending).await? else {
+                        self.input.clear();
+                        self.input_mode = InputMode::Normal;
+                        self.warn(format!(
+                            "Session '{}' no longer exists; rename cancelled",
+                            pending
+                        ));
+                        return Ok(false);
+                    };
+                    let note = if target.name != pending.name {
+                        format!(
+                            " (it had been renamed to '{}' outside tmux-ui)",
+                            target.name
+                        )
+                    } else {
+                        String::new()
+                    };
+                    let (session, name) = (target.tmux_target().to_string(), new_name.clone());
+                    match self
+                        .client_for(target.server.as_deref())
+                        .blocking(move |c| c.rename_session(&session, &name))
+                        .await
+                    {
+                        Ok(_) => {
+                            self.track(EventKind::Renamed, &new_name);
+                            self.success(format!(
+                                "Session renamed from '{}' to '{}'!{}",
+                                target, new_name, note
+                            ));
+                            self.input.clear();
+                            self.input_mode = InputMode::Normal;
+                            self.refresh_sessions().await?;
+                        }
+                        Err(e) => {
+                            self.report_failure(
+                                RetryAction::RenameSession {
+                                    session: target,
+                                    new_name,
+                                },
+                                e,
+                            );
+                            self.input_mode = InputMode::Normal;
+                        }
+                    }
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.pending_rename = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(false)
+    }
+
+    async fn handle_editing_tags_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter => {
+                let tags = match parse_tags(&self.input) {
+                    Ok(tags) => tags,
+                    Err(e) => {
+                        self.input_error = Some(e.to_string());
+                        return Ok(());
+                    }
+                };
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                let Some(pending) = self.pending_tags.take() else {
+                    return Ok(());
+                };
+                let (session, new_tags) = (pending.tmux_target().to_string(), tags.clone());
+                match self
+                    .client_for(pending.server.as_deref())
+                    .blocking(move |c| c.set_session_tags(&session, &new_tags))
+                    .await
+                {
+                    Ok(_) if tags.is_empty() => {
+                        self.success(format!("Tags of '{}' cleared", pending));
+                    }
+                    Ok(_) => {
+                        self.success(format!("Session '{}' tagged {}", pending, tags.join(", ")));
+                    }
+                    Err(e) => self.report_error(format!("Error setting tags: {}", e)),
+                }
+                self.refresh_sessions().await?;
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.pending_tags = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_display_message_input(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter if !self.input.is_empty() => {
+                // Evaluate in the context of the selected session when there is one
+                let target = self.selected_session().map(SessionRef::of);
+                let (client, name) = match target {
+                    Some(t) => (self.client_for(t.server.as_deref()), Some(t.name)),
+                    None => (&self.client, None),
+                };
+                let format = self.input.to_string();
+                match client
+                    .blocking(move |c| c.display_message(name.as_deref(), &format))
+                    .await
+                {
+                    Ok(result) => {
+                        self.info(format!("{} {} {}", self.input, self.icons.arrow(), result));
+                    }
+                    Err(e) => {
+                        self.error(format!("Error expanding format: {}", e));
+                    }
+                }
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(false)
+    }
+
+    async fn handle_raw_command_input(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter if !self.input.trim().is_empty() => {
+                let command = self.input.trim().to_string();
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+
+                let args = match split_args(&command) {
+                    Ok(args) => args,
+                    Err(e) => {
+                        self.error(format!("Invalid command: {}", e));
+                        return Ok(false);
+                    }
+                };
+
+                // Run on the server of the selected session so the combined view works too
+                let server = self.selected_session().and_then(|s| s.server.clone());
+                match self
+                    .client_for(server.as_deref())
+                    .blocking(move |c| c.run_raw(&args))
+                    .await
+                {
+                    Ok(output) => {
+                        self.info(match output.code {
+                            Some(0) => format!("tmux {} succeeded", command),
+                            Some(code) => format!("tmux {} exited with {}", command, code),
+                            None => format!("tmux {} was terminated", command),
+                        });
+                        self.output_view = Some(OutputView::new(
+                            format!("tmux {}", command),
+                            &output.combined(),
+                            !output.success(),
+                        ));
+                        self.input_mode = InputMode::ViewingOutput;
+                    }
+                    Err(e) => {
+                        self.report_error(format!("Error running tmux command: {}", e));
+                    }
+                }
+                self.refresh_sessions().await?;
+            }
+            KeyCode::Tab => match complete_command(self.input.as_str()) {
+                Some((_, matches)) if matches.is_empty() => {
+                    let name = self.input.trim().to_string();
+                    self.warn(format!("No tmux command starts with '{}'", name));
+                }
+                Some((completed, matches)) => {
+                    self.input.set(completed);
+                    if matches.len() > 1 {
+                        self.info(matches.join("  "));
+                    }
+                }
+                None => self.info("Arguments are not completed, only command names"),
+            },
+            KeyCode::Esc => {
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(false)
+    }
+
+    fn handle_viewing_output_input(&mut self, key: KeyCode) {
+        let Some(view) = self.output_view.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        match key {
+            KeyCode::Up => view.scroll_by(-1),
+            KeyCode::Down => view.scroll_by(1),
+            KeyCode::PageUp => view.scroll_by(-10),
+            KeyCode::PageDown => view.scroll_by(10),
+            KeyCode::Home => view.scroll_to_start(),
+            KeyCode::End => view.scroll_to_end(),
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                self.output_view = None;
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether a session is detached and has had no activity for `stale_after_days`
+    fn is_stale(&self, session: &TmuxSession) -> bool {
+        !session.attached
+            && self
+                .config
+                .stale_after()
+                .is_some_and(|threshold| Utc::now() - session.last_activity > threshold)
+    }
+
+    async fn handle_confirming_prune_input(&mut self, key: KeyCode) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        let candidates = std::mem::take(&mut self.prune_candidates);
+        if !matches!(key, KeyCode::Char('y') | KeyCode::Char('Y')) {
+            self.info("Cancelled");
+            return Ok(());
+        }
+        if self.prune_kind == PruneKind::Stale {
+            return self.prune_stale_sessions(candidates).await;
+        }
+
+        // Sessions may have been attached or started something since they were listed
+        let still_idle = match self.session_source().idle_async().await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                self.report_error(format!("Error finding idle sessions: {}", e));
+                return Ok(());
+            }
+        };
+        let mut killed = 0;
+        for session in candidates.iter().filter(|c| {
+            still_idle
+                .iter()
+                .any(|s| s.id == c.id && s.server == c.server)
+        }) {
+            let id = session.id.clone();
+            match self
+                .client_for(session.server.as_deref())
+                .blocking(move |c| c.kill_session(&id))
+                .await
+            {
+                Ok(_) => {
+                    self.track(EventKind::Killed, &session.name);
+                    killed += 1;
+                }
+                Err(e) => {
+                    self.report_error(format!("Error killing session '{}': {}", session.name, e));
+                }
+            }
+        }
+        if self.error_banner.is_none() {
+            self.success(match candidates.len() - killed {
+                0 => format!("Pruned {} idle session(s)", killed),
+                kept => format!(
+                    "Pruned {} idle session(s); {} became active and were kept",
+                    killed, kept
+                ),
+            });
+        }
+        self.refresh_sessions().await?;
+        Ok(())
+    }
+
+    async fn prune_stale_sessions(&mut self, candidates: Vec<TmuxSession>) -> Result<()> {
+        // Sessions may have been attached or used since they were listed
+        self.refresh_sessions().await?;
+        let still_stale: Vec<TmuxSession> = self
+            .sessions
+            .iter()
+            .filter(|s| {
+                candidates
+                    .iter()
+                    .any(|c| c.id == s.id && c.server == s.server)
+            })
+            .filter(|s| self.is_stale(s))
+            .cloned()
+            .collect();
+        let mut killed = 0;
+        for session in &still_stale {
+            let id = session.id.clone();
+            match self
+                .client_for(session.server.as_deref())
+                .blocking(move |c| c.kill_session(&id))
+                .await
+            {
+                Ok(_) => {
+                    self.track(EventKind::Killed, &session.name);
+                    killed += 1;
+                }
+                Err(e) => {
+                    self.report_error(format!("Error killing session '{}': {}", session.name, e));
+                }
+            }
+        }
+        if self.error_banner.is_none() {
+            self.success(match candidates.len() - killed {
+                0 => format!("Pruned {} stale session(s)", killed),
+                kept => format!(
+                    "Pruned {} stale session(s); {} became active and were kept",
+                    killed, kept
+                ),
+            });
+        }
+        self.refresh_sessions().await?;
+        Ok(())
+    }
+
+    async fn handle_confirming_kill_input(&mut self, key: KeyCode) -> Result<()> {
+        self.input_mode = InputMode::Normal;
+        let Some(pending) = self.pending_kill.take() else {
+            return Ok(());
+        };
+        if !matches!(key, KeyCode::Char('y') | KeyCode::Char('Y')) {
+            self.info("Cancelled");
+            return Ok(());
+        }
+
+        let (target, result) = match &pending {
+            PendingKill::Session(target) => return self.kill_session(target.clone()).await,
+            PendingKill::Window(target) => {
+                let window = target.target.clone();
+                let result = self
+                    .client_for(target.server.as_deref())
+                    .blocking(move |c| {
+                        let snapshot = c.snapshot_window(&window).ok();
+                        c.kill_window(&window).map(|_| snapshot)
+                    })
+                    .await;
+                if let Ok(Some(snapshot)) = &result {
+                    self.remember_kill(target.server.clone(), snapshot.clone(), true);
+                }
+                (target, result.map(|_| ()))
+            }
+            PendingKill::Pane(target) => {
+                let pane = target.target.clone();
+                let result = self
+                    .client_for(target.server.as_deref())
+                    .blocking(move |c| c.kill_pane(&pane))
+                    .await;
+                (target, result)
+            }
+        };
+        match result {
+            Ok(_) if matches!(pending, PendingKill::Window(_)) => self.success(format!(
+                "Killed {} ({} to undo)",
+                target.label,
+                self.keymap.first_key(Action::Undo, self.scope())
+            )),
+            Ok(_) => self.success(format!("Killed {}", target.label)),
+            Err(e) => self.report_error(format!("Error killing {}: {}", target.label, e)),
+        }
+        match pending {
+            PendingKill::Session(_) => self.refresh_sessions().await,
+            PendingKill::Window(_) => self.refresh_windows().await,
+            PendingKill::Pane(_) => self.refresh_panes().await,
+        }
+    }
+
+    async fn kill_session(&mut self, target: SessionRef) -> Result<()> {
+        let session = target.tmux_target().to_string();
+        let killed = self
+            .client_for(target.server.as_deref())
+            .blocking(move |c| {
+                let snapshot = c.snapshot_session(&session).ok();
+                c.kill_session(&session).map(|_| snapshot)
+            })
+            .await;
+        match killed {
+            Ok(snapshot) => {
+                self.track(EventKind::Killed, &target.name);
+                match snapshot {
+                    Some(snapshot) => {
+                        self.remember_kill(target.server.clone(), snapshot, false);
+                        self.success(format!(
+                            "Session '{}' deleted! ({} to undo)",
+                            target,
+                            self.keymap.first_key(Action::Undo, self.scope())
+                        ));
+                    }
+                    None => self.success(format!("Session '{}' deleted!", target)),
+                }
+                self.refresh_sessions().await?;
+            }
+            Err(e) => {
+                self.report_failure(RetryAction::KillSession(target), e);
+            }
+        }
+        Ok(())
+    }
+
+    fn remember_kill(&mut self, server: Option<String>, snapshot: SessionSnapshot, window: bool) {
+        self.undo.retain(|killed| killed.at.elapsed() < UNDO_GRACE);
+        self.undo.push(Killed {
+            server,
+            snapshot,
+            window,
+            at: Instant::now(),
+        });
+    }
+
+    /// Recreate the session or window killed last with its windows, splits and
+    /// working directories; what ran in its panes is not started again
+    async fn undo(&mut self) -> Result<()> {
+        self.undo.retain(|killed| killed.at.elapsed() < UNDO_GRACE);
+        let Some(killed) = self.undo.pop() else {
+            self.warn(format!(
+                "Nothing to undo; kills can be undone for {} minutes",
+                UNDO_GRACE.as_secs() / 60
+            ));
+            return Ok(());
+        };
+        let label = match killed.snapshot.windows.first() {
+            Some(window) if killed.window => format!("window '{}'", window.name),
+            _ => format!("session '{}'", killed.snapshot.name),
+        };
+        let result = self
+            .client_for(killed.server.as_deref())
+            .blocking(move |c| {
+                let snapshot = &killed.snapshot;
+                // Killing the last window of a session kills the session too
+                let session_exists = c
+                    .list_sessions_with_columns(&[])
+                    .is_ok_and(|sessions| sessions.iter().any(|s| s.name == snapshot.name));
+                match snapshot.windows.first() {
+                    Some(window) if killed.window && session_exists => {
+                        c.restore_window(&snapshot.name, window)
+                    }
+                    _ => c.restore_session(snapshot),
+                }
+            })
+            .await;
+        match result {
+            Ok(_) => self.success(format!(
+                "Restored {}; programs that ran in it were not restarted",
+                label
+            )),
+            Err(e) => {
+                self.report_error(format!("Error restoring {}: {:#}", label, e));
+                return Ok(());
+            }
+        }
+        self.refresh_sessions().await?;
+        self.refresh_windows().await
+    }
+
+    fn start_sending_command(&mut self, target: PaneTarget) {
+        self.info(format!(
+            "Enter command for {} (ESC to cancel, Enter to send):",
+            target.label
+        ));
+        self.send_target = Some(target);
+        self.input.clear();
+        self.input_mode = InputMode::SendingCommand;
+    }
+
+    async fn handle_sending_command_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter if !self.input.is_empty() => {
+                if let Some(target) = self.send_target.take() {
+                    let command = self.input.take();
+                    let (pane, keys) = (target.target.clone(), [Key::Literal(command.clone())]);
+                    match self
+                        .client_for(target.server.as_deref())
+                        .blocking(move |c| c.send_keys(&pane, &keys, true))
+                        .await
+                    {
+                        Ok(_) => {
+                            self.success(format!("Sent '{}' to {}", command, target.label));
+                        }
+                        Err(e) => self.report_error(format!("Error sending command: {}", e)),
+                    }
+                }
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.send_target = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+    }
+
+    /// Pipe a pane's output to a new file in the pane log directory, named after the
+    /// pane and the time on the tmux server, e.g. `work-1.0-20240501-093000.log`
+    async fn start_pane_log(&self, server: Option<&str>, pane: &str) -> Result<PathBuf> {
+        let dir = self
+            .config
+            .pane_log_dir()
+            .context("No directory for pane logs; set `pane_log_dir` in the config")?;
+        let pane = pane.to_string();
+        self.client_for(server)
+            .blocking(move |c| {
+                // A remote server writes the file on its own machine
+                if c.host().is_none() {
+                    fs::create_dir_all(&dir)
+                        .with_context(|| format!("Failed to create {}", dir.display()))?;
+                }
+                let name = c.display_message(
+                    Some(&pane),
+                    "#{session_name}-#{window_index}.#{pane_index}-%Y%m%d-%H%M%S",
+                )?;
+                let file = dir.join(format!("{}.log", name.trim().replace('/', "_")));
+                c.pipe_pane(&pane, Some(&file))?;
+                Ok(file)
+            })
+            .await
+    }
+
+    async fn handle_respawning_pane_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter => {
+                if let Some(target) = self.respawn_target.take() {
+                    let command = self.input.take().trim().to_string();
+                    let pane = target.target.clone();
+                    match self
+                        .client_for(target.server.as_deref())
+                        .blocking(move |c| {
+                            c.respawn_pane(&pane, Some(command.as_str()).filter(|c| !c.is_empty()))
+                        })
+                        .await
+                    {
+                        Ok(_) => self.success(format!("Respawned {}", target.label)),
+                        Err(e) => self.report_error(format!("Error respawning pane: {}", e)),
+                    }
+                }
+                self.input_mode = InputMode::Normal;
+                self.refresh_panes().await?;
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.respawn_target = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_remote_typing_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(target) = self.remote_target.clone() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+
+        if remote::is_exit_key(&key) {
+            self.remote_target = None;
+            self.input_mode = InputMode::Normal;
+            self.info(format!("Stopped typing into session '{}'", target));
+            return Ok(());
+        }
+
+        if let Some(tmux_key) = remote::key_event_to_tmux(&key) {
+            self.send_remote_keys(target, tmux_key);
+        }
+
+        Ok(())
+    }
+
+    /// Send a key to a session being typed into in the background, so a slow
+    /// server does not hold up the TUI. Each `send-keys` waits for the one before,
+    /// keeping the keys in the order they were typed.
+    fn send_remote_keys(&mut self, target: SessionRef, key: Key) {
+        let previous = self.remote_send.take().map(|(_, task)| task);
+        let client = self.client_for(target.server.as_deref()).clone();
+        let session = target.tmux_target().to_string();
+        let task = tokio::spawn(async move {
+            // After a failure the keys typed since are dropped, not sent out of order
+            if let Some(previous) = previous {
+                previous.await.map_err(|e| tmux::Error::Io(e.into()))??;
+            }
+            client
+                .blocking(move |c| c.send_keys(&session, &[key], false))
+                .await
+        });
+        self.remote_send = Some((target, task));
+    }
+
+    /// Report a failed `send-keys` of remote typing once the keys typed so far are sent
+    async fn finish_remote_keys(&mut self) {
+        if !self
+            .remote_send
+            .as_ref()
+            .is_some_and(|(_, task)| task.is_finished())
+        {
+            return;
+        }
+        if let Some((target, task)) = self.remote_send.take() {
+            let result = task
+                .await
+                .unwrap_or_else(|e| Err(tmux::Error::Io(e.into())));
+            if let Err(e) = result {
+                // The pane is most likely gone, so stop forwarding keys
+                if self.remote_target.as_ref() == Some(&target) {
+                    self.remote_target = None;
+                    self.input_mode = InputMode::Normal;
+                }
+                self.error(format!("Error sending keys: {}", e));
+                self.needs_redraw = true;
+            }
+        }
+    }
+
+    /// Attach a control-mode client to the primary server so changes made outside
+    /// tmux-ui show up immediately. Without sessions there is nothing to attach to;
+    /// it is started once the first session appears.
+    fn start_control_mode(&mut self) {
+        if self.control.is_some()
+            || self.control_unavailable
+            || !self.capabilities.has_client_flags()
+        {
+            return;
+        }
+        // Prefer the session tmux-ui runs in, which is attached anyway
+        let session = self
+            .original_session
+            .clone()
+            .filter(|_| self.client.is_inside_this_server())
+            .or_else(|| {
+                self.sessions
+                    .iter()
+                    .find(|s| s.server.is_none())
+                    .map(|s| s.id.clone())
+            });
+        if let Some(session) = session {
+            self.control = self.client.control_mode(&session).ok();
+        }
+    }
+
+    /// Refresh whatever is shown when tmux reported a change
+    fn process_notifications(&mut self) {
+        let Some(control) = self.control.as_mut() else {
+            return;
+        };
+
+        let mut changed = false;
+        while let Some(notification) = control.try_next() {
+            if notification == Notification::Exit {
+                // Exiting before ever attaching means control mode does not work here
+                // (e.g. tmux older than 3.2); otherwise its session was killed
+                self.control_unavailable = control.session_id().is_none();
+                self.control = None;
+                changed = true;
+                break;
+            }
+            changed = true;
+        }
+
+        if changed {
+            self.start_reload();
+            self.start_control_mode();
+        }
+    }
+
+    fn toggle_preview(&mut self) {
+        self.show_preview = !self.show_preview;
+        self.info(if self.show_preview {
+            "Pane preview shown (on wide terminals)"
+        } else {
+            "Pane preview hidden"
+        });
+    }
+
+    async fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+        let what = if matches!(self.view, View::Panes(_)) {
+            "Pane processes"
+        } else {
+            "Session details"
+        };
+        if self.show_details {
+            self.details.invalidate();
+            self.update_details().await;
+            // Open the panel with the details rather than empty
+            self.finish_details(true).await;
+            self.info(format!("{} shown (on wide terminals)", what));
+        } else {
+            self.info(format!("{} hidden", what));
+        }
+    }
+
+    /// Query the details of the selected session when the selection changed. Like
+    /// the pane capture, the query runs in the background, since tmux may be slow
+    /// or behind ssh.
+    async fn update_details(&mut self) {
+        self.finish_details(false).await;
+        if !self.show_details || !self.side_panels_fit() || self.loading {
+            return;
+        }
+        if let View::Panes(view) = &self.view {
+            let server = view.parent.session.server.clone();
+            let pane = view.selected_pane().map(|p| (p.id.clone(), p.pid));
+            self.update_processes(server, pane);
+            return;
+        }
+        if !matches!(self.view, View::Sessions) || self.details_task.is_some() {
+            return;
+        }
+        let target: Option<DetailsTarget> = self.selected_session().map(|session| {
+            (
+                session.server.clone(),
+                SessionRef::of(session).tmux_target().to_string(),
+            )
+        });
+        // With nothing selected the panel says so without asking tmux
+        let Some((server, session)) = target.as_ref() else {
+            return;
+        };
+        if !self.details.needs_update(target.as_ref()) {
+            return;
+        }
+        let client = self.client_for(server.as_deref()).clone();
+        let session = session.clone();
+        let task = tokio::spawn(async move {
+            client
+                .blocking(move |c| {
+                    let details = c.session_details(&session)?;
+                    Ok((details, c.show_environment(Some(&session))?))
+                })
+                .await
+        });
+        self.details_task = Some((target, task));
+    }
+
+    /// Store the details read by `details_task` once it finished, or wait for it
+    async fn finish_details(&mut self, wait: bool) {
+        if !self
+            .details_task
+            .as_ref()
+            .is_some_and(|(_, task)| wait || task.is_finished())
+        {
+            return;
+        }
+        if let Some((target, task)) = self.details_task.take() {
+            let result = task
+                .await
+                .unwrap_or_else(|e| Err(tmux::Error::Io(e.into())));
+            if self.details.update(target, result) {
+                self.needs_redraw = true;
+            }
+        }
+    }
+
+    /// Read the process tree of the selected pane again when the selection changed
+    /// or the last read is getting old
+    fn update_processes(&mut self, server: Option<String>, pane: Option<(String, u32)>) {
+        let Some((id, pid)) = pane else {
+            return;
+        };
+        if !self.processes.needs_update(Some(&id)) {
+            return;
+        }
+        let result = if self.client_for(server.as_deref()).host().is_some() {
+            Err(anyhow::anyhow!(
+                "Processes of panes on another host cannot be read"
+            ))
+        } else if pid == 0 {
+            Err(anyhow::anyhow!("tmux reports no process for this pane"))
+        } else {
+            crate::process::process_tree(pid)
+        };
+        if self.processes.update(Some(id), result) {
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Capture the active pane of the selected session or window again when the
+    /// selection changed or the last capture is getting old. The capture runs in
+    /// the background and is shown once it has finished.
+    async fn update_preview(&mut self) {
+        if self
+            .preview_task
+            .as_ref()
+            .is_some_and(|(_, task)| task.is_finished())
+        {
+            if let Some((target, task)) = self.preview_task.take() {
+                let content = task
+                    .await
+                    .unwrap_or_else(|e| Err(tmux::Error::Io(e.into())));
+                if self.preview.update(target, content) {
+                    self.needs_redraw = true;
+                }
+            }
+        }
+
+        if !self.show_preview
+            || !self.side_panels_fit()
+            || self.loading
+            || self.preview_task.is_some()
+        {
+            return;
+        }
+        let target = match &self.view {
+            View::Sessions => self.selected_session().map(|session| PaneTarget {
+                server: session.server.clone(),
+                target: SessionRef::of(session).tmux_target().to_string(),
+                label: format!("'{}'", session.name),
+            }),
+            View::Windows(view) => view.selected_window().map(|window| PaneTarget {
+                server: view.session.server.clone(),
+                target: window.id.clone(),
+                label: format!("window '{}'", window.name),
+            }),
+            View::Panes(_) | View::Tree(_) | View::Clients(_) => return,
+        };
+        if !self.preview.needs_update(target.as_ref()) {
+            return;
+        }
+        let task = match &target {
+            Some(t) => {
+                let client = self.client_for(t.server.as_deref()).clone();
+                let pane = t.target.clone();
+                tokio::spawn(async move { client.blocking(move |c| c.capture_pane(&pane)).await })
+            }
+            None => tokio::spawn(async { Ok(String::new()) }),
+        };
+        self.preview_task = Some((target, task));
+    }
+
+    /// Show an error that is not tied to a retryable operation
+    fn report_error(&mut self, message: String) {
+        tracing::error!("{}", message);
+        self.error_banner = Some(ErrorBanner::new(message, None));
+    }
+
+    /// Show a failed operation in the error banner so it can be retried.
+    /// Repeated failures of the same operation back off the automatic retry.
+    fn report_failure(&mut self, action: RetryAction, error: impl Into<anyhow::Error>) {
+        let message = format!("Error {}: {}", action.describe(), error.into());
+        tracing::error!("{}", message);
+        match &mut self.error_banner {
+            Some(banner) if banner.retry.as_ref() == Some(&action) => banner.failed_again(message),
+            _ => self.error_banner = Some(ErrorBanner::new(message, Some(action))),
+        }
+    }
+
+    /// Run the operation shown in the error banner again
+    async fn retry_failed_action(&mut self) -> Result<()> {
+        let Some(action) = self.error_banner.as_ref().and_then(|b| b.retry.clone()) else {
+            return Ok(());
+        };
+
+        let server = match &action {
+            RetryAction::Refresh | RetryAction::CreateSession { .. } => None,
+            RetryAction::KillSession(target)
+            | RetryAction::CreateWindow(target)
+            | RetryAction::SwitchClient(target) => target.server.as_deref(),
+            RetryAction::RenameSession { session, .. } => session.server.as_deref(),
+        };
+        let retried = action.clone();
+        let result = match &action {
+            RetryAction::Refresh => self.session_source().fetch_async().await.map(|sessions| {
+                self.sessions_loaded(sessions);
+            }),
+            _ => self
+                .client_for(server)
+                .blocking(move |c| match retried {
+                    RetryAction::Refresh => Ok(()),
+                    RetryAction::CreateSession { name, dir, command } => {
+                        c.create_session(&name, dir.as_deref(), command.as_deref())
+                    }
+                    RetryAction::KillSession(target) => c.kill_session(target.tmux_target()),
+                    RetryAction::RenameSession { session, new_name } => {
+                        c.rename_session(session.tmux_target(), &new_name)
+                    }
+                    RetryAction::CreateWindow(target) => {
+                        c.create_window(target.tmux_target(), None)
+                    }
+                    RetryAction::SwitchClient(target) => c.switch_client(target.tmux_target()),
+                })
+                .await
+                .map_err(Into::into),
+        };
+
+        match result {
+            Ok(()) => {
+                match &action {
+                    RetryAction::Refresh => {}
+                    RetryAction::CreateSession { name, .. } => self.track(EventKind::Created, name),
+                    RetryAction::KillSession(target) => self.track(EventKind::Killed, &target.name),
+                    RetryAction::RenameSession { new_name, .. } => {
+                        self.track(EventKind::Renamed, new_name)
+                    }
+                    RetryAction::CreateWindow(target) => {
+                        self.track(EventKind::WindowCreated, &target.name)
+                    }
+                    RetryAction::SwitchClient(target) => {
+                        self.track(EventKind::Attached, &target.name);
+                        self.history.visit(target.clone());
+                    }
+                }
+                self.error_banner = None;
+                self.success(format!("Succeeded {}", action.describe()));
+                if action != RetryAction::Refresh {
+                    self.refresh_sessions().await?;
+                }
+            }
+            Err(e) => self.report_failure(action, e),
+        }
+
+        Ok(())
+    }
+
+    /// Refresh the session list, reporting failures in the error banner instead of
+    /// aborting the TUI
+    async fn refresh_sessions(&mut self) -> Result<()> {
+        match self.session_source().fetch_async().await {
+            Ok(sessions) => {
+                self.sessions_loaded(sessions);
+            }
+            Err(e) => self.report_failure(RetryAction::Refresh, e),
+        }
+        Ok(())
+    }
+
+    /// Find a session in the current list by id, falling back to the name
+    fn locate(&self, target: &SessionRef) -> Option<&TmuxSession> {
+        self.sessions.iter().find(|s| {
+            s.server == target.server
+                && if target.id.is_empty() {
+                    s.name == target.name
+                } else {
+                    s.id == target.id
+                }
+        })
+    }
+
+    /// Refresh and look the session up again, returning its current identity,
+    /// or `None` if it was killed outside tmux-ui
+    async fn revalidate(&mut self, target: &SessionRef) -> Result<Option<SessionRef>> {
+        self.refresh_sessions().await?;
+        Ok(self.locate(target).map(SessionRef::of))
+    }
+
+    /// Keep a pending rename pointed at the right session when the list changes under it
+    fn reconcile_pending_rename(&mut self) {
+        let Some(pending) = self.pending_rename.clone() else {
+            return;
+        };
+        match self.locate(&pending).map(SessionRef::of) {
+            None => {
+                self.pending_rename = None;
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.warn(format!(
+                    "Session '{}' was closed; rename cancelled",
+                    pending
+                ));
+            }
+            Some(current) if current.name != pending.name => {
+                self.warn(format!(
+                    "Session '{}' was renamed to '{}' outside tmux-ui; renaming that session",
+                    pending, current.name
+                ));
+                self.pending_rename = Some(current);
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// What `fetch_sessions` lists, for running it off the render loop
+    fn session_source(&self) -> SessionSource<C> {
+        let shown = |s: &&Server<C>| self.combined || self.shown_server.as_ref() == Some(&s.name);
+        SessionSource {
+            client: (self.combined || self.shown_server.is_none()).then(|| self.client.clone()),
+            servers: self
+                .servers
+                .iter()
+                .filter(shown)
+                .map(|s| (s.name.clone(), s.client.clone()))
+                .collect(),
+            columns: self.config.column_formats(),
+        }
+    }
+
+    /// Store a freshly listed session list; returns whether anything shown changed
+    fn sessions_loaded(&mut self, mut sessions: Vec<TmuxSession>) -> bool {
+        // The control-mode client counts as attached; only real clients should
+        if let Some(id) = self.control.as_ref().and_then(|c| c.session_id()) {
+            if let Some(session) = sessions
+                .iter_mut()
+                .find(|s| s.server.is_none() && s.id == id)
+            {
+                session.clients = session.clients.saturating_sub(1);
+                session.attached = session.clients > 0;
+            }
+        }
+
+        self.sort_spec().sort(&mut sessions);
+        self.sessions_generation += 1;
+        let mut changed = sessions != self.sessions;
+        if changed {
+            self.sessions = sessions;
+            self.apply_filter();
+        }
+
+        if self
+            .error_banner
+            .as_ref()
+            .is_some_and(|b| b.retry == Some(RetryAction::Refresh))
+        {
+            self.error_banner = None;
+            changed = true;
+        }
+        self.reconcile_pending_rename();
+        changed
+    }
+
+    /// Explanation appended to a prompt whose input was rejected
+    fn input_error_note(&self) -> String {
+        match &self.input_error {
+            Some(error) => format!("  ({})", error),
+            None => String::new(),
+        }
+    }
+
+    fn session_list_title(&self) -> String {
+        let count = if self.search.is_empty() && self.tag_filter.is_none() {
+            self.sessions.len().to_string()
+        } else {
+            format!("{}/{}", self.visible.len(), self.sessions.len())
+        };
+        let mut title = format!("tmux Sessions ({})", count);
+        if self.combined {
+            title.push_str(&format!(" on {} servers", self.servers.len() + 1));
+        } else if let Some(server) = &self.shown_server {
+            title.push_str(&format!(" on server '{}'", server));
+        }
+        if let Some(tag) = &self.tag_filter {
+            title.push_str(&format!(" tagged '{}'", tag));
+        }
+        if !self.search.is_empty() {
+            title.push_str(&format!(" matching '{}'", self.search));
+        }
+        title
+    }
+
+    /// Vertical layout of the main screen
+    fn layout_constraints(&self) -> [Constraint; 4] {
+        [
+            Constraint::Length(if self.title_shown() { 3 } else { 0 }),
+            Constraint::Min(MIN_LIST_HEIGHT),
+            Constraint::Length(if self.error_banner.is_some() { 3 } else { 0 }),
+            Constraint::Length(3),
+        ]
+    }
+
+    /// Smallest terminal size (width, height) the current layout can be drawn in
+    fn min_size(&self) -> (u16, u16) {
+        let height = self
+            .layout_constraints()
+            .iter()
+            .map(|c| match c {
+                Constraint::Length(n) | Constraint::Min(n) => *n,
+                _ => 0,
+            })
+            .sum::<u16>()
+            + 2 * self.margin();
+        (MIN_WIDTH + 2 * self.margin(), height)
+    }
+
+    fn ui(&mut self, f: &mut Frame) {
+        let area = f.size();
+        self.screen = Some(area);
+        let (min_width, min_height) = self.min_size();
+        if area.width < min_width || area.height < min_height {
+            // Drawing the full layout would clip it into garbage; resizing redraws normally
+            let message = Paragraph::new(format!(
+                "Terminal too small\n(need {}x{}, have {}x{})",
+                min_width, min_height, area.width, area.height
+            ))
+            .style(Style::default().fg(self.theme.input))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+            let middle = area.height.saturating_sub(2) / 2;
+            let text_area = Rect::new(area.x, area.y + middle, area.width, 2.min(area.height));
+            f.render_widget(message, text_area);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(self.margin())
+            .constraints(self.layout_constraints())
+            .split(area);
+
+        // Title, collapsed on short terminals to leave the rows to the list
+        let theme = self.theme;
+        if self.title_shown() {
+            let title = Paragraph::new(if self.icons == IconSet::Ascii {
+                "tmux-ui - Session Manager"
+            } else {
+                "🖥️  tmux-ui - Session Manager"
+            })
+            .style(Style::default().fg(theme.title))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+            f.render_widget(title, chunks[0]);
+        }
+
+        // Session list, with a server column when several servers are shown
+        let server_width = if self.combined {
+            self.servers
+                .iter()
+                .map(|s| s.name.chars().count())
+                .chain([DEFAULT_SERVER_LABEL.len()])
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let servers: Vec<&str> = self
+            .visible
+            .iter()
+            .map(|m| {
+                self.sessions[m.index]
+                    .server
+                    .as_deref()
+                    .unwrap_or(DEFAULT_SERVER_LABEL)
+            })
+            .collect();
+        // Tag groups are nested in the server groups of the combined view
+        let groups: Vec<(&str, &str)> = self
+            .visible
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let server = if self.combined { servers[i] } else { "" };
+                (server, tag_group(&self.sessions[m.index]))
+            })
+            .collect();
+        let group_width = groups
+            .iter()
+            .map(|(_, g)| g.chars().count())
+            .max()
+            .unwrap_or(0);
+        let sort_column = self.sort_column;
+        let header = Row::new(
+            self.table_columns
+                .iter()
+                .map(|c| c.header(sort_column, self.icons))
+                .chain(self.config.columns.iter().map(|c| c.header.clone())),
+        )
+        .style(
+            Style::default()
+                .fg(theme.secondary)
+                .add_modifier(Modifier::BOLD),
+        );
+        // The name column is as wide as the longest name, the path takes what is left
+        let mut name_width = 0;
+        let sessions: Vec<Row> = self
+            .visible
+            .iter()
+            .enumerate()
+            .map(|(i, visible)| {
+                let session = &self.sessions[visible.index];
+                let stale = self.is_stale(session);
+                let style = if session.attached {
+                    Style::default()
+                        .fg(theme.attached)
+                        .add_modifier(Modifier::BOLD)
+                } else if stale {
+                    Style::default().fg(theme.dim)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+
+                let mut cells: Vec<Cell> = Vec::new();
+                for column in &self.table_columns {
+                    cells.push(match column {
+                        SessionColumn::Name => {
+                            let mut spans = Vec::new();
+                            if self.combined {
+                                // Server groups: the server name on the first row, branches below
+                                let first = i == 0 || servers[i - 1] != servers[i];
+                                let last = servers.get(i + 1) != Some(&servers[i]);
+                                let label = if first { servers[i] } else { "" };
+                                spans.push(Span::styled(
+                                    format!(
+                                        "{:width$} {} ",
+                                        label,
+                                        self.icons.branch(last),
+                                        width = server_width
+                                    ),
+                                    Style::default()
+                                        .fg(theme.accent)
+                                        .add_modifier(Modifier::BOLD),
+                                ));
+                            }
+                            if self.group_by_tag {
+                                // Tree of groups: the label on the first row, branches below
+                                let first = i == 0 || groups[i - 1] != groups[i];
+                                let last = groups.get(i + 1) != Some(&groups[i]);
+                                let label = if first { groups[i].1 } else { "" };
+                                spans.push(Span::styled(
+                                    format!(
+                                        "{:width$} {} ",
+                                        label,
+                                        self.icons.branch(last),
+                                        width = group_width
+                                    ),
+                                    Style::default().fg(theme.accent),
+                                ));
+                            }
+                            spans.push(Span::raw(format!(
+                                "{} ",
+                                self.icons.command(&session.current_command)
+                            )));
+                            spans.extend(alert_badges(&session.alerts, &theme));
+                            // Highlight the characters that matched the search
+                            let highlight = Style::default()
+                                .fg(theme.search_match)
+                                .add_modifier(Modifier::UNDERLINED);
+                            for (i, c) in session.name.chars().enumerate() {
+                                if visible.positions.contains(&i) {
+                                    spans.push(Span::styled(c.to_string(), highlight));
+                                } else {
+                                    spans.push(Span::raw(c.to_string()));
+                                }
+                            }
+                            if !session.tags.is_empty() {
+                                let tags: Vec<String> =
+                                    session.tags.iter().map(|t| format!("#{}", t)).collect();
+                                spans.push(Span::styled(
+                                    format!("  {}", tags.join(" ")),
+                                    Style::default().fg(theme.accent),
+                                ));
+                            }
+                            let line = Line::from(spans);
+                            name_width = name_width.max(line.width());
+                            Cell::from(line)
+                        }
+                        SessionColumn::Windows => Cell::from(session.windows.to_string()),
+                        SessionColumn::Panes => Cell::from(session.panes.to_string()),
+                        SessionColumn::Attached => Cell::from(if session.attached {
+                            format!("{} {}", self.icons.attached(true), session.clients)
+                        } else {
+                            self.icons.attached(false).to_string()
+                        }),
+                        SessionColumn::Created => Cell::from(elapsed_since(session.created_at))
+                            .style(Style::default().fg(theme.dim)),
+                        SessionColumn::Activity => {
+                            Cell::from(elapsed_since(session.last_activity)).style(
+                                Style::default().fg(if stale { theme.alert } else { theme.dim }),
+                            )
+                        }
+                        SessionColumn::Command => Cell::from(session.current_command.clone())
+                            .style(Style::default().fg(theme.secondary)),
+                        SessionColumn::Path => Cell::from(collapse_home(&session.current_path))
+                            .style(Style::default().fg(theme.dim)),
+                    });
+                }
+                for (column, value) in self.config.columns.iter().zip(&session.columns) {
+                    let value: String = match column.width {
+                        Some(width) => value.chars().take(width).collect(),
+                        None => value.clone(),
+                    };
+                    cells.push(Cell::from(value).style(Style::default().fg(theme.secondary)));
+                }
+                Row::new(cells).style(style)
+            })
+            .collect();
+        let widths: Vec<Constraint> = self
+            .table_columns
+            .iter()
+            .map(|c| c.width(name_width))
+            .chain(self.config.columns.iter().map(|c| {
+                let width = c
+                    .width
+                    .unwrap_or(USER_COLUMN_WIDTH)
+                    .max(c.header.chars().count());
+                Constraint::Length(width.try_into().unwrap_or(u16::MAX))
+            }))
+            .collect();
+
+        // Inside the borders; the session table also has a header row
+        self.list_rows = usize::from(chunks[1].height.saturating_sub(3)).max(1);
+        let side_panels = self.side_panels_fit();
+        let show_preview = self.show_preview && side_panels;
+        let show_details = self.show_details && side_panels;
+        if let View::Windows(view) = &mut self.view {
+            if show_preview {
+                let halves = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[1]);
+                view.render(f, halves[0], &theme, self.icons);
+                self.preview.render(f, halves[1], &theme);
+            } else {
+                view.render(f, chunks[1], &theme, self.icons);
+            }
+        } else if let View::Panes(view) = &mut self.view {
+            let show_titles = self.capabilities.has_pane_titles();
+            if show_details {
+                let halves = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[1]);
+                view.render(f, halves[0], &theme, self.icons, show_titles);
+                let label = view.selected_pane().map(|p| p.index.to_string());
+                self.processes
+                    .render(f, halves[1], &theme, label.as_deref());
+            } else {
+                view.render(f, chunks[1], &theme, self.icons, show_titles);
+            }
+        } else if let View::Tree(view) = &mut self.view {
+            view.render(
+                f,
+                chunks[1],
+                &theme,
+                self.icons,
+                self.capabilities.has_pane_titles(),
+            );
+        } else if let View::Clients(view) = &mut self.view {
+            view.render(f, chunks[1], &theme, self.icons);
+        } else if self.loading {
+            let placeholder = Paragraph::new(format!("Loading sessions{}", self.icons.ellipsis()))
+                .style(Style::default().fg(theme.dim))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("tmux Sessions"),
+                );
+            f.render_widget(placeholder, chunks[1]);
+        } else {
+            let sessions_list = Table::new(sessions)
+                .header(header)
+                .widths(&widths)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(self.session_list_title()),
+                )
+                .highlight_style(
+                    Style::default()
+                        .bg(theme.selected_bg)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol(">> ");
+
+            if show_details || show_preview {
+                let halves = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[1]);
+                f.render_stateful_widget(sessions_list, halves[0], &mut self.selected);
+                // The header takes a row like another session would
+                render_scrollbar(
+                    f,
+                    halves[0],
+                    self.icons,
+                    self.visible.len() + 1,
+                    self.selected.selected(),
+                );
+                if show_details {
+                    self.details
+                        .render(f, halves[1], &theme, self.selected_session());
+                } else {
+                    self.preview.render(f, halves[1], &theme);
+                }
+            } else {
+                f.render_stateful_widget(sessions_list, chunks[1], &mut self.selected);
+                // The header takes a row like another session would
+                render_scrollbar(
+                    f,
+                    chunks[1],
+                    self.icons,
+                    self.visible.len() + 1,
+                    self.selected.selected(),
+                );
+            }
+        }
+
+        // Error banner stays until the failed operation succeeds or is dismissed
+        if let Some(banner) = &self.error_banner {
+            let error = Paragraph::new(banner.text(&self.keymap, self.scope(), self.icons))
+                .style(
+                    Style::default()
+                        .fg(theme.error)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .block(Block::default().borders(Borders::ALL).title("Error"));
+            f.render_widget(error, chunks[2]);
+        }
+
+        self.render_status_bar(f, chunks[3]);
+
+        if let Some(view) = &self.output_view {
+            view.render(f, area, &theme, self.icons);
+        }
+        if let Some(picker) = &mut self.template_picker {
+            picker.render(f, area, &theme);
+        }
+        if let Some(picker) = &mut self.session_picker {
+            picker.render(f, area, &theme);
+        }
+        if let Some(picker) = &mut self.window_picker {
+            picker.render(f, area, &theme);
+        }
+        if let Some(picker) = &mut self.server_picker {
+            picker.render(f, area, &theme);
+        }
+        if let Some(picker) = &mut self.client_picker {
+            picker.render(f, area, &theme);
+        }
+        if let Some(picker) = &mut self.layout_picker {
+            picker.render(f, area, &theme);
+        }
+        if let Some(picker) = &mut self.history_picker {
+            picker.render(f, area, &theme);
+        }
+        if let Some(browser) = &mut self.buffers {
+            browser.render(f, area, &theme);
+        }
+        if let Some(browser) = &mut self.archived {
+            browser.render(f, area, &theme);
+        }
+        if let Some(browser) = &mut self.options {
+            browser.render(f, area, &theme);
+        }
+        if let Some(browser) = &mut self.environment {
+            browser.render(f, area, &theme);
+        }
+        if let Some(browser) = &mut self.hooks {
+            browser.render(f, area, &theme);
+        }
     }
 }
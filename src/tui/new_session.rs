@@ -0,0 +1,244 @@
+//! The new-session prompt: a name, and optionally a start directory and a
+//! command, reached with Tab. Also the prompt for copying a session.
+
+use super::banner::RetryAction;
+use super::{App, InputMode, SessionRef};
+use crate::template::expand_home;
+use crate::tmux::{self, validate_session_name, TmuxBackend};
+use crate::usage::EventKind;
+use crate::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::path::PathBuf;
+
+/// The field of the new-session prompt being typed into
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NewSessionField {
+    #[default]
+    Name,
+    Directory,
+    Command,
+}
+
+impl NewSessionField {
+    fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Directory,
+            Self::Directory => Self::Command,
+            Self::Command => Self::Name,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Self::Name => Self::Command,
+            Self::Directory => Self::Name,
+            Self::Command => Self::Directory,
+        }
+    }
+}
+
+/// Values of the new-session prompt; the field being edited lives in `App::input`
+#[derive(Debug, Default)]
+pub(crate) struct NewSessionForm {
+    pub field: NewSessionField,
+    pub name: String,
+    pub dir: String,
+    pub command: String,
+}
+
+impl NewSessionForm {
+    fn value_mut(&mut self, field: NewSessionField) -> &mut String {
+        match field {
+            NewSessionField::Name => &mut self.name,
+            NewSessionField::Directory => &mut self.dir,
+            NewSessionField::Command => &mut self.command,
+        }
+    }
+}
+
+impl<C: TmuxBackend> App<C> {
+    pub(crate) fn start_creating_session(&mut self) {
+        self.new_session = NewSessionForm::default();
+        self.input.clear();
+        self.input_mode = InputMode::CreatingSession;
+        self.info("Enter session name (Tab for directory and command, ESC to cancel):");
+    }
+
+    /// Prompt for the field being edited, e.g. `Start directory for 'api': `
+    pub(crate) fn new_session_prompt(&self) -> String {
+        let form = &self.new_session;
+        match form.field {
+            NewSessionField::Name => "New session name: ".to_string(),
+            NewSessionField::Directory if form.name.is_empty() => "Start directory: ".to_string(),
+            NewSessionField::Directory => format!("Start directory for '{}': ", form.name),
+            NewSessionField::Command if form.name.is_empty() => "Command: ".to_string(),
+            NewSessionField::Command => format!("Command for '{}': ", form.name),
+        }
+    }
+
+    /// Keep what was typed and edit another field
+    fn switch_field(&mut self, field: NewSessionField) {
+        let current = self.new_session.field;
+        *self.new_session.value_mut(current) = self.input.take();
+        self.input
+            .set(std::mem::take(self.new_session.value_mut(field)));
+        self.new_session.field = field;
+        self.input_error = None;
+    }
+
+    pub(crate) async fn handle_creating_input(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter => {
+                let field = self.new_session.field;
+                *self.new_session.value_mut(field) = self.input.to_string();
+                let session_name = match validate_session_name(&self.new_session.name) {
+                    Ok(name) => name,
+                    Err(e) => {
+                        // Stay in the prompt so the name can be fixed
+                        self.switch_field(NewSessionField::Name);
+                        self.input_error = Some(e.to_string());
+                        return Ok(false);
+                    }
+                };
+                let dir = match self.new_session.dir.trim() {
+                    "" => None,
+                    dir => Some(expand_home(dir)),
+                };
+                // Directories on other machines cannot be checked from here
+                if let Some(dir) = dir.as_ref().filter(|_| self.client.host().is_none()) {
+                    if !dir.is_dir() {
+                        self.switch_field(NewSessionField::Directory);
+                        self.input_error = Some(format!("No directory {}", dir.display()));
+                        return Ok(false);
+                    }
+                }
+                let command = match self.new_session.command.trim() {
+                    "" => None,
+                    command => Some(command.to_string()),
+                };
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.create_session(session_name, dir, command).await?;
+            }
+            KeyCode::Tab => self.switch_field(self.new_session.field.next()),
+            KeyCode::BackTab => self.switch_field(self.new_session.field.previous()),
+            KeyCode::Esc => {
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(false)
+    }
+
+    async fn create_session(
+        &mut self,
+        name: String,
+        dir: Option<PathBuf>,
+        command: Option<String>,
+    ) -> Result<()> {
+        let (session, cwd, program) = (name.clone(), dir.clone(), command.clone());
+        match self
+            .client
+            .blocking(move |c| c.create_session(&session, cwd.as_deref(), program.as_deref()))
+            .await
+        {
+            Ok(_) => {
+                self.track(EventKind::Created, &name);
+                self.success(format!("Session '{}' created!", name));
+                self.refresh_sessions().await?;
+            }
+            // Retrying cannot help, so go back to the prompt to pick another name
+            Err(tmux::Error::DuplicateSession(_)) => {
+                self.new_session.field = NewSessionField::Name;
+                self.new_session.name.clear();
+                self.input.set(name.clone());
+                self.input_mode = InputMode::CreatingSession;
+                self.input_error = Some(format!("Session '{}' already exists", name));
+            }
+            Err(e) => self.report_failure(RetryAction::CreateSession { name, dir, command }, e),
+        }
+        Ok(())
+    }
+
+    /// Offer to create a session again after it went away, e.g. while switching to it
+    pub(crate) fn offer_to_create_session(&mut self, name: &str) {
+        self.start_creating_session();
+        self.input.set(name.to_string());
+        self.warn(format!(
+            "Session '{}' no longer exists; Enter creates it, ESC cancels",
+            name
+        ));
+    }
+
+    pub(crate) fn start_cloning_session(&mut self, source: SessionRef) {
+        // Suggest the first free name of the form `name-2`, `name-3`, ...
+        let name = (2..)
+            .map(|n| format!("{}-{}", source.name, n))
+            .find(|name| {
+                !self
+                    .sessions
+                    .iter()
+                    .any(|s| s.server == source.server && &s.name == name)
+            })
+            .unwrap_or_default();
+        self.input.set(name);
+        self.info(format!(
+            "Name for the copy of '{}' (Tab: start its editors too, ESC to cancel):",
+            source
+        ));
+        self.pending_clone = Some((source, true));
+        self.input_mode = InputMode::CloningSession;
+    }
+
+    pub(crate) async fn handle_cloning_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter => {
+                let new_name = match validate_session_name(&self.input) {
+                    Ok(name) => name,
+                    Err(e) => {
+                        self.input_error = Some(e.to_string());
+                        return Ok(());
+                    }
+                };
+                self.input.clear();
+                self.input_mode = InputMode::Normal;
+                let Some((source, commands)) = self.pending_clone.take() else {
+                    return Ok(());
+                };
+                let client = self.client_for(source.server.as_deref()).clone();
+                let target = source.tmux_target().to_string();
+                let name = new_name.clone();
+                let result = client
+                    .blocking(move |c| c.clone_session(&target, &name, commands))
+                    .await;
+                match result {
+                    Ok(()) => {
+                        self.track(EventKind::Created, &new_name);
+                        self.success(format!("Session '{}' copied to '{}'", source, new_name));
+                        self.refresh_sessions().await?;
+                    }
+                    Err(e) => self.report_error(format!("Error copying session: {:#}", e)),
+                }
+            }
+            KeyCode::Tab => {
+                if let Some((_, commands)) = self.pending_clone.as_mut() {
+                    *commands = !*commands;
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.pending_clone = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+}
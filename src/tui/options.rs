@@ -0,0 +1,373 @@
+//! Browser for the options of the server, a session or a window, with inline editing
+
+use super::theme::Theme;
+use super::{App, InputMode, SessionRef, View};
+use crate::tmux::{OptionScope, TmuxBackend, TmuxOption};
+use crate::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+/// Widest name column; longer names push their value to the right
+const NAME_WIDTH: usize = 28;
+
+/// Rows moved by PageUp and PageDown
+const PAGE: usize = 10;
+
+/// Popup listing the options of one scope, filtered by the typed text
+pub(crate) struct OptionsBrowser {
+    /// Server the options belong to, `None` for the primary server
+    server: Option<String>,
+    /// Scopes Tab switches between, with labels such as `session 'work'`
+    scopes: Vec<(OptionScope, String)>,
+    scope: usize,
+    options: Vec<TmuxOption>,
+    /// Only options whose name contains this are listed
+    filter: String,
+    /// Indices into `options` of the listed options
+    visible: Vec<usize>,
+    selected: ListState,
+}
+
+impl OptionsBrowser {
+    fn new(server: Option<String>, scopes: Vec<(OptionScope, String)>) -> Self {
+        Self {
+            server,
+            scopes,
+            scope: 0,
+            options: Vec::new(),
+            filter: String::new(),
+            visible: Vec::new(),
+            selected: ListState::default(),
+        }
+    }
+
+    fn scope(&self) -> &(OptionScope, String) {
+        &self.scopes[self.scope]
+    }
+
+    /// Replace the options, keeping the selection on the same option where possible
+    fn set_options(&mut self, options: Vec<TmuxOption>) {
+        let previous = self.selected_option().map(|o| o.name.clone());
+        self.options = options;
+        self.apply_filter();
+        if let Some(i) = previous.and_then(|name| {
+            self.visible
+                .iter()
+                .position(|&i| self.options[i].name == name)
+        }) {
+            self.selected.select(Some(i));
+        }
+    }
+
+    fn apply_filter(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.visible = (0..self.options.len())
+            .filter(|&i| self.options[i].name.to_lowercase().contains(&filter))
+            .collect();
+        self.selected
+            .select((!self.visible.is_empty()).then_some(0));
+    }
+
+    fn selected_option(&self) -> Option<&TmuxOption> {
+        self.selected
+            .selected()
+            .and_then(|i| self.visible.get(i))
+            .map(|&i| &self.options[i])
+    }
+
+    /// Move the selection by `delta` rows, stopping at either end
+    fn move_by(&mut self, delta: isize) {
+        if let Some(i) = self.selected.selected() {
+            let last = self.visible.len().saturating_sub(1);
+            self.selected
+                .select(Some(i.saturating_add_signed(delta).min(last)));
+        }
+    }
+
+    /// Draw the popup centered over `area`
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let width = area.width.saturating_sub(4).max(1);
+        let height = area.height.saturating_sub(4).max(1);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|&i| {
+                let option = &self.options[i];
+                let value_style = if option.inherited {
+                    Style::default().fg(theme.dim)
+                } else {
+                    Style::default().fg(theme.secondary)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:width$} ", option.name, width = NAME_WIDTH)),
+                    Span::styled(option.value.clone(), value_style),
+                ]))
+            })
+            .collect();
+
+        let others: Vec<&str> = self
+            .scopes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.scope)
+            .map(|(_, (_, label))| label.as_str())
+            .collect();
+        let mut title = format!("Options of {}", self.scope().1);
+        if !others.is_empty() {
+            title.push_str(&format!(" (Tab: {})", others.join(", ")));
+        }
+        if !self.filter.is_empty() {
+            title.push_str(&format!(" [filter: {}]", self.filter));
+        }
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.accent))
+                    .title(title),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        f.render_widget(Clear, popup);
+        f.render_stateful_widget(list, popup, &mut self.selected);
+    }
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Open the options of the selected session or window, with the other scopes a Tab away
+    pub(crate) async fn open_options(&mut self) -> Result<()> {
+        let (server, mut scopes) = match &self.view {
+            View::Sessions => match self.selected_session() {
+                Some(session) => {
+                    let target = SessionRef::of(session).tmux_target().to_string();
+                    (
+                        session.server.clone(),
+                        vec![
+                            (
+                                OptionScope::Session(target.clone()),
+                                format!("session '{}'", session.name),
+                            ),
+                            (
+                                OptionScope::Window(target),
+                                format!("the current window of '{}'", session.name),
+                            ),
+                        ],
+                    )
+                }
+                None => (self.shown_server.clone(), Vec::new()),
+            },
+            View::Windows(view) => {
+                let session = &view.session;
+                let mut scopes: Vec<(OptionScope, String)> = view
+                    .selected_window()
+                    .map(|window| {
+                        (
+                            OptionScope::Window(window.id.clone()),
+                            format!("window '{}'", window.name),
+                        )
+                    })
+                    .into_iter()
+                    .collect();
+                scopes.push((
+                    OptionScope::Session(session.tmux_target().to_string()),
+                    format!("session '{}'", session.name),
+                ));
+                (session.server.clone(), scopes)
+            }
+            View::Panes(view) => {
+                let session = &view.parent.session;
+                (
+                    session.server.clone(),
+                    vec![
+                        (
+                            OptionScope::Window(view.window.id.clone()),
+                            format!("window '{}'", view.window.name),
+                        ),
+                        (
+                            OptionScope::Session(session.tmux_target().to_string()),
+                            format!("session '{}'", session.name),
+                        ),
+                    ],
+                )
+            }
+            View::Tree(_) | View::Clients(_) => (None, Vec::new()),
+        };
+        scopes.push((OptionScope::Server, "the server".to_string()));
+
+        self.options = Some(OptionsBrowser::new(server, scopes));
+        if self.reload_options().await {
+            self.input_mode = InputMode::BrowsingOptions;
+            self.info("Type to filter, Enter to edit, Tab for other scopes, ESC to close");
+        }
+        Ok(())
+    }
+
+    /// Load the options of the current scope; closes the browser if they cannot be listed
+    async fn reload_options(&mut self) -> bool {
+        let Some(browser) = &self.options else {
+            return false;
+        };
+        let client = self.client_for(browser.server.as_deref()).clone();
+        let scope = browser.scope().0.clone();
+        match client.blocking(move |c| c.show_options(&scope)).await {
+            Ok(options) => {
+                if let Some(browser) = self.options.as_mut() {
+                    browser.set_options(options);
+                }
+                true
+            }
+            Err(e) => {
+                self.options = None;
+                self.input_mode = InputMode::Normal;
+                self.report_error(format!("Error listing options: {:#}", e));
+                false
+            }
+        }
+    }
+
+    pub(crate) async fn handle_browsing_options_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(browser) = self.options.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Down => browser.move_by(1),
+            KeyCode::Up => browser.move_by(-1),
+            KeyCode::PageDown => browser.move_by(PAGE as isize),
+            KeyCode::PageUp => browser.move_by(-(PAGE as isize)),
+            KeyCode::Home => browser.move_by(isize::MIN),
+            KeyCode::End => browser.move_by(isize::MAX),
+            KeyCode::Tab | KeyCode::BackTab => {
+                let len = browser.scopes.len();
+                browser.scope = if key.code == KeyCode::Tab {
+                    (browser.scope + 1) % len
+                } else {
+                    (browser.scope + len - 1) % len
+                };
+                browser.options.clear();
+                self.reload_options().await;
+            }
+            KeyCode::Enter => {
+                if let Some(option) = browser.selected_option() {
+                    self.input.set(option.value.clone());
+                    self.input_mode = InputMode::EditingOption;
+                }
+            }
+            KeyCode::Delete => {
+                let (scope, label) = browser.scope().clone();
+                let server = browser.server.clone();
+                let Some(option) = browser.selected_option().cloned() else {
+                    return Ok(());
+                };
+                if scope == OptionScope::Server || option.inherited {
+                    self.warn(format!("'{}' is not set on {}", option.name, label));
+                    return Ok(());
+                }
+                let name = option.name.clone();
+                match self
+                    .client_for(server.as_deref())
+                    .blocking(move |c| c.unset_option(&scope, &name))
+                    .await
+                {
+                    Ok(()) => {
+                        self.success(format!("'{}' of {} reset", option.name, label));
+                        self.reload_options().await;
+                    }
+                    Err(e) => self.error(format!("Error resetting '{}': {:#}", option.name, e)),
+                }
+            }
+            KeyCode::Backspace => {
+                browser.filter.pop();
+                browser.apply_filter();
+            }
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                browser.filter.push(c);
+                browser.apply_filter();
+            }
+            KeyCode::Esc if !browser.filter.is_empty() => {
+                browser.filter.clear();
+                browser.apply_filter();
+            }
+            KeyCode::Esc => {
+                self.options = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Closed");
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Prompt for the new value of the selected option, e.g. `history-limit of session 'work': `
+    pub(crate) fn option_prompt(&self) -> String {
+        match &self.options {
+            Some(browser) => match browser.selected_option() {
+                Some(option) => format!("{} of {}: ", option.name, browser.scope().1),
+                None => "Value: ".to_string(),
+            },
+            None => "Value: ".to_string(),
+        }
+    }
+
+    pub(crate) async fn handle_editing_option_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter => {
+                let Some(browser) = &self.options else {
+                    self.input_mode = InputMode::Normal;
+                    return Ok(());
+                };
+                let Some(name) = browser.selected_option().map(|o| o.name.clone()) else {
+                    self.input_mode = InputMode::BrowsingOptions;
+                    return Ok(());
+                };
+                let (scope, label) = browser.scope().clone();
+                let (option, value) = (name.clone(), self.input.to_string());
+                match self
+                    .client_for(browser.server.as_deref())
+                    .blocking(move |c| c.set_option(&scope, &option, &value))
+                    .await
+                {
+                    Ok(()) => {
+                        self.success(format!("Set '{}' of {} to '{}'", name, label, self.input));
+                        self.input.clear();
+                        self.input_mode = InputMode::BrowsingOptions;
+                        self.reload_options().await;
+                    }
+                    // Stay in the prompt so the value can be fixed
+                    Err(e) => self.input_error = Some(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.input_mode = InputMode::BrowsingOptions;
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(())
+    }
+}
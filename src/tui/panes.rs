@@ -0,0 +1,143 @@
+use super::icons::IconSet;
+use super::theme::Theme;
+use super::widgets::{render_scrollbar, Jump};
+use super::windows::WindowView;
+use crate::tmux::{TmuxPane, TmuxWindow};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+/// Panes of a single window, shown after drilling down from the window view
+pub(crate) struct PaneView {
+    /// The window view to return to
+    pub parent: WindowView,
+    pub window: TmuxWindow,
+    pub panes: Vec<TmuxPane>,
+    pub selected: ListState,
+}
+
+impl PaneView {
+    pub fn new(parent: WindowView, window: TmuxWindow) -> Self {
+        Self {
+            parent,
+            window,
+            panes: Vec::new(),
+            selected: ListState::default(),
+        }
+    }
+
+    /// Replace the pane list, keeping the selection on the same pane where possible
+    pub fn set_panes(&mut self, panes: Vec<TmuxPane>) {
+        let previous = self.selected_pane().map(|p| p.id.clone());
+        self.panes = panes;
+
+        let index = previous
+            .and_then(|id| self.panes.iter().position(|p| p.id == id))
+            .or_else(|| self.panes.iter().position(|p| p.active))
+            .or((!self.panes.is_empty()).then_some(0));
+        self.selected.select(index);
+    }
+
+    /// Move the selection to the pane with the given id, if it is listed
+    pub fn select_pane_id(&mut self, id: &str) {
+        if let Some(i) = self.panes.iter().position(|p| p.id == id) {
+            self.selected.select(Some(i));
+        }
+    }
+
+    pub fn selected_pane(&self) -> Option<&TmuxPane> {
+        self.selected.selected().and_then(|i| self.panes.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        let i = match self.selected.selected() {
+            Some(i) if i + 1 < self.panes.len() => i + 1,
+            _ => 0,
+        };
+        self.selected.select(Some(i));
+    }
+
+    pub fn select_previous(&mut self) {
+        let i = match self.selected.selected() {
+            Some(0) | None => self.panes.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.selected.select(Some(i));
+    }
+
+    pub fn jump(&mut self, jump: Jump) {
+        self.selected
+            .select(jump.apply(self.selected.selected(), self.panes.len()));
+    }
+
+    /// Draw the pane list; `show_titles` is false when tmux cannot set pane titles
+    pub fn render(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        icons: IconSet,
+        show_titles: bool,
+    ) {
+        let items: Vec<ListItem> = self
+            .panes
+            .iter()
+            .map(|pane| {
+                let active_indicator = icons.attached(pane.active);
+                // Dead panes stand out so a crashed program is noticed
+                let style = if pane.dead {
+                    Style::default().fg(theme.error)
+                } else if pane.active {
+                    Style::default()
+                        .fg(theme.attached)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+
+                let mut spans = vec![Span::raw(format!(
+                    "{} {}: {} ({}x{})",
+                    active_indicator, pane.index, pane.current_command, pane.width, pane.height
+                ))];
+                if pane.dead {
+                    spans.push(Span::raw(" [dead]"));
+                }
+                if pane.piped {
+                    spans.push(Span::styled(
+                        " [logging]",
+                        Style::default().fg(theme.accent),
+                    ));
+                }
+                if show_titles {
+                    spans.push(Span::styled(
+                        format!("  {}", pane.title),
+                        Style::default().fg(theme.secondary),
+                    ));
+                }
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Panes of '{}:{}' ({}){}",
+                self.parent.session,
+                self.window.name,
+                self.panes.len(),
+                if self.window.zoomed { " [zoomed]" } else { "" }
+            )))
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        f.render_stateful_widget(list, area, &mut self.selected);
+        render_scrollbar(f, area, icons, self.panes.len(), self.selected.selected());
+    }
+}
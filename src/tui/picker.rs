@@ -0,0 +1,91 @@
+use super::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+/// Popup list to choose an item from, e.g. a session template
+pub(crate) struct Picker {
+    title: String,
+    items: Vec<String>,
+    selected: ListState,
+}
+
+impl Picker {
+    pub fn new(title: String, items: Vec<String>) -> Self {
+        let mut selected = ListState::default();
+        selected.select((!items.is_empty()).then_some(0));
+        Self {
+            title,
+            items,
+            selected,
+        }
+    }
+
+    pub fn selected_item(&self) -> Option<&str> {
+        self.selected
+            .selected()
+            .and_then(|i| self.items.get(i))
+            .map(String::as_str)
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected.selected()
+    }
+
+    pub fn select_next(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            self.selected.select(Some((i + 1) % self.items.len()));
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Some(i) = self.selected.selected() {
+            let len = self.items.len();
+            self.selected.select(Some((i + len - 1) % len));
+        }
+    }
+
+    /// Draw the popup centered over `area`
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let longest = self
+            .items
+            .iter()
+            .map(|item| item.chars().count())
+            .chain([self.title.chars().count()])
+            .max()
+            .unwrap_or(0);
+        let width = (longest as u16 + 8).clamp(20, area.width);
+        let height = (self.items.len() as u16 + 2).clamp(3, area.height);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .map(|item| ListItem::new(item.as_str()))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.accent))
+                    .title(self.title.as_str()),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        f.render_widget(Clear, popup);
+        f.render_stateful_widget(list, popup, &mut self.selected);
+    }
+}
@@ -0,0 +1,73 @@
+use super::theme::Theme;
+use super::PaneTarget;
+use crate::tmux;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+/// How often the preview of the selected session is captured again
+const PREVIEW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Captured content of the active pane of the selected session or window
+#[derive(Default)]
+pub(crate) struct PanePreview {
+    target: Option<PaneTarget>,
+    lines: Vec<String>,
+    error: Option<String>,
+    captured_at: Option<Instant>,
+}
+
+impl PanePreview {
+    /// Whether the preview shows another pane or is due for a refresh
+    pub fn needs_update(&self, target: Option<&PaneTarget>) -> bool {
+        self.target.as_ref() != target
+            || !matches!(self.captured_at, Some(at) if at.elapsed() < PREVIEW_INTERVAL)
+    }
+
+    /// Store a new capture; trailing blank lines are dropped so the prompt sits at the bottom.
+    /// Returns whether the preview looks any different.
+    pub fn update(&mut self, target: Option<PaneTarget>, content: tmux::Result<String>) -> bool {
+        let (lines, error) = match content {
+            Ok(content) => {
+                let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+                while lines.last().is_some_and(|l| l.trim().is_empty()) {
+                    lines.pop();
+                }
+                (lines, None)
+            }
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        let changed = self.target != target || self.lines != lines || self.error != error;
+        self.target = target;
+        self.lines = lines;
+        self.error = error;
+        self.captured_at = Some(Instant::now());
+        changed
+    }
+
+    /// Draw the last lines that fit into `area`
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let visible = usize::from(area.height.saturating_sub(2));
+        let (text, style) = match (&self.target, &self.error) {
+            (None, _) => ("Nothing selected".to_string(), theme.dim),
+            (Some(_), Some(error)) => (error.clone(), theme.error),
+            (Some(_), None) => {
+                let start = self.lines.len().saturating_sub(visible);
+                (self.lines[start..].join("\n"), theme.secondary)
+            }
+        };
+        let title = match &self.target {
+            Some(target) => format!("Preview of {}", target.label),
+            None => "Preview".to_string(),
+        };
+
+        let preview = Paragraph::new(text)
+            .style(Style::default().fg(style))
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(preview, area);
+    }
+}
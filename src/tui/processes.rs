@@ -0,0 +1,108 @@
+//! Process tree of the selected pane, with CPU and memory, shown next to the pane list
+
+use super::theme::Theme;
+use crate::process::ProcessInfo;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+/// How often the processes of the selected pane are read again
+const PROCESSES_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+pub(crate) struct ProcessPanel {
+    /// Id of the pane the processes run in
+    pane: Option<String>,
+    processes: Vec<ProcessInfo>,
+    error: Option<String>,
+    read_at: Option<Instant>,
+}
+
+impl ProcessPanel {
+    /// Whether the panel shows another pane or is due for a refresh
+    pub fn needs_update(&self, pane: Option<&str>) -> bool {
+        self.pane.as_deref() != pane
+            || !matches!(self.read_at, Some(at) if at.elapsed() < PROCESSES_INTERVAL)
+    }
+
+    /// Store the processes of a pane; returns whether the panel looks any different
+    pub fn update(
+        &mut self,
+        pane: Option<String>,
+        result: crate::Result<Vec<ProcessInfo>>,
+    ) -> bool {
+        let (processes, error) = match result {
+            Ok(processes) => (processes, None),
+            Err(e) => (Vec::new(), Some(format!("{:#}", e))),
+        };
+        let changed = self.pane != pane || self.processes != processes || self.error != error;
+        self.pane = pane;
+        self.processes = processes;
+        self.error = error;
+        self.read_at = Some(Instant::now());
+        changed
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme, label: Option<&str>) {
+        let title = match label {
+            Some(label) => format!("Processes of pane {}", label),
+            None => "Processes".to_string(),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        if label.is_none() || self.processes.is_empty() {
+            let (text, color) = match &self.error {
+                Some(error) if label.is_some() => (error.as_str(), theme.error),
+                _ => ("Nothing selected", theme.dim),
+            };
+            let placeholder = Paragraph::new(text)
+                .style(Style::default().fg(color))
+                .block(block);
+            f.render_widget(placeholder, area);
+            return;
+        }
+
+        let mut lines = vec![Line::from(Span::styled(
+            format!("{:>7} {:>5} {:>8}  COMMAND", "PID", "CPU%", "RSS"),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))];
+        for process in &self.processes {
+            let indent = if process.depth == 0 {
+                String::new()
+            } else {
+                format!("{}└─ ", "   ".repeat(process.depth - 1))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{:>7} {:>5.1} {:>8}  ",
+                        process.pid,
+                        process.cpu_percent,
+                        format_memory(process.rss_kib)
+                    ),
+                    Style::default().fg(theme.secondary),
+                ),
+                Span::styled(
+                    format!("{}{}", indent, process.command),
+                    Style::default().fg(theme.text),
+                ),
+            ]));
+        }
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+/// Memory in KiB as `812K`, `45.2M` or `1.3G`
+fn format_memory(kib: u64) -> String {
+    match kib {
+        0..=1023 => format!("{}K", kib),
+        1024..=1_048_575 => format!("{:.1}M", kib as f64 / 1024.0),
+        _ => format!("{:.1}G", kib as f64 / 1_048_576.0),
+    }
+}
@@ -0,0 +1,236 @@
+//! Reloading what the TUI shows without blocking the render loop.
+//!
+//! The periodic poll and control-mode notifications start a background task that
+//! queries tmux on the blocking pool; the loop keeps drawing and handling keys,
+//! and picks the result up once the task has finished. Results equal to what is
+//! already shown are dropped, so idle polls neither touch the state nor redraw.
+
+use super::{App, SessionRef, View};
+use crate::tmux::{
+    self, prefetch_panes, AttachedClient, SessionTree, TmuxBackend, TmuxPane, TmuxSession,
+    TmuxWindow, DEFAULT_PREFETCH_CONCURRENCY,
+};
+use crate::Result;
+use anyhow::Context;
+use std::collections::HashMap;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Windows of a session together with the panes of each window
+pub(crate) type WindowList = (Vec<TmuxWindow>, HashMap<String, Vec<TmuxPane>>);
+
+/// Everything needed to list the sessions, detached from the `App` so the
+/// listing can run on the blocking pool
+pub(crate) struct SessionSource<C> {
+    /// The default server, unless another server is shown on its own
+    pub client: Option<C>,
+    /// Additional servers: all of them in the combined view, or the one shown
+    pub servers: Vec<(String, C)>,
+    pub columns: Vec<String>,
+}
+
+impl<C: TmuxBackend> SessionSource<C> {
+    pub fn fetch(&self) -> Result<Vec<TmuxSession>> {
+        self.collect(|client| client.list_sessions_with_columns(&self.columns))
+    }
+
+    pub async fn fetch_async(self) -> Result<Vec<TmuxSession>> {
+        tokio::task::spawn_blocking(move || self.fetch()).await?
+    }
+
+    /// Detached sessions running only the shell, on every server listed
+    pub async fn idle_async(self) -> Result<Vec<TmuxSession>> {
+        tokio::task::spawn_blocking(move || self.collect(|client| client.idle_sessions())).await?
+    }
+
+    /// Sessions `list` returns for each server, tagged with the server they live on
+    fn collect(
+        &self,
+        list: impl Fn(&C) -> tmux::Result<Vec<TmuxSession>>,
+    ) -> Result<Vec<TmuxSession>> {
+        let mut sessions = match &self.client {
+            Some(client) => list(client)?,
+            None => Vec::new(),
+        };
+        for (name, client) in &self.servers {
+            let server_sessions = list(client).with_context(|| format!("server '{}'", name))?;
+            sessions.extend(server_sessions.into_iter().map(|mut session| {
+                session.server = Some(name.clone());
+                session
+            }));
+        }
+        Ok(sessions)
+    }
+}
+
+/// List the windows of a session and prefetch their panes
+pub(crate) async fn fetch_windows<C: TmuxBackend>(client: C, target: String) -> Result<WindowList> {
+    let windows = client.blocking(move |c| c.list_windows(&target)).await?;
+    let panes = prefetch_panes(&client, &windows, DEFAULT_PREFETCH_CONCURRENCY).await;
+    Ok((windows, panes))
+}
+
+/// What the view shown when a reload started needs besides the session list
+enum ViewData {
+    Sessions,
+    Windows {
+        session: SessionRef,
+        windows: Result<WindowList>,
+    },
+    Panes {
+        window_id: String,
+        panes: tmux::Result<Vec<TmuxPane>>,
+    },
+    Tree(tmux::Result<Vec<SessionTree>>),
+    Clients(tmux::Result<Vec<AttachedClient>>),
+}
+
+/// Result of a background reload
+pub(crate) struct Reload {
+    /// Value of `App::sessions_generation` when the reload started
+    generation: u64,
+    sessions: Result<Vec<TmuxSession>>,
+    view: ViewData,
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Start reloading the session list and the current view in the background.
+    /// If a reload is already running, another one follows when it finishes.
+    pub(crate) fn start_reload(&mut self) {
+        self.last_poll = Instant::now();
+        if self.reload.is_some() {
+            self.reload_again = true;
+            return;
+        }
+
+        let source = self.session_source();
+        let generation = self.sessions_generation;
+        let view = match &self.view {
+            View::Sessions => None,
+            View::Windows(view) => Some((
+                self.client_for(view.session.server.as_deref()).clone(),
+                ViewKey::Windows(view.session.clone()),
+            )),
+            View::Panes(view) => Some((
+                self.client_for(view.parent.session.server.as_deref())
+                    .clone(),
+                ViewKey::Panes(view.window.id.clone()),
+            )),
+            View::Tree(_) => Some((self.client.clone(), ViewKey::Tree)),
+            View::Clients(_) => Some((self.client.clone(), ViewKey::Clients)),
+        };
+
+        self.reload = Some(tokio::spawn(async move {
+            let sessions = source.fetch_async().await;
+            let view = match view {
+                None => ViewData::Sessions,
+                Some((client, ViewKey::Windows(session))) => {
+                    let target = session.tmux_target().to_string();
+                    ViewData::Windows {
+                        session,
+                        windows: fetch_windows(client, target).await,
+                    }
+                }
+                Some((client, ViewKey::Panes(window_id))) => {
+                    let target = window_id.clone();
+                    ViewData::Panes {
+                        window_id,
+                        panes: client.blocking(move |c| c.list_panes(&target)).await,
+                    }
+                }
+                Some((client, ViewKey::Tree)) => {
+                    ViewData::Tree(client.blocking(|c| c.list_tree()).await)
+                }
+                Some((client, ViewKey::Clients)) => {
+                    ViewData::Clients(client.blocking(|c| c.list_clients()).await)
+                }
+            };
+            Reload {
+                generation,
+                sessions,
+                view,
+            }
+        }));
+    }
+
+    /// Apply the result of the background reload once it is available
+    pub(crate) async fn finish_reload(&mut self) -> Result<()> {
+        if !self.reload.as_ref().is_some_and(JoinHandle::is_finished) {
+            return Ok(());
+        }
+        let Some(handle) = self.reload.take() else {
+            return Ok(());
+        };
+        let reload = handle.await.context("Reload task failed")?;
+
+        // The list was refreshed directly since the reload started, so it is stale
+        if reload.generation == self.sessions_generation && self.apply_reload(reload) {
+            self.needs_redraw = true;
+        }
+        if std::mem::take(&mut self.reload_again) {
+            self.start_reload();
+        }
+        Ok(())
+    }
+
+    /// Apply a finished reload; returns whether anything shown changed
+    fn apply_reload(&mut self, reload: Reload) -> bool {
+        let sessions_changed = match reload.sessions {
+            Ok(sessions) => self.sessions_loaded(sessions),
+            Err(e) => {
+                self.report_failure(super::RetryAction::Refresh, e);
+                return true;
+            }
+        };
+
+        let view_changed = match reload.view {
+            ViewData::Sessions => false,
+            ViewData::Windows { session, windows } => {
+                if !matches!(&self.view, View::Windows(view) if view.session.id == session.id) {
+                    return sessions_changed;
+                }
+                if self.locate(&session).is_none() {
+                    self.view = View::Sessions;
+                    self.warn(format!("Session '{}' no longer exists", session));
+                    return true;
+                }
+                let unchanged = matches!(
+                    (&self.view, &windows),
+                    (View::Windows(view), Ok((windows, panes)))
+                        if view.windows == *windows && view.panes == *panes
+                );
+                if !unchanged {
+                    self.apply_windows(windows);
+                }
+                !unchanged
+            }
+            ViewData::Panes { window_id, panes } => {
+                if !matches!(&self.view, View::Panes(view) if view.window.id == window_id) {
+                    return sessions_changed;
+                }
+                let unchanged = matches!(
+                    (&self.view, &panes),
+                    (View::Panes(view), Ok(panes)) if view.panes == *panes
+                );
+                if !unchanged && self.apply_panes(panes) {
+                    // Back in the window view, which needs reloading as well
+                    self.reload_again = true;
+                }
+                !unchanged
+            }
+            ViewData::Tree(tree) => matches!(self.view, View::Tree(_)) && self.apply_tree(tree),
+            ViewData::Clients(clients) => {
+                matches!(self.view, View::Clients(_)) && self.apply_clients(clients)
+            }
+        };
+        sessions_changed || view_changed
+    }
+}
+
+/// The view a reload fetches data for
+enum ViewKey {
+    Windows(SessionRef),
+    Panes(String),
+    Tree,
+    Clients,
+}
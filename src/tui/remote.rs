@@ -0,0 +1,50 @@
+use crate::tmux::Key;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Returns true for the key that leaves remote typing mode (Ctrl+]).
+///
+/// Terminals report Ctrl+] as the raw byte 0x1D, which crossterm decodes as Ctrl+5,
+/// so both spellings are accepted.
+pub fn is_exit_key(event: &KeyEvent) -> bool {
+    event.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(event.code, KeyCode::Char(']') | KeyCode::Char('5'))
+}
+
+/// Translate a terminal key event into the equivalent tmux key
+pub fn key_event_to_tmux(event: &KeyEvent) -> Option<Key> {
+    let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = event.modifiers.contains(KeyModifiers::ALT);
+
+    let base = match event.code {
+        KeyCode::Char(c) if !ctrl && !alt => return Some(Key::Literal(c.to_string())),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Escape".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BTab".to_string(),
+        KeyCode::Backspace => "BSpace".to_string(),
+        KeyCode::Delete => "DC".to_string(),
+        KeyCode::Insert => "IC".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PPage".to_string(),
+        KeyCode::PageDown => "NPage".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        _ => return None,
+    };
+
+    let mut name = String::new();
+    if ctrl {
+        name.push_str("C-");
+    }
+    if alt {
+        name.push_str("M-");
+    }
+    name.push_str(&base);
+    Some(Key::Named(name))
+}
@@ -0,0 +1,171 @@
+//! The run-command prompt: a shell command run in a new window of the selected
+//! session, or in a popup over the TUI, without attaching. Also scratch terminals:
+//! a shell in a popup, started in the directory of the selected session, window
+//! or pane.
+
+use super::{App, InputMode, SessionRef, View};
+use crate::tmux::TmuxBackend;
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Where a command typed into the run-command prompt runs
+#[derive(Debug, Clone)]
+pub(crate) struct RunTarget {
+    server: Option<String>,
+    /// Session that gets the new window
+    session: String,
+    /// Session, window or pane whose directory the popup starts in
+    pane: String,
+    /// Shown in the prompt, e.g. `window 'editor'`
+    label: String,
+    /// Run in a popup rather than a new window
+    popup: bool,
+}
+
+impl<C: TmuxBackend> App<C> {
+    /// Where commands run for the selected session, window or pane
+    fn selected_run_target(&self) -> Option<RunTarget> {
+        match &self.view {
+            View::Sessions => self.selected_session().map(|session| {
+                let target = SessionRef::of(session).tmux_target().to_string();
+                RunTarget {
+                    server: session.server.clone(),
+                    session: target.clone(),
+                    pane: target,
+                    label: format!("session '{}'", session.name),
+                    popup: false,
+                }
+            }),
+            View::Windows(view) => view.selected_window().map(|window| RunTarget {
+                server: view.session.server.clone(),
+                session: view.session.tmux_target().to_string(),
+                pane: window.id.clone(),
+                label: format!("window '{}'", window.name),
+                popup: false,
+            }),
+            View::Panes(view) => view.selected_pane().map(|pane| RunTarget {
+                server: view.parent.session.server.clone(),
+                session: view.parent.session.tmux_target().to_string(),
+                pane: pane.id.clone(),
+                label: format!("pane {} of '{}'", pane.index, view.window.name),
+                popup: false,
+            }),
+            View::Tree(_) | View::Clients(_) => None,
+        }
+    }
+
+    /// Ask for a command to run for the selected session, window or pane
+    pub(crate) fn start_run_command(&mut self) {
+        let Some(target) = self.selected_run_target() else {
+            return;
+        };
+        self.info(format!(
+            "Command to run for {} (Tab for a popup, ESC to cancel, Enter to run):",
+            target.label
+        ));
+        self.run_target = Some(target);
+        self.input.clear();
+        self.input_mode = InputMode::RunCommand;
+    }
+
+    /// Popups show on the client tmux-ui runs in, so they need it inside that server
+    fn can_run_in_popup(&self, target: &RunTarget) -> bool {
+        target.server.is_none()
+            && self.client.is_inside_this_server()
+            && self.capabilities.has_popup()
+    }
+
+    /// Open a shell in a popup over the TUI, in the directory of the selected
+    /// session, window or pane; waits until the shell exits
+    pub(crate) async fn open_scratch_terminal(&mut self) {
+        let Some(target) = self.selected_run_target() else {
+            return;
+        };
+        if !self.can_run_in_popup(&target) {
+            self.warn("Scratch terminals need tmux 3.2 and tmux-ui running inside tmux");
+            return;
+        }
+        let size = self.config.popup_size().unwrap_or_default();
+        let pane = target.pane.clone();
+        match self
+            .client
+            .blocking(move |c| c.display_popup(&pane, None, &size))
+            .await
+        {
+            Ok(()) => self.info(format!("Closed the scratch terminal of {}", target.label)),
+            Err(e) => self.report_error(format!("Error opening a scratch terminal: {}", e)),
+        }
+    }
+
+    pub(crate) fn run_command_prompt(&self) -> (String, String) {
+        match &self.run_target {
+            Some(target) => (
+                format!("Run for {}: ", target.label),
+                format!(
+                    "{}  [in: {}]",
+                    self.input_error_note(),
+                    if target.popup { "popup" } else { "new window" }
+                ),
+            ),
+            None => ("Run: ".to_string(), String::new()),
+        }
+    }
+
+    pub(crate) async fn handle_run_command_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter if !self.input.is_empty() => {
+                self.input_mode = InputMode::Normal;
+                let command = self.input.take();
+                let Some(target) = self.run_target.take() else {
+                    return;
+                };
+                let client = self.client_for(target.server.as_deref()).clone();
+                if target.popup {
+                    let size = self.config.popup_size().unwrap_or_default();
+                    let (pane, program) = (target.pane.clone(), command.clone());
+                    match client
+                        .blocking(move |c| c.display_popup(&pane, Some(&program), &size))
+                        .await
+                    {
+                        Ok(()) => self.info(format!("Closed the popup of '{}'", command)),
+                        Err(e) => self.report_error(format!("Error running command: {}", e)),
+                    }
+                } else {
+                    let (session, program) = (target.session.clone(), command.clone());
+                    match client
+                        .blocking(move |c| c.run_in_window(&session, &program))
+                        .await
+                    {
+                        Ok(()) => self.success(format!(
+                            "Running '{}' in a new window of {}",
+                            command, target.label
+                        )),
+                        Err(e) => self.report_error(format!("Error running command: {}", e)),
+                    }
+                    self.start_reload();
+                }
+            }
+            KeyCode::Tab => {
+                let Some(target) = self.run_target.clone() else {
+                    return;
+                };
+                if target.popup || self.can_run_in_popup(&target) {
+                    if let Some(target) = self.run_target.as_mut() {
+                        target.popup = !target.popup;
+                    }
+                } else {
+                    self.input_error =
+                        Some("popups need tmux 3.2 and tmux-ui running inside tmux".to_string());
+                }
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.run_target = None;
+                self.input_mode = InputMode::Normal;
+                self.info("Cancelled");
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+    }
+}
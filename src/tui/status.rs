@@ -0,0 +1,467 @@
+//! The status bar: messages and prompts on the left, key hints for the current
+//! view or prompt on the right
+
+use super::keymap::Action;
+use super::{App, InputMode, View};
+use crate::tmux::TmuxBackend;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tokio::time::{Duration, Instant};
+
+/// How long a message stays in the status bar
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Gap between two key hints
+const HINT_GAP: &str = "  ";
+
+/// How a message is colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// A message shown on the left of the status bar
+#[derive(Debug, Clone)]
+pub(crate) struct StatusMessage {
+    pub text: String,
+    pub severity: Severity,
+    /// When the message disappears; `None` keeps it until it is replaced
+    expires: Option<Instant>,
+}
+
+impl StatusMessage {
+    pub fn new(severity: Severity, text: String) -> Self {
+        Self {
+            text,
+            severity,
+            expires: Some(Instant::now() + MESSAGE_TIMEOUT),
+        }
+    }
+
+    /// A message that stays until it is replaced, like the help line
+    pub fn sticky(text: String) -> Self {
+        Self {
+            text,
+            severity: Severity::Info,
+            expires: None,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires.is_some_and(|at| Instant::now() >= at)
+    }
+}
+
+/// A key and what it does, shown on the right of the status bar
+struct Hint {
+    key: String,
+    label: &'static str,
+    /// Destructive actions are drawn in the error color
+    danger: bool,
+    /// Other hints are left out first when the bar is too narrow
+    keep: bool,
+}
+
+impl Hint {
+    fn new(key: &str, label: &'static str) -> Self {
+        Self {
+            key: key.to_string(),
+            label,
+            danger: false,
+            keep: false,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.key.chars().count() + self.label.chars().count() + 3
+    }
+}
+
+impl<C: TmuxBackend> App<C> {
+    pub(crate) fn info(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        tracing::info!("{}", text);
+        self.status = Some(StatusMessage::new(Severity::Info, text));
+    }
+
+    pub(crate) fn success(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        tracing::info!("{}", text);
+        self.status = Some(StatusMessage::new(Severity::Success, text));
+    }
+
+    pub(crate) fn warn(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        tracing::warn!("{}", text);
+        self.status = Some(StatusMessage::new(Severity::Warning, text));
+    }
+
+    pub(crate) fn error(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        tracing::error!("{}", text);
+        self.status = Some(StatusMessage::new(Severity::Error, text));
+    }
+
+    /// Show the keys the given actions have in the current list, until the next message
+    pub(crate) fn show_help(&mut self, actions: &[(Action, &str)]) {
+        let text = self.keymap.help(self.scope(), actions);
+        self.status = Some(StatusMessage::sticky(text));
+    }
+
+    /// Clear an expired message; returns whether the status bar changed.
+    /// Prompts stay while their input mode is active.
+    pub(crate) fn expire_status(&mut self) -> bool {
+        let expired = matches!(self.input_mode, InputMode::Normal)
+            && self.status.as_ref().is_some_and(StatusMessage::is_expired);
+        if expired {
+            self.status = None;
+        }
+        expired
+    }
+
+    fn message_text(&self) -> String {
+        self.status
+            .as_ref()
+            .map(|s| s.text.clone())
+            .unwrap_or_default()
+    }
+
+    /// Left part of the status bar: the message, or the prompt with the text typed so far
+    fn status_line(&self) -> Line<'static> {
+        let theme = &self.theme;
+        let input_style = Style::default().fg(theme.input);
+        let (prompt, note) = match self.input_mode {
+            InputMode::Normal => {
+                let Some(status) = &self.status else {
+                    return Line::default();
+                };
+                let color = match status.severity {
+                    Severity::Info => theme.text,
+                    Severity::Success => theme.attached,
+                    Severity::Warning => theme.alert,
+                    Severity::Error => theme.error,
+                };
+                return Line::styled(status.text.clone(), Style::default().fg(color));
+            }
+            InputMode::RemoteTyping => {
+                // Make it obvious that keystrokes are going to another pane
+                return Line::styled(
+                    format!(" {} ", self.message_text()),
+                    Style::default()
+                        .fg(theme.remote_fg)
+                        .bg(theme.remote_bg)
+                        .add_modifier(Modifier::BOLD),
+                );
+            }
+            InputMode::CreatingSession => (self.new_session_prompt(), self.input_error_note()),
+            InputMode::RenamingSession => match &self.pending_rename {
+                Some(pending) => (
+                    format!("Rename '{}' to: ", pending),
+                    self.input_error_note(),
+                ),
+                None => ("Rename to: ".to_string(), self.input_error_note()),
+            },
+            InputMode::EditingTags => match &self.pending_tags {
+                Some(pending) => (format!("Tags of '{}': ", pending), self.input_error_note()),
+                None => ("Tags: ".to_string(), self.input_error_note()),
+            },
+            InputMode::CloningSession => match &self.pending_clone {
+                Some((source, commands)) => (
+                    format!("Copy '{}' as: ", source),
+                    format!(
+                        "{}  [start editors: {}]",
+                        self.input_error_note(),
+                        if *commands { "on" } else { "off" }
+                    ),
+                ),
+                None => ("Copy as: ".to_string(), self.input_error_note()),
+            },
+            InputMode::EditingOption => (self.option_prompt(), self.input_error_note()),
+            InputMode::EditingEnvironment => (self.environment_prompt(), self.input_error_note()),
+            InputMode::EditingHook => (self.hook_prompt(), self.input_error_note()),
+            InputMode::EnteringLayout | InputMode::NamingLayout => {
+                (self.layout_prompt(), self.input_error_note())
+            }
+            InputMode::DisplayMessage => ("Format: ".to_string(), String::new()),
+            InputMode::RawCommand => (":".to_string(), String::new()),
+            InputMode::CreatingWindow => ("New window name: ".to_string(), String::new()),
+            InputMode::RenamingWindow => ("Rename window to: ".to_string(), String::new()),
+            InputMode::Searching => ("/".to_string(), String::new()),
+            InputMode::SendingCommand => match &self.send_target {
+                Some(target) => (format!("Send to {}: ", target.label), String::new()),
+                None => ("Send: ".to_string(), String::new()),
+            },
+            InputMode::RunCommand => self.run_command_prompt(),
+            InputMode::RespawningPane => match &self.respawn_target {
+                Some(target) => (format!("Respawn {} with: ", target.label), String::new()),
+                None => ("Respawn with: ".to_string(), String::new()),
+            },
+            InputMode::PickingTemplate
+            | InputMode::PickingSession
+            | InputMode::PickingWindow
+            | InputMode::PickingServer
+            | InputMode::PickingHistory
+            | InputMode::BrowsingBuffers
+            | InputMode::BrowsingArchive
+            | InputMode::BrowsingOptions
+            | InputMode::BrowsingEnvironment
+            | InputMode::BrowsingHooks
+            | InputMode::PickingHookTemplate
+            | InputMode::PickingClient
+            | InputMode::PickingLayout
+            | InputMode::ConfirmingPrune
+            | InputMode::ConfirmingKill
+            | InputMode::ViewingOutput => return Line::styled(self.message_text(), input_style),
+        };
+
+        self.input.line(prompt, note, input_style)
+    }
+
+    /// Keys that matter in the current view or prompt, most important first
+    fn key_hints(&self) -> Vec<Hint> {
+        let keymap = &self.keymap;
+        let scope = self.scope();
+        let hint = |action: Action, label: &'static str| Hint {
+            key: keymap.first_key(action, scope),
+            label,
+            danger: false,
+            keep: false,
+        };
+        match self.input_mode {
+            InputMode::Normal => {}
+            InputMode::RemoteTyping => return vec![Hint::new("Ctrl+]", "Stop typing")],
+            InputMode::Searching => {
+                return vec![Hint::new("Enter", "Keep filter"), Hint::new("Esc", "Clear")]
+            }
+            InputMode::PickingTemplate
+            | InputMode::PickingSession
+            | InputMode::PickingWindow
+            | InputMode::PickingServer
+            | InputMode::PickingHistory
+            | InputMode::PickingHookTemplate
+            | InputMode::PickingClient
+            | InputMode::PickingLayout => {
+                return vec![
+                    Hint::new("Enter", "Choose"),
+                    Hint::new(self.icons.up_down(), "Move"),
+                    Hint::new("Esc", "Cancel"),
+                ]
+            }
+            InputMode::BrowsingBuffers => {
+                return vec![
+                    Hint::new("Enter", "Paste"),
+                    Hint::new("y", "Copy to clipboard"),
+                    Hint::new("d", "Delete"),
+                    Hint::new("Esc", "Close"),
+                ]
+            }
+            InputMode::BrowsingArchive => {
+                return vec![
+                    Hint::new("Enter", "Restore"),
+                    Hint::new("d", "Delete"),
+                    Hint::new("Esc", "Close"),
+                ]
+            }
+            InputMode::BrowsingOptions => {
+                return vec![
+                    Hint::new("Enter", "Edit"),
+                    Hint::new("Tab", "Scope"),
+                    Hint::new("Del", "Reset"),
+                    Hint::new("Esc", "Close"),
+                ]
+            }
+            InputMode::BrowsingEnvironment => {
+                return vec![
+                    Hint::new("Enter", "Edit"),
+                    Hint::new("n", "Add"),
+                    Hint::new("d", "Unset"),
+                    Hint::new("Tab", "Global"),
+                    Hint::new("Esc", "Close"),
+                ]
+            }
+            InputMode::BrowsingHooks => {
+                return vec![
+                    Hint::new("Enter", "Edit"),
+                    Hint::new("n", "Add"),
+                    Hint::new("t", "Templates"),
+                    Hint::new("d", "Remove"),
+                    Hint::new("Tab", "Global"),
+                    Hint::new("Esc", "Close"),
+                ]
+            }
+            InputMode::EditingOption | InputMode::EditingEnvironment | InputMode::EditingHook => {
+                return vec![Hint::new("Enter", "Set"), Hint::new("Esc", "Cancel")]
+            }
+            InputMode::EnteringLayout => {
+                return vec![Hint::new("Enter", "Apply"), Hint::new("Esc", "Cancel")]
+            }
+            InputMode::NamingLayout => {
+                return vec![Hint::new("Enter", "Save"), Hint::new("Esc", "Cancel")]
+            }
+            InputMode::ConfirmingPrune => {
+                return vec![Hint::new("y", "Kill them"), Hint::new("any", "Cancel")]
+            }
+            InputMode::ConfirmingKill => {
+                return vec![Hint::new("y", "Kill"), Hint::new("any", "Cancel")]
+            }
+            InputMode::ViewingOutput => {
+                return vec![
+                    Hint::new(self.icons.up_down(), "Scroll"),
+                    Hint::new("Esc", "Close"),
+                ]
+            }
+            InputMode::CreatingSession => {
+                return vec![
+                    Hint::new("Enter", "Create"),
+                    Hint::new("Tab", "Next field"),
+                    Hint::new("Esc", "Cancel"),
+                ]
+            }
+            InputMode::RunCommand => {
+                return vec![
+                    Hint::new("Enter", "Run"),
+                    Hint::new("Tab", "Window/popup"),
+                    Hint::new("Esc", "Cancel"),
+                ]
+            }
+            InputMode::RawCommand => {
+                return vec![
+                    Hint::new("Enter", "Run"),
+                    Hint::new("Tab", "Complete"),
+                    Hint::new("Esc", "Cancel"),
+                ]
+            }
+            InputMode::CloningSession => {
+                return vec![
+                    Hint::new("Enter", "Copy"),
+                    Hint::new("Tab", "Editors on/off"),
+                    Hint::new("Esc", "Cancel"),
+                ]
+            }
+            InputMode::RenamingSession
+            | InputMode::EditingTags
+            | InputMode::DisplayMessage
+            | InputMode::CreatingWindow
+            | InputMode::RenamingWindow
+            | InputMode::SendingCommand
+            | InputMode::RespawningPane => {
+                return vec![Hint::new("Enter", "Confirm"), Hint::new("Esc", "Cancel")]
+            }
+        }
+
+        let mut hints = match self.view {
+            View::Sessions if self.client.is_inside_tmux() => vec![
+                hint(Action::Attach, "Switch"),
+                hint(Action::Back, "Back to UI"),
+                Hint {
+                    danger: true,
+                    ..hint(Action::Detach, "Detach")
+                },
+                hint(Action::New, "New"),
+                hint(Action::Kill, "Delete"),
+                hint(Action::Rename, "Rename"),
+            ],
+            View::Sessions => vec![
+                hint(Action::Attach, "Attach"),
+                hint(Action::Detach, "Detach"),
+                hint(Action::New, "New"),
+                hint(Action::Kill, "Delete"),
+                hint(Action::Rename, "Rename"),
+                hint(Action::NewWindow, "New Window"),
+            ],
+            View::Windows(_) => vec![
+                hint(Action::Attach, "Select"),
+                hint(Action::DrillDown, "Panes"),
+                hint(Action::New, "New"),
+                hint(Action::Rename, "Rename"),
+                hint(Action::Kill, "Kill"),
+                hint(Action::Back, "Back"),
+            ],
+            View::Panes(_) => vec![
+                hint(Action::Attach, "Switch"),
+                hint(Action::SelectPane, "Make Active"),
+                hint(Action::ToggleZoom, "Zoom"),
+                hint(Action::Kill, "Kill"),
+                hint(Action::Back, "Back"),
+            ],
+            View::Tree(_) => vec![
+                hint(Action::Attach, "Switch"),
+                hint(Action::Expand, "Expand"),
+                hint(Action::Collapse, "Collapse"),
+                hint(Action::ToggleTree, "Session list"),
+            ],
+            View::Clients(_) => vec![
+                hint(Action::Detach, "Detach client"),
+                hint(Action::Attach, "Switch to session"),
+                hint(Action::Clients, "Session list"),
+            ],
+        };
+        if self.error_banner.is_some() {
+            hints.insert(0, hint(Action::DismissError, "Dismiss error"));
+        }
+        hints.push(Hint {
+            keep: true,
+            ..hint(Action::Help, "Help")
+        });
+        hints.push(Hint {
+            keep: true,
+            ..hint(Action::Quit, "Quit")
+        });
+        hints
+    }
+
+    pub(crate) fn render_status_bar(&self, f: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title("Status");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        // Hints get up to half of the bar; the least important ones are left out
+        // until the rest fits
+        let theme = &self.theme;
+        let mut hints = self.key_hints();
+        let budget = usize::from(inner.width / 2);
+        while hints
+            .iter()
+            .map(|h| h.width() + HINT_GAP.len())
+            .sum::<usize>()
+            > budget + HINT_GAP.len()
+        {
+            match hints.iter().rposition(|h| !h.keep) {
+                Some(i) => {
+                    hints.remove(i);
+                }
+                None => break,
+            }
+        }
+        let mut spans = Vec::new();
+        for hint in hints {
+            if !spans.is_empty() {
+                spans.push(Span::raw(HINT_GAP));
+            }
+            let color: Color = if hint.danger { theme.error } else { theme.hint };
+            spans.push(Span::styled(
+                format!("[{}] {}", hint.key, hint.label),
+                Style::default().fg(color),
+            ));
+        }
+        let hints = Line::from(spans);
+
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(hints.width() as u16 + 1),
+            ])
+            .split(inner);
+        f.render_widget(Paragraph::new(self.status_line()), parts[0]);
+        f.render_widget(Paragraph::new(hints).alignment(Alignment::Right), parts[1]);
+    }
+}
@@ -0,0 +1,217 @@
+//! Minimal fuzzy session list of `tmux-ui pick`, for shell keybindings and
+//! `tmux display-popup`
+//!
+//! It draws on stderr so that stdout carries nothing but the chosen name,
+//! which makes `session=$(tmux-ui pick)` work like it does with fzf.
+
+use super::fuzzy::{fuzzy_match, FuzzyMatch};
+use super::theme::Theme;
+use crate::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+use std::io;
+
+/// What a key press did to the switcher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwitcherEvent {
+    /// Keep going
+    None,
+    /// A session was chosen with Enter
+    Chosen(String),
+    /// Esc or Ctrl-C, nothing chosen
+    Cancelled,
+}
+
+/// Query line over the sessions matching it, best match first
+pub struct SessionSwitcher {
+    sessions: Vec<String>,
+    query: String,
+    /// Indices into `sessions` with their matches, in display order
+    matches: Vec<(usize, FuzzyMatch)>,
+    selected: usize,
+}
+
+impl SessionSwitcher {
+    pub fn new(sessions: Vec<String>) -> Self {
+        let mut switcher = Self {
+            sessions,
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+        };
+        switcher.filter();
+        switcher
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Names of the sessions matching the query, best match first
+    pub fn matches(&self) -> Vec<&str> {
+        self.matches
+            .iter()
+            .map(|(index, _)| self.sessions[*index].as_str())
+            .collect()
+    }
+
+    /// The session Enter would choose
+    pub fn selected(&self) -> Option<&str> {
+        self.matches
+            .get(self.selected)
+            .map(|(index, _)| self.sessions[*index].as_str())
+    }
+
+    fn filter(&mut self) {
+        self.matches = self
+            .sessions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, name)| Some((index, fuzzy_match(&self.query, name)?)))
+            .collect();
+        // Stable, so equally good matches keep the order tmux lists them in
+        self.matches
+            .sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+        self.selected = 0;
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> SwitcherEvent {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return SwitcherEvent::Cancelled,
+            KeyCode::Char('c' | 'g') if ctrl => return SwitcherEvent::Cancelled,
+            KeyCode::Enter => {
+                return match self.selected() {
+                    Some(name) => SwitcherEvent::Chosen(name.to_string()),
+                    None => SwitcherEvent::None,
+                }
+            }
+            KeyCode::Up | KeyCode::BackTab => self.move_selection(-1),
+            KeyCode::Char('p' | 'k') if ctrl => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Tab => self.move_selection(1),
+            KeyCode::Char('n' | 'j') if ctrl => self.move_selection(1),
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.filter();
+            }
+            KeyCode::Char('w') if ctrl => {
+                let kept = self.query.trim_end().rfind(' ').map_or(0, |i| i + 1);
+                self.query.truncate(kept);
+                self.filter();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.filter();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.filter();
+            }
+            _ => {}
+        }
+        SwitcherEvent::None
+    }
+
+    /// Move the selection by `delta`, wrapping around like the other lists
+    fn move_selection(&mut self, delta: isize) {
+        let len = self.matches.len() as isize;
+        if len > 0 {
+            self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+        }
+    }
+
+    /// Draw the query line on top and the matches below, nothing else
+    pub fn render(&self, f: &mut Frame, theme: &Theme) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(f.size());
+
+        let count = format!("  {}/{}", self.matches.len(), self.sessions.len());
+        let prompt = Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.accent)),
+            Span::styled(self.query.as_str(), Style::default().fg(theme.input)),
+            Span::styled(count, Style::default().fg(theme.dim)),
+        ]);
+        f.render_widget(Paragraph::new(prompt), chunks[0]);
+        f.set_cursor(
+            chunks[0].x + 2 + self.query.chars().count() as u16,
+            chunks[0].y,
+        );
+
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|(index, m)| {
+                let spans: Vec<Span> = self.sessions[*index]
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        let style = if m.positions.contains(&i) {
+                            Style::default()
+                                .fg(theme.search_match)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(theme.text)
+                        };
+                        Span::styled(c.to_string(), style)
+                    })
+                    .collect();
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(theme.selected_bg))
+            .highlight_symbol("› ");
+        let mut state = ListState::default();
+        state.select((!self.matches.is_empty()).then_some(self.selected));
+        f.render_stateful_widget(list, chunks[1], &mut state);
+    }
+
+    /// Let the user pick a session on the terminal behind stderr; `None` if they
+    /// cancelled
+    pub fn run(mut self, theme: &Theme) -> Result<Option<String>> {
+        enable_raw_mode()?;
+        let mut stderr = io::stderr();
+        execute!(stderr, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stderr))?;
+
+        let result = self.run_loop(&mut terminal, theme);
+
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+        result
+    }
+
+    fn run_loop<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        theme: &Theme,
+    ) -> Result<Option<String>> {
+        loop {
+            terminal.draw(|f| self.render(f, theme))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match self.handle_key(key) {
+                    SwitcherEvent::None => {}
+                    SwitcherEvent::Chosen(name) => return Ok(Some(name)),
+                    SwitcherEvent::Cancelled => return Ok(None),
+                }
+            }
+        }
+    }
+}
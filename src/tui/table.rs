@@ -0,0 +1,139 @@
+//! Built-in columns of the session table
+
+use super::icons::IconSet;
+use crate::sort::{SortField, SortKey};
+use anyhow::Result;
+use ratatui::layout::Constraint;
+use std::str::FromStr;
+
+/// Bounds of the name column, which otherwise fits the longest name
+const MIN_NAME_WIDTH: usize = 8;
+const MAX_NAME_WIDTH: usize = 40;
+
+/// A built-in column of the session table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionColumn {
+    /// Session name with its alerts and tags
+    Name,
+    Windows,
+    Panes,
+    /// Whether clients are attached, and how many
+    Attached,
+    /// Time since the session was created
+    Created,
+    /// Time since the last activity in any pane
+    Activity,
+    /// Command running in the active pane
+    Command,
+    /// Working directory of the active pane
+    Path,
+}
+
+impl SessionColumn {
+    /// All columns with their config names, in their default order
+    pub const ALL: [(SessionColumn, &'static str); 8] = [
+        (SessionColumn::Name, "name"),
+        (SessionColumn::Windows, "windows"),
+        (SessionColumn::Panes, "panes"),
+        (SessionColumn::Attached, "attached"),
+        (SessionColumn::Created, "created"),
+        (SessionColumn::Activity, "activity"),
+        (SessionColumn::Command, "command"),
+        (SessionColumn::Path, "path"),
+    ];
+
+    /// Name used for this column in the `table_columns` setting
+    pub fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(column, _)| *column == self)
+            .map(|(_, name)| *name)
+            .unwrap_or("")
+    }
+
+    /// Field the table is sorted by when this column is chosen; the command and
+    /// path columns cannot be sorted by
+    pub fn sort_field(self) -> Option<SortField> {
+        match self {
+            SessionColumn::Name => Some(SortField::Name),
+            SessionColumn::Windows => Some(SortField::Windows),
+            SessionColumn::Panes => Some(SortField::Panes),
+            SessionColumn::Attached => Some(SortField::Attached),
+            SessionColumn::Created => Some(SortField::Created),
+            SessionColumn::Activity => Some(SortField::Activity),
+            SessionColumn::Command | SessionColumn::Path => None,
+        }
+    }
+
+    /// Header cell text, with an arrow when the table is sorted by this column
+    pub fn header(self, sort: Option<SortKey>, icons: IconSet) -> String {
+        let title = match self {
+            SessionColumn::Name => "Name",
+            SessionColumn::Windows => "Windows",
+            SessionColumn::Panes => "Panes",
+            SessionColumn::Attached => "Attached",
+            SessionColumn::Created => "Created",
+            SessionColumn::Activity => "Idle",
+            SessionColumn::Command => "Command",
+            SessionColumn::Path => "Path",
+        };
+        match sort {
+            Some(key) if Some(key.field) == self.sort_field() => {
+                format!("{} {}", title, icons.sort_arrow(key.descending))
+            }
+            _ => title.to_string(),
+        }
+    }
+
+    /// Width of the column, given the width of the longest name cell; the path
+    /// takes the space left by the others
+    pub fn width(self, name_width: usize) -> Constraint {
+        match self {
+            SessionColumn::Name => {
+                Constraint::Length(name_width.clamp(MIN_NAME_WIDTH, MAX_NAME_WIDTH) as u16)
+            }
+            SessionColumn::Windows => Constraint::Length(9),
+            SessionColumn::Panes => Constraint::Length(7),
+            SessionColumn::Attached => Constraint::Length(10),
+            SessionColumn::Created | SessionColumn::Activity => Constraint::Length(9),
+            SessionColumn::Command => Constraint::Length(10),
+            SessionColumn::Path => Constraint::Min(0),
+        }
+    }
+}
+
+impl FromStr for SessionColumn {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(s.trim()))
+            .map(|(column, _)| *column)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|(_, name)| *name).collect();
+                anyhow::anyhow!(
+                    "Unknown table column '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The sort key following `current` when cycling through the sortable `columns`;
+/// `None` after the last one, which goes back to the configured order
+pub fn next_sort(columns: &[SessionColumn], current: Option<SortKey>) -> Option<SortKey> {
+    let fields: Vec<SortField> = columns.iter().filter_map(|c| c.sort_field()).collect();
+    let next = match current {
+        None => fields.first(),
+        Some(key) => fields
+            .iter()
+            .position(|f| *f == key.field)
+            .and_then(|i| fields.get(i + 1)),
+    };
+    next.map(|field| SortKey {
+        field: *field,
+        descending: false,
+    })
+}
@@ -0,0 +1,83 @@
+//! What the terminal can display, detected from the environment at startup
+//!
+//! Terminals do not report this themselves, so it is inferred the way most
+//! command line tools do it: `NO_COLOR`, `TERM`, `COLORTERM` and the locale.
+
+use std::env;
+
+/// How many colors the terminal shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// No colors at all, only bold and similar attributes
+    None,
+    /// The 16 ANSI colors
+    Basic,
+    /// The 256-color palette
+    Extended,
+    /// 24-bit colors
+    TrueColor,
+}
+
+/// Display capabilities of the terminal the TUI runs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSupport {
+    /// Whether characters outside ASCII, such as `●` or emoji, display correctly
+    pub unicode: bool,
+    pub color: ColorSupport,
+}
+
+impl Default for TerminalSupport {
+    fn default() -> Self {
+        Self {
+            unicode: true,
+            color: ColorSupport::TrueColor,
+        }
+    }
+}
+
+impl TerminalSupport {
+    /// Capabilities of the terminal described by the process environment
+    pub fn detect() -> Self {
+        Self::from_env(|name| env::var(name).ok())
+    }
+
+    /// Capabilities described by the given environment variables
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let term = var("TERM").unwrap_or_default();
+        let dumb = term.is_empty() || term == "dumb";
+
+        let color = if var("NO_COLOR").is_some() || dumb {
+            ColorSupport::None
+        } else if var("COLORTERM")
+            .is_some_and(|c| c.eq_ignore_ascii_case("truecolor") || c.eq_ignore_ascii_case("24bit"))
+        {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Extended
+        } else {
+            ColorSupport::Basic
+        };
+
+        // The first locale variable that is set decides, like in the C library
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(var)
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let unicode = !dumb && (locale.contains("utf-8") || locale.contains("utf8"));
+
+        Self { unicode, color }
+    }
+
+    /// Restrict the detected capabilities, for `--ascii` and `--no-color`
+    pub fn limited(mut self, ascii: bool, no_color: bool) -> Self {
+        if ascii {
+            self.unicode = false;
+        }
+        if no_color {
+            self.color = ColorSupport::None;
+        }
+        self
+    }
+}
@@ -0,0 +1,209 @@
+//! Color schemes for the TUI, selected with `theme = "<name>"` in the config file
+
+use super::terminal::ColorSupport;
+use ratatui::style::Color;
+
+/// The ANSI color with the same hue as an RGB color. Nearest-by-distance would
+/// turn the muted colors of most schemes grey, so the hue is kept instead.
+fn nearest_ansi((r, g, b): (u8, u8, u8)) -> Color {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    if max - min < 48 {
+        return match max {
+            0..=63 => Color::Black,
+            64..=159 => Color::DarkGray,
+            160..=223 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+    // Channels above the middle of the range make up the hue
+    let mid = (u16::from(max) + u16::from(min)) / 2;
+    let on = |c: u8| u16::from(c) > mid;
+    let bright = max >= 230;
+    match (on(r), on(g), on(b), bright) {
+        (true, false, false, false) => Color::Red,
+        (true, false, false, true) => Color::LightRed,
+        (false, true, false, false) => Color::Green,
+        (false, true, false, true) => Color::LightGreen,
+        (true, true, false, false) => Color::Yellow,
+        (true, true, false, true) => Color::LightYellow,
+        (false, false, true, false) => Color::Blue,
+        (false, false, true, true) => Color::LightBlue,
+        (true, false, true, false) => Color::Magenta,
+        (true, false, true, true) => Color::LightMagenta,
+        (false, true, true, false) => Color::Cyan,
+        (false, true, true, true) => Color::LightCyan,
+        // The largest channel is always above the middle and the smallest below
+        _ => Color::Gray,
+    }
+}
+
+/// Index of the closest color in the 6x6x6 cube of the 256-color palette
+fn nearest_indexed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| ((u16::from(c) * 5 + 127) / 255) as u8;
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+/// A color the terminal can show, as close as possible to `color`
+fn reduce(color: Color, support: ColorSupport) -> Color {
+    match (support, color) {
+        (ColorSupport::None, _) => Color::Reset,
+        (ColorSupport::Basic, Color::Rgb(r, g, b)) => nearest_ansi((r, g, b)),
+        (ColorSupport::Basic, Color::Indexed(i)) if i >= 16 => {
+            // Only the color cube; the grey ramp maps onto it well enough
+            let i = i.min(231) - 16;
+            let level = |l: u8| if l == 0 { 0 } else { 55 + 40 * l };
+            nearest_ansi((level(i / 36), level(i / 6 % 6), level(i % 6)))
+        }
+        (ColorSupport::Extended, Color::Rgb(r, g, b)) => Color::Indexed(nearest_indexed(r, g, b)),
+        _ => color,
+    }
+}
+
+/// Colors used throughout the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Title bar text
+    pub title: Color,
+    /// Popup borders and the server column
+    pub accent: Color,
+    /// Key hints in the status bar
+    pub hint: Color,
+    /// Status bar while text is entered, and other prompts
+    pub input: Color,
+    /// Error banner, failed output and the detach hint
+    pub error: Color,
+    /// Attached sessions, active windows and active panes
+    pub attached: Color,
+    /// Regular list entries
+    pub text: Color,
+    /// Secondary details such as custom columns and pane previews
+    pub secondary: Color,
+    /// Placeholders and de-emphasized details
+    pub dim: Color,
+    /// Background of the selected list entry
+    pub selected_bg: Color,
+    /// Characters matching the search pattern
+    pub search_match: Color,
+    /// Bell, activity and silence badges
+    pub alert: Color,
+    /// Remote typing banner
+    pub remote_fg: Color,
+    pub remote_bg: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            title: Color::Cyan,
+            accent: Color::Cyan,
+            hint: Color::Yellow,
+            input: Color::Yellow,
+            error: Color::Red,
+            attached: Color::Green,
+            text: Color::White,
+            secondary: Color::Gray,
+            dim: Color::DarkGray,
+            selected_bg: Color::DarkGray,
+            search_match: Color::Yellow,
+            alert: Color::LightRed,
+            remote_fg: Color::Black,
+            remote_bg: Color::Magenta,
+        }
+    }
+}
+
+impl Theme {
+    /// Names of the built-in color schemes
+    pub const NAMES: [&'static str; 4] = ["default", "gruvbox", "nord", "solarized"];
+
+    /// The same scheme with its colors reduced to those the terminal shows; without
+    /// colors everything is drawn in the terminal's default colors
+    pub fn for_terminal(self, support: ColorSupport) -> Self {
+        let c = |color| reduce(color, support);
+        Self {
+            title: c(self.title),
+            accent: c(self.accent),
+            hint: c(self.hint),
+            input: c(self.input),
+            error: c(self.error),
+            attached: c(self.attached),
+            text: c(self.text),
+            secondary: c(self.secondary),
+            dim: c(self.dim),
+            selected_bg: c(self.selected_bg),
+            search_match: c(self.search_match),
+            alert: c(self.alert),
+            remote_fg: c(self.remote_fg),
+            remote_bg: c(self.remote_bg),
+        }
+    }
+
+    /// Look up a built-in color scheme by name
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "gruvbox" => Some(Self::gruvbox()),
+            "nord" => Some(Self::nord()),
+            "solarized" => Some(Self::solarized()),
+            _ => None,
+        }
+    }
+
+    fn gruvbox() -> Self {
+        Self {
+            title: Color::Rgb(0x83, 0xa5, 0x98),
+            accent: Color::Rgb(0x8e, 0xc0, 0x7c),
+            hint: Color::Rgb(0xfa, 0xbd, 0x2f),
+            input: Color::Rgb(0xfe, 0x80, 0x19),
+            error: Color::Rgb(0xfb, 0x49, 0x34),
+            attached: Color::Rgb(0xb8, 0xbb, 0x26),
+            text: Color::Rgb(0xeb, 0xdb, 0xb2),
+            secondary: Color::Rgb(0xa8, 0x99, 0x84),
+            dim: Color::Rgb(0x66, 0x5c, 0x54),
+            selected_bg: Color::Rgb(0x50, 0x49, 0x45),
+            search_match: Color::Rgb(0xfa, 0xbd, 0x2f),
+            alert: Color::Rgb(0xfe, 0x80, 0x19),
+            remote_fg: Color::Rgb(0x28, 0x28, 0x28),
+            remote_bg: Color::Rgb(0xd3, 0x86, 0x9b),
+        }
+    }
+
+    fn nord() -> Self {
+        Self {
+            title: Color::Rgb(0x88, 0xc0, 0xd0),
+            accent: Color::Rgb(0x81, 0xa1, 0xc1),
+            hint: Color::Rgb(0xeb, 0xcb, 0x8b),
+            input: Color::Rgb(0xeb, 0xcb, 0x8b),
+            error: Color::Rgb(0xbf, 0x61, 0x6a),
+            attached: Color::Rgb(0xa3, 0xbe, 0x8c),
+            text: Color::Rgb(0xec, 0xef, 0xf4),
+            secondary: Color::Rgb(0xd8, 0xde, 0xe9),
+            dim: Color::Rgb(0x4c, 0x56, 0x6a),
+            selected_bg: Color::Rgb(0x43, 0x4c, 0x5e),
+            search_match: Color::Rgb(0x88, 0xc0, 0xd0),
+            alert: Color::Rgb(0xd0, 0x87, 0x70),
+            remote_fg: Color::Rgb(0x2e, 0x34, 0x40),
+            remote_bg: Color::Rgb(0xb4, 0x8e, 0xad),
+        }
+    }
+
+    fn solarized() -> Self {
+        Self {
+            title: Color::Rgb(0x26, 0x8b, 0xd2),
+            accent: Color::Rgb(0x2a, 0xa1, 0x98),
+            hint: Color::Rgb(0xb5, 0x89, 0x00),
+            input: Color::Rgb(0xcb, 0x4b, 0x16),
+            error: Color::Rgb(0xdc, 0x32, 0x2f),
+            attached: Color::Rgb(0x85, 0x99, 0x00),
+            text: Color::Rgb(0x93, 0xa1, 0xa1),
+            secondary: Color::Rgb(0x83, 0x94, 0x96),
+            dim: Color::Rgb(0x58, 0x6e, 0x75),
+            selected_bg: Color::Rgb(0x07, 0x36, 0x42),
+            search_match: Color::Rgb(0xb5, 0x89, 0x00),
+            alert: Color::Rgb(0xcb, 0x4b, 0x16),
+            remote_fg: Color::Rgb(0x00, 0x2b, 0x36),
+            remote_bg: Color::Rgb(0xd3, 0x36, 0x82),
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use super::icons::IconSet;
+use super::theme::Theme;
+use super::widgets::{render_scrollbar, Jump};
+use crate::tmux::SessionTree;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+use std::collections::HashSet;
+
+/// Kind of node on a row of the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NodeKind {
+    Session,
+    Window,
+    Pane,
+}
+
+/// A visible row of the tree; the indexes point into `TreeView::sessions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TreeRow {
+    pub kind: NodeKind,
+    /// tmux id of the node (`$1`, `@2` or `%3`)
+    pub id: String,
+    pub session: usize,
+    pub window: Option<usize>,
+    pub pane: Option<usize>,
+}
+
+/// Sessions, windows and panes of a server as one collapsible tree, like `choose-tree`
+pub(crate) struct TreeView {
+    pub sessions: Vec<SessionTree>,
+    /// Ids of the expanded sessions and windows
+    expanded: HashSet<String>,
+    rows: Vec<TreeRow>,
+    pub selected: ListState,
+}
+
+impl TreeView {
+    pub fn new() -> Self {
+        Self {
+            sessions: Vec::new(),
+            expanded: HashSet::new(),
+            rows: Vec::new(),
+            selected: ListState::default(),
+        }
+    }
+
+    /// Replace the tree, keeping the selection on the same node where possible
+    pub fn set_sessions(&mut self, sessions: Vec<SessionTree>) {
+        let previous = self.selected_row().map(|r| r.id.clone());
+        self.sessions = sessions;
+        self.rebuild(previous.as_deref());
+    }
+
+    /// Expand a session and select it
+    pub fn reveal_session(&mut self, id: &str) {
+        self.expanded.insert(id.to_string());
+        self.rebuild(Some(id));
+    }
+
+    pub fn selected_row(&self) -> Option<&TreeRow> {
+        self.selected.selected().and_then(|i| self.rows.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        let i = match self.selected.selected() {
+            Some(i) if i + 1 < self.rows.len() => i + 1,
+            _ => 0,
+        };
+        self.selected.select(Some(i));
+    }
+
+    pub fn select_previous(&mut self) {
+        let i = match self.selected.selected() {
+            Some(0) | None => self.rows.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.selected.select(Some(i));
+    }
+
+    pub fn jump(&mut self, jump: Jump) {
+        self.selected
+            .select(jump.apply(self.selected.selected(), self.rows.len()));
+    }
+
+    /// Expand the selected node, or move to its first child if it already is
+    pub fn expand(&mut self) {
+        let Some(row) = self.selected_row().cloned() else {
+            return;
+        };
+        if row.kind == NodeKind::Pane {
+            return;
+        }
+        if self.expanded.insert(row.id.clone()) {
+            self.rebuild(Some(&row.id));
+        } else {
+            self.select_next();
+        }
+    }
+
+    /// Collapse the selected node, or move to its parent if it has no children shown
+    pub fn collapse(&mut self) {
+        let Some(row) = self.selected_row().cloned() else {
+            return;
+        };
+        if row.kind != NodeKind::Pane && self.expanded.remove(&row.id) {
+            self.rebuild(Some(&row.id));
+            return;
+        }
+        let parent = match row.kind {
+            NodeKind::Session => None,
+            NodeKind::Window => Some(self.sessions[row.session].id.clone()),
+            NodeKind::Pane => row
+                .window
+                .map(|w| self.sessions[row.session].windows[w].window.id.clone()),
+        };
+        if let Some(parent) = parent {
+            self.select_id(&parent);
+        }
+    }
+
+    /// Expand a collapsed node or collapse an expanded one
+    pub fn toggle(&mut self) {
+        let Some(row) = self.selected_row().cloned() else {
+            return;
+        };
+        if row.kind == NodeKind::Pane {
+            return;
+        }
+        if !self.expanded.remove(&row.id) {
+            self.expanded.insert(row.id.clone());
+        }
+        self.rebuild(Some(&row.id));
+    }
+
+    fn select_id(&mut self, id: &str) {
+        if let Some(i) = self.rows.iter().position(|r| r.id == id) {
+            self.selected.select(Some(i));
+        }
+    }
+
+    /// Flatten the expanded part of the tree into rows and restore the selection
+    fn rebuild(&mut self, selected: Option<&str>) {
+        let previous_index = self.selected.selected();
+        self.rows.clear();
+        for (s, session) in self.sessions.iter().enumerate() {
+            self.rows.push(TreeRow {
+                kind: NodeKind::Session,
+                id: session.id.clone(),
+                session: s,
+                window: None,
+                pane: None,
+            });
+            if !self.expanded.contains(&session.id) {
+                continue;
+            }
+            for (w, window) in session.windows.iter().enumerate() {
+                self.rows.push(TreeRow {
+                    kind: NodeKind::Window,
+                    id: window.window.id.clone(),
+                    session: s,
+                    window: Some(w),
+                    pane: None,
+                });
+                if !self.expanded.contains(&window.window.id) {
+                    continue;
+                }
+                for (p, pane) in window.panes.iter().enumerate() {
+                    self.rows.push(TreeRow {
+                        kind: NodeKind::Pane,
+                        id: pane.id.clone(),
+                        session: s,
+                        window: Some(w),
+                        pane: Some(p),
+                    });
+                }
+            }
+        }
+
+        let index = if self.rows.is_empty() {
+            None
+        } else {
+            selected
+                .and_then(|id| self.rows.iter().position(|r| r.id == id))
+                .or(previous_index)
+                .map(|i| i.min(self.rows.len() - 1))
+                .or(Some(0))
+        };
+        self.selected.select(index);
+    }
+
+    /// Draw the tree; `show_titles` is false when tmux cannot set pane titles
+    pub fn render(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        icons: IconSet,
+        show_titles: bool,
+    ) {
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let session = &self.sessions[row.session];
+                let marker = |id: &str| format!("{} ", icons.expander(self.expanded.contains(id)));
+                let mut spans = Vec::new();
+                let style = match (row.kind, row.window, row.pane) {
+                    (NodeKind::Session, _, _) => {
+                        let attached = session.clients > 0;
+                        spans.push(Span::raw(marker(&session.id)));
+                        spans.push(Span::raw(format!(
+                            "{} {} ({} windows)",
+                            icons.attached(attached),
+                            session.name,
+                            session.windows.len()
+                        )));
+                        if attached {
+                            Style::default()
+                                .fg(theme.attached)
+                                .add_modifier(Modifier::BOLD)
+                        } else {
+                            Style::default().fg(theme.text)
+                        }
+                    }
+                    (NodeKind::Window, Some(w), _) => {
+                        let window = &session.windows[w].window;
+                        spans.push(Span::raw(format!("    {}", marker(&window.id))));
+                        spans.extend(super::alert_badges(&window.alerts, theme));
+                        spans.push(Span::raw(format!(
+                            "{}: {} ({} panes)",
+                            window.index, window.name, window.panes
+                        )));
+                        if window.active {
+                            Style::default().fg(theme.attached)
+                        } else {
+                            Style::default().fg(theme.text)
+                        }
+                    }
+                    (NodeKind::Pane, Some(w), Some(p)) => {
+                        let pane = &session.windows[w].panes[p];
+                        spans.push(Span::raw(format!(
+                            "          {}: {} [{}x{}]",
+                            pane.index, pane.current_command, pane.width, pane.height
+                        )));
+                        if pane.dead {
+                            spans.push(Span::raw(" [dead]"));
+                        }
+                        if pane.piped {
+                            spans.push(Span::styled(
+                                " [logging]",
+                                Style::default().fg(theme.accent),
+                            ));
+                        }
+                        if show_titles && !pane.title.is_empty() {
+                            spans.push(Span::styled(
+                                format!("  {}", pane.title),
+                                Style::default().fg(theme.secondary),
+                            ));
+                        }
+                        if pane.dead {
+                            Style::default().fg(theme.error)
+                        } else if pane.active {
+                            Style::default().fg(theme.attached)
+                        } else {
+                            Style::default().fg(theme.text)
+                        }
+                    }
+                    _ => Style::default(),
+                };
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Session tree ({} sessions)", self.sessions.len())),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        f.render_stateful_widget(list, area, &mut self.selected);
+        render_scrollbar(f, area, icons, self.rows.len(), self.selected.selected());
+    }
+}
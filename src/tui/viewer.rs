@@ -0,0 +1,87 @@
+use super::icons::IconSet;
+use super::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Scrollable popup showing captured command output
+pub(crate) struct OutputView {
+    title: String,
+    lines: Vec<String>,
+    scroll: usize,
+    failed: bool,
+}
+
+impl OutputView {
+    pub fn new(title: String, text: &str, failed: bool) -> Self {
+        let mut lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
+        if lines.is_empty() {
+            lines.push("(no output)".to_string());
+        }
+        Self {
+            title,
+            lines,
+            scroll: 0,
+            failed,
+        }
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.lines.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+
+    pub fn scroll_to_start(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_end(&mut self) {
+        self.scroll = self.lines.len().saturating_sub(1);
+    }
+
+    /// Draw the popup centered over `area`
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme, icons: IconSet) {
+        let width = area.width.saturating_sub(4).max(1);
+        let height = area.height.saturating_sub(4).max(1);
+        let popup = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+
+        let visible = usize::from(height.saturating_sub(2));
+        let text = self
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .take(visible)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let border = if self.failed {
+            theme.error
+        } else {
+            theme.accent
+        };
+        let paragraph = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border))
+                .title(format!(
+                    "{} [{}/{}] ({} scroll, Esc close)",
+                    self.title,
+                    (self.scroll + 1).min(self.lines.len()),
+                    self.lines.len(),
+                    icons.up_down()
+                )),
+        );
+
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+}
@@ -0,0 +1,246 @@
+//! Reusable pieces of the TUI
+
+use super::icons::IconSet;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+use std::fmt;
+use std::ops::Deref;
+
+/// A single line of editable text with a cursor, as typed into a prompt.
+///
+/// Supports the usual readline keys: Left/Right, Home/End (or Ctrl+A/E),
+/// Alt+B/F (or Ctrl+Left/Right) to move by word, Backspace/Delete, Ctrl+W to
+/// delete the word before the cursor and Ctrl+U/K to delete to either end.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    text: String,
+    /// Position in characters, from 0 to the length of the text
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the text, with the cursor at its end
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Take the text out, leaving the input empty
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.text)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Cursor position in characters
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Byte offset of a character position
+    fn byte(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    /// Insert text at the cursor, e.g. when pasting. Line breaks become spaces
+    /// and other control characters are dropped, since prompts are one line.
+    pub fn insert_str(&mut self, text: &str) {
+        let text: String = text
+            .trim_end_matches(['\r', '\n'])
+            .chars()
+            .map(|c| if c == '\n' { ' ' } else { c })
+            .filter(|c| !c.is_control())
+            .collect();
+        let at = self.byte(self.cursor);
+        self.text.insert_str(at, &text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Delete the characters between two positions
+    fn delete(&mut self, from: usize, to: usize) {
+        let (from, to) = (from.min(to), from.max(to));
+        let range = self.byte(from)..self.byte(to);
+        self.text.replace_range(range, "");
+        self.cursor = from;
+    }
+
+    /// Start of the word before the cursor; words are runs of characters
+    /// for which `in_word` holds
+    fn word_start(&self, in_word: impl Fn(char) -> bool) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut i = self.cursor;
+        while i > 0 && !in_word(chars[i - 1]) {
+            i -= 1;
+        }
+        while i > 0 && in_word(chars[i - 1]) {
+            i -= 1;
+        }
+        i
+    }
+
+    /// End of the word after the cursor
+    fn word_end(&self, in_word: impl Fn(char) -> bool) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut i = self.cursor;
+        while i < chars.len() && !in_word(chars[i]) {
+            i += 1;
+        }
+        while i < chars.len() && in_word(chars[i]) {
+            i += 1;
+        }
+        i
+    }
+
+    /// Apply an editing key; returns whether the key was one. Enter, Esc, Tab
+    /// and the like are left to the prompt.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let len = self.len();
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = len,
+            KeyCode::Char('w') if ctrl => {
+                let start = self.word_start(|c| !c.is_whitespace());
+                self.delete(start, self.cursor);
+            }
+            KeyCode::Char('u') if ctrl => self.delete(0, self.cursor),
+            KeyCode::Char('k') if ctrl => self.delete(self.cursor, len),
+            KeyCode::Char('b') if alt => self.cursor = self.word_start(char::is_alphanumeric),
+            KeyCode::Char('f') if alt => self.cursor = self.word_end(char::is_alphanumeric),
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => {
+                let at = self.byte(self.cursor);
+                self.text.insert(at, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => self.delete(self.cursor - 1, self.cursor),
+            KeyCode::Delete if self.cursor < len => self.delete(self.cursor, self.cursor + 1),
+            KeyCode::Backspace | KeyCode::Delete => {}
+            KeyCode::Left if ctrl => self.cursor = self.word_start(char::is_alphanumeric),
+            KeyCode::Right if ctrl => self.cursor = self.word_end(char::is_alphanumeric),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(len),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = len,
+            _ => return false,
+        }
+        true
+    }
+
+    /// The prompt, the text with the character under the cursor reversed (a blank
+    /// at the end), and a note after the text
+    pub fn line(&self, prompt: String, note: String, style: Style) -> Line<'static> {
+        let (before, after) = self.text.split_at(self.byte(self.cursor));
+        let mut rest = after.chars();
+        let under = rest.next().map_or(" ".to_string(), String::from);
+        Line::from(vec![
+            Span::styled(format!("{}{}", prompt, before), style),
+            Span::styled(under, style.add_modifier(Modifier::REVERSED)),
+            Span::styled(format!("{}{}", rest.as_str(), note), style),
+        ])
+    }
+}
+
+impl Deref for TextInput {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for TextInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// A move of the selection in a list by more than one entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jump {
+    First,
+    Last,
+    /// To the entry at this index, or the last one
+    To(usize),
+    /// Up by this many entries, e.g. the number of rows the list shows
+    Up(usize),
+    Down(usize),
+}
+
+impl Jump {
+    /// Index selected after the jump in a list of `len` entries. Unlike moving
+    /// up and down one entry, jumps stop at either end instead of wrapping around.
+    pub fn apply(self, selected: Option<usize>, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let current = selected.unwrap_or(0).min(len - 1);
+        Some(match self {
+            Jump::First => 0,
+            Jump::Last => len - 1,
+            Jump::To(index) => index.min(len - 1),
+            Jump::Up(rows) => current.saturating_sub(rows.max(1)),
+            Jump::Down(rows) => current.saturating_add(rows.max(1)).min(len - 1),
+        })
+    }
+}
+
+/// Scrollbar on the right border of a bordered list, drawn only once the list has
+/// more entries than fit into `area`
+pub fn render_scrollbar(
+    f: &mut Frame,
+    area: Rect,
+    icons: IconSet,
+    len: usize,
+    selected: Option<usize>,
+) {
+    let rows = usize::from(area.height.saturating_sub(2));
+    if len <= rows {
+        return;
+    }
+    let (thumb, track) = if icons == IconSet::Ascii {
+        ("#", "|")
+    } else {
+        ("█", "│")
+    };
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .thumb_symbol(thumb)
+        .track_symbol(Some(track));
+    let mut state = ScrollbarState::new(len).position(selected.unwrap_or(0));
+    f.render_stateful_widget(
+        scrollbar,
+        area.inner(&Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut state,
+    );
+}
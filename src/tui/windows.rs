@@ -0,0 +1,146 @@
+use super::icons::IconSet;
+use super::theme::Theme;
+use super::widgets::{render_scrollbar, Jump};
+use super::SessionRef;
+use crate::tmux::{TmuxPane, TmuxWindow};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+use std::collections::HashMap;
+
+/// Windows of a single session, shown after drilling down from the session list
+pub(crate) struct WindowView {
+    pub session: SessionRef,
+    pub windows: Vec<TmuxWindow>,
+    /// Panes of each window, keyed by window id
+    pub panes: HashMap<String, Vec<TmuxPane>>,
+    pub selected: ListState,
+}
+
+impl WindowView {
+    pub fn new(session: SessionRef) -> Self {
+        Self {
+            session,
+            windows: Vec::new(),
+            panes: HashMap::new(),
+            selected: ListState::default(),
+        }
+    }
+
+    /// Replace the window list, keeping the selection on the same window where possible
+    pub fn set_windows(&mut self, windows: Vec<TmuxWindow>, panes: HashMap<String, Vec<TmuxPane>>) {
+        let previous = self.selected_window().map(|w| w.id.clone());
+        self.windows = windows;
+        self.panes = panes;
+
+        let index = previous
+            .and_then(|id| self.windows.iter().position(|w| w.id == id))
+            .or_else(|| self.windows.iter().position(|w| w.active))
+            .or((!self.windows.is_empty()).then_some(0));
+        self.selected.select(index);
+    }
+
+    pub fn selected_window(&self) -> Option<&TmuxWindow> {
+        self.selected.selected().and_then(|i| self.windows.get(i))
+    }
+
+    pub fn select_next(&mut self) {
+        let i = match self.selected.selected() {
+            Some(i) if i + 1 < self.windows.len() => i + 1,
+            _ => 0,
+        };
+        self.selected.select(Some(i));
+    }
+
+    pub fn select_previous(&mut self) {
+        let i = match self.selected.selected() {
+            Some(0) | None => self.windows.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.selected.select(Some(i));
+    }
+
+    pub fn jump(&mut self, jump: Jump) {
+        self.selected
+            .select(jump.apply(self.selected.selected(), self.windows.len()));
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme, icons: IconSet) {
+        let items: Vec<ListItem> = self
+            .windows
+            .iter()
+            .map(|window| {
+                let active_indicator = icons.attached(window.active);
+                let style = if window.active {
+                    Style::default()
+                        .fg(theme.attached)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+
+                let mut spans = vec![Span::raw(format!("{} ", active_indicator))];
+                spans.extend(super::alert_badges(&window.alerts, theme));
+                spans.push(Span::raw(format!(
+                    "{}: {} ({} panes)",
+                    window.index, window.name, window.panes
+                )));
+                if window.synchronized {
+                    spans.push(Span::styled(
+                        "  [sync]",
+                        Style::default()
+                            .fg(theme.alert)
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                }
+                if window.zoomed {
+                    spans.push(Span::styled(
+                        "  [zoomed]",
+                        Style::default().fg(theme.accent),
+                    ));
+                }
+                if window.linked {
+                    spans.push(Span::styled(
+                        "  [linked]",
+                        Style::default().fg(theme.accent),
+                    ));
+                }
+                if window.automatic_rename {
+                    spans.push(Span::styled(
+                        "  [auto-rename]",
+                        Style::default().fg(theme.dim),
+                    ));
+                }
+                if let Some(panes) = self.panes.get(&window.id) {
+                    let commands: Vec<&str> =
+                        panes.iter().map(|p| p.current_command.as_str()).collect();
+                    spans.push(Span::styled(
+                        format!("  {}", commands.join(", ")),
+                        Style::default().fg(theme.secondary),
+                    ));
+                }
+                ListItem::new(Line::from(spans)).style(style)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Windows of '{}' ({})",
+                self.session,
+                self.windows.len()
+            )))
+            .highlight_style(
+                Style::default()
+                    .bg(theme.selected_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+
+        f.render_stateful_widget(list, area, &mut self.selected);
+        render_scrollbar(f, area, icons, self.windows.len(), self.selected.selected());
+    }
+}
@@ -0,0 +1,366 @@
+//! Local usage tracking and reports (`tmux-ui report`)
+//!
+//! Actions taken through tmux-ui are appended to a tab-separated log under
+//! `~/.local/share/tmux-ui/usage.log`. Nothing leaves the machine.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Attach intervals longer than this are capped, so a session left attached
+/// overnight does not dominate the report
+const MAX_ATTACH_INTERVAL: u64 = 4 * 60 * 60;
+
+/// Kind of a tracked action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Created,
+    Killed,
+    Attached,
+    Detached,
+    WindowCreated,
+    Renamed,
+}
+
+impl EventKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::Killed => "killed",
+            EventKind::Attached => "attached",
+            EventKind::Detached => "detached",
+            EventKind::WindowCreated => "window-created",
+            EventKind::Renamed => "renamed",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "created" => EventKind::Created,
+            "killed" => EventKind::Killed,
+            "attached" => EventKind::Attached,
+            "detached" => EventKind::Detached,
+            "window-created" => EventKind::WindowCreated,
+            "renamed" => EventKind::Renamed,
+            _ => return None,
+        })
+    }
+}
+
+/// A single line of the usage log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageEvent {
+    /// Unix time in seconds
+    pub timestamp: u64,
+    pub kind: EventKind,
+    pub session: String,
+}
+
+impl UsageEvent {
+    fn to_line(&self) -> String {
+        // Tabs and newlines would break the line format
+        let session: String = self
+            .session
+            .chars()
+            .map(|c| if c == '\t' || c == '\n' { ' ' } else { c })
+            .collect();
+        format!("{}\t{}\t{}", self.timestamp, self.kind.as_str(), session)
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(3, '\t');
+        Some(Self {
+            timestamp: parts.next()?.parse().ok()?,
+            kind: EventKind::parse(parts.next()?)?,
+            session: parts.next()?.to_string(),
+        })
+    }
+}
+
+/// Current unix time in seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Append-only log of tracked actions
+#[derive(Debug, Clone)]
+pub struct UsageLog {
+    path: PathBuf,
+}
+
+impl UsageLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Default log location, honoring `XDG_DATA_HOME`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?)
+                .join(".local")
+                .join("share"),
+        };
+        Some(base.join("tmux-ui").join("usage.log"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record an action now. Tracking is best effort and never fails the action itself.
+    pub fn record(&self, kind: EventKind, session: &str) {
+        let event = UsageEvent {
+            timestamp: now(),
+            kind,
+            session: session.to_string(),
+        };
+        let _ = self.append(&event);
+    }
+
+    fn append(&self, event: &UsageEvent) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", event.to_line())?;
+        Ok(())
+    }
+
+    /// Read all events, skipping malformed lines. A missing log has no events.
+    pub fn read(&self) -> Result<Vec<UsageEvent>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read usage log {}", self.path.display()))?;
+        Ok(contents.lines().filter_map(UsageEvent::from_line).collect())
+    }
+}
+
+/// Sessions most recently attached to, latest first and without duplicates
+pub fn recent_sessions(events: &[UsageEvent], limit: usize) -> Vec<String> {
+    let mut sessions: Vec<String> = Vec::new();
+    for event in events.iter().rev() {
+        if sessions.len() == limit {
+            break;
+        }
+        if event.kind == EventKind::Attached && !sessions.contains(&event.session) {
+            sessions.push(event.session.clone());
+        }
+    }
+    sessions
+}
+
+/// A relative time span such as `7d`, `12h`, `30m` or `2w`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Since(pub u64);
+
+impl FromStr for Since {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        let number: u64 = number
+            .parse()
+            .with_context(|| format!("Invalid duration '{}' (expected e.g. 7d)", s))?;
+        let seconds = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "" | "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => anyhow::bail!(
+                "Unknown duration unit '{}' (expected s, m, h, d or w)",
+                unit
+            ),
+        };
+        let seconds = number
+            .checked_mul(seconds)
+            .with_context(|| format!("Duration '{}' is too long", s))?;
+        Ok(Since(seconds))
+    }
+}
+
+/// Output format of a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    Markdown,
+    Json,
+}
+
+/// Usage of a single session ("project") over the report period
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProjectUsage {
+    pub session: String,
+    pub attached_seconds: u64,
+    pub attaches: usize,
+    pub windows_created: usize,
+}
+
+/// Summary of tracked usage between two points in time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UsageReport {
+    pub since: u64,
+    pub until: u64,
+    pub sessions_created: usize,
+    pub sessions_killed: usize,
+    /// Sessions ordered by attached time, most used first
+    pub projects: Vec<ProjectUsage>,
+}
+
+impl UsageReport {
+    /// Summarize events in `[since, until]`
+    pub fn build(events: &[UsageEvent], since: u64, until: u64) -> Self {
+        let mut events: Vec<&UsageEvent> = events
+            .iter()
+            .filter(|e| e.timestamp >= since && e.timestamp <= until)
+            .collect();
+        events.sort_by_key(|e| e.timestamp);
+
+        let mut projects: BTreeMap<&str, ProjectUsage> = BTreeMap::new();
+        let mut sessions_created = 0;
+        let mut sessions_killed = 0;
+
+        for (i, event) in events.iter().enumerate() {
+            let project = projects
+                .entry(event.session.as_str())
+                .or_insert_with(|| ProjectUsage {
+                    session: event.session.clone(),
+                    ..Default::default()
+                });
+
+            match event.kind {
+                EventKind::Created => sessions_created += 1,
+                EventKind::Killed => sessions_killed += 1,
+                EventKind::WindowCreated => project.windows_created += 1,
+                EventKind::Attached => {
+                    // Attached until the next attach, detach or kill of any session
+                    let end = events[i + 1..]
+                        .iter()
+                        .find(|e| {
+                            matches!(
+                                e.kind,
+                                EventKind::Attached | EventKind::Detached | EventKind::Killed
+                            )
+                        })
+                        .map(|e| e.timestamp)
+                        .unwrap_or(until);
+                    project.attaches += 1;
+                    project.attached_seconds +=
+                        end.saturating_sub(event.timestamp).min(MAX_ATTACH_INTERVAL);
+                }
+                EventKind::Detached | EventKind::Renamed => {}
+            }
+        }
+
+        let mut projects: Vec<ProjectUsage> = projects.into_values().collect();
+        projects.sort_by(|a, b| {
+            b.attached_seconds
+                .cmp(&a.attached_seconds)
+                .then_with(|| b.windows_created.cmp(&a.windows_created))
+                .then_with(|| a.session.cmp(&b.session))
+        });
+
+        Self {
+            since,
+            until,
+            sessions_created,
+            sessions_killed,
+            projects,
+        }
+    }
+
+    /// Render the report in the requested format
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        Ok(match format {
+            ReportFormat::Text => self.to_string(),
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = String::from("# tmux-ui usage report\n\n");
+        out.push_str(&format!(
+            "Last {}: **{}** sessions created, **{}** sessions killed.\n\n",
+            format_duration(self.until.saturating_sub(self.since)),
+            self.sessions_created,
+            self.sessions_killed
+        ));
+        out.push_str("| Session | Attached | Attaches | Windows created |\n");
+        out.push_str("|---|---:|---:|---:|\n");
+        for project in &self.projects {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                project.session.replace('|', "\\|"),
+                format_duration(project.attached_seconds),
+                project.attaches,
+                project.windows_created
+            ));
+        }
+        out
+    }
+}
+
+impl fmt::Display for UsageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Usage over the last {}:",
+            format_duration(self.until.saturating_sub(self.since))
+        )?;
+        writeln!(f, "  Sessions created: {}", self.sessions_created)?;
+        writeln!(f, "  Sessions killed:  {}", self.sessions_killed)?;
+        if self.projects.is_empty() {
+            return writeln!(f, "\nNo session activity recorded.");
+        }
+        writeln!(f, "\nTime attached per session:")?;
+        let width = self
+            .projects
+            .iter()
+            .map(|p| p.session.chars().count())
+            .max()
+            .unwrap_or(0);
+        for project in &self.projects {
+            writeln!(
+                f,
+                "  {:width$}  {:>8}  {} attach(es), {} window(s) created",
+                project.session,
+                format_duration(project.attached_seconds),
+                project.attaches,
+                project.windows_created,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Format seconds as a short human-readable duration, e.g. `3h 12m`
+pub fn format_duration(seconds: u64) -> String {
+    let days = seconds / 86_400;
+    let hours = (seconds % 86_400) / 3_600;
+    let minutes = (seconds % 3_600) / 60;
+    match (days, hours, minutes) {
+        (0, 0, m) => format!("{}m", m),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
@@ -0,0 +1,128 @@
+//! The web page of `tmux-ui serve`, built with the `web` feature: a session and
+//! window list with the TUI's actions, for managing sessions from another device.
+//!
+//! The page is compiled into the binary and served at `/`. It talks to `/ws` over
+//! a WebSocket, sending the [`Daemon`] requests as text messages and getting their
+//! answers and the daemon's `sessions` events back the same way.
+
+use crate::daemon::Daemon;
+use crate::serve::HttpRequest;
+use crate::tmux::TmuxBackend;
+use futures_util::{SinkExt, StreamExt};
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{
+    HeaderValue, CONNECTION, CONTENT_TYPE, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY,
+    SEC_WEBSOCKET_VERSION, UPGRADE,
+};
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::{Role, WebSocketConfig};
+use tokio_tungstenite::tungstenite::{Error, Message};
+use tokio_tungstenite::WebSocketStream;
+
+/// The page, served at `/`
+pub const INDEX_HTML: &str = include_str!("index.html");
+
+/// Path of the WebSocket
+pub const SOCKET_PATH: &str = "/ws";
+
+/// Largest message accepted from the page; requests are a line of JSON
+const MAX_MESSAGE: usize = 1024 * 1024;
+
+/// Whether the request asks to open the page's WebSocket
+pub fn is_upgrade(request: &HttpRequest) -> bool {
+    request.path == SOCKET_PATH
+        && request
+            .header("upgrade")
+            .is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+}
+
+/// Answer an upgrade request, then pass messages between the browser and the
+/// daemon on the upgraded connection until either side closes
+pub fn upgrade<C: TmuxBackend>(
+    daemon: Arc<Daemon<C>>,
+    request: &mut Request<Incoming>,
+) -> Response<Full<Bytes>> {
+    let headers = request.headers();
+    let key = headers.get(SEC_WEBSOCKET_KEY).filter(|_| {
+        headers
+            .get(SEC_WEBSOCKET_VERSION)
+            .is_some_and(|v| v == "13")
+    });
+    let Some(key) = key else {
+        let mut response = Response::new(Full::from("WebSocket version 13 with a key is required"));
+        *response.status_mut() = StatusCode::BAD_REQUEST;
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        return response;
+    };
+    let accept = derive_accept_key(key.as_bytes());
+
+    let upgraded = hyper::upgrade::on(request);
+    tokio::spawn(async move {
+        let io = match upgraded.await {
+            Ok(upgraded) => TokioIo::new(upgraded),
+            Err(e) => {
+                tracing::debug!("Web socket upgrade failed: {}", e);
+                return;
+            }
+        };
+        let config = WebSocketConfig::default().max_message_size(Some(MAX_MESSAGE));
+        let socket = WebSocketStream::from_raw_socket(io, Role::Server, Some(config)).await;
+        if let Err(e) = serve_socket(daemon, socket).await {
+            tracing::debug!("Web socket failed: {}", e);
+        }
+    });
+
+    let mut response = Response::new(Full::default());
+    *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+    let headers = response.headers_mut();
+    headers.insert(CONNECTION, HeaderValue::from_static("Upgrade"));
+    headers.insert(UPGRADE, HeaderValue::from_static("websocket"));
+    if let Ok(accept) = HeaderValue::from_str(&accept) {
+        headers.insert(SEC_WEBSOCKET_ACCEPT, accept);
+    }
+    response
+}
+
+/// Pass messages between the browser and the daemon until either side closes.
+/// Pings are answered, and closes acknowledged, by the socket itself.
+async fn serve_socket<C, S>(
+    daemon: Arc<Daemon<C>>,
+    mut socket: WebSocketStream<S>,
+) -> Result<(), Error>
+where
+    C: TmuxBackend,
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut updates = daemon.subscribe();
+    let sessions = serde_json::json!({ "event": "sessions", "sessions": daemon.sessions() });
+    socket.send(Message::text(sessions.to_string())).await?;
+    loop {
+        tokio::select! {
+            message = socket.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let (response, _) = daemon.handle(&text).await;
+                    socket.send(Message::text(response.to_string())).await?;
+                }
+                // The socket queued its reply to the close; send it
+                Some(Ok(Message::Close(_))) => return socket.flush().await,
+                None => return Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+            },
+            event = updates.recv() => match event {
+                Ok(event) => socket.send(Message::text(event.trim_end())).await?,
+                // The next event has the complete list again
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return socket.close(None).await,
+            },
+        }
+    }
+}
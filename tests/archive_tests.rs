@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::PathBuf;
+use tmux_ui::archive::Archive;
+use tmux_ui::tmux::{PaneSnapshot, SessionSnapshot, WindowSnapshot};
+
+fn session(name: &str) -> SessionSnapshot {
+    SessionSnapshot {
+        name: name.to_string(),
+        tags: vec!["work".to_string()],
+        windows: vec![WindowSnapshot {
+            index: 0,
+            name: "editor".to_string(),
+            layout: "b25d,80x24,0,0,1".to_string(),
+            active: true,
+            automatic_rename: false,
+            panes: vec![PaneSnapshot {
+                index: 0,
+                active: true,
+                cwd: PathBuf::from("/tmp"),
+                command: "vim".to_string(),
+            }],
+        }],
+    }
+}
+
+#[test]
+fn test_archive_round_trip() {
+    let dir = std::env::temp_dir().join(format!("tmux-ui-test-archive-{}", std::process::id()));
+    let archive = Archive::new(&dir);
+
+    // Nothing archived yet, and no directory either
+    assert!(archive.list().unwrap().is_empty());
+
+    let first = archive.add(None, session("api/v2")).unwrap();
+    let second = archive.add(Some("remote"), session("api/v2")).unwrap();
+    fs::write(dir.join("broken.json"), "{").unwrap();
+    let listed = archive.list();
+    archive.remove(&first).unwrap();
+    let after = archive.list();
+    let _ = fs::remove_dir_all(&dir);
+
+    // Names are made safe for files, and archiving twice keeps both
+    assert!(first
+        .path
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .starts_with("api_v2-"));
+    assert_ne!(first.path, second.path);
+
+    let listed = listed.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert!(listed.contains(&first));
+    assert!(listed.contains(&second));
+    assert_eq!(after.unwrap(), vec![second]);
+}
@@ -0,0 +1,388 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Run the tmux-ui binary with config and usage log kept out of the real home directory
+fn tmux_ui(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tmux-ui"))
+        .args(args)
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env_remove("TMUX")
+        .output()
+        .expect("failed to run tmux-ui")
+}
+
+#[test]
+fn test_socket_flag_selects_the_server() {
+    let socket = format!("tmux-ui-test-cli-{}", std::process::id());
+    let home = std::env::temp_dir().join(&socket);
+
+    let output = tmux_ui(&home, &["-L", &socket, "new", "cli-socket"]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = tmux_ui(&home, &["--socket", &socket, "list", "--format", "json"]);
+    let sessions: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sessions[0]["name"], "cli-socket");
+
+    // The session lives on the named server, not on the default one
+    let status = Command::new("tmux")
+        .args(["-L", &socket, "has-session", "-t", "=cli-socket"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = tmux_ui(&home, &["-L", &socket, "-S", "/tmp/other", "list"]);
+    assert!(!output.status.success());
+
+    let output = tmux_ui(&home, &["kill-server", "-L", &socket]);
+    assert!(output.status.success(), "{:?}", output);
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_dry_run_and_verbose_print_tmux_commands() {
+    let socket = format!("tmux-ui-test-dry-{}", std::process::id());
+    let home = std::env::temp_dir().join(&socket);
+
+    // A dry run prints the command and leaves the server alone
+    let output = tmux_ui(&home, &["-L", &socket, "--dry-run", "new", "dry"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!(
+            "[dry-run] tmux -u -L {} new-session -d -s dry",
+            socket
+        )),
+        "{}",
+        stderr
+    );
+    let status = Command::new("tmux")
+        .args(["-L", &socket, "has-session", "-t", "=dry"])
+        .status()
+        .unwrap();
+    assert!(!status.success());
+
+    // Verbose runs the command and prints it as well
+    let output = tmux_ui(&home, &["-L", &socket, "-v", "new", "loud"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("+ tmux -u -L"), "{}", stderr);
+    assert!(stderr.contains("new-session -d -s loud"), "{}", stderr);
+
+    // Queries still run in a dry run, so the session is found
+    let output = tmux_ui(&home, &["-L", &socket, "--dry-run", "kill", "loud"]);
+    assert!(output.status.success(), "{:?}", output);
+    let output = tmux_ui(&home, &["-L", &socket, "list"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("loud"));
+
+    // The TUI has no terminal to print to
+    let output = tmux_ui(&home, &["-L", &socket, "--dry-run"]);
+    assert!(!output.status.success());
+
+    let output = tmux_ui(&home, &["kill-server", "-L", &socket]);
+    assert!(output.status.success(), "{:?}", output);
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_log_level_writes_tmux_commands_to_the_log() {
+    let socket = format!("tmux-ui-test-log-{}", std::process::id());
+    let home = std::env::temp_dir().join(&socket);
+    let log = home.join("cache/tmux-ui/log");
+
+    let output = tmux_ui(
+        &home,
+        &["-L", &socket, "--log-level", "debug", "new", "logged"],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let content = std::fs::read_to_string(&log).unwrap();
+    assert!(content.contains("new-session -d -s logged"), "{}", content);
+
+    // Failures are logged with their stderr
+    let output = tmux_ui(
+        &home,
+        &["-L", &socket, "--log-level", "warn", "kill", "missing"],
+    );
+    assert!(!output.status.success());
+    let content = std::fs::read_to_string(&log).unwrap();
+    assert!(content.contains("WARN"), "{}", content);
+
+    let output = tmux_ui(&home, &["-L", &socket, "--log-level", "loud", "list"]);
+    assert!(!output.status.success());
+
+    let output = tmux_ui(&home, &["kill-server", "-L", &socket]);
+    assert!(output.status.success(), "{:?}", output);
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_tui_explains_a_missing_tmux() {
+    let home = std::env::temp_dir().join(format!("tmux-ui-test-notmux-{}", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_tmux-ui"))
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_DATA_HOME", home.join("data"))
+        .env("PATH", home.join("bin"))
+        .env_remove("TMUX")
+        .output()
+        .expect("failed to run tmux-ui");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("tmux is not installed"), "{}", stderr);
+}
+
+#[test]
+fn test_completions_offer_session_names() {
+    let home = std::env::temp_dir().join(format!("tmux-ui-test-complete-{}", std::process::id()));
+    let output = tmux_ui(&home, &["completions", "bash"]);
+    assert!(output.status.success(), "{:?}", output);
+    let script = String::from_utf8_lossy(&output.stdout);
+    assert!(script.contains("complete -o nospace"), "{}", script);
+    assert!(script.contains("COMPLETE=\"bash\""), "{}", script);
+
+    // The scripts call back with COMPLETE set; session names come from the default
+    // server, which lives in TMUX_TMPDIR
+    let tmpdir = home.join("tmux");
+    std::fs::create_dir_all(&tmpdir).unwrap();
+    let tmux = |args: &[&str]| {
+        Command::new("tmux")
+            .args(args)
+            .env("TMUX_TMPDIR", &tmpdir)
+            .env_remove("TMUX")
+            .status()
+            .unwrap()
+    };
+    assert!(tmux(&["new-session", "-d", "-s", "completed"]).success());
+    let output = Command::new(env!("CARGO_BIN_EXE_tmux-ui"))
+        .args(["--", "tmux-ui", "kill", "comp"])
+        .env("COMPLETE", "fish")
+        .env("TMUX_TMPDIR", &tmpdir)
+        .env_remove("TMUX")
+        .output()
+        .unwrap();
+    let candidates = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        candidates.lines().any(|l| l.starts_with("completed\t")),
+        "{}",
+        candidates
+    );
+
+    // With -L, before or after the subcommand, the sessions of that server
+    assert!(tmux(&["-L", "other", "new-session", "-d", "-s", "compiled"]).success());
+    for words in [
+        ["tmux-ui", "-L", "other", "kill", "comp"],
+        ["tmux-ui", "kill", "-L", "other", "comp"],
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_tmux-ui"))
+            .arg("--")
+            .args(words)
+            .env("COMPLETE", "fish")
+            .env("TMUX_TMPDIR", &tmpdir)
+            .env_remove("TMUX")
+            .output()
+            .unwrap();
+        let candidates = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            candidates.lines().any(|l| l.starts_with("compiled\t")),
+            "{:?}: {}",
+            words,
+            candidates
+        );
+        assert!(!candidates.contains("completed"), "{}", candidates);
+    }
+
+    tmux(&["-L", "other", "kill-server"]);
+    tmux(&["kill-server"]);
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_window_and_pane_subcommands() {
+    let socket = format!("tmux-ui-test-windows-{}", std::process::id());
+    let home = std::env::temp_dir().join(&socket);
+    let run = |args: &[&str]| {
+        let output = tmux_ui(&home, &[&["-L", &socket], args].concat());
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    run(&["new", "work"]);
+    run(&["new-window", "work", "--name", "editor"]);
+    let split = run(&[
+        "split",
+        "work:editor",
+        "-H",
+        "--size",
+        "30",
+        "--format",
+        "json",
+    ]);
+    let split: serde_json::Value = serde_json::from_str(&split).unwrap();
+    let pane = split["pane"].as_str().unwrap().to_string();
+    assert!(pane.starts_with('%'), "{}", pane);
+
+    let windows = run(&["windows", "work"]);
+    assert!(windows.starts_with("   INDEX  NAME"), "{}", windows);
+    assert!(
+        windows.lines().any(|l| l.contains(" editor ")),
+        "{}",
+        windows
+    );
+
+    let panes: serde_json::Value =
+        serde_json::from_str(&run(&["panes", "work:editor", "--format", "json"])).unwrap();
+    assert_eq!(panes.as_array().unwrap().len(), 2);
+    assert_eq!(panes[1]["id"], pane.as_str());
+
+    run(&["kill-window", "work:editor"]);
+    let windows: serde_json::Value =
+        serde_json::from_str(&run(&["windows", "work", "--format", "json"])).unwrap();
+    assert_eq!(windows.as_array().unwrap().len(), 1);
+
+    let output = tmux_ui(&home, &["-L", &socket, "kill-window", "work:missing"]);
+    assert!(!output.status.success());
+
+    run(&["kill-server"]);
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_rename_subcommands() {
+    let socket = format!("tmux-ui-test-rename-{}", std::process::id());
+    let home = std::env::temp_dir().join(&socket);
+    let run = |args: &[&str]| tmux_ui(&home, &[&["-L", &socket], args].concat());
+
+    assert!(run(&["new", "old"]).status.success());
+    let output = run(&["rename", "old", "new"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("renamed to 'new'"));
+
+    let output = run(&["rename-window", "new:0", "editor", "--format", "json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let windows: serde_json::Value =
+        serde_json::from_slice(&run(&["windows", "new", "--format", "json"]).stdout).unwrap();
+    assert_eq!(windows[0]["name"], "editor");
+
+    let output = run(&["rename-window", "new:0", "api:v1"]);
+    assert_eq!(output.status.code(), Some(65));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot contain"));
+    assert!(!run(&["rename-window", "new:0", " "]).status.success());
+
+    assert!(!run(&["rename", "old", "other"]).status.success());
+
+    assert!(run(&["kill-server"]).status.success());
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_go_creates_missing_sessions_only() {
+    let socket = format!("tmux-ui-test-go-{}", std::process::id());
+    let home = std::env::temp_dir().join(&socket);
+    let run = |args: &[&str]| {
+        let output = tmux_ui(
+            &home,
+            &[&["-L", &socket, "--format", "json"], args].concat(),
+        );
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    assert_eq!(run(&["go", "work", "--detached"])["created"], true);
+    assert_eq!(run(&["go", "work", "--detached"])["created"], false);
+
+    // A template gives the new session its windows, under the name asked for
+    std::fs::create_dir_all(&home).unwrap();
+    let template = home.join("web.toml");
+    std::fs::write(
+        &template,
+        "[[windows]]\nname = \"editor\"\n\n[[windows]]\nname = \"server\"\n",
+    )
+    .unwrap();
+    let template = template.to_string_lossy().into_owned();
+    let result = run(&["go", "web", "--template", &template, "--detached"]);
+    assert_eq!(result["created"], true);
+    let windows = run(&["windows", "web"]);
+    assert_eq!(windows[1]["name"], "server");
+
+    let output = tmux_ui(&home, &["-L", &socket, "kill-server"]);
+    assert!(output.status.success(), "{:?}", output);
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_exit_codes_and_json_errors() {
+    let socket = format!("tmux-ui-test-exit-{}", std::process::id());
+    let home = std::env::temp_dir().join(&socket);
+    let run = |args: &[&str]| tmux_ui(&home, &[&["-L", &socket], args].concat());
+
+    // Quiet runs print nothing on success
+    let output = run(&["new", "work", "--quiet"]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert!(output.stdout.is_empty());
+
+    assert_eq!(run(&["new", "work"]).status.code(), Some(3));
+    assert_eq!(run(&["kill", "missing"]).status.code(), Some(2));
+    assert_eq!(run(&["tag", "missing"]).status.code(), Some(2));
+    assert_eq!(run(&["windows", "missing"]).status.code(), Some(2));
+    assert_eq!(run(&["panes", "missing"]).status.code(), Some(2));
+    assert_eq!(run(&["--bogus"]).status.code(), Some(64));
+    let output = run(&["list", "--format", "markdown"]);
+    assert_eq!(output.status.code(), Some(64));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("report"));
+
+    let output = run(&["kill", "missing", "--format", "json", "-q"]);
+    assert_eq!(output.status.code(), Some(2));
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["kind"], "session_not_found");
+    assert_eq!(error["exit_code"], 2);
+    assert!(error["error"].as_str().unwrap().contains("missing"));
+
+    assert!(run(&["kill-server"]).status.success());
+    assert_eq!(run(&["kill", "work"]).status.code(), Some(4));
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_install_keybinding_prints_or_appends() {
+    let home = std::env::temp_dir().join(format!("tmux-ui-test-keybinding-{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    let conf = home.join("tmux.conf");
+
+    let output = tmux_ui(&home, &["install-keybinding", "--size", "70%"]);
+    assert!(output.status.success(), "{:?}", output);
+    let line = String::from_utf8(output.stdout).unwrap();
+    assert!(line.starts_with("bind-key S run-shell -b \""));
+    assert!(line.trim_end().ends_with("--popup=70%x70%\""));
+    assert!(!conf.exists());
+
+    let file = conf.to_str().unwrap();
+    let output = tmux_ui(
+        &home,
+        &[
+            "install-keybinding",
+            "--size",
+            "70%",
+            "--append",
+            "-y",
+            "--file",
+            file,
+        ],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    assert!(std::fs::read_to_string(&conf)
+        .unwrap()
+        .contains(line.trim_end()));
+
+    // Without --yes, anything but yes keeps the file as it is
+    let output = Command::new(env!("CARGO_BIN_EXE_tmux-ui"))
+        .args(["install-keybinding", "--window", "--append", "--file", file])
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(!std::fs::read_to_string(&conf)
+        .unwrap()
+        .contains("new-window"));
+    let _ = std::fs::remove_dir_all(&home);
+}
@@ -0,0 +1,18 @@
+use tmux_ui::clipboard::{base64, osc52};
+
+#[test]
+fn test_base64_padding() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64("é\n".as_bytes()), "w6kK");
+}
+
+#[test]
+fn test_osc52_sequence() {
+    assert_eq!(osc52("hi", false), "\x1b]52;c;aGk=\x07");
+    // tmux passes DCS sequences on with their escape characters doubled
+    assert_eq!(osc52("hi", true), "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\");
+}
@@ -0,0 +1,228 @@
+use chrono::TimeDelta;
+use std::time::Duration;
+use tmux_ui::config::Config;
+use tmux_ui::tmux::Socket;
+
+#[test]
+fn test_empty_config_uses_defaults() {
+    let config = Config::parse("").unwrap();
+    assert!(config.columns.is_empty());
+    assert!(config.split_inherit_cwd);
+    assert!(!config.lock_window_names);
+}
+
+#[test]
+fn test_parse_custom_columns() {
+    let config = Config::parse(
+        r##"
+[[columns]]
+header = "cmd"
+format = "#{pane_current_command}"
+
+[[columns]]
+header = "path"
+format = "#{pane_current_path}"
+width = 20
+"##,
+    )
+    .unwrap();
+
+    assert_eq!(config.columns.len(), 2);
+    assert_eq!(config.columns[0].header, "cmd");
+    assert_eq!(config.columns[1].width, Some(20));
+    assert_eq!(
+        config.column_formats(),
+        vec!["#{pane_current_command}", "#{pane_current_path}"]
+    );
+}
+
+#[test]
+fn test_parse_table_columns() {
+    use tmux_ui::tui::table::SessionColumn;
+
+    assert_eq!(
+        Config::default().table_columns().unwrap().len(),
+        SessionColumn::ALL.len()
+    );
+    let config = Config::parse(r#"table_columns = ["name", "Panes", "activity"]"#).unwrap();
+    assert_eq!(
+        config.table_columns().unwrap(),
+        vec![
+            SessionColumn::Name,
+            SessionColumn::Panes,
+            SessionColumn::Activity
+        ]
+    );
+    assert!(Config::parse(r#"table_columns = ["size"]"#).is_err());
+    assert!(Config::parse("table_columns = []").is_err());
+}
+
+#[test]
+fn test_unknown_config_keys_are_rejected() {
+    assert!(Config::parse("colums = []").is_err());
+}
+
+#[test]
+fn test_parse_servers() {
+    let config = Config::parse(
+        r#"
+[[servers]]
+name = "work"
+socket_name = "work"
+
+[[servers]]
+name = "shared"
+socket_path = "/tmp/shared"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.servers.len(), 2);
+    assert_eq!(
+        config.servers[0].socket().unwrap(),
+        Socket::Name("work".to_string())
+    );
+    assert_eq!(
+        config.servers[1].socket().unwrap(),
+        Socket::Path("/tmp/shared".into())
+    );
+}
+
+#[test]
+fn test_server_with_both_sockets_is_rejected() {
+    let result = Config::parse(
+        r#"
+[[servers]]
+name = "bad"
+socket_name = "a"
+socket_path = "/tmp/b"
+"#,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_parse_terminal() {
+    let config = Config::parse(r#"terminal = "wezterm start --""#).unwrap();
+    assert_eq!(
+        config.terminal_command().as_deref(),
+        Some("wezterm start --")
+    );
+    assert!(Config::parse(r#"terminal = " ""#).is_err());
+}
+
+#[test]
+fn test_parse_stale_after_days() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.stale_after(), Some(TimeDelta::days(7)));
+    let config = Config::parse("stale_after_days = 30").unwrap();
+    assert_eq!(config.stale_after(), Some(TimeDelta::days(30)));
+    let config = Config::parse("stale_after_days = 0").unwrap();
+    assert_eq!(config.stale_after(), None);
+}
+
+#[test]
+fn test_parse_refresh_interval() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.refresh_interval(), Some(Duration::from_secs(3)));
+    let config = Config::parse("refresh_interval = 10").unwrap();
+    assert_eq!(config.refresh_interval(), Some(Duration::from_secs(10)));
+    let config = Config::parse("refresh_interval = 0").unwrap();
+    assert_eq!(config.refresh_interval(), None);
+    assert!(Config::parse("refresh_interval = -1").is_err());
+}
+
+#[test]
+fn test_parse_log_level() {
+    assert_eq!(Config::parse("").unwrap().log_level, None);
+    let config = Config::parse("log_level = \"debug\"").unwrap();
+    assert_eq!(config.log_level.as_deref(), Some("debug"));
+    assert!(Config::parse("log_level = \"loud\"").is_err());
+}
+
+#[test]
+fn test_parse_min_tmux_version() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.min_tmux_version().unwrap().to_string(), "3.2");
+    let config = Config::parse("min_tmux_version = \"2.9a\"").unwrap();
+    assert_eq!(config.min_tmux_version().unwrap().to_string(), "2.9a");
+    assert!(Config::parse("min_tmux_version = \"three\"").is_err());
+}
+
+#[test]
+fn test_parse_popup_size() {
+    let size = Config::parse("").unwrap().popup_size().unwrap();
+    assert_eq!(size.to_string(), "80%x80%");
+    let size = Config::parse("popup_size = \"120x40\"")
+        .unwrap()
+        .popup_size()
+        .unwrap();
+    assert_eq!((size.width.as_str(), size.height.as_str()), ("120", "40"));
+    let size = Config::parse("popup_size = \"90%\"")
+        .unwrap()
+        .popup_size()
+        .unwrap();
+    assert_eq!(size.to_string(), "90%x90%");
+    assert!(Config::parse("popup_size = \"150%x50%\"").is_err());
+    assert!(Config::parse("popup_size = \"big\"").is_err());
+}
+
+#[test]
+fn test_layouts_file() {
+    let config = Config::parse("layouts_file = \"/etc/tmux-layouts.toml\"").unwrap();
+    assert_eq!(
+        config.layouts_file(),
+        Some(std::path::PathBuf::from("/etc/tmux-layouts.toml"))
+    );
+    let default = Config::parse("").unwrap().layouts_file();
+    assert!(default.is_some_and(|path| path.ends_with("tmux-ui/layouts.toml")));
+}
+
+#[test]
+fn test_pane_log_dir() {
+    let config = Config::parse("pane_log_dir = \"/var/log/panes\"").unwrap();
+    assert_eq!(
+        config.pane_log_dir(),
+        Some(std::path::PathBuf::from("/var/log/panes"))
+    );
+    let default = Config::parse("").unwrap().pane_log_dir();
+    assert!(default.is_some_and(|dir| dir.ends_with("tmux-ui/pane-logs")));
+}
+
+#[test]
+fn test_parse_remote_server() {
+    let config = Config::parse(
+        r#"
+[[servers]]
+name = "devbox"
+host = "me@devbox"
+socket_name = "work"
+"#,
+    )
+    .unwrap();
+    let client = config.servers[0].client().unwrap();
+    assert_eq!(client.host(), Some("me@devbox"));
+    assert_eq!(client.socket(), &Socket::Name("work".to_string()));
+
+    let result = Config::parse("[[servers]]\nname = \"devbox\"\nhost = \" \"");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_archive_dir() {
+    let config = Config::parse("archive_dir = \"/var/tmux-archive\"").unwrap();
+    assert_eq!(
+        config.archive_dir(),
+        Some(std::path::PathBuf::from("/var/tmux-archive"))
+    );
+    let default = Config::parse("").unwrap().archive_dir();
+    assert!(default.is_some_and(|path| path.ends_with("tmux-ui/archive")));
+}
+
+#[test]
+fn test_usage_tracking_is_opt_in() {
+    assert!(!Config::default().track_usage);
+    assert!(Config::default().usage_log().is_none());
+    let config: Config = toml::from_str("track_usage = true").unwrap();
+    assert!(config.track_usage);
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+use tmux_ui::tmux::{ControlMode, Notification, Socket, TmuxClient};
+
+#[test]
+fn test_parse_notifications() {
+    assert_eq!(
+        Notification::parse("%sessions-changed"),
+        Some(Notification::SessionsChanged)
+    );
+    assert_eq!(
+        Notification::parse("%session-renamed $3 my project"),
+        Some(Notification::SessionRenamed {
+            session_id: "$3".to_string(),
+            name: "my project".to_string(),
+        })
+    );
+    assert_eq!(
+        Notification::parse("%unlinked-window-close @5"),
+        Some(Notification::WindowClose("@5".to_string()))
+    );
+    assert_eq!(
+        Notification::parse("%layout-change @1 b25f,80x24,0,0,1 b25f,80x24,0,0,1 *"),
+        Some(Notification::LayoutChange("@1".to_string()))
+    );
+    assert_eq!(Notification::parse("%exit"), Some(Notification::Exit));
+}
+
+#[test]
+fn test_parse_ignores_output_and_replies() {
+    assert_eq!(Notification::parse("%output %1 hello"), None);
+    assert_eq!(Notification::parse("%begin 1792059031 282 0"), None);
+    assert_eq!(Notification::parse("a: 2 windows"), None);
+    assert_eq!(Notification::parse("%session-changed"), None);
+}
+
+#[tokio::test]
+async fn test_control_mode_reports_new_sessions() {
+    let socket = format!("tmux-ui-test-control-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket.clone()));
+
+    if client.create_session("control-test", None, None).is_ok() {
+        let mut control = ControlMode::spawn(&client, "control-test").unwrap();
+        let attached = tokio::time::timeout(Duration::from_secs(5), control.next()).await;
+        client.create_session("control-other", None, None).unwrap();
+
+        let mut changed = false;
+        while let Ok(Some(notification)) =
+            tokio::time::timeout(Duration::from_secs(5), control.next()).await
+        {
+            if notification == Notification::SessionsChanged {
+                changed = true;
+                break;
+            }
+        }
+        let session_id = control.session_id().map(|s| s.to_string());
+        drop(control);
+        let _ = std::process::Command::new("tmux")
+            .args(["-L", &socket, "kill-server"])
+            .status();
+
+        assert!(matches!(
+            attached,
+            Ok(Some(Notification::SessionChanged { .. }))
+        ));
+        assert!(session_id.is_some());
+        assert!(changed);
+    }
+}
@@ -0,0 +1,170 @@
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::time::Duration;
+use tmux_ui::daemon::{self, Daemon};
+use tmux_ui::tmux::{mock::MockBackend, TmuxBackend};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+#[tokio::test]
+async fn test_requests_and_errors() {
+    let mock = MockBackend::new().with_session("work");
+    let daemon = Daemon::new(mock.clone());
+    daemon.refresh().await.unwrap();
+
+    let (response, subscribed) = daemon.handle(r#"{"id": 1, "method": "ping"}"#).await;
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"], "pong");
+    assert!(!subscribed);
+
+    let (response, _) = daemon.handle(r#"{"id": 2, "method": "sessions"}"#).await;
+    assert_eq!(response["result"][0]["name"], "work");
+
+    let (response, _) = daemon
+        .handle(r#"{"id": 3, "method": "create", "params": {"name": "api"}}"#)
+        .await;
+    assert_eq!(response["result"]["created"], "api");
+    // Operations reload the list right away
+    assert_eq!(daemon.sessions().len(), 2);
+
+    let (response, _) = daemon
+        .handle(r#"{"id": 4, "method": "send-keys", "params": {"target": "api", "text": "make"}}"#)
+        .await;
+    assert_eq!(response["result"]["sent"], true);
+    assert_eq!(
+        mock.calls(),
+        vec!["new-session api", "send-keys api 'make'"]
+    );
+
+    let (response, _) = daemon
+        .handle(r#"{"id": 5, "method": "kill", "params": {"name": "missing"}}"#)
+        .await;
+    assert_eq!(response["id"], 5);
+    assert_eq!(response["error"]["kind"], "session_not_found");
+    assert_eq!(response["error"]["exit_code"], 2);
+
+    let (response, _) = daemon.handle(r#"{"id": 6, "method": "explode"}"#).await;
+    assert_eq!(response["error"]["kind"], "invalid_request");
+    assert!(response["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Unknown method 'explode'"));
+    let (response, _) = daemon
+        .handle(r#"{"id": 7, "method": "kill", "params": {}}"#)
+        .await;
+    assert_eq!(response["error"]["kind"], "invalid_request");
+    let (response, _) = daemon.handle("not json").await;
+    assert_eq!(response["error"]["exit_code"], 64);
+}
+
+#[tokio::test]
+async fn test_window_methods() {
+    let daemon = Daemon::new(MockBackend::new().with_session("work"));
+    daemon.refresh().await.unwrap();
+    let windows = |daemon: &Daemon<MockBackend>| daemon.sessions()[0].windows;
+    let before = windows(&daemon);
+
+    let (response, _) = daemon
+        .handle(
+            r#"{"id": 1, "method": "new-window", "params": {"session": "work", "name": "logs"}}"#,
+        )
+        .await;
+    assert_eq!(response["result"]["created"]["name"], "logs");
+    assert_eq!(windows(&daemon), before + 1);
+
+    let (response, _) = daemon
+        .handle(r#"{"id": 2, "method": "windows", "params": {"session": "work"}}"#)
+        .await;
+    let logs = response["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|w| w["name"] == "logs")
+        .unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let rename = serde_json::json!({
+        "id": 3, "method": "rename-window", "params": { "target": logs, "name": "tail" }
+    });
+    let (response, _) = daemon.handle(&rename.to_string()).await;
+    assert_eq!(response["result"]["to"], "tail");
+
+    let kill =
+        serde_json::json!({ "id": 4, "method": "kill-window", "params": { "target": logs } });
+    let (response, _) = daemon.handle(&kill.to_string()).await;
+    assert_eq!(response["result"]["killed"], logs);
+    assert_eq!(windows(&daemon), before);
+}
+
+async fn next<R: tokio::io::AsyncBufRead + Unpin>(
+    lines: &mut tokio::io::Lines<R>,
+) -> serde_json::Value {
+    let line = lines.next_line().await.unwrap().unwrap();
+    serde_json::from_str(&line).unwrap()
+}
+
+#[tokio::test]
+async fn test_subscribers_hear_about_changes_over_the_socket() {
+    let path = std::env::temp_dir()
+        .join(format!("tmux-ui-test-daemon-{}", std::process::id()))
+        .join("daemon.sock");
+    let mock = MockBackend::new().with_session("work");
+    daemon::create_socket_dir(path.parent().unwrap()).unwrap();
+    let listener = daemon::bind(&path).await.unwrap();
+    tokio::spawn(Daemon::new(mock.clone()).serve(listener, Duration::from_secs(60)));
+    // A second daemon may not take over the socket of a running one
+    assert!(daemon::bind(&path).await.is_err());
+    let mode = std::fs::metadata(path.parent().unwrap())
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o700);
+
+    let stream = UnixStream::connect(&path).await.unwrap();
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+
+    write
+        .write_all(b"{\"id\": \"a\", \"method\": \"subscribe\"}\n")
+        .await
+        .unwrap();
+    let response = next(&mut lines).await;
+    assert_eq!(response["id"], "a");
+    assert_eq!(response["result"][0]["name"], "work");
+
+    // Another tool changes the server; the next reload tells the subscriber
+    mock.create_session("api", None, None).unwrap();
+    write
+        .write_all(b"{\"id\": \"b\", \"method\": \"refresh\"}\n")
+        .await
+        .unwrap();
+    let first = next(&mut lines).await;
+    let second = next(&mut lines).await;
+    let event = if first.get("event").is_some() {
+        first
+    } else {
+        second
+    };
+    assert_eq!(event["event"], "sessions");
+    assert_eq!(event["sessions"].as_array().unwrap().len(), 2);
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[tokio::test]
+async fn test_bind_leaves_the_directory_of_a_given_path_alone() {
+    let dir = std::env::temp_dir().join(format!("tmux-ui-test-bind-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = dir.join("d.sock");
+
+    let _listener = daemon::bind(&path).await.unwrap();
+    let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode();
+    assert_eq!(mode(&dir) & 0o777, 0o755);
+    assert_eq!(mode(&path) & 0o777, 0o600);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
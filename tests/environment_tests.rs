@@ -0,0 +1,22 @@
+use tmux_ui::tmux::environment::parse_environment_line;
+
+#[test]
+fn test_parse_environment_lines() {
+    let variable = parse_environment_line("SSH_AUTH_SOCK=/tmp/ssh-abc/agent.42").unwrap();
+    assert_eq!(variable.name, "SSH_AUTH_SOCK");
+    assert_eq!(variable.value.as_deref(), Some("/tmp/ssh-abc/agent.42"));
+
+    // Only the first = separates the name
+    let variable = parse_environment_line("OPTS=a=b").unwrap();
+    assert_eq!(variable.value.as_deref(), Some("a=b"));
+
+    let variable = parse_environment_line("EMPTY=").unwrap();
+    assert_eq!(variable.value.as_deref(), Some(""));
+
+    let variable = parse_environment_line("-DISPLAY").unwrap();
+    assert_eq!(variable.name, "DISPLAY");
+    assert_eq!(variable.value, None);
+
+    assert!(parse_environment_line("").is_none());
+    assert!(parse_environment_line("=value").is_none());
+}
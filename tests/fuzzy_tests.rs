@@ -0,0 +1,27 @@
+use tmux_ui::tui::fuzzy::fuzzy_match;
+
+#[test]
+fn test_subsequence_matches_with_positions() {
+    let m = fuzzy_match("tui", "tmux-ui").unwrap();
+    assert_eq!(m.positions, vec![0, 5, 6]);
+    assert!(fuzzy_match("uit", "tmux-ui").is_none());
+    assert_eq!(fuzzy_match("", "anything").unwrap().score, 0);
+}
+
+#[test]
+fn test_consecutive_and_boundary_matches_score_higher() {
+    let consecutive = fuzzy_match("api", "api-server").unwrap();
+    let scattered = fuzzy_match("api", "a-project-index").unwrap();
+    assert!(consecutive.score > scattered.score);
+
+    let boundary = fuzzy_match("ws", "web-server").unwrap();
+    let inner = fuzzy_match("ws", "newsfeed").unwrap();
+    assert!(boundary.score > inner.score);
+}
+
+#[test]
+fn test_smart_case() {
+    assert!(fuzzy_match("dot", "Dotfiles").is_some());
+    assert!(fuzzy_match("Dot", "dotfiles").is_none());
+    assert!(fuzzy_match("Dot", "Dotfiles").is_some());
+}
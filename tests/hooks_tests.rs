@@ -0,0 +1,17 @@
+use tmux_ui::tmux::hooks::parse_hook_line;
+
+#[test]
+fn test_parse_hook_lines() {
+    let hook = parse_hook_line(r#"session-created[0] display-message "hi there""#).unwrap();
+    assert_eq!(hook.name, "session-created");
+    assert_eq!(hook.index, 0);
+    assert_eq!(hook.command, r#"display-message "hi there""#);
+    assert_eq!(hook.target(), "session-created[0]");
+
+    let hook = parse_hook_line("pane-exited[12] kill-session").unwrap();
+    assert_eq!(hook.index, 12);
+
+    // Hooks without commands are only listed by name
+    assert!(parse_hook_line("after-new-session").is_none());
+    assert!(parse_hook_line("").is_none());
+}
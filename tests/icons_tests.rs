@@ -0,0 +1,40 @@
+use tmux_ui::config::Config;
+use tmux_ui::tui::icons::IconSet;
+
+#[test]
+fn test_icon_follows_the_running_command() {
+    let icons = IconSet::Unicode;
+    assert_eq!(icons.command("nvim"), icons.command("vim"));
+    assert_eq!(icons.command("/usr/bin/node"), icons.command("npm"));
+    assert_ne!(icons.command("ssh"), icons.command("docker"));
+    assert_eq!(icons.command("zsh"), icons.command(""));
+    assert_eq!(icons.command("cargo"), icons.command("make"));
+}
+
+#[test]
+fn test_ascii_icons_are_plain_ascii() {
+    for command in ["vim", "node", "docker", "ssh", "bash", "htop"] {
+        assert!(IconSet::Ascii.command(command).is_ascii());
+    }
+    assert!(IconSet::Ascii.attached(true).is_ascii());
+    assert!(IconSet::Ascii.attached(false).is_ascii());
+    assert_ne!(
+        IconSet::Ascii.attached(true),
+        IconSet::Ascii.attached(false)
+    );
+}
+
+#[test]
+fn test_icon_set_from_config() {
+    assert_eq!(
+        Config::parse("").unwrap().icons().unwrap(),
+        IconSet::Unicode
+    );
+    let config = Config::parse(r#"icons = "nerd-font""#).unwrap();
+    assert_eq!(config.icons().unwrap(), IconSet::NerdFont);
+    assert_eq!(
+        IconSet::NerdFont.to_string().parse::<IconSet>().unwrap(),
+        IconSet::NerdFont
+    );
+    assert!(Config::parse(r#"icons = "emoji""#).is_err());
+}
@@ -1,5 +1,51 @@
-use tmux_ui::tmux::{TmuxClient, TmuxSession};
+use chrono::DateTime;
 use std::env;
+use tmux_ui::tmux::{
+    parse_tags, prefetch_panes, validate_session_name, validate_window_name, Alerts, Error,
+    HookScope, Key, OptionScope, Socket, SplitDirection, TmuxClient, TmuxOption, TmuxSession,
+    TmuxVariable, TmuxWindow, HOOK_TEMPLATES,
+};
+
+/// A tmux server of a test's own on a separate socket, killed when the test ends,
+/// whether it passed or not
+struct TestServer {
+    socket: String,
+    client: TmuxClient,
+}
+
+impl TestServer {
+    /// A client for a fresh socket; `None`, skipping the test, when tmux is not installed
+    fn new(name: &str) -> Option<Self> {
+        if std::process::Command::new("tmux")
+            .arg("-V")
+            .output()
+            .is_err()
+        {
+            eprintln!("skipping: tmux is not installed");
+            return None;
+        }
+        let socket = format!("tmux-ui-test-{}-{}", name, std::process::id());
+        let client = TmuxClient::new().with_socket(Socket::Name(socket.clone()));
+        Some(Self { socket, client })
+    }
+
+    /// Run a tmux command on this server
+    fn tmux(&self, args: &[&str]) -> std::process::ExitStatus {
+        std::process::Command::new("tmux")
+            .args(["-L", &self.socket])
+            .args(args)
+            .status()
+            .unwrap()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = std::process::Command::new("tmux")
+            .args(["-L", &self.socket, "kill-server"])
+            .status();
+    }
+}
 
 #[test]
 fn test_tmux_client_creation() {
@@ -11,16 +57,58 @@ fn test_tmux_client_creation() {
 #[test]
 fn test_session_struct() {
     let session = TmuxSession {
+        id: "$0".to_string(),
         name: "test-session".to_string(),
         windows: 2,
+        panes: 3,
         attached: true,
-        created: "1234567890".to_string(),
+        clients: 1,
+        created_at: DateTime::from_timestamp(1234567890, 0).unwrap(),
+        last_activity: DateTime::from_timestamp(1234567890, 0).unwrap(),
+        columns: Vec::new(),
+        server: None,
+        alerts: Alerts::default(),
+        tags: Vec::new(),
+        current_command: String::new(),
+        current_path: String::new(),
     };
 
     assert_eq!(session.name, "test-session");
     assert_eq!(session.windows, 2);
     assert!(session.attached);
-    assert_eq!(session.created, "1234567890");
+    assert_eq!(session.created_at.timestamp(), 1234567890);
+}
+
+#[test]
+fn test_session_serializes_to_json() {
+    let session = TmuxSession {
+        id: "$1".to_string(),
+        name: "work".to_string(),
+        windows: 3,
+        panes: 4,
+        attached: false,
+        clients: 0,
+        created_at: DateTime::from_timestamp(1700000000, 0).unwrap(),
+        last_activity: DateTime::from_timestamp(1700000100, 0).unwrap(),
+        columns: vec!["vim".to_string()],
+        server: Some("remote".to_string()),
+        alerts: Alerts::default(),
+        tags: Vec::new(),
+        current_command: String::new(),
+        current_path: String::new(),
+    };
+
+    let json = serde_json::to_value(&session).unwrap();
+    assert_eq!(json["id"], "$1");
+    assert_eq!(json["name"], "work");
+    assert_eq!(json["windows"], 3);
+    assert_eq!(json["panes"], 4);
+    assert_eq!(json["attached"], false);
+    assert_eq!(json["columns"][0], "vim");
+    assert_eq!(json["server"], "remote");
+    // Timestamps stay unix times, as tmux prints them
+    assert_eq!(json["created_at"], 1700000000);
+    assert_eq!(json["last_activity"], 1700000100);
 }
 
 #[test]
@@ -36,18 +124,18 @@ fn test_list_sessions_when_none_exist() {
 #[test]
 fn test_is_inside_tmux() {
     let client = TmuxClient::new();
-    
+
     // Save current TMUX env var
     let original = env::var("TMUX").ok();
-    
+
     // Test when TMUX is not set
     env::remove_var("TMUX");
     assert!(!client.is_inside_tmux());
-    
+
     // Test when TMUX is set
     env::set_var("TMUX", "/tmp/tmux-1000/default,1234,0");
     assert!(client.is_inside_tmux());
-    
+
     // Restore original TMUX env var
     if let Some(val) = original {
         env::set_var("TMUX", val);
@@ -56,3 +144,943 @@ fn test_is_inside_tmux() {
     }
 }
 
+#[tokio::test]
+async fn test_prefetch_panes_returns_entry_per_window() {
+    let client = TmuxClient::new();
+    let windows: Vec<TmuxWindow> = (0..6)
+        .map(|i| TmuxWindow {
+            id: format!("@tmux-ui-missing-{}", i),
+            index: i,
+            name: format!("window-{}", i),
+            panes: 1,
+            active: false,
+            automatic_rename: true,
+            synchronized: false,
+            alerts: Alerts::default(),
+            zoomed: false,
+            linked: false,
+        })
+        .collect();
+
+    let panes = prefetch_panes(&client, &windows, 2).await;
+    assert_eq!(panes.len(), windows.len());
+    assert!(panes.values().all(|p| p.is_empty()));
+}
+
+#[test]
+fn test_client_uses_configured_socket() {
+    let Some(server) = TestServer::new("socket") else {
+        return;
+    };
+    let client = &server.client;
+
+    // A fresh socket has no server, which is reported as no sessions
+    assert!(client.list_sessions().unwrap().is_empty());
+
+    client.create_session("socket-test", None, None).unwrap();
+    let sessions = client.list_sessions().unwrap();
+    let default_sessions = TmuxClient::new().list_sessions().unwrap_or_default();
+
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].name, "socket-test");
+    assert!(default_sessions.iter().all(|s| s.name != "socket-test"));
+}
+
+#[test]
+fn test_sessions_count_panes_of_all_windows() {
+    let Some(server) = TestServer::new("panes") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("panes-test", None, None).unwrap();
+    client.create_window("panes-test", None).unwrap();
+    client
+        .split_window("panes-test", SplitDirection::Vertical, None, None)
+        .unwrap();
+    let sessions = client.list_sessions().unwrap();
+
+    assert_eq!(sessions[0].windows, 2);
+    assert_eq!(sessions[0].panes, 3);
+}
+
+#[test]
+fn test_sessions_list_current_command_and_path() {
+    let Some(server) = TestServer::new("cmd") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("cmd-test", None, None).unwrap();
+    let sessions = client
+        .list_sessions_with_columns(&["#{session_name}".to_string()])
+        .unwrap();
+
+    assert_eq!(sessions.len(), 1);
+    assert!(!sessions[0].current_command.is_empty());
+    assert!(sessions[0].current_path.starts_with('/'));
+    // User columns still follow the built-in fields
+    assert_eq!(sessions[0].columns, vec!["cmd-test".to_string()]);
+}
+
+#[test]
+fn test_toggle_zoom() {
+    let Some(server) = TestServer::new("zoom") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("zoom-test", None, None).unwrap();
+    let pane = client
+        .split_window("zoom-test", SplitDirection::Vertical, None, None)
+        .unwrap();
+    client.toggle_zoom(&pane).unwrap();
+    let zoomed = client.list_windows("zoom-test").unwrap();
+    client.toggle_zoom(&pane).unwrap();
+    let unzoomed = client.list_windows("zoom-test").unwrap();
+
+    assert!(zoomed[0].zoomed);
+    assert!(!unzoomed[0].zoomed);
+}
+
+#[test]
+fn test_show_and_set_options() {
+    let Some(server) = TestServer::new("opt") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("option-test", None, None).unwrap();
+    let session = OptionScope::Session("option-test".to_string());
+    let before = client.show_options(&session).unwrap();
+    client
+        .set_option(&session, "status-left", "[#S] \"quoted\" ")
+        .unwrap();
+    let after = client.show_options(&session).unwrap();
+    let invalid = client.set_option(&session, "history-limit", "lots");
+    client.unset_option(&session, "status-left").unwrap();
+    let reset = client.show_options(&session).unwrap();
+    let server = client.show_options(&OptionScope::Server).unwrap();
+
+    let status_left = |options: &[TmuxOption]| {
+        options
+            .iter()
+            .find(|o| o.name == "status-left")
+            .cloned()
+            .unwrap()
+    };
+    assert!(status_left(&before).inherited);
+    assert_eq!(status_left(&after).value, "[#S] \"quoted\" ");
+    assert!(!status_left(&after).inherited);
+    assert!(invalid.is_err());
+    assert!(status_left(&reset).inherited);
+    assert!(server.iter().any(|o| o.name == "buffer-limit"));
+}
+
+#[test]
+fn test_show_and_set_environment() {
+    let Some(server) = TestServer::new("env") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("env-test", None, None).unwrap();
+    let session = Some("env-test");
+    client
+        .set_environment(session, "SSH_AUTH_SOCK", "/tmp/agent one")
+        .unwrap();
+    let set = client.show_environment(session).unwrap();
+    client.unset_environment(session, "SSH_AUTH_SOCK").unwrap();
+    let unset = client.show_environment(session).unwrap();
+    let global = client.show_environment(None).unwrap();
+    let missing = client.show_environment(Some("no-such-session"));
+
+    let agent = |variables: &[TmuxVariable]| {
+        variables
+            .iter()
+            .find(|v| v.name == "SSH_AUTH_SOCK")
+            .and_then(|v| v.value.clone())
+    };
+    assert_eq!(agent(&set).as_deref(), Some("/tmp/agent one"));
+    assert_eq!(agent(&unset), None);
+    assert!(global.iter().any(|v| v.name == "PATH"));
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_set_and_show_hooks() {
+    let Some(server) = TestServer::new("hook") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("hook-test", None, None).unwrap();
+    let global = HookScope::Global;
+    let session = HookScope::Session("hook-test".to_string());
+    let templates: Vec<_> = HOOK_TEMPLATES
+        .iter()
+        .map(|t| client.set_hook(&global, t.hook, t.command, true))
+        .collect();
+    let with_templates = client.show_hooks(&global).unwrap();
+
+    client
+        .set_hook(&session, "alert-bell", "display-message one", false)
+        .unwrap();
+    client
+        .set_hook(&session, "alert-bell", "display-message two", true)
+        .unwrap();
+    client
+        .set_hook(&session, "alert-bell[0]", "display-message first", false)
+        .unwrap();
+    let set = client.show_hooks(&session).unwrap();
+    client.unset_hook(&session, "alert-bell[1]").unwrap();
+    let unset = client.show_hooks(&session).unwrap();
+    let unknown = client.set_hook(&session, "no-such-hook", "display-message x", false);
+    let invalid = client.set_hook(&session, "alert-bell", "no-such-command", false);
+
+    assert!(templates.iter().all(|r| r.is_ok()));
+    assert_eq!(with_templates.len(), HOOK_TEMPLATES.len());
+    let commands: Vec<&str> = set.iter().map(|h| h.command.as_str()).collect();
+    assert_eq!(commands, ["display-message first", "display-message two"]);
+    assert_eq!(unset.len(), 1);
+    assert_eq!(unset[0].target(), "alert-bell[0]");
+    assert!(unknown.is_err());
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn test_list_and_detach_clients() {
+    let Some(server) = TestServer::new("clients") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("client-test", None, None).unwrap();
+    // `script` gives the attaching client a terminal
+    let attach = std::process::Command::new("script")
+        .args([
+            "-qc",
+            &format!("tmux -L {} attach -t client-test", server.socket),
+            "/dev/null",
+        ])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .spawn();
+    let mut clients = Vec::new();
+    if attach.is_ok() {
+        for _ in 0..50 {
+            clients = client.list_clients().unwrap();
+            if !clients.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    }
+    let detached = clients
+        .first()
+        .map(|c| client.detach_client(&c.name).is_ok());
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    let after = client.list_clients().unwrap();
+    let unknown = client.detach_client("/dev/no-such-tty");
+    // Ends the attached client, should detaching it have failed
+    server.tmux(&["kill-server"]);
+    if let Ok(mut attach) = attach {
+        let _ = attach.wait();
+    }
+
+    if let Some(detached) = detached {
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].session, "client-test");
+        assert!(clients[0].session_id.starts_with('$'));
+        assert!(clients[0].width > 0);
+        assert!(!clients[0].control_mode);
+        assert!(detached);
+        assert!(after.is_empty());
+    }
+    assert!(unknown.is_err());
+}
+
+#[test]
+fn test_paste_buffers() {
+    let Some(server) = TestServer::new("buf") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("buffer-test", None, None).unwrap();
+    client.set_buffer(None, "first\nline").unwrap();
+    client.set_buffer(Some("named"), "second").unwrap();
+    let buffers = client.list_buffers().unwrap();
+    let content = client.show_buffer(&buffers[1].name);
+    let pasted = client.paste_buffer("named", "buffer-test");
+    client.delete_buffer("named").unwrap();
+    let remaining = client.list_buffers().unwrap();
+
+    // Most recent first, with line breaks escaped in the sample
+    assert_eq!(buffers.len(), 2);
+    assert_eq!(buffers[0].name, "named");
+    assert_eq!(buffers[1].size, 10);
+    assert_eq!(buffers[1].sample, "first\\nline");
+    assert_eq!(content.unwrap(), "first\nline");
+    assert!(pasted.is_ok());
+    assert_eq!(remaining.len(), 1);
+}
+
+#[test]
+fn test_window_options_and_automatic_rename() {
+    let Some(server) = TestServer::new("win") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("window-test", None, None).unwrap();
+    let before = client.list_windows("window-test").unwrap();
+    let target = before[0].id.clone();
+    client.disable_automatic_rename(&target).unwrap();
+    client
+        .set_window_option(&target, "synchronize-panes", "on")
+        .unwrap();
+    let option = client.show_window_option(&target, "automatic-rename");
+    let after = client.list_windows("window-test").unwrap();
+
+    assert!(before[0].automatic_rename);
+    assert_eq!(option.unwrap().as_deref(), Some("off"));
+    assert!(!after[0].automatic_rename);
+    assert!(!before[0].synchronized);
+    assert!(after[0].synchronized);
+}
+
+#[test]
+fn test_select_rename_and_kill_window() {
+    let Some(server) = TestServer::new("select") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("select-test", None, None).unwrap();
+    client.create_window("select-test", Some("second")).unwrap();
+    let windows = client.list_windows("select-test").unwrap();
+    let first = windows[0].id.clone();
+    client.select_window(&first).unwrap();
+    client.rename_window(&first, "first").unwrap();
+    let selected = client.list_windows("select-test").unwrap();
+    client.kill_window(&first).unwrap();
+    let remaining = client.list_windows("select-test").unwrap();
+
+    assert_eq!(windows.len(), 2);
+    assert!(selected[0].active);
+    assert_eq!(selected[0].name, "first");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].name, "second");
+}
+
+#[test]
+fn test_select_and_kill_pane() {
+    let Some(server) = TestServer::new("pane") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("pane-test", None, None).unwrap();
+    let window = client.list_windows("pane-test").unwrap()[0].id.clone();
+    server.tmux(&["split-window", "-t", &window]);
+    let panes = client.list_panes(&window).unwrap();
+    let first = panes[0].id.clone();
+    client.select_pane(&first).unwrap();
+    let selected = client.list_panes(&window).unwrap();
+    client.kill_pane(&first).unwrap();
+    let remaining = client.list_panes(&window).unwrap();
+
+    assert_eq!(panes.len(), 2);
+    assert!(panes[0].pid > 0 && panes[0].pid != panes[1].pid);
+    assert!(selected[0].active);
+    assert_eq!(remaining.len(), 1);
+    assert_ne!(remaining[0].id, first);
+}
+
+#[test]
+fn test_respawn_dead_pane() {
+    let Some(server) = TestServer::new("respawn") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("respawn-test", None, None).unwrap();
+    let window = client.list_windows("respawn-test").unwrap()[0].id.clone();
+    server.tmux(&["set-option", "-g", "remain-on-exit", "on"]);
+    server.tmux(&["split-window", "-t", &window, "true"]);
+    let dead = |client: &TmuxClient| {
+        client
+            .list_panes(&window)
+            .unwrap()
+            .into_iter()
+            .find(|p| p.dead)
+    };
+    let mut exited = None;
+    for _ in 0..50 {
+        exited = dead(client);
+        if exited.is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    let live = client.list_panes(&window).unwrap()[0].clone();
+    let live_error = client.respawn_pane(&live.id, None);
+    let respawned = exited
+        .as_ref()
+        .map(|pane| client.respawn_pane(&pane.id, Some("sleep 30")));
+    let after = dead(client);
+
+    let exited = exited.expect("the pane running true died");
+    assert_eq!(exited.start_command, "true");
+    assert!(!live.dead);
+    assert!(live_error.is_err());
+    assert!(respawned.unwrap().is_ok());
+    assert!(after.is_none());
+}
+
+#[test]
+fn test_run_in_window_keeps_the_pane() {
+    let Some(server) = TestServer::new("run") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("run-test", None, None).unwrap();
+    client.run_in_window("run-test", "echo run marker").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let windows = client.list_windows("run-test").unwrap();
+    let panes = client.list_panes(&windows[1].id).unwrap();
+    let missing = client.run_in_window("no-such-session", "true");
+
+    assert!(panes[0].dead);
+    assert_eq!(panes[0].start_command, "echo run marker");
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_select_layout() {
+    let Some(server) = TestServer::new("layout") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("layout-test", None, None).unwrap();
+    client
+        .split_window("layout-test", SplitDirection::Horizontal, None, None)
+        .unwrap();
+    let layout = |c: &TmuxClient| {
+        c.display_message(Some("layout-test"), "#{window_layout}")
+            .unwrap()
+    };
+    let split = layout(client);
+    client
+        .select_layout("layout-test", "even-vertical")
+        .unwrap();
+    let even = layout(client);
+    // A saved layout string brings the arrangement back
+    client.select_layout("layout-test", &split).unwrap();
+    let restored = layout(client);
+    let invalid = client.select_layout("layout-test", "not-a-layout");
+
+    assert_ne!(even, split);
+    assert_eq!(restored, split);
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn test_swap_and_rotate_panes() {
+    let Some(server) = TestServer::new("swap") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("swap-test", None, None).unwrap();
+    let ids = |c: &TmuxClient| -> Vec<String> {
+        c.list_panes("swap-test")
+            .unwrap()
+            .into_iter()
+            .map(|p| p.id)
+            .collect()
+    };
+    let first = ids(client).remove(0);
+    let second = client
+        .split_window(&first, SplitDirection::Horizontal, None, None)
+        .unwrap();
+    client.swap_pane(&first, &second).unwrap();
+    let swapped = ids(client);
+    client.rotate_window("swap-test", false).unwrap();
+    let rotated = ids(client);
+    let missing = client.swap_pane(&first, "%999");
+
+    assert_eq!(swapped, vec![second.clone(), first.clone()]);
+    assert_eq!(rotated, vec![first, second]);
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_session_details() {
+    let Some(server) = TestServer::new("details") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("details-test", None, None).unwrap();
+    let window = client.list_windows("details-test").unwrap().remove(0);
+    client.rename_window(&window.id, "editor").unwrap();
+    let details = client.session_details("details-test");
+    let missing = client.session_details("no-such-session");
+
+    let details = details.unwrap();
+    assert_eq!(details.window_name, "editor");
+    assert_eq!(details.group, None);
+    assert!(details.width > 0 && details.height > 0);
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_running_commands() {
+    let Some(server) = TestServer::new("running") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("running-test", None, None).unwrap();
+    let idle = client.running_commands("running-test");
+    client.run_in_window("running-test", "sleep 30").unwrap();
+    client.run_in_window("running-test", "sleep 60").unwrap();
+    let busy = client.running_commands("running-test");
+
+    assert!(idle.unwrap().is_empty());
+    assert_eq!(busy.unwrap(), vec!["sleep"]);
+}
+
+#[test]
+fn test_link_and_unlink_window() {
+    let Some(server) = TestServer::new("link") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("link-a", None, None).unwrap();
+    client.create_session("link-b", None, None).unwrap();
+    let window = client.list_windows("link-a").unwrap().remove(0);
+    client.link_window(&window.id, "link-b").unwrap();
+    let linked = client.list_windows("link-b").unwrap();
+    client.unlink_window("link-b", &window.id).unwrap();
+    let unlinked = client.list_windows("link-a").unwrap();
+    // The window is now only in link-a, so tmux refuses to unlink it
+    let last = client.unlink_window("link-a", &window.id);
+
+    assert_eq!(linked.len(), 2);
+    assert_eq!(linked[1].id, window.id);
+    assert!(linked[1].linked);
+    assert!(!unlinked[0].linked);
+    assert!(last.is_err());
+}
+
+#[test]
+fn test_capture_pane() {
+    let Some(server) = TestServer::new("capture") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("capture-test", None, None).unwrap();
+    client
+        .send_key_spec("capture-test", "'echo preview-marker' Enter")
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let content = client.capture_pane("capture-test");
+    let missing = client.capture_pane("no-such-session");
+
+    assert!(content.unwrap().contains("preview-marker"));
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_pipe_pane_to_file() {
+    let Some(server) = TestServer::new("pipe") else {
+        return;
+    };
+    let client = &server.client;
+    let log = env::temp_dir().join(format!("{}.log", server.socket));
+    client
+        .create_session("pipe-test", None, Some("sh"))
+        .unwrap();
+    let pane = client.list_panes("pipe-test").unwrap()[0].id.clone();
+    client.pipe_pane(&pane, Some(&log)).unwrap();
+    let piped = client.list_panes(&pane).unwrap()[0].piped;
+    client
+        .send_key_spec(&pane, "'echo log-marker' Enter")
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    client.pipe_pane(&pane, None).unwrap();
+    let stopped = !client.list_panes(&pane).unwrap()[0].piped;
+    let content = std::fs::read_to_string(&log).unwrap_or_default();
+    let _ = std::fs::remove_file(&log);
+
+    assert!(piped);
+    assert!(stopped);
+    assert!(content.contains("log-marker"));
+}
+
+#[test]
+fn test_send_keys_with_enter() {
+    let Some(server) = TestServer::new("send") else {
+        return;
+    };
+    let client = &server.client;
+
+    // A plain sh starts quickly and does not depend on the user's shell setup
+    assert!(server
+        .tmux(&["new-session", "-d", "-s", "send-test", "sh"])
+        .success());
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    client
+        .send_keys(
+            "send-test",
+            &[Key::Literal("echo sent-$((40+2))".into())],
+            true,
+        )
+        .unwrap();
+    client
+        .send_keys(
+            "send-test",
+            &[Key::Literal("echo typed-only".into())],
+            false,
+        )
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let content = client.capture_pane("send-test").unwrap();
+
+    assert!(content.lines().any(|line| line.trim() == "sent-42"));
+    assert!(content.contains("echo typed-only"));
+    assert!(!content.lines().any(|line| line.trim() == "typed-only"));
+}
+
+#[test]
+fn test_split_window() {
+    let Some(server) = TestServer::new("split") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("split-test", None, None).unwrap();
+    let windows = client.list_windows("split-test").unwrap();
+    let first = client.list_panes(&windows[0].id).unwrap()[0].id.clone();
+    let right = client.split_window(
+        &first,
+        SplitDirection::Horizontal,
+        Some(30),
+        Some(std::path::Path::new("/tmp")),
+    );
+    let below = client.split_window(&first, SplitDirection::Vertical, None, None);
+    let invalid = client.split_window(&first, SplitDirection::Vertical, Some(0), None);
+    let panes = client.list_panes(&windows[0].id).unwrap();
+
+    let right = right.unwrap();
+    assert!(right.starts_with('%'));
+    assert!(below.is_ok());
+    assert!(invalid.is_err());
+    assert_eq!(panes.len(), 3);
+    // -d keeps the split pane active
+    assert!(panes.iter().any(|p| p.id == first && p.active));
+    let new_pane = panes.iter().find(|p| p.id == right).unwrap();
+    assert_eq!(new_pane.current_path, "/tmp");
+}
+
+#[test]
+fn test_attach_in_new_terminal_appends_attach_command() {
+    let client = TmuxClient::new().with_socket(Socket::Name("tmux-ui-test-term".to_string()));
+    let out = env::temp_dir().join(format!("tmux-ui-terminal-{}", std::process::id()));
+    // A fake terminal that records the command it was asked to run
+    let terminal = format!("sh -c 'echo \"$@\" > {}' sh", out.display());
+
+    client.attach_in_new_terminal("work", &terminal).unwrap();
+    let mut recorded = String::new();
+    for _ in 0..50 {
+        recorded = std::fs::read_to_string(&out).unwrap_or_default();
+        if !recorded.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    let _ = std::fs::remove_file(&out);
+
+    assert_eq!(
+        recorded.trim(),
+        "tmux -L tmux-ui-test-term attach-session -t =work"
+    );
+    assert!(client.attach_in_new_terminal("work", "").is_err());
+}
+
+#[test]
+fn test_validate_session_name() {
+    assert_eq!(validate_session_name("  work ").unwrap(), "work");
+    assert_eq!(validate_session_name("api:v1.2").unwrap(), "api_v1_2");
+    assert_eq!(validate_session_name("multi\nline\r").unwrap(), "multiline");
+    assert!(validate_session_name("").is_err());
+    assert!(validate_session_name(" \n\t").is_err());
+}
+
+#[test]
+fn test_validate_window_name() {
+    assert_eq!(validate_window_name("  editor ").unwrap(), "editor");
+    assert_eq!(validate_window_name("multi\nline\r").unwrap(), "multiline");
+    assert!(validate_window_name("").is_err());
+    assert!(validate_window_name(" \n\t").is_err());
+    assert!(validate_window_name("api:v1").is_err());
+    assert!(validate_window_name("v1.2").is_err());
+}
+
+#[test]
+fn test_session_commands_match_names_exactly() {
+    let Some(server) = TestServer::new("exact") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("foobar", None, None).unwrap();
+    let prefix_kill = client.kill_session("foo");
+    let dotted = client.create_session("app.v2", None, None);
+    let sessions = client.list_sessions().unwrap();
+
+    assert!(prefix_kill.is_err());
+    assert!(dotted.is_ok());
+    let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+    assert!(names.contains(&"foobar"));
+    assert!(names.contains(&"app_v2"));
+
+    // Window commands must not fall back to a prefix or a window index either
+    assert!(matches!(
+        client.list_windows("foo"),
+        Err(Error::SessionNotFound(_))
+    ));
+    assert!(client.create_window("foo", None).is_err());
+    assert!(client.run_in_window("foo", "true").is_err());
+    client.create_session("1", None, None).unwrap();
+    client.create_window("1", Some("logs")).unwrap();
+    client.run_in_window("1", "sleep 60").unwrap();
+    assert_eq!(client.list_windows("1").unwrap().len(), 3);
+    assert_eq!(client.list_windows("foobar").unwrap().len(), 1);
+}
+
+#[test]
+fn test_alert_badges() {
+    let alerts = Alerts::from_session_alerts("1!,3#");
+    assert!(alerts.bell && alerts.activity && !alerts.silence);
+    assert_eq!(alerts.badges(), "!~");
+    assert_eq!(Alerts::from_session_alerts("2~").badges(), "_");
+    assert!(!Alerts::from_session_alerts("").any());
+}
+
+#[test]
+fn test_bell_in_background_window() {
+    let Some(server) = TestServer::new("bell") else {
+        return;
+    };
+    let client = &server.client;
+
+    assert!(server
+        .tmux(&["new-session", "-d", "-s", "bell", "sh"])
+        .success());
+    server.tmux(&["new-window", "-d", "-t", "bell:", "sh"]);
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    server.tmux(&["send-keys", "-t", "bell:1", "printf '\\a'", "Enter"]);
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let sessions = client.list_sessions().unwrap();
+    let windows = client.list_windows("bell").unwrap();
+
+    assert!(sessions[0].alerts.bell);
+    assert!(!windows[0].alerts.bell);
+    assert!(windows[1].alerts.bell);
+}
+
+#[test]
+fn test_detach_other_clients_without_clients() {
+    let Some(server) = TestServer::new("detach") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("lonely", None, None).unwrap();
+    let detached = client.detach_other_clients("lonely");
+    let missing = client.detach_other_clients("missing");
+
+    assert_eq!(detached.unwrap(), 0);
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_prune_kills_only_idle_sessions() {
+    let Some(server) = TestServer::new("prune") else {
+        return;
+    };
+    let client = &server.client;
+
+    assert!(server
+        .tmux(&["new-session", "-d", "-s", "idle", "sh"])
+        .success());
+    server.tmux(&["set-option", "-g", "default-shell", "/bin/sh"]);
+    server.tmux(&["new-session", "-d", "-s", "busy", "exec sleep 30"]);
+    std::thread::sleep(std::time::Duration::from_millis(300));
+
+    let idle = client.idle_sessions().unwrap();
+    let pruned = client.prune_sessions().unwrap();
+    let remaining = client.list_sessions().unwrap();
+    client.kill_server().unwrap();
+    // The server needs a moment to shut down
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    let after_kill = client.list_sessions().unwrap();
+
+    assert_eq!(
+        idle.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+        ["idle"]
+    );
+    assert_eq!(pruned, ["idle"]);
+    assert_eq!(
+        remaining
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>(),
+        ["busy"]
+    );
+    assert!(after_kill.is_empty());
+    // Killing a server that is not running is not an error
+    assert!(client.kill_server().is_ok());
+}
+
+#[test]
+fn test_swap_and_move_window() {
+    let Some(server) = TestServer::new("move") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("move-a", None, None).unwrap();
+    client.create_window("move-a", Some("second")).unwrap();
+    client.create_session("move-b", None, None).unwrap();
+    let before = client.list_windows("move-a").unwrap();
+    client.swap_window(&before[0].id, &before[1].id).unwrap();
+    let swapped = client.list_windows("move-a").unwrap();
+    client.move_window(&before[1].id, "move-b").unwrap();
+    let source = client.list_windows("move-a").unwrap();
+    let target = client.list_windows("move-b").unwrap();
+
+    assert_eq!(swapped[0].id, before[1].id);
+    assert_eq!(swapped[0].index, before[0].index);
+    assert_eq!(swapped[1].id, before[0].id);
+    assert_eq!(source.len(), 1);
+    assert_eq!(target.len(), 2);
+    assert_eq!(target[1].name, "second");
+    assert_eq!(target[1].index, target[0].index + 1);
+}
+
+#[test]
+fn test_break_and_join_pane() {
+    let Some(server) = TestServer::new("join") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("join-test", None, None).unwrap();
+    let window = client.list_windows("join-test").unwrap()[0].id.clone();
+    let pane = client
+        .split_window(&window, SplitDirection::Horizontal, None, None)
+        .unwrap();
+    let new_window = client.break_pane(&pane).unwrap();
+    let after_break = client.list_windows("join-test").unwrap();
+    client
+        .join_pane(&pane, &window, SplitDirection::Vertical)
+        .unwrap();
+    let after_join = client.list_windows("join-test").unwrap();
+
+    assert_eq!(after_break.len(), 2);
+    assert!(after_break.iter().any(|w| w.id == new_window));
+    // Joining the only pane of the new window closes it
+    assert_eq!(after_join.len(), 1);
+    assert_eq!(after_join[0].panes, 2);
+}
+
+#[test]
+fn test_parse_tags() {
+    assert_eq!(
+        parse_tags("work, #client work  personal").unwrap(),
+        ["client", "personal", "work"]
+    );
+    assert!(parse_tags(" , ").unwrap().is_empty());
+    assert!(parse_tags("a|b").is_err());
+}
+
+#[test]
+fn test_session_tags() {
+    let Some(server) = TestServer::new("tags") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("tagged", None, None).unwrap();
+    let untagged = client.list_sessions().unwrap();
+    let tags = parse_tags("work,client").unwrap();
+    client.set_session_tags("tagged", &tags).unwrap();
+    let tagged = client.list_sessions().unwrap();
+    client.set_session_tags("tagged", &[]).unwrap();
+    let cleared = client.list_sessions().unwrap();
+
+    assert!(untagged[0].tags.is_empty());
+    assert_eq!(tagged[0].tags, ["client", "work"]);
+    assert_eq!(tagged[0].name, "tagged");
+    assert!(cleared[0].tags.is_empty());
+}
+
+#[test]
+fn test_list_tree() {
+    let Some(server) = TestServer::new("tree") else {
+        return;
+    };
+    let client = &server.client;
+    assert!(client.list_tree().unwrap().is_empty());
+
+    client.create_session("tree-a", None, None).unwrap();
+    client.create_window("tree-a", Some("second")).unwrap();
+    client.create_session("tree-b", None, None).unwrap();
+    let window = client.list_windows("tree-a").unwrap()[1].id.clone();
+    client
+        .split_window(&window, SplitDirection::Vertical, None, None)
+        .unwrap();
+    let tree = client.list_tree().unwrap();
+
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree[0].name, "tree-a");
+    assert_eq!(tree[0].windows.len(), 2);
+    assert_eq!(tree[0].windows[1].window.name, "second");
+    assert_eq!(tree[0].windows[1].window.panes, 2);
+    assert_eq!(tree[0].windows[1].panes.len(), 2);
+    assert_eq!(tree[1].name, "tree-b");
+    assert_eq!(tree[1].windows[0].panes.len(), 1);
+}
+
+#[tokio::test]
+async fn test_blocking_runs_client_calls_off_the_runtime() {
+    let socket = format!("tmux-ui-test-blocking-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket));
+
+    let sessions = client.blocking(|c| c.list_sessions()).await.unwrap();
+    let missing = client.blocking(|c| c.list_panes("%tmux-ui-missing")).await;
+
+    assert!(sessions.is_empty());
+    assert!(missing.is_err());
+}
+
+#[test]
+fn test_errors_include_tmux_stderr() {
+    let Some(server) = TestServer::new("stderr") else {
+        return;
+    };
+    let client = &server.client;
+    client.create_session("stderr", None, None).unwrap();
+    let duplicate = client.create_session("stderr", None, None).unwrap_err();
+    let missing = client.kill_window("stderr:9").unwrap_err();
+    let gone = client.kill_session("gone").unwrap_err();
+
+    assert!(
+        matches!(&duplicate, Error::DuplicateSession(name) if name == "stderr"),
+        "{:#}",
+        duplicate
+    );
+    assert!(
+        matches!(&gone, Error::SessionNotFound(name) if name == "gone"),
+        "{:#}",
+        gone
+    );
+    assert!(
+        format!("{:#}", missing).contains("can't find window"),
+        "{:#}",
+        missing
+    );
+}
+
+#[test]
+fn test_missing_server_is_reported_as_such() {
+    let socket = format!("tmux-ui-test-noserver-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket));
+
+    let error = client.kill_session("anything").unwrap_err();
+    assert!(matches!(error, Error::ServerNotRunning), "{:#}", error);
+}
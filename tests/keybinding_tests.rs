@@ -0,0 +1,44 @@
+use tmux_ui::keybinding::{Keybinding, Launch};
+
+fn binding(launch: Launch) -> Keybinding {
+    Keybinding {
+        key: "S".to_string(),
+        no_prefix: false,
+        program: "/opt/my tools/tmux-ui".to_string(),
+        launch,
+    }
+}
+
+#[test]
+fn test_line_for_popup_and_window() {
+    let popup = binding(Launch::Popup("90%x70%".parse().unwrap()));
+    assert_eq!(
+        popup.line(),
+        "bind-key S run-shell -b \"'/opt/my tools/tmux-ui' --popup=90%x70%\""
+    );
+
+    let mut window = binding(Launch::Window);
+    window.key = "C-s".to_string();
+    window.no_prefix = true;
+    assert_eq!(
+        window.line(),
+        "bind-key -n C-s new-window -n tmux-ui \"'/opt/my tools/tmux-ui'\""
+    );
+}
+
+#[test]
+fn test_append_only_once() {
+    let path = std::env::temp_dir().join(format!("tmux-ui-test-conf-{}", std::process::id()));
+    std::fs::write(&path, "set -g mouse on").unwrap();
+    let binding = binding(Launch::Window);
+
+    assert!(binding.append_to(&path).unwrap());
+    assert!(!binding.append_to(&path).unwrap());
+    let conf = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        conf,
+        format!("set -g mouse on\n\n# Launch tmux-ui\n{}\n", binding.line())
+    );
+    assert!(binding.is_in(&conf));
+    let _ = std::fs::remove_file(&path);
+}
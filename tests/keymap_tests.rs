@@ -0,0 +1,262 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use tmux_ui::config::Config;
+use tmux_ui::tui::icons::IconSet;
+use tmux_ui::tui::keymap::{Action, KeyBinding, Keymap, Scope};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn test_default_bindings() {
+    let keymap = Keymap::default();
+    assert_eq!(
+        keymap.action(
+            &key(KeyCode::Char('q'), KeyModifiers::NONE),
+            Scope::Sessions
+        ),
+        Some(Action::Quit)
+    );
+    assert_eq!(
+        keymap.action(&key(KeyCode::Enter, KeyModifiers::NONE), Scope::Sessions),
+        Some(Action::Attach)
+    );
+    assert_eq!(
+        keymap.action(&key(KeyCode::Enter, KeyModifiers::SHIFT), Scope::Sessions),
+        Some(Action::AttachExclusive)
+    );
+    assert_eq!(
+        keymap.action(&key(KeyCode::Up, KeyModifiers::SHIFT), Scope::Sessions),
+        Some(Action::MoveWindowUp)
+    );
+    assert_eq!(
+        keymap.action(&key(KeyCode::Right, KeyModifiers::NONE), Scope::Sessions),
+        Some(Action::Expand)
+    );
+    assert_eq!(
+        keymap.action(&key(KeyCode::Up, KeyModifiers::NONE), Scope::Sessions),
+        Some(Action::Up)
+    );
+    // Terminals report Shift for upper-case characters
+    assert_eq!(
+        keymap.action(
+            &key(KeyCode::Char('R'), KeyModifiers::SHIFT),
+            Scope::Sessions
+        ),
+        Some(Action::Refresh)
+    );
+    assert_eq!(
+        keymap.action(
+            &key(KeyCode::Char('q'), KeyModifiers::CONTROL),
+            Scope::Sessions
+        ),
+        None
+    );
+    assert_eq!(keymap.label(Action::Attach, Scope::Sessions), "a/Enter");
+}
+
+#[test]
+fn test_overrides_replace_action_keys() {
+    let overrides = BTreeMap::from([
+        ("down".to_string(), vec!["j".to_string(), "C-n".to_string()]),
+        ("quit".to_string(), vec!["d".to_string()]),
+    ]);
+    let keymap = Keymap::with_overrides(&overrides).unwrap();
+
+    assert_eq!(
+        keymap.action(
+            &key(KeyCode::Char('j'), KeyModifiers::NONE),
+            Scope::Sessions
+        ),
+        Some(Action::Down)
+    );
+    assert_eq!(
+        keymap.action(
+            &key(KeyCode::Char('n'), KeyModifiers::CONTROL),
+            Scope::Sessions
+        ),
+        Some(Action::Down)
+    );
+    // The default key of an overridden action is gone
+    assert_eq!(
+        keymap.action(&key(KeyCode::Down, KeyModifiers::NONE), Scope::Sessions),
+        None
+    );
+    // A key taken by another action is removed from its default action
+    assert_eq!(
+        keymap.action(
+            &key(KeyCode::Char('d'), KeyModifiers::NONE),
+            Scope::Sessions
+        ),
+        Some(Action::Quit)
+    );
+    assert!(keymap.keys(Action::Kill, Scope::Sessions).is_empty());
+}
+
+#[test]
+fn test_invalid_overrides() {
+    let unknown = BTreeMap::from([("launch".to_string(), vec!["l".to_string()])]);
+    assert!(Keymap::with_overrides(&unknown).is_err());
+
+    let conflict = BTreeMap::from([
+        ("up".to_string(), vec!["k".to_string()]),
+        ("down".to_string(), vec!["k".to_string()]),
+    ]);
+    assert!(Keymap::with_overrides(&conflict).is_err());
+
+    assert!(KeyBinding::parse("Hyper").is_err());
+    assert!(KeyBinding::parse("C-C-x").is_err());
+    assert!(KeyBinding::parse("F5").is_ok());
+}
+
+#[test]
+fn test_keys_from_config() {
+    let config = Config::parse(
+        r#"
+        [keys]
+        up = "k"
+        down = ["j", "Down"]
+        "#,
+    )
+    .unwrap();
+    let keymap = config.keymap().unwrap();
+    assert_eq!(
+        keymap.action(
+            &key(KeyCode::Char('k'), KeyModifiers::NONE),
+            Scope::Sessions
+        ),
+        Some(Action::Up)
+    );
+    assert_eq!(
+        keymap.action(&key(KeyCode::Down, KeyModifiers::NONE), Scope::Sessions),
+        Some(Action::Down)
+    );
+
+    assert!(Config::parse("[keys]\nup = \"Hyper-k\"").is_err());
+}
+
+#[test]
+fn test_extended_keys_can_be_removed() {
+    assert!(KeyBinding::parse("S-Enter").unwrap().needs_extended_keys());
+    assert!(KeyBinding::parse("C-1").unwrap().needs_extended_keys());
+    assert!(!KeyBinding::parse("C-a").unwrap().needs_extended_keys());
+    assert!(!KeyBinding::parse("S-Up").unwrap().needs_extended_keys());
+    assert!(!KeyBinding::parse("Enter").unwrap().needs_extended_keys());
+
+    let mut keymap = Keymap::default();
+    keymap.remove_extended_keys();
+    assert_eq!(
+        keymap.action(&key(KeyCode::Enter, KeyModifiers::SHIFT), Scope::Sessions),
+        None
+    );
+    assert_eq!(keymap.label(Action::AttachExclusive, Scope::Sessions), "-");
+    assert_eq!(keymap.label(Action::Attach, Scope::Sessions), "a/Enter");
+    assert_eq!(
+        keymap.action(&key(KeyCode::Up, KeyModifiers::SHIFT), Scope::Sessions),
+        Some(Action::MoveWindowUp)
+    );
+}
+
+#[test]
+fn test_vim_mode_bindings() {
+    let config =
+        Config::parse("vim_mode = true\n[keys]\nhelp = \"?\"\nformat_query = \"F2\"").unwrap();
+    let keymap = config.keymap().unwrap();
+    assert!(keymap.is_vim());
+    assert!(!Keymap::default().is_vim());
+
+    let plain = |c| key(KeyCode::Char(c), KeyModifiers::NONE);
+    assert_eq!(
+        keymap.action(&plain('j'), Scope::Sessions),
+        Some(Action::Down)
+    );
+    assert_eq!(
+        keymap.action(&plain('k'), Scope::Sessions),
+        Some(Action::Up)
+    );
+    assert_eq!(
+        keymap.action(&plain('h'), Scope::Sessions),
+        Some(Action::Collapse)
+    );
+    assert_eq!(
+        keymap.action(&plain('l'), Scope::Sessions),
+        Some(Action::Expand)
+    );
+    assert_eq!(
+        keymap.action(&plain('G'), Scope::Sessions),
+        Some(Action::Bottom)
+    );
+    assert_eq!(
+        keymap.action(
+            &key(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            Scope::Sessions
+        ),
+        Some(Action::HalfPageDown)
+    );
+    // `g` is left unbound for `gg`; the actions vim keys took moved elsewhere
+    assert_eq!(keymap.action(&plain('g'), Scope::Sessions), None);
+    assert_eq!(keymap.label(Action::EditTags, Scope::Sessions), "#");
+    assert_eq!(keymap.label(Action::LastSession, Scope::Sessions), "'");
+    // Overrides apply on top of vim mode
+    assert_eq!(keymap.label(Action::Help, Scope::Sessions), "?");
+    assert_eq!(keymap.label(Action::Down, Scope::Sessions), "j/↓");
+}
+
+#[test]
+fn test_arrow_key_names_follow_the_icon_set() {
+    let keymap = Keymap::default().with_icons(IconSet::Ascii);
+    assert_eq!(keymap.label(Action::MoveWindowUp, Scope::Sessions), "S-Up");
+    assert_eq!(keymap.first_key(Action::Up, Scope::Sessions), "Up");
+    assert_eq!(
+        Keymap::default().first_key(Action::Up, Scope::Sessions),
+        "↑"
+    );
+    assert_eq!(
+        KeyBinding::parse("C-Left").unwrap().name(IconSet::Ascii),
+        "C-Left"
+    );
+}
+
+#[test]
+fn test_keys_bound_in_one_list() {
+    let keymap = Keymap::default();
+    let plain = |c| key(KeyCode::Char(c), KeyModifiers::NONE);
+    assert_eq!(
+        keymap.action(&plain('S'), Scope::Sessions),
+        Some(Action::SaveSnapshot)
+    );
+    assert_eq!(
+        keymap.action(&plain('S'), Scope::Windows),
+        Some(Action::ToggleSync)
+    );
+    assert_eq!(
+        keymap.action(&plain('c'), Scope::Sessions),
+        Some(Action::CloneSession)
+    );
+    assert_eq!(
+        keymap.action(&plain('c'), Scope::Windows),
+        Some(Action::SendCommand)
+    );
+    // Labels leave out the keys another action has in the list
+    assert_eq!(keymap.label(Action::SendCommand, Scope::Sessions), "e");
+    assert_eq!(keymap.label(Action::SendCommand, Scope::Panes), "c/e");
+    assert_eq!(keymap.label(Action::SaveSnapshot, Scope::Windows), "-");
+    assert_eq!(keymap.label(Action::ToggleSync, Scope::Windows), "S");
+    assert_eq!(keymap.label(Action::ToggleSync, Scope::Sessions), "-");
+
+    // A key from the config means the same in every list
+    let overrides = BTreeMap::from([("save_snapshot".to_string(), vec!["S".to_string()])]);
+    let keymap = Keymap::with_overrides(&overrides).unwrap();
+    assert_eq!(
+        keymap.action(&plain('S'), Scope::Windows),
+        Some(Action::SaveSnapshot)
+    );
+    let overrides = BTreeMap::from([("toggle_sync".to_string(), vec!["Y".to_string()])]);
+    let keymap = Keymap::with_overrides(&overrides).unwrap();
+    assert_eq!(
+        keymap.action(&plain('S'), Scope::Windows),
+        Some(Action::SaveSnapshot)
+    );
+    assert_eq!(keymap.label(Action::ToggleSync, Scope::Sessions), "Y");
+}
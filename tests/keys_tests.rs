@@ -0,0 +1,112 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tmux_ui::tmux::keys::{parse_key_name, parse_keys, Key};
+use tmux_ui::tui::remote::{is_exit_key, key_event_to_tmux};
+
+#[test]
+fn test_parse_keys_mixed_sequence() {
+    let keys = parse_keys("C-c 'make test' Enter").unwrap();
+    assert_eq!(
+        keys,
+        vec![
+            Key::Named("C-c".to_string()),
+            Key::Literal("make test".to_string()),
+            Key::Named("Enter".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_key_name_aliases() {
+    assert_eq!(parse_key_name("esc").unwrap(), "Escape");
+    assert_eq!(parse_key_name("PageUp").unwrap(), "PPage");
+    assert_eq!(parse_key_name("f5").unwrap(), "F5");
+    assert_eq!(parse_key_name("c-m-left").unwrap(), "C-M-Left");
+}
+
+#[test]
+fn test_parse_keys_double_quote_escapes() {
+    let keys = parse_keys(r#""echo \"hi\"""#).unwrap();
+    assert_eq!(keys, vec![Key::Literal("echo \"hi\"".to_string())]);
+}
+
+#[test]
+fn test_parse_keys_rejects_ambiguous_input() {
+    // Unquoted words must be quoted so they are never mistaken for key names
+    assert!(parse_keys("make").is_err());
+    assert!(parse_keys("x").is_err());
+    assert!(parse_keys("'unterminated").is_err());
+    assert!(parse_keys("C-C-c").is_err());
+}
+
+#[test]
+fn test_key_events_to_tmux() {
+    let ctrl = KeyModifiers::CONTROL;
+    let alt = KeyModifiers::ALT;
+    let none = KeyModifiers::NONE;
+    let literal = |text: &str| Some(Key::Literal(text.to_string()));
+    let named = |name: &str| Some(Key::Named(name.to_string()));
+
+    let cases = [
+        // Plain characters are typed as text, whatever their case
+        (KeyCode::Char('a'), none, literal("a")),
+        (KeyCode::Char('A'), KeyModifiers::SHIFT, literal("A")),
+        (KeyCode::Char(' '), none, literal(" ")),
+        // Modifiers turn them into key names
+        (KeyCode::Char('c'), ctrl, named("C-c")),
+        (KeyCode::Char('x'), alt, named("M-x")),
+        (KeyCode::Char('x'), ctrl | alt, named("C-M-x")),
+        (KeyCode::Char(' '), ctrl, named("C-Space")),
+        // Special keys use tmux's names
+        (KeyCode::Enter, none, named("Enter")),
+        (KeyCode::Esc, none, named("Escape")),
+        (KeyCode::BackTab, KeyModifiers::SHIFT, named("BTab")),
+        (KeyCode::Backspace, none, named("BSpace")),
+        (KeyCode::Delete, none, named("DC")),
+        (KeyCode::Insert, none, named("IC")),
+        (KeyCode::PageUp, none, named("PPage")),
+        (KeyCode::PageDown, none, named("NPage")),
+        (KeyCode::Left, ctrl, named("C-Left")),
+        (KeyCode::Up, alt, named("M-Up")),
+        (KeyCode::F(1), none, named("F1")),
+        (KeyCode::F(12), ctrl, named("C-F12")),
+        // Keys tmux has no name for are dropped
+        (KeyCode::CapsLock, none, None),
+        (KeyCode::Null, none, None),
+    ];
+    for (code, modifiers, expected) in cases {
+        assert_eq!(
+            key_event_to_tmux(&KeyEvent::new(code, modifiers)),
+            expected,
+            "{:?} with {:?}",
+            code,
+            modifiers
+        );
+    }
+}
+
+#[test]
+fn test_exit_key() {
+    let ctrl = KeyModifiers::CONTROL;
+    let alt = KeyModifiers::ALT;
+    let none = KeyModifiers::NONE;
+    let cases = [
+        (KeyCode::Char(']'), ctrl, true),
+        // The raw byte of Ctrl+] as crossterm decodes it
+        (KeyCode::Char('5'), ctrl, true),
+        (KeyCode::Char(']'), ctrl | alt, true),
+        (KeyCode::Char(']'), none, false),
+        (KeyCode::Char('5'), none, false),
+        (KeyCode::Char(']'), alt, false),
+        (KeyCode::Char('c'), ctrl, false),
+        (KeyCode::Esc, none, false),
+    ];
+    for (code, modifiers, expected) in cases {
+        assert_eq!(
+            is_exit_key(&KeyEvent::new(code, modifiers)),
+            expected,
+            "{:?} with {:?}",
+            code,
+            modifiers
+        );
+    }
+}
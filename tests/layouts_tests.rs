@@ -0,0 +1,34 @@
+use std::fs;
+use tmux_ui::layouts::SavedLayouts;
+
+#[test]
+fn test_saved_layouts_round_trip() {
+    let dir = std::env::temp_dir().join(format!("tmux-ui-test-layouts-{}", std::process::id()));
+    let path = dir.join("layouts.toml");
+
+    // Nothing saved yet
+    let mut layouts = SavedLayouts::load(&path).unwrap();
+    assert!(layouts.is_empty());
+
+    layouts
+        .insert(" editor ", "8205,80x24,0,0{40x24,0,0,0,39x24,41,0,1}")
+        .unwrap();
+    layouts.insert("logs", "b25d,80x24,0,0,1").unwrap();
+    assert!(layouts.insert("  ", "b25d,80x24,0,0,1").is_err());
+    layouts.save(&path).unwrap();
+
+    let mut loaded = SavedLayouts::load(&path).unwrap();
+    assert_eq!(loaded, layouts);
+    assert_eq!(loaded.names().collect::<Vec<_>>(), vec!["editor", "logs"]);
+    assert_eq!(
+        loaded.get("editor"),
+        Some("8205,80x24,0,0{40x24,0,0,0,39x24,41,0,1}")
+    );
+    assert!(loaded.remove("logs"));
+    assert!(!loaded.remove("logs"));
+
+    fs::write(&path, "editor = 3").unwrap();
+    let invalid = SavedLayouts::load(&path);
+    let _ = fs::remove_dir_all(&dir);
+    assert!(invalid.is_err());
+}
@@ -0,0 +1,1029 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tmux_ui::{
+    config::Config,
+    tmux::{
+        mock::MockBackend, Capabilities, HookScope, Key, OptionScope, SplitDirection, TmuxBackend,
+        HOOK_TEMPLATES,
+    },
+    tui::App,
+};
+
+async fn press(app: &mut App<MockBackend>, code: KeyCode) -> bool {
+    app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+        .await
+        .unwrap()
+}
+
+async fn type_text(app: &mut App<MockBackend>, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_create_session_from_prompt() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('n')).await;
+    type_text(&mut app, "notes").await;
+    assert!(!press(&mut app, KeyCode::Enter).await);
+
+    assert_eq!(mock.session_names(), vec!["work", "notes"]);
+    assert!(mock.calls().contains(&"new-session notes".to_string()));
+}
+
+#[tokio::test]
+async fn test_create_session_with_directory_and_command() {
+    let mock = MockBackend::new();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('n')).await;
+    type_text(&mut app, "api").await;
+    press(&mut app, KeyCode::Tab).await;
+    type_text(&mut app, "/does/not/exist").await;
+    press(&mut app, KeyCode::Enter).await;
+    assert!(mock.session_names().is_empty());
+
+    // The directory is rejected, and what was typed into the other fields is kept
+    for _ in "/does/not/exist".chars() {
+        press(&mut app, KeyCode::Backspace).await;
+    }
+    type_text(&mut app, "/tmp").await;
+    press(&mut app, KeyCode::Tab).await;
+    type_text(&mut app, "vim .").await;
+    press(&mut app, KeyCode::Enter).await;
+
+    assert_eq!(mock.session_names(), vec!["api"]);
+    assert!(mock
+        .calls()
+        .contains(&"new-session api -c /tmp vim .".to_string()));
+    let session = &mock.list_sessions_with_columns(&[]).unwrap()[0];
+    assert_eq!(session.current_command, "vim");
+    assert_eq!(session.current_path, "/tmp");
+}
+
+#[tokio::test]
+async fn test_clone_selected_session() {
+    let mock = MockBackend::new().with_session("dev").with_session("dev-2");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // The suggested name skips names already in use
+    press(&mut app, KeyCode::Char('c')).await;
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Enter).await;
+
+    assert_eq!(mock.session_names(), vec!["dev", "dev-2", "dev-3"]);
+    assert!(mock
+        .calls()
+        .contains(&"clone-session $1 dev-3 -n".to_string()));
+}
+
+#[tokio::test]
+async fn test_blank_session_name_is_not_created() {
+    let mock = MockBackend::new();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('n')).await;
+    type_text(&mut app, "  ").await;
+    press(&mut app, KeyCode::Enter).await;
+    assert!(mock.session_names().is_empty());
+
+    // Still in the prompt, so the name can be fixed
+    type_text(&mut app, "a:b").await;
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(mock.session_names(), vec!["a_b"]);
+}
+
+#[tokio::test]
+async fn test_rename_edits_the_current_name() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('r')).await;
+    press(&mut app, KeyCode::Home).await;
+    type_text(&mut app, "my-").await;
+    press(&mut app, KeyCode::End).await;
+    press(&mut app, KeyCode::Left).await;
+    press(&mut app, KeyCode::Left).await;
+    press(&mut app, KeyCode::Delete).await;
+    type_text(&mut app, "n").await;
+    press(&mut app, KeyCode::Enter).await;
+
+    assert_eq!(mock.session_names(), vec!["my-wonk"]);
+
+    // Pasted text is inserted at the cursor
+    press(&mut app, KeyCode::Char('r')).await;
+    press(&mut app, KeyCode::Home).await;
+    app.handle_paste("team-\n");
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(mock.session_names(), vec!["team-my-wonk"]);
+}
+
+#[tokio::test]
+async fn test_remote_typing_forwards_keys_and_pastes_in_order() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('T')).await;
+    type_text(&mut app, "ls ").await;
+    app.handle_paste("Enter -la");
+    press(&mut app, KeyCode::Char('!')).await;
+
+    // Keys are sent in the background; wait for the last one to arrive
+    let sent = || -> Vec<String> {
+        mock.calls()
+            .into_iter()
+            .filter(|c| c.starts_with("send-keys"))
+            .collect()
+    };
+    for _ in 0..200 {
+        if sent().len() == 5 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    // The paste goes out as one literal, so its "Enter" is not read as the key
+    let id = &mock.list_sessions_with_columns(&[]).unwrap()[0].id;
+    let expected: Vec<String> = ["'l'", "'s'", "' '", "'Enter -la'", "'!'"]
+        .iter()
+        .map(|key| format!("send-keys {} {}", id, key))
+        .collect();
+    assert_eq!(sent(), expected);
+}
+
+#[tokio::test]
+async fn test_new_window_follows_a_session_renamed_meanwhile() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    mock.rename_session("work", "api").unwrap();
+    press(&mut app, KeyCode::Char('w')).await;
+    assert_eq!(mock.calls().last(), Some(&"new-window $1".to_string()));
+    assert_eq!(mock.list_windows("api").unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_kill_selected_session() {
+    let mock = MockBackend::new().with_session("one").with_session("two");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('d')).await;
+
+    assert_eq!(mock.session_names(), vec!["two"]);
+    assert!(mock.calls().contains(&"kill-session $1".to_string()));
+}
+
+#[test]
+fn test_mock_send_keys_shows_in_capture() {
+    let mock = MockBackend::new().with_session("work");
+    let pane = &mock.list_panes("work").unwrap()[0].id;
+    mock.send_keys(pane, &[Key::Literal("echo hi".to_string())], true)
+        .unwrap();
+    assert!(mock.capture_pane(pane).unwrap().contains("echo hi"));
+}
+
+#[tokio::test]
+async fn test_paste_buffer_into_selected_session() {
+    let mock = MockBackend::new()
+        .with_session("work")
+        .with_buffer("make test")
+        .with_buffer("git status");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // The most recent buffer is selected first
+    press(&mut app, KeyCode::Char('B')).await;
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Enter).await;
+    assert!(mock
+        .calls()
+        .contains(&"paste-buffer -b buffer0 -t $1".to_string()));
+    let pane = &mock.list_panes("work").unwrap()[0].id;
+    assert!(mock.capture_pane(pane).unwrap().contains("make test"));
+
+    press(&mut app, KeyCode::Char('B')).await;
+    press(&mut app, KeyCode::Char('d')).await;
+    let names: Vec<String> = mock
+        .list_buffers()
+        .unwrap()
+        .into_iter()
+        .map(|b| b.name)
+        .collect();
+    assert_eq!(names, vec!["buffer0"]);
+
+    // Deleting the last buffer closes the browser
+    press(&mut app, KeyCode::Char('d')).await;
+    assert!(mock.list_buffers().unwrap().is_empty());
+    press(&mut app, KeyCode::Char('n')).await;
+    type_text(&mut app, "notes").await;
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(mock.session_names(), vec!["work", "notes"]);
+}
+
+#[tokio::test]
+async fn test_zoom_selected_pane() {
+    let mock = MockBackend::new().with_session("work");
+    let pane = mock.list_panes("work").unwrap()[0].id.clone();
+    let split = mock
+        .split_window(&pane, SplitDirection::Horizontal, None, None)
+        .unwrap();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // Sessions -> windows -> panes, then zoom the second pane
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Char('z')).await;
+    assert!(mock.calls().contains(&format!("resize-pane -Z {}", split)));
+    assert!(mock.list_windows("work").unwrap()[0].zoomed);
+
+    press(&mut app, KeyCode::Char('z')).await;
+    assert!(!mock.list_windows("work").unwrap()[0].zoomed);
+}
+
+#[tokio::test]
+async fn test_move_window_to_session_from_picker() {
+    let mock = MockBackend::new()
+        .with_session("api")
+        .with_session("web")
+        .with_session("docs");
+    mock.create_window("api", Some("logs")).unwrap();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    let logs = mock.list_windows("api").unwrap().remove(1);
+
+    // The new window is the active one, so it is selected; the picker lists the
+    // other sessions of the server, in list order
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char('m')).await;
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Enter).await;
+
+    assert!(mock
+        .calls()
+        .contains(&format!("move-window {} docs", logs.id)));
+    assert_eq!(mock.list_windows("api").unwrap().len(), 1);
+    let docs = mock.list_windows("docs").unwrap();
+    assert_eq!(docs.len(), 2);
+    assert_eq!(docs[1].name, "logs");
+}
+
+#[tokio::test]
+async fn test_prune_stale_sessions() {
+    let mock = MockBackend::new()
+        .with_session("work")
+        .with_idle_session("old", 30)
+        .with_idle_session("older", 90)
+        .with_idle_session("recent", 2)
+        .with_client("older", "/dev/pts/1");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // Only detached sessions idle for more than the default 7 days are killed
+    press(&mut app, KeyCode::Char('Z')).await;
+    press(&mut app, KeyCode::Char('y')).await;
+    assert_eq!(mock.session_names(), vec!["work", "older", "recent"]);
+
+    // Nothing is left to prune
+    press(&mut app, KeyCode::Char('Z')).await;
+    press(&mut app, KeyCode::Char('y')).await;
+    assert_eq!(mock.session_names(), vec!["work", "older", "recent"]);
+}
+
+#[tokio::test]
+async fn test_link_and_unlink_window() {
+    let mock = MockBackend::new().with_session("api").with_session("web");
+    mock.create_window("api", Some("logs")).unwrap();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    let logs = mock.list_windows("api").unwrap().remove(1);
+
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char('I')).await;
+    press(&mut app, KeyCode::Enter).await;
+
+    assert!(mock
+        .calls()
+        .contains(&format!("link-window {} web", logs.id)));
+    let web = mock.list_windows("web").unwrap();
+    assert_eq!(web.len(), 2);
+    assert_eq!(web[1].id, logs.id);
+    assert!(web[1].linked);
+    assert!(mock.list_windows("api").unwrap()[1].linked);
+
+    // Unlinking keeps the window in the other session
+    press(&mut app, KeyCode::Char('U')).await;
+    assert!(mock
+        .calls()
+        .contains(&format!("unlink-window api {}", logs.id)));
+    assert_eq!(mock.list_windows("api").unwrap().len(), 1);
+    let web = mock.list_windows("web").unwrap();
+    assert_eq!(web[1].id, logs.id);
+    assert!(!web[1].linked);
+}
+
+#[tokio::test]
+async fn test_swap_and_rotate_panes() {
+    let mock = MockBackend::new().with_session("work");
+    let first = mock.list_panes("work").unwrap()[0].id.clone();
+    let second = mock
+        .split_window(&first, SplitDirection::Horizontal, None, None)
+        .unwrap();
+    let third = mock
+        .split_window(&second, SplitDirection::Vertical, None, None)
+        .unwrap();
+    let ids = |mock: &MockBackend| -> Vec<String> {
+        mock.list_panes("work")
+            .unwrap()
+            .into_iter()
+            .map(|p| p.id)
+            .collect()
+    };
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // The selection follows the moved pane, so it can be moved on
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Tab).await;
+    for _ in 0..2 {
+        app.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::SHIFT))
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        ids(&mock),
+        vec![second.clone(), third.clone(), first.clone()]
+    );
+    app.handle_key(KeyEvent::new(KeyCode::Up, KeyModifiers::SHIFT))
+        .await
+        .unwrap();
+    assert_eq!(
+        ids(&mock),
+        vec![second.clone(), first.clone(), third.clone()]
+    );
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL))
+        .await
+        .unwrap();
+    assert_eq!(ids(&mock), vec![third, second, first]);
+}
+
+#[tokio::test]
+async fn test_kill_pane_and_window_after_confirmation() {
+    let mock = MockBackend::new().with_session("work");
+    let pane = mock.list_panes("work").unwrap()[0].id.clone();
+    mock.split_window(&pane, SplitDirection::Horizontal, None, None)
+        .unwrap();
+    mock.create_window("work", Some("logs")).unwrap();
+    let window = mock.list_windows("work").unwrap()[0].id.clone();
+    mock.select_window(&window).unwrap();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // Any key but y cancels
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char('d')).await;
+    press(&mut app, KeyCode::Char('n')).await;
+    assert_eq!(mock.list_panes(&window).unwrap().len(), 2);
+
+    press(&mut app, KeyCode::Char('d')).await;
+    press(&mut app, KeyCode::Char('y')).await;
+    assert!(mock
+        .calls()
+        .iter()
+        .any(|call| call.starts_with("kill-pane")));
+    assert_eq!(mock.list_panes(&window).unwrap().len(), 1);
+
+    // Back in the window list, kill the second window
+    press(&mut app, KeyCode::Esc).await;
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Char('d')).await;
+    press(&mut app, KeyCode::Char('y')).await;
+    let windows = mock.list_windows("work").unwrap();
+    assert_eq!(windows.len(), 1);
+    assert_ne!(windows[0].name, "logs");
+}
+
+#[tokio::test]
+async fn test_respawn_dead_pane_with_a_new_command() {
+    let mock = MockBackend::new();
+    mock.create_session("dev", None, Some("npm run dev"))
+        .unwrap();
+    let pane = mock.list_panes("dev").unwrap()[0].id.clone();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Tab).await;
+
+    // A running pane is left alone
+    press(&mut app, KeyCode::Char('r')).await;
+    mock.exit_pane(&pane);
+    press(&mut app, KeyCode::Char('R')).await;
+    assert!(mock.list_panes("dev").unwrap()[0].dead);
+
+    // The prompt starts out with the original command
+    press(&mut app, KeyCode::Char('r')).await;
+    type_text(&mut app, " -- --port 3001").await;
+    press(&mut app, KeyCode::Enter).await;
+    assert!(mock
+        .calls()
+        .contains(&format!("respawn-pane {} npm run dev -- --port 3001", pane)));
+    assert!(!mock.list_panes("dev").unwrap()[0].dead);
+}
+
+#[tokio::test]
+async fn test_toggle_pane_log() {
+    let dir = std::env::temp_dir().join(format!("tmux-ui-pane-logs-{}", std::process::id()));
+    let config = Config::parse(&format!("pane_log_dir = {:?}", dir.display().to_string())).unwrap();
+    let mock = MockBackend::new().with_session("work");
+    let pane = mock.list_panes("work").unwrap()[0].id.clone();
+    let mut app = App::new(mock.clone()).with_config(config);
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Tab).await;
+
+    press(&mut app, KeyCode::Char('o')).await;
+    assert!(mock.list_panes("work").unwrap()[0].piped);
+    let call = format!("pipe-pane {} {}", pane, dir.display());
+    assert!(mock
+        .calls()
+        .iter()
+        .any(|c| c.starts_with(&call) && c.ends_with(".log")));
+    assert!(dir.is_dir());
+
+    press(&mut app, KeyCode::Char('o')).await;
+    assert!(!mock.list_panes("work").unwrap()[0].piped);
+    assert_eq!(mock.calls().last(), Some(&format!("pipe-pane {}", pane)));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_run_command_in_new_window_or_popup() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // Outside tmux there is no popup to switch to
+    press(&mut app, KeyCode::Char('!')).await;
+    type_text(&mut app, "make test").await;
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Enter).await;
+    assert!(mock
+        .calls()
+        .contains(&"new-window $1 make test".to_string()));
+    let windows = mock.list_windows("work").unwrap();
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows[1].name, "make");
+
+    let mock = MockBackend::new()
+        .with_session("work")
+        .inside_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Char('!')).await;
+    type_text(&mut app, "htop").await;
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(mock.calls(), vec!["display-popup $1 80%x80% htop"]);
+}
+
+#[tokio::test]
+async fn test_apply_layout_preset_or_string() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    let window = mock.list_windows("work").unwrap().remove(0);
+
+    // The presets come first, in tmux's order
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char(' ')).await;
+    for _ in 0..4 {
+        press(&mut app, KeyCode::Down).await;
+    }
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(
+        mock.calls(),
+        vec![format!("select-layout {} tiled", window.id)]
+    );
+
+    // The custom entry prompts for a string; tmux's rejection keeps the prompt open
+    press(&mut app, KeyCode::Char(' ')).await;
+    for _ in 0..3 {
+        press(&mut app, KeyCode::Up).await;
+    }
+    press(&mut app, KeyCode::Enter).await;
+    app.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL))
+        .await
+        .unwrap();
+    type_text(&mut app, "bogus").await;
+    press(&mut app, KeyCode::Enter).await;
+    app.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL))
+        .await
+        .unwrap();
+    type_text(&mut app, "b25d,80x24,0,0,1").await;
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(
+        mock.calls()[1..],
+        [
+            format!("select-layout {} bogus", window.id),
+            format!("select-layout {} b25d,80x24,0,0,1", window.id),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_save_and_apply_named_layout() {
+    let dir = std::env::temp_dir().join(format!("tmux-ui-test-saved-{}", std::process::id()));
+    let path = dir.join("layouts.toml");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "dev = \"b25d,80x24,0,0,1\"\n").unwrap();
+    let config =
+        Config::parse(&format!("layouts_file = {:?}", path.display().to_string())).unwrap();
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone()).with_config(config);
+    app.load_initial_state().await.unwrap();
+    let window = mock.list_windows("work").unwrap().remove(0);
+
+    // Saved layouts follow the five presets
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char(' ')).await;
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Down).await;
+    }
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(
+        mock.calls(),
+        vec![format!("select-layout {} b25d,80x24,0,0,1", window.id)]
+    );
+
+    // The last entry saves the window's #{window_layout} under a name
+    press(&mut app, KeyCode::Char(' ')).await;
+    press(&mut app, KeyCode::Up).await;
+    press(&mut app, KeyCode::Enter).await;
+    type_text(&mut app, "wide").await;
+    press(&mut app, KeyCode::Enter).await;
+    let saved = std::fs::read_to_string(&path).unwrap();
+
+    // d deletes the selected saved layout
+    press(&mut app, KeyCode::Char(' ')).await;
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Down).await;
+    }
+    press(&mut app, KeyCode::Char('d')).await;
+    press(&mut app, KeyCode::Esc).await;
+    let after_delete = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(saved.contains("dev = \"b25d,80x24,0,0,1\""));
+    assert!(saved.contains("wide = \"#{window_layout}\""));
+    assert!(!after_delete.contains("dev"));
+    assert!(after_delete.contains("wide"));
+}
+
+#[tokio::test]
+async fn test_scratch_terminal_in_popup() {
+    // Outside tmux there is no client to show the popup on
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Char('`')).await;
+    assert!(mock.calls().is_empty());
+
+    let mock = MockBackend::new()
+        .with_session("work")
+        .inside_session("work");
+    let config = Config::parse("popup_size = \"100x30\"").unwrap();
+    let mut app = App::new(mock.clone()).with_config(config);
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Char('`')).await;
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char('`')).await;
+    let window = mock.list_windows("work").unwrap().remove(0);
+    assert_eq!(
+        mock.calls(),
+        vec![
+            "display-popup $1 100x30".to_string(),
+            format!("display-popup {} 100x30", window.id),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_toggle_synchronize_panes() {
+    let mock = MockBackend::new().with_session("fleet");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char('S')).await;
+    let window = mock.list_windows("fleet").unwrap().remove(0);
+    assert!(window.synchronized);
+    assert!(mock
+        .calls()
+        .contains(&format!("set-option -w synchronize-panes on {}", window.id)));
+
+    press(&mut app, KeyCode::Char('S')).await;
+    assert!(!mock.list_windows("fleet").unwrap()[0].synchronized);
+}
+
+#[tokio::test]
+async fn test_rename_window_keeps_automatic_rename_unless_configured() {
+    for lock in [false, true] {
+        let mock = MockBackend::new().with_session("fleet");
+        let config = Config::parse(&format!("lock_window_names = {}", lock)).unwrap();
+        let mut app = App::new(mock.clone()).with_config(config);
+        app.load_initial_state().await.unwrap();
+
+        press(&mut app, KeyCode::Tab).await;
+        press(&mut app, KeyCode::Char('r')).await;
+        type_text(&mut app, "server").await;
+        press(&mut app, KeyCode::Enter).await;
+        let window = mock.list_windows("fleet").unwrap().remove(0);
+        assert_eq!(window.name, "server");
+        assert_eq!(window.automatic_rename, !lock);
+    }
+}
+
+#[tokio::test]
+async fn test_edit_session_option() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    let scope = OptionScope::Session("$1".to_string());
+
+    // Filter down to history-limit and replace its value
+    press(&mut app, KeyCode::Char('O')).await;
+    type_text(&mut app, "hist").await;
+    press(&mut app, KeyCode::Enter).await;
+    app.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL))
+        .await
+        .unwrap();
+    type_text(&mut app, "50000").await;
+    press(&mut app, KeyCode::Enter).await;
+    let option = mock.show_options(&scope).unwrap().remove(0);
+    assert_eq!(option.name, "history-limit");
+    assert_eq!(option.value, "50000");
+    assert!(!option.inherited);
+
+    // Delete goes back to the global value
+    press(&mut app, KeyCode::Delete).await;
+    assert!(mock.show_options(&scope).unwrap()[0].inherited);
+    assert!(mock
+        .calls()
+        .contains(&"set-option -u session $1 history-limit".to_string()));
+}
+
+#[tokio::test]
+async fn test_edit_session_environment() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // Add a variable, then fix its value
+    press(&mut app, KeyCode::Char('E')).await;
+    press(&mut app, KeyCode::Char('n')).await;
+    type_text(&mut app, "SSH_AUTH_SOCK").await;
+    // Without a value the prompt stays open
+    press(&mut app, KeyCode::Enter).await;
+    type_text(&mut app, "=/tmp/old").await;
+    press(&mut app, KeyCode::Enter).await;
+    press(&mut app, KeyCode::Enter).await;
+    app.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL))
+        .await
+        .unwrap();
+    type_text(&mut app, "/tmp/new").await;
+    press(&mut app, KeyCode::Enter).await;
+    let variables = mock.show_environment(Some("$1")).unwrap();
+    assert_eq!(variables.len(), 1);
+    assert_eq!(variables[0].value.as_deref(), Some("/tmp/new"));
+
+    // Tab switches between the global environment and the session's
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char('d')).await;
+    assert!(mock
+        .calls()
+        .contains(&"set-environment -u -g HOME".to_string()));
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char('d')).await;
+    assert!(mock.show_environment(Some("$1")).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_manage_session_hooks() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    let scope = HookScope::Session("$1".to_string());
+
+    // Add a hook from the first template, then one typed in
+    press(&mut app, KeyCode::Char('K')).await;
+    press(&mut app, KeyCode::Char('t')).await;
+    press(&mut app, KeyCode::Enter).await;
+    press(&mut app, KeyCode::Char('n')).await;
+    type_text(&mut app, "alert-activity display-message again").await;
+    press(&mut app, KeyCode::Enter).await;
+    let hooks = mock.show_hooks(&scope).unwrap();
+    assert_eq!(hooks.len(), 2);
+    assert_eq!(hooks[0].command, HOOK_TEMPLATES[0].command);
+    assert_eq!(hooks[1].target(), "alert-activity[1]");
+
+    // Edit the selected command in place, then remove it
+    press(&mut app, KeyCode::Enter).await;
+    app.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL))
+        .await
+        .unwrap();
+    type_text(&mut app, "display-message edited").await;
+    press(&mut app, KeyCode::Enter).await;
+    assert!(mock
+        .calls()
+        .contains(&"set-hook session $1 alert-activity[0] display-message edited".to_string()));
+    press(&mut app, KeyCode::Char('d')).await;
+    let hooks = mock.show_hooks(&scope).unwrap();
+    assert_eq!(hooks.len(), 1);
+    assert_eq!(hooks[0].command, "display-message again");
+    assert!(mock.show_hooks(&HookScope::Global).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_detach_one_client() {
+    let mock = MockBackend::new()
+        .with_session("work")
+        .with_client("work", "/dev/pts/1")
+        .with_client("work", "/dev/pts/2");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('X')).await;
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Char('x')).await;
+    assert_eq!(mock.calls(), ["detach-client -t /dev/pts/2"]);
+    let clients = mock.list_clients().unwrap();
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0].name, "/dev/pts/1");
+    assert_eq!(mock.list_sessions_with_columns(&[]).unwrap()[0].clients, 1);
+}
+
+#[tokio::test]
+async fn test_detach_asks_which_client() {
+    let mock = MockBackend::new()
+        .with_session("work")
+        .with_client("work", "/dev/pts/1")
+        .with_client("work", "/dev/pts/2")
+        .with_session("solo")
+        .with_client("solo", "/dev/pts/3");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // Several clients: pick the second one
+    press(&mut app, KeyCode::Char('x')).await;
+    assert!(mock.calls().is_empty());
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(mock.calls(), ["detach-client -t /dev/pts/2"]);
+
+    // A single client is detached right away
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Char('x')).await;
+    assert_eq!(mock.calls().last().unwrap(), "detach-client -t /dev/pts/3");
+
+    // Nothing attached any more: nothing to do
+    press(&mut app, KeyCode::Char('x')).await;
+    assert_eq!(mock.calls().len(), 2);
+}
+
+#[tokio::test]
+async fn test_detach_all_clients_from_picker() {
+    let mock = MockBackend::new()
+        .with_session("work")
+        .with_client("work", "/dev/pts/1")
+        .with_client("work", "/dev/pts/2");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // The last entry detaches every client
+    press(&mut app, KeyCode::Char('x')).await;
+    press(&mut app, KeyCode::Up).await;
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(mock.calls(), ["detach-client -s work"]);
+    assert!(mock.list_clients().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_last_session_and_history() {
+    let mock = MockBackend::new()
+        .with_session("home")
+        .with_session("one")
+        .with_session("two")
+        .inside_session("home");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Char('a')).await;
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Char('a')).await;
+    assert_eq!(mock.client_session().as_deref(), Some("two"));
+
+    press(&mut app, KeyCode::Char('l')).await;
+    assert_eq!(mock.client_session().as_deref(), Some("one"));
+    assert!(mock.calls().contains(&"switch-client -l".to_string()));
+
+    // History is one, two, home; pick the second entry
+    press(&mut app, KeyCode::Char('H')).await;
+    press(&mut app, KeyCode::Down).await;
+    assert!(!press(&mut app, KeyCode::Enter).await);
+    assert_eq!(mock.client_session().as_deref(), Some("two"));
+}
+
+#[tokio::test]
+async fn test_switching_to_a_vanished_session_offers_to_create_it() {
+    let mock = MockBackend::new()
+        .with_session("home")
+        .with_session("gone")
+        .inside_session("home");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // Killed behind the TUI's back, so the list still shows it
+    mock.kill_session("gone").unwrap();
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Char('a')).await;
+    assert_eq!(mock.session_names(), vec!["home"]);
+
+    // The new-session prompt opens with the name filled in
+    assert!(!press(&mut app, KeyCode::Enter).await);
+    assert_eq!(mock.session_names(), vec!["home", "gone"]);
+}
+
+#[tokio::test]
+async fn test_duplicate_session_name_stays_in_the_prompt() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('n')).await;
+    type_text(&mut app, "work").await;
+    press(&mut app, KeyCode::Enter).await;
+
+    // The name is still there to be changed
+    type_text(&mut app, "-2").await;
+    press(&mut app, KeyCode::Enter).await;
+    assert_eq!(mock.session_names(), vec!["work", "work-2"]);
+}
+
+#[tokio::test]
+async fn test_old_tmux_hides_what_it_lacks() {
+    // A configured terminal is used instead of a popup
+    if std::env::var_os("TERMINAL").is_some() {
+        return;
+    }
+    let old = Capabilities::new(Some("3.0".parse().unwrap()));
+    let mock = MockBackend::new()
+        .with_session("home")
+        .with_session("work")
+        .inside_session("home");
+    let mut app = App::new(mock.clone()).with_capabilities(old);
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Down).await;
+
+    // tmux 3.0 passes neither S-Enter on nor has popups
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT))
+        .await
+        .unwrap();
+    press(&mut app, KeyCode::Char('A')).await;
+    assert!(mock.calls().is_empty());
+
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Down).await;
+    press(&mut app, KeyCode::Char('A')).await;
+    assert_eq!(mock.calls(), vec!["display-popup $2"]);
+}
+
+#[tokio::test]
+async fn test_kill_session_with_running_jobs_asks_first() {
+    let mock = MockBackend::new().with_session("build");
+    mock.run_in_window("build", "cargo build").unwrap();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    // Any key but `y` keeps the session and its build
+    press(&mut app, KeyCode::Char('d')).await;
+    assert_eq!(mock.running_commands("build").unwrap(), vec!["cargo"]);
+    press(&mut app, KeyCode::Char('n')).await;
+    assert_eq!(mock.session_names(), vec!["build"]);
+
+    press(&mut app, KeyCode::Char('d')).await;
+    press(&mut app, KeyCode::Char('y')).await;
+    assert!(mock.session_names().is_empty());
+}
+
+#[tokio::test]
+async fn test_undo_kill_session_and_window() {
+    let mock = MockBackend::new().with_session("work");
+    mock.create_window("work", Some("logs")).unwrap();
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('d')).await;
+    assert!(mock.session_names().is_empty());
+    press(&mut app, KeyCode::Char('u')).await;
+    assert_eq!(mock.session_names(), vec!["work"]);
+    let windows = mock.list_windows("work").unwrap();
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows[1].name, "logs");
+
+    // The window list starts at the active window, logs, which is killed and brought back
+    press(&mut app, KeyCode::Tab).await;
+    press(&mut app, KeyCode::Char('d')).await;
+    press(&mut app, KeyCode::Char('y')).await;
+    assert_eq!(mock.list_windows("work").unwrap().len(), 1);
+    press(&mut app, KeyCode::Char('u')).await;
+    let windows = mock.list_windows("work").unwrap();
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows[1].name, "logs");
+    assert!(mock
+        .calls()
+        .contains(&"restore-window work logs".to_string()));
+
+    // Each kill is undone only once
+    press(&mut app, KeyCode::Char('u')).await;
+    assert_eq!(mock.list_windows("work").unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_archive_and_restore_session() {
+    let dir = std::env::temp_dir().join(format!("tmux-ui-test-archived-{}", std::process::id()));
+    let config = Config::parse(&format!("archive_dir = {:?}", dir.display().to_string())).unwrap();
+    let mock = MockBackend::new().with_session("work");
+    mock.create_window("work", Some("logs")).unwrap();
+    mock.set_session_tags("work", &["api".to_string()]).unwrap();
+    let mut app = App::new(mock.clone()).with_config(config);
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char('v')).await;
+    assert!(mock.session_names().is_empty());
+    let files = std::fs::read_dir(&dir).unwrap().count();
+
+    // Restoring brings back the windows and tags, and empties the archive
+    press(&mut app, KeyCode::Char('F')).await;
+    press(&mut app, KeyCode::Enter).await;
+    let windows = mock.list_windows("work");
+    let remaining = std::fs::read_dir(&dir).unwrap().count();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(files, 1);
+    assert_eq!(remaining, 0);
+    assert_eq!(mock.session_names(), vec!["work"]);
+    let windows = windows.unwrap();
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows[1].name, "logs");
+    assert_eq!(
+        mock.list_sessions_with_columns(&[]).unwrap()[0].tags,
+        vec!["api"]
+    );
+}
+
+#[tokio::test]
+async fn test_raw_command_prompt_completes_command_names() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+
+    press(&mut app, KeyCode::Char(':')).await;
+    type_text(&mut app, "list-se").await;
+    press(&mut app, KeyCode::Tab).await;
+    type_text(&mut app, "-F x").await;
+    press(&mut app, KeyCode::Enter).await;
+
+    assert!(mock.calls().contains(&"list-sessions -F x".to_string()));
+}
+
+#[tokio::test]
+async fn test_popup_closes_after_switching() {
+    let mock = MockBackend::new()
+        .with_session("home")
+        .with_session("work")
+        .inside_session("home");
+    let mut app = App::new(mock.clone());
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Down).await;
+    assert!(!press(&mut app, KeyCode::Char('a')).await);
+
+    let mut app = App::new(mock.clone()).with_popup(true);
+    app.load_initial_state().await.unwrap();
+    press(&mut app, KeyCode::Down).await;
+    assert!(press(&mut app, KeyCode::Char('a')).await);
+    assert_eq!(mock.calls(), vec!["switch-client $2", "switch-client $2"]);
+}
@@ -0,0 +1,22 @@
+use tmux_ui::tmux::options::parse_option_line;
+
+#[test]
+fn test_parse_option_lines() {
+    let option = parse_option_line("history-limit* 2000").unwrap();
+    assert_eq!(option.name, "history-limit");
+    assert_eq!(option.value, "2000");
+    assert!(option.inherited);
+
+    let option = parse_option_line(r#"status-left "[x \"y\"] \\ ok""#).unwrap();
+    assert_eq!(option.value, r#"[x "y"] \ ok"#);
+    assert!(!option.inherited);
+
+    let option = parse_option_line("default-command* ''").unwrap();
+    assert_eq!(option.value, "");
+
+    let option = parse_option_line(r#"command-alias[2] "server-info=show-messages -JT""#).unwrap();
+    assert_eq!(option.name, "command-alias[2]");
+    assert_eq!(option.value, "server-info=show-messages -JT");
+
+    assert!(parse_option_line("").is_none());
+}
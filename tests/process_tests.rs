@@ -0,0 +1,37 @@
+use std::process::Command;
+use tmux_ui::process::{parse_stat, process_tree};
+
+#[test]
+fn test_parse_stat_with_parentheses_in_name() {
+    let stat = "4242 (my (odd) prog) S 17 4242 17 34816 4242 4194304 120 0 0 0 250 50 0 0 \
+                20 0 1 0 98765 12345678 900 18446744073709551615";
+    let stat = parse_stat(stat).unwrap();
+    assert_eq!(stat.pid, 4242);
+    assert_eq!(stat.name, "my (odd) prog");
+    assert_eq!(stat.ppid, 17);
+    assert_eq!(stat.cpu_ticks, 300);
+    assert_eq!(stat.start_ticks, 98765);
+
+    assert!(parse_stat("").is_none());
+    assert!(parse_stat("12 (short) S 1").is_none());
+}
+
+#[test]
+fn test_process_tree_lists_children() {
+    if !std::path::Path::new("/proc/self/stat").exists() {
+        return;
+    }
+    let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+    let tree = process_tree(std::process::id());
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let tree = tree.unwrap();
+    assert_eq!(tree[0].pid, std::process::id());
+    assert_eq!(tree[0].depth, 0);
+    assert!(tree[0].rss_kib > 0);
+    let sleep = tree.iter().find(|p| p.pid == child.id()).unwrap();
+    assert_eq!(sleep.depth, 1);
+    assert_eq!(sleep.command, "sleep 5");
+    assert!(process_tree(u32::MAX).is_err());
+}
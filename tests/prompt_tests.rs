@@ -0,0 +1,81 @@
+use tmux_ui::prompt::{PromptStatus, SegmentStyle};
+
+#[test]
+fn test_plain_segment() {
+    let status = PromptStatus {
+        current_session: Some("main".to_string()),
+        alerting_sessions: 2,
+    };
+    assert_eq!(status.render(SegmentStyle::Plain), "main !2");
+
+    let quiet = PromptStatus {
+        current_session: Some("main".to_string()),
+        alerting_sessions: 0,
+    };
+    assert_eq!(quiet.render(SegmentStyle::Plain), "main");
+}
+
+#[test]
+fn test_empty_segment_outside_tmux_without_alerts() {
+    let status = PromptStatus::default();
+    assert_eq!(status.render(SegmentStyle::Plain), "");
+    assert_eq!(status.render(SegmentStyle::Powerline), "");
+}
+
+#[test]
+fn test_powerline_segment_ends_with_separator() {
+    let status = PromptStatus {
+        current_session: None,
+        alerting_sessions: 1,
+    };
+    let segment = status.render(SegmentStyle::Powerline);
+    assert!(segment.contains(" 1 "));
+    assert!(segment.ends_with('\u{e0b0}'));
+}
+
+#[test]
+fn test_status_from_sessions() {
+    let sessions = vec![
+        ("$0".to_string(), "main".to_string(), false),
+        ("$1".to_string(), "logs".to_string(), true),
+        ("$2".to_string(), "build".to_string(), true),
+    ];
+    let status = PromptStatus::from_sessions(Some("$0"), sessions);
+    assert_eq!(status.current_session.as_deref(), Some("main"));
+    assert_eq!(status.alerting_sessions, 2);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sessions_come_from_the_daemon_cache() {
+    use std::time::Duration;
+    use tmux_ui::daemon::{self, Daemon};
+    use tmux_ui::prompt::daemon_sessions;
+    use tmux_ui::tmux::mock::MockBackend;
+
+    let path = std::env::temp_dir()
+        .join(format!("tmux-ui-test-prompt-{}", std::process::id()))
+        .join("daemon.sock");
+    let lookup = path.clone();
+    let missing = tokio::task::spawn_blocking(move || daemon_sessions(&lookup))
+        .await
+        .unwrap();
+    assert_eq!(missing, None);
+
+    let mock = MockBackend::new().with_session("work");
+    let daemon = Daemon::new(mock);
+    daemon.refresh().await.unwrap();
+    daemon::create_socket_dir(path.parent().unwrap()).unwrap();
+    let listener = daemon::bind(&path).await.unwrap();
+    tokio::spawn(daemon.serve(listener, Duration::from_secs(60)));
+
+    let lookup = path.clone();
+    let sessions = tokio::task::spawn_blocking(move || daemon_sessions(&lookup))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].1, "work");
+    assert!(!sessions[0].2);
+    let _ = std::fs::remove_file(&path);
+}
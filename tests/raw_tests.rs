@@ -0,0 +1,55 @@
+use tmux_ui::{complete_command, split_args, RawOutput};
+
+#[test]
+fn test_split_args_plain_and_quoted() {
+    assert_eq!(
+        split_args("list-keys -T prefix").unwrap(),
+        vec!["list-keys", "-T", "prefix"]
+    );
+    assert_eq!(
+        split_args(r#"display-message -p '#{session_name}' "a \"b\"""#).unwrap(),
+        vec!["display-message", "-p", "#{session_name}", "a \"b\""]
+    );
+    assert_eq!(split_args(r"set -g status\ left x").unwrap().len(), 4);
+    assert_eq!(split_args("''").unwrap(), vec![""]);
+}
+
+#[test]
+fn test_split_args_rejects_unterminated_quotes() {
+    assert!(split_args("display-message 'oops").is_err());
+    assert!(split_args("display-message \"oops").is_err());
+}
+
+#[test]
+fn test_raw_output_combined() {
+    let output = RawOutput {
+        code: Some(1),
+        stdout: "out".to_string(),
+        stderr: "err\n".to_string(),
+    };
+    assert!(!output.success());
+    assert_eq!(output.combined(), "out\nerr\n");
+}
+
+#[test]
+fn test_complete_command_names() {
+    let (line, matches) = complete_command("new-s").unwrap();
+    assert_eq!(line, "new-session ");
+    assert_eq!(matches, vec!["new-session"]);
+
+    // Several matches extend the name as far as they agree
+    let (line, matches) = complete_command("list-").unwrap();
+    assert_eq!(line, "list-");
+    assert!(matches.contains(&"list-keys") && matches.contains(&"list-windows"));
+    assert_eq!(
+        complete_command("show-w").unwrap().0,
+        "show-window-options "
+    );
+    assert_eq!(complete_command("swa").unwrap().0, "swap-");
+
+    let (line, matches) = complete_command("frobnicate").unwrap();
+    assert_eq!(line, "frobnicate");
+    assert!(matches.is_empty());
+    // Arguments are not completed
+    assert!(complete_command("list-keys -T").is_none());
+}
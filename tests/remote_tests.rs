@@ -0,0 +1,77 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use tmux_ui::tmux::{
+    remote::{shell_quote, ssh_args},
+    Socket, TmuxClient,
+};
+
+#[test]
+fn test_shell_quote() {
+    assert_eq!(shell_quote("list-sessions"), "list-sessions");
+    assert_eq!(shell_quote("=work"), "=work");
+    assert_eq!(shell_quote(""), "''");
+    assert_eq!(shell_quote("#{session_name}|x"), "'#{session_name}|x'");
+    assert_eq!(shell_quote("it's"), r"'it'\''s'");
+}
+
+#[test]
+fn test_ssh_args_quote_the_remote_command() {
+    let args: Vec<OsString> = ["-L", "work", "list-sessions", "-F", "#{session_name} $HOME"]
+        .iter()
+        .map(OsString::from)
+        .collect();
+    assert_eq!(
+        ssh_args("devbox", &args, false),
+        [
+            "-o",
+            "BatchMode=yes",
+            "--",
+            "devbox",
+            "tmux -L work list-sessions -F '#{session_name} $HOME'"
+        ]
+    );
+
+    let attach: Vec<OsString> = ["attach-session", "-t", "=my session"]
+        .iter()
+        .map(OsString::from)
+        .collect();
+    assert_eq!(
+        ssh_args("devbox", &attach, true),
+        ["-t", "--", "devbox", "tmux attach-session -t '=my session'"]
+    );
+}
+
+/// Put an `ssh` on the PATH that runs the remote command locally, so the quoting is
+/// checked against a real shell and tmux
+fn install_fake_ssh() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tmux-ui-fake-ssh-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let ssh = dir.join("ssh");
+    std::fs::write(
+        &ssh,
+        "#!/bin/sh\nwhile [ \"$1\" != \"--\" ]; do shift; done\nshift 2\nexec sh -c \"$1\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&ssh, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+    let path = std::env::var("PATH").unwrap_or_default();
+    std::env::set_var("PATH", format!("{}:{}", dir.display(), path));
+    dir
+}
+
+#[test]
+fn test_remote_client_runs_tmux_over_ssh() {
+    let fake_ssh = install_fake_ssh();
+    let socket = format!("tmux-ui-test-remote-{}", std::process::id());
+    let client = TmuxClient::new()
+        .with_socket(Socket::Name(socket.clone()))
+        .with_host("devbox");
+
+    client.create_session("remote", None, None).unwrap();
+    let sessions = client.list_sessions().unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].name, "remote");
+    assert!(!client.is_inside_this_server());
+
+    client.kill_server().unwrap();
+    let _ = std::fs::remove_dir_all(fake_ssh);
+}
@@ -0,0 +1,110 @@
+use std::process::Command;
+use tmux_ui::script::{Script, Step};
+use tmux_ui::tmux::{Key, Socket, SplitDirection, TmuxClient};
+
+#[test]
+fn test_parse_script() {
+    let script = Script::parse(
+        r#"
+        # Provision the app
+        session app ~/src/app
+        window server
+        split horizontal 30% logs
+        send cargo run --release
+        keys C-c 'make test' Enter
+        option status-style "bg=blue fg=white"
+        layout main-vertical
+        "#,
+    )
+    .unwrap();
+
+    let steps: Vec<&Step> = script.steps.iter().map(|(_, step)| step).collect();
+    assert_eq!(script.steps[0].0, 3);
+    assert_eq!(
+        steps[0],
+        &Step::Session {
+            name: "app".to_string(),
+            dir: Some("~/src/app".to_string())
+        }
+    );
+    assert_eq!(
+        steps[2],
+        &Step::Split {
+            direction: SplitDirection::Horizontal,
+            percent: Some(30),
+            dir: Some("logs".to_string())
+        }
+    );
+    assert_eq!(steps[3], &Step::Send("cargo run --release".to_string()));
+    assert_eq!(
+        steps[4],
+        &Step::Keys(vec![
+            Key::Named("C-c".to_string()),
+            Key::Literal("make test".to_string()),
+            Key::Named("Enter".to_string()),
+        ])
+    );
+    assert_eq!(
+        steps[5],
+        &Step::Option {
+            name: "status-style".to_string(),
+            value: "bg=blue fg=white".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_parse_errors_name_the_line() {
+    for (script, line) in [
+        ("session a\nfrobnicate", "line 2"),
+        ("session a\n\nsplit diagonal", "line 3"),
+        ("session a\nsplit v 120%", "line 2"),
+        ("session 'a", "line 1"),
+        ("option status", "line 1"),
+    ] {
+        let err = format!("{:#}", Script::parse(script).unwrap_err());
+        assert!(err.starts_with(line), "{}: {}", script, err);
+    }
+    assert!(Script::parse("# nothing\n").is_err());
+}
+
+#[test]
+fn test_run_script() {
+    let socket = format!("tmux-ui-test-script-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket.clone()));
+    let script = Script::parse(
+        r#"
+        session provisioned /tmp
+        option @role ci
+        window shells
+        split horizontal 40% /
+        split vertical
+        "#,
+    )
+    .unwrap();
+
+    let created = script.run(&client);
+    let windows = client.list_windows("provisioned");
+    let panes = windows
+        .as_ref()
+        .ok()
+        .and_then(|w| w.get(1))
+        .map(|w| client.list_panes(&w.id));
+    let role = client.display_message(Some("provisioned"), "#{@role}");
+    let again = script.run(&client);
+    let _ = Command::new("tmux")
+        .args(["-L", &socket, "kill-server"])
+        .status();
+
+    // tmux is not available
+    if created.is_err() && windows.is_err() {
+        return;
+    }
+    assert_eq!(created.unwrap(), vec!["provisioned"]);
+    let windows = windows.unwrap();
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows[1].name, "shells");
+    assert_eq!(panes.unwrap().unwrap().len(), 3);
+    assert_eq!(role.unwrap().trim(), "ci");
+    assert!(format!("{:#}", again.unwrap_err()).contains("already exists"));
+}
@@ -0,0 +1,196 @@
+#![cfg(all(feature = "serve", unix))]
+
+use tmux_ui::daemon::Daemon;
+use tmux_ui::serve::{percent_decode, Api, HttpRequest};
+use tmux_ui::tmux::mock::MockBackend;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::Duration;
+
+fn request(method: &str, path: &str, body: &str) -> HttpRequest {
+    HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: String::new(),
+        headers: Vec::new(),
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+#[test]
+fn test_request_parts() {
+    let mut request = request("GET", "/sessions", "");
+    request.query = "x=1&name=my+api&s=%C3%A9".to_string();
+    request
+        .headers
+        .push(("host".to_string(), "localhost".to_string()));
+    assert_eq!(request.query_param("x").as_deref(), Some("1"));
+    assert_eq!(request.query_param("name").as_deref(), Some("my api"));
+    assert_eq!(request.query_param("s").as_deref(), Some("é"));
+    assert_eq!(request.query_param("y"), None);
+    assert_eq!(request.header("HOST"), Some("localhost"));
+
+    assert_eq!(percent_decode("my%20api").as_deref(), Some("my api"));
+    assert_eq!(percent_decode("bad%FF"), None);
+}
+
+async fn serve(api: std::sync::Arc<Api<MockBackend>>) -> std::net::SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(api.serve(listener, Duration::from_secs(60)));
+    addr
+}
+
+/// Send a raw request and return the whole response
+async fn exchange(addr: std::net::SocketAddr, raw: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(raw.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_over_http() {
+    let mock = MockBackend::new().with_session("work");
+    let addr = serve(Api::new(Daemon::new(mock.clone()), None)).await;
+
+    let response = exchange(
+        addr,
+        "POST /sessions HTTP/1.1\r\nHost: localhost\r\nContent-Length: 15\r\nConnection: close\r\n\r\n{\"name\":\"api\"}\n",
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 201"), "{}", response);
+    assert!(response.contains("application/json"));
+    assert!(response.ends_with(r#"{"created":"api"}"#), "{}", response);
+    assert_eq!(mock.calls(), vec!["new-session api"]);
+
+    let response = exchange(addr, "nonsense\r\n\r\n").await;
+    assert!(response.starts_with("HTTP/1.1 400"), "{}", response);
+    let headers = "X-A: b\r\n".repeat(101);
+    let response = exchange(addr, &format!("GET / HTTP/1.1\r\n{}\r\n", headers)).await;
+    assert!(response.starts_with("HTTP/1.1 431"), "{}", response);
+    let response = exchange(
+        addr,
+        "POST /sessions HTTP/1.1\r\nHost: localhost\r\nContent-Length: 100000\r\n\r\n",
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 413"), "{}", response);
+}
+
+#[tokio::test]
+async fn test_session_endpoints() {
+    let mock = MockBackend::new().with_session("work");
+    let daemon = Daemon::new(mock.clone());
+    daemon.refresh().await.unwrap();
+    let api = Api::new(daemon, None);
+
+    let response = api.route(&request("GET", "/sessions", "")).await;
+    assert_eq!(response.status, 200);
+    assert_eq!(response.json_body()[0]["name"], "work");
+
+    let response = api
+        .route(&request("POST", "/sessions", r#"{"name": "api"}"#))
+        .await;
+    assert_eq!(response.status, 201);
+    assert_eq!(response.json_body()["created"], "api");
+    let response = api
+        .route(&request("POST", "/sessions", r#"{"name": "api"}"#))
+        .await;
+    assert_eq!(response.status, 409);
+    assert_eq!(response.json_body()["error"]["kind"], "duplicate_session");
+
+    let response = api
+        .route(&request(
+            "POST",
+            "/sessions/api/send-keys",
+            r#"{"text": "make", "enter": false}"#,
+        ))
+        .await;
+    assert_eq!(response.status, 200);
+
+    let response = api.route(&request("DELETE", "/sessions/api", "")).await;
+    assert_eq!(response.status, 200);
+    assert_eq!(response.json_body()["killed"], "api");
+    let response = api.route(&request("DELETE", "/sessions/api", "")).await;
+    assert_eq!(response.status, 404);
+
+    // The mock records the refused calls as well
+    assert_eq!(
+        mock.calls(),
+        vec![
+            "new-session api",
+            "new-session api",
+            "send-keys api 'make'",
+            "kill-session api",
+            "kill-session api"
+        ]
+    );
+
+    assert_eq!(
+        api.route(&request("PUT", "/sessions", "")).await.status,
+        405
+    );
+    assert_eq!(api.route(&request("GET", "/nothing", "")).await.status, 404);
+    assert_eq!(
+        api.route(&request("POST", "/sessions", "{")).await.status,
+        400
+    );
+}
+
+#[tokio::test]
+async fn test_bearer_token() {
+    let daemon = Daemon::new(MockBackend::new().with_session("work"));
+    let api = Api::new(daemon, Some("secret".to_string()));
+
+    let mut get = request("GET", "/sessions", "");
+    assert_eq!(api.route(&get).await.status, 401);
+    get.headers
+        .push(("authorization".to_string(), "Bearer wrong".to_string()));
+    assert_eq!(api.route(&get).await.status, 401);
+    get.headers[0].1 = "Bearer secre".to_string();
+    assert_eq!(api.route(&get).await.status, 401);
+    get.headers[0].1 = "Bearer secret".to_string();
+    assert_eq!(api.route(&get).await.status, 200);
+
+    // Browsers cannot set headers on a WebSocket, so the query may carry it
+    let mut get = request("GET", "/sessions", "");
+    get.query = "token=secret".to_string();
+    assert_eq!(api.route(&get).await.status, 200);
+}
+
+#[tokio::test]
+async fn test_refuses_requests_from_web_pages() {
+    let daemon = Daemon::new(MockBackend::new().with_session("work"));
+    let api = Api::new(daemon, None);
+    let with_headers = |headers: &[(&str, &str)]| {
+        let mut request = request("POST", "/sessions/work/send-keys", r#"{"text": "ls"}"#);
+        for (name, value) in headers {
+            request.headers.push((name.to_string(), value.to_string()));
+        }
+        request
+    };
+
+    let request = with_headers(&[
+        ("host", "localhost:8787"),
+        ("origin", "https://evil.example"),
+    ]);
+    assert_eq!(api.route(&request).await.status, 403);
+    // A page whose domain was rebound to 127.0.0.1 is its own origin
+    let request = with_headers(&[
+        ("host", "evil.example:8787"),
+        ("origin", "http://evil.example:8787"),
+    ]);
+    assert_eq!(api.route(&request).await.status, 403);
+
+    for host in ["localhost:8787", "127.0.0.1:8787", "[::1]:8787"] {
+        let request = with_headers(&[("host", host), ("origin", &format!("http://{}", host))]);
+        assert_eq!(api.route(&request).await.status, 200, "{}", host);
+    }
+    assert_eq!(
+        api.route(&with_headers(&[("host", "localhost:8787")]))
+            .await
+            .status,
+        200
+    );
+}
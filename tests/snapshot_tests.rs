@@ -0,0 +1,136 @@
+use std::process::Command;
+use tmux_ui::tmux::{Snapshot, Socket, TmuxClient};
+
+fn tmux(socket: &str, args: &[&str]) {
+    let _ = Command::new("tmux")
+        .args(["-L", socket])
+        .args(args)
+        .status();
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let socket = format!("tmux-ui-test-snapshot-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket.clone()));
+    let dir = std::env::temp_dir().join(format!("tmux-ui-snapshot-{}", std::process::id()));
+    let path = dir.join("snapshot.json");
+
+    if client.create_session("snap", None, None).is_err() {
+        return;
+    }
+    client.create_window("snap", Some("editor")).unwrap();
+    client
+        .set_session_tags("snap", &["work".to_string()])
+        .unwrap();
+    let windows = client.list_windows("snap").unwrap();
+    tmux(
+        &socket,
+        &["split-window", "-t", &windows[1].id, "-c", "/tmp"],
+    );
+
+    let snapshot = Snapshot::capture(&client).unwrap();
+    snapshot.save(&path).unwrap();
+    // Keep the server running while "snap" is gone
+    client.create_session("other", None, None).unwrap();
+    client.kill_session("snap").unwrap();
+
+    let loaded = Snapshot::load(&path).unwrap();
+    let report = loaded.restore(&client).unwrap();
+    let second = loaded.restore(&client).unwrap();
+    let restored = Snapshot::capture(&client).unwrap();
+    tmux(&socket, &["kill-server"]);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert_eq!(loaded, snapshot);
+    assert_eq!(snapshot.sessions.len(), 1);
+    assert_eq!(snapshot.sessions[0].windows.len(), 2);
+    assert_eq!(snapshot.sessions[0].windows[1].panes.len(), 2);
+    assert_eq!(report.restored, vec!["snap".to_string()]);
+    assert_eq!(second.skipped, vec!["snap".to_string()]);
+
+    let snap = restored.sessions.iter().find(|s| s.name == "snap").unwrap();
+    assert_eq!(snap.tags, ["work"]);
+    assert_eq!(snap.windows.len(), 2);
+    assert_eq!(snap.windows[1].name, "editor");
+    assert_eq!(snap.windows[1].panes.len(), 2);
+    assert_eq!(
+        snap.windows[1].panes[1].cwd,
+        snapshot.sessions[0].windows[1].panes[1].cwd
+    );
+}
+
+#[test]
+fn test_clone_session_copies_windows_and_splits() {
+    let socket = format!("tmux-ui-test-clone-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket.clone()));
+
+    if client
+        .create_session("orig", Some(std::path::Path::new("/tmp")), None)
+        .is_err()
+    {
+        return;
+    }
+    client.create_window("orig", Some("logs")).unwrap();
+    let windows = client.list_windows("orig").unwrap();
+    tmux(&socket, &["split-window", "-t", &windows[1].id, "-c", "/"]);
+
+    let cloned = client.clone_session("orig", "copy", false);
+    let duplicate = client.clone_session("orig", "copy", false);
+    let snapshot = Snapshot::capture(&client).unwrap();
+    tmux(&socket, &["kill-server"]);
+
+    cloned.unwrap();
+    assert!(duplicate.is_err());
+    let orig = snapshot.sessions.iter().find(|s| s.name == "orig").unwrap();
+    let copy = snapshot.sessions.iter().find(|s| s.name == "copy").unwrap();
+    assert_eq!(copy.windows.len(), 2);
+    assert_eq!(copy.windows[1].name, "logs");
+    assert_eq!(copy.windows[1].panes.len(), 2);
+    for (original, copied) in orig.windows.iter().zip(&copy.windows) {
+        let cwds: Vec<_> = original.panes.iter().map(|p| &p.cwd).collect();
+        let copied: Vec<_> = copied.panes.iter().map(|p| &p.cwd).collect();
+        assert_eq!(copied, cwds);
+    }
+}
+
+#[test]
+fn test_restore_killed_window() {
+    let socket = format!("tmux-ui-test-undo-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket.clone()));
+
+    if client.create_session("undo", None, None).is_err() {
+        return;
+    }
+    client.create_window("undo", Some("logs")).unwrap();
+    let windows = client.list_windows("undo").unwrap();
+    tmux(
+        &socket,
+        &["split-window", "-t", &windows[1].id, "-c", "/tmp"],
+    );
+
+    let recorded = client.snapshot_window(&windows[1].id).unwrap();
+    client.kill_window(&windows[1].id).unwrap();
+    let restored = client.restore_window("undo", &recorded.windows[0]);
+    let after = client.snapshot_session("undo").unwrap();
+    tmux(&socket, &["kill-server"]);
+
+    restored.unwrap();
+    assert_eq!(recorded.name, "undo");
+    assert_eq!(recorded.windows.len(), 1);
+    assert_eq!(after.windows.len(), 2);
+    assert_eq!(after.windows[1].name, "logs");
+    assert_eq!(after.windows[1].panes.len(), 2);
+    assert_eq!(
+        after.windows[1].panes[1].cwd,
+        recorded.windows[0].panes[1].cwd
+    );
+}
+
+#[test]
+fn test_load_rejects_other_versions() {
+    let path = std::env::temp_dir().join(format!("tmux-ui-snapshot-v-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{"version": 99, "created": 0, "sessions": []}"#).unwrap();
+    let result = Snapshot::load(&path);
+    let _ = std::fs::remove_file(&path);
+    assert!(result.is_err());
+}
@@ -0,0 +1,78 @@
+use chrono::DateTime;
+use tmux_ui::sort::SortSpec;
+use tmux_ui::tmux::{Alerts, TmuxSession};
+
+fn session(name: &str, attached: bool, activity: i64) -> TmuxSession {
+    TmuxSession {
+        id: "$0".to_string(),
+        name: name.to_string(),
+        windows: 1,
+        panes: 1,
+        attached,
+        clients: usize::from(attached),
+        created_at: DateTime::UNIX_EPOCH,
+        last_activity: DateTime::from_timestamp(activity, 0).unwrap(),
+        columns: Vec::new(),
+        server: None,
+        alerts: Alerts::default(),
+        tags: Vec::new(),
+        current_command: String::new(),
+        current_path: String::new(),
+    }
+}
+
+fn names(sessions: &[TmuxSession]) -> Vec<&str> {
+    sessions.iter().map(|s| s.name.as_str()).collect()
+}
+
+#[test]
+fn test_compound_sort_attached_then_activity_then_name() {
+    let mut sessions = vec![
+        session("delta", false, 100),
+        session("alpha", false, 300),
+        session("charlie", true, 100),
+        session("bravo", false, 300),
+        session("echo", true, 200),
+    ];
+
+    let spec: SortSpec = "attached,activity:desc,name".parse().unwrap();
+    spec.sort(&mut sessions);
+
+    assert_eq!(
+        names(&sessions),
+        vec!["echo", "charlie", "alpha", "bravo", "delta"]
+    );
+}
+
+#[test]
+fn test_empty_spec_keeps_order() {
+    let mut sessions = vec![session("b", false, 1), session("a", false, 2)];
+    SortSpec::default().sort(&mut sessions);
+    assert_eq!(names(&sessions), vec!["b", "a"]);
+}
+
+#[test]
+fn test_invalid_sort_keys_are_rejected() {
+    assert!("size".parse::<SortSpec>().is_err());
+    assert!("name:sideways".parse::<SortSpec>().is_err());
+    assert!(SortSpec::from_keys(&["name:DESC"]).unwrap().keys[0].descending);
+}
+
+#[test]
+fn test_table_columns_cycle_through_sortable_fields() {
+    use tmux_ui::sort::SortField;
+    use tmux_ui::tui::table::{next_sort, SessionColumn};
+
+    let columns = [
+        SessionColumn::Command,
+        SessionColumn::Name,
+        SessionColumn::Path,
+        SessionColumn::Panes,
+    ];
+    let first = next_sort(&columns, None).unwrap();
+    assert_eq!(first.field, SortField::Name);
+    let second = next_sort(&columns, Some(first)).unwrap();
+    assert_eq!(second.field, SortField::Panes);
+    // After the last sortable column the configured order is used again
+    assert_eq!(next_sort(&columns, Some(second)), None);
+}
@@ -0,0 +1,42 @@
+use ratatui::{backend::TestBackend, Terminal};
+use tmux_ui::{
+    tmux::{mock::MockBackend, TmuxClient},
+    tui::App,
+};
+
+fn buffer_text(terminal: &Terminal<TestBackend>) -> String {
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content()
+        .iter()
+        .map(|cell| cell.symbol.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_first_frame_renders_before_sessions_load() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone());
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    app.draw(&mut terminal).unwrap();
+
+    // Nothing waits on tmux before the first frame is on screen
+    assert_eq!(mock.queries(), 0);
+    assert!(buffer_text(&terminal).contains("Loading sessions"));
+
+    app.load_initial_state().await.unwrap();
+    assert!(mock.queries() > 0);
+    app.draw(&mut terminal).unwrap();
+    assert!(buffer_text(&terminal).contains("work"));
+}
+
+#[tokio::test]
+async fn test_loading_placeholder_cleared_after_initial_load() {
+    let mut app = App::new(TmuxClient::new());
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+
+    app.load_initial_state().await.unwrap();
+    app.draw(&mut terminal).unwrap();
+
+    assert!(!buffer_text(&terminal).contains("Loading sessions"));
+}
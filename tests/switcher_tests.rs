@@ -0,0 +1,96 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+use tmux_ui::tui::switcher::{SessionSwitcher, SwitcherEvent};
+use tmux_ui::tui::theme::Theme;
+
+fn switcher() -> SessionSwitcher {
+    SessionSwitcher::new(
+        ["dotfiles", "api-server", "web", "a-project-index"]
+            .map(String::from)
+            .to_vec(),
+    )
+}
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn type_text(switcher: &mut SessionSwitcher, text: &str) {
+    for c in text.chars() {
+        assert_eq!(
+            switcher.handle_key(key(KeyCode::Char(c))),
+            SwitcherEvent::None
+        );
+    }
+}
+
+#[test]
+fn test_typing_filters_and_ranks_sessions() {
+    let mut switcher = switcher();
+    assert_eq!(switcher.matches().len(), 4);
+    assert_eq!(switcher.selected(), Some("dotfiles"));
+
+    type_text(&mut switcher, "api");
+    assert_eq!(switcher.query(), "api");
+    assert_eq!(switcher.matches(), vec!["api-server", "a-project-index"]);
+    assert_eq!(switcher.selected(), Some("api-server"));
+
+    type_text(&mut switcher, "z");
+    assert!(switcher.matches().is_empty());
+    assert_eq!(switcher.selected(), None);
+    assert_eq!(
+        switcher.handle_key(key(KeyCode::Enter)),
+        SwitcherEvent::None
+    );
+
+    switcher.handle_key(key(KeyCode::Backspace));
+    assert_eq!(switcher.matches().len(), 2);
+    switcher.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+    assert_eq!(switcher.query(), "");
+    assert_eq!(switcher.matches().len(), 4);
+}
+
+#[test]
+fn test_choose_or_cancel() {
+    let mut switcher = switcher();
+    switcher.handle_key(key(KeyCode::Down));
+    switcher.handle_key(key(KeyCode::Down));
+    assert_eq!(
+        switcher.handle_key(key(KeyCode::Enter)),
+        SwitcherEvent::Chosen("web".to_string())
+    );
+    // Up from the first match wraps to the last
+    switcher.handle_key(key(KeyCode::Up));
+    switcher.handle_key(key(KeyCode::Up));
+    switcher.handle_key(key(KeyCode::Up));
+    assert_eq!(switcher.selected(), Some("a-project-index"));
+
+    assert_eq!(
+        switcher.handle_key(key(KeyCode::Esc)),
+        SwitcherEvent::Cancelled
+    );
+    assert_eq!(
+        switcher.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+        SwitcherEvent::Cancelled
+    );
+}
+
+#[test]
+fn test_renders_only_the_query_and_matches() {
+    let mut switcher = switcher();
+    type_text(&mut switcher, "web");
+    let mut terminal = Terminal::new(TestBackend::new(30, 4)).unwrap();
+    terminal
+        .draw(|f| switcher.render(f, &Theme::default()))
+        .unwrap();
+    let lines: Vec<String> = terminal
+        .backend()
+        .buffer()
+        .content()
+        .chunks(30)
+        .map(|row| row.iter().map(|cell| cell.symbol.as_str()).collect())
+        .collect();
+    assert_eq!(lines[0].trim_end(), "> web  1/4");
+    assert_eq!(lines[1].trim_end(), "› web");
+    assert!(lines[2].trim().is_empty());
+}
@@ -0,0 +1,95 @@
+use std::process::Command;
+use tmux_ui::template::Template;
+use tmux_ui::tmux::{Socket, SplitDirection, TmuxClient};
+
+#[test]
+fn test_parse_template() {
+    let template = Template::parse(
+        r#"
+        root = "~/src/app"
+
+        [[windows]]
+        name = "editor"
+        panes = [{ command = "vim" }]
+
+        [[windows]]
+        name = "server"
+        layout = "even-horizontal"
+        panes = [{ command = "cargo run" }, { cwd = "logs", split = "horizontal" }]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(template.name, None);
+    assert_eq!(template.windows.len(), 2);
+    assert_eq!(template.windows[1].panes[0].split, SplitDirection::Vertical);
+    assert_eq!(
+        template.windows[1].panes[1].split,
+        SplitDirection::Horizontal
+    );
+    assert_eq!(template.windows[1].panes[1].cwd.as_deref(), Some("logs"));
+}
+
+#[test]
+fn test_parse_rejects_invalid_templates() {
+    assert!(Template::parse("name = \"empty\"").is_err());
+    assert!(Template::parse("[[windows]]\nname = \"a\"\nsplits = 2").is_err());
+    assert!(Template::parse("[[windows]]\npanes = [{ split = \"diagonal\" }]").is_err());
+}
+
+#[test]
+fn test_load_uses_file_name_as_session_name() {
+    let path = std::env::temp_dir().join(format!("tmux-ui-template-{}.toml", std::process::id()));
+    std::fs::write(&path, "[[windows]]\n").unwrap();
+    let template = Template::load(path.to_str().unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        template.name.as_deref(),
+        path.file_stem().and_then(|s| s.to_str())
+    );
+}
+
+#[test]
+fn test_start_template() {
+    let socket = format!("tmux-ui-test-template-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket.clone()));
+    let template = Template::parse(
+        r#"
+        name = "project"
+        root = "/tmp"
+
+        [[windows]]
+        name = "editor"
+
+        [[windows]]
+        name = "shells"
+        panes = [{}, { cwd = "/", split = "horizontal" }, {}]
+        "#,
+    )
+    .unwrap();
+
+    let started = template.start(&client);
+    let windows = client.list_windows("project");
+    let panes = windows
+        .as_ref()
+        .ok()
+        .and_then(|w| w.get(1))
+        .map(|w| client.list_panes(&w.id));
+    let again = template.start(&client);
+    let _ = Command::new("tmux")
+        .args(["-L", &socket, "kill-server"])
+        .status();
+
+    // tmux is not available
+    if started.is_err() && windows.is_err() {
+        return;
+    }
+    assert_eq!(started.unwrap(), "project");
+    let windows = windows.unwrap();
+    assert_eq!(windows.len(), 2);
+    assert_eq!(windows[0].name, "editor");
+    assert_eq!(windows[1].name, "shells");
+    assert_eq!(panes.unwrap().unwrap().len(), 3);
+    assert!(again.is_err());
+}
@@ -0,0 +1,117 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, style::Color, Terminal};
+use std::collections::HashMap;
+use tmux_ui::tmux::{mock::MockBackend, TmuxBackend};
+use tmux_ui::tui::terminal::{ColorSupport, TerminalSupport};
+use tmux_ui::tui::theme::Theme;
+use tmux_ui::tui::App;
+
+fn detect(vars: &[(&str, &str)]) -> TerminalSupport {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    TerminalSupport::from_env(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn test_detect_colors_from_term_and_colorterm() {
+    let term = ("TERM", "xterm-256color");
+    assert_eq!(detect(&[term]).color, ColorSupport::Extended);
+    assert_eq!(
+        detect(&[term, ("COLORTERM", "truecolor")]).color,
+        ColorSupport::TrueColor
+    );
+    assert_eq!(detect(&[("TERM", "vt100")]).color, ColorSupport::Basic);
+    assert_eq!(detect(&[("TERM", "dumb")]).color, ColorSupport::None);
+    assert_eq!(detect(&[term, ("NO_COLOR", "1")]).color, ColorSupport::None);
+    // An empty NO_COLOR does not count
+    assert_eq!(
+        detect(&[term, ("NO_COLOR", "")]).color,
+        ColorSupport::Extended
+    );
+}
+
+#[test]
+fn test_detect_unicode_from_locale() {
+    let term = ("TERM", "xterm");
+    assert!(detect(&[term, ("LANG", "en_US.UTF-8")]).unicode);
+    assert!(!detect(&[term, ("LANG", "C")]).unicode);
+    assert!(!detect(&[term]).unicode);
+    // LC_ALL wins over LANG
+    assert!(!detect(&[term, ("LC_ALL", "POSIX"), ("LANG", "en_US.utf8")]).unicode);
+    assert!(!detect(&[("TERM", "dumb"), ("LANG", "en_US.UTF-8")]).unicode);
+
+    let limited = detect(&[term, ("LANG", "en_US.UTF-8")]).limited(true, true);
+    assert!(!limited.unicode);
+    assert_eq!(limited.color, ColorSupport::None);
+}
+
+#[test]
+fn test_theme_reduced_to_terminal_colors() {
+    let nord = Theme::named("nord").unwrap();
+    assert_eq!(nord.for_terminal(ColorSupport::TrueColor), nord);
+    assert!(matches!(
+        nord.for_terminal(ColorSupport::Extended).title,
+        Color::Indexed(_)
+    ));
+    let basic = nord.for_terminal(ColorSupport::Basic);
+    assert!(!matches!(basic.title, Color::Rgb(..) | Color::Indexed(_)));
+    assert_eq!(basic.error, Color::Red);
+    assert_eq!(
+        Theme::default().for_terminal(ColorSupport::None).attached,
+        Color::Reset
+    );
+}
+
+#[tokio::test]
+async fn test_ascii_terminal_draws_no_symbols() {
+    let mut app =
+        App::new(MockBackend::new().with_session("work")).with_terminal(TerminalSupport {
+            unicode: false,
+            color: ColorSupport::None,
+        });
+    app.load_initial_state().await.unwrap();
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+    app.draw(&mut terminal).unwrap();
+    let text: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol.as_str())
+        .collect();
+    assert!(text.contains(" tmux-ui - Session Manager"));
+    assert!(!text.contains('🖥'));
+    assert!(!text.contains('○'));
+    assert!(text.contains("$ work"));
+}
+
+#[tokio::test]
+async fn test_ascii_terminal_spells_out_banner_and_hint_glyphs() {
+    let mock = MockBackend::new().with_session("work");
+    let mut app = App::new(mock.clone()).with_terminal(TerminalSupport {
+        unicode: false,
+        color: ColorSupport::None,
+    });
+    app.load_initial_state().await.unwrap();
+
+    // The window cannot be created once the session is gone
+    mock.kill_session("work").unwrap();
+    app.handle_key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE))
+        .await
+        .unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(160, 24)).unwrap();
+    app.draw(&mut terminal).unwrap();
+    let text: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol.as_str())
+        .collect();
+    assert!(text.contains("! Error creating window"), "{}", text);
+    assert!(!text.contains('⚠'));
+    assert!(!text.contains('↑'));
+}
@@ -0,0 +1,25 @@
+use tmux_ui::config::Config;
+use tmux_ui::tui::theme::Theme;
+
+#[test]
+fn test_builtin_themes_resolve() {
+    for name in Theme::NAMES {
+        assert!(Theme::named(name).is_some(), "missing theme {}", name);
+    }
+    assert_eq!(Theme::named("default"), Some(Theme::default()));
+    assert_ne!(Theme::named("nord"), Theme::named("gruvbox"));
+    assert_eq!(Theme::named("neon"), None);
+}
+
+#[test]
+fn test_theme_from_config() {
+    let config = Config::parse(r#"theme = "solarized""#).unwrap();
+    assert_eq!(config.theme().unwrap(), Theme::named("solarized").unwrap());
+    assert_eq!(
+        Config::parse("").unwrap().theme().unwrap(),
+        Theme::default()
+    );
+
+    let error = Config::parse(r#"theme = "neon""#).unwrap_err();
+    assert!(format!("{:#}", error).contains("gruvbox"));
+}
@@ -0,0 +1,363 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+use tmux_ui::{
+    config::Config,
+    tmux::{mock::MockBackend, Socket, TmuxBackend, TmuxClient},
+    tui::App,
+};
+
+fn render<C: TmuxBackend>(app: &mut App<C>, width: u16, height: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    app.draw(&mut terminal).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content()
+        .iter()
+        .map(|cell| cell.symbol.as_str())
+        .collect()
+}
+
+#[test]
+fn test_small_terminal_shows_size_hint() {
+    let mut app = App::new(TmuxClient::new());
+    let text = render(&mut app, 40, 10);
+    assert!(text.contains("Terminal too small"));
+    // Short terminals do without the title bar
+    assert!(text.contains("need 60x8"));
+}
+
+#[tokio::test]
+async fn test_layout_adapts_to_small_terminals() {
+    let mut app = App::new(MockBackend::new().with_session("work"));
+    app.load_initial_state().await.unwrap();
+    app.handle_key(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE))
+        .await
+        .unwrap();
+
+    let text = render(&mut app, 120, 24);
+    assert!(text.contains("Session Manager"));
+    assert!(text.contains("Details of 'work'"));
+
+    // A narrow pane has no room for the panel next to the list
+    app.handle_resize(80, 24);
+    let text = render(&mut app, 80, 24);
+    assert!(text.contains("tmux Sessions"));
+    assert!(!text.contains("Details of"));
+
+    // A short one drops the title bar but keeps the list and status bar
+    app.handle_resize(80, 12);
+    let text = render(&mut app, 80, 12);
+    assert!(!text.contains("Session Manager"));
+    assert!(text.contains("work"));
+    assert!(text.contains("Status"));
+}
+
+#[test]
+fn test_layout_recovers_after_resize() {
+    let mut app = App::new(TmuxClient::new());
+    assert!(render(&mut app, 40, 10).contains("Terminal too small"));
+
+    let text = render(&mut app, 80, 24);
+    assert!(!text.contains("Terminal too small"));
+    assert!(text.contains("tmux Sessions"));
+}
+
+#[tokio::test]
+async fn test_status_bar_shows_hints_for_the_mode() {
+    let mut app = App::new(MockBackend::new().with_session("work"));
+    app.load_initial_state().await.unwrap();
+
+    let text = render(&mut app, 100, 20);
+    assert!(text.contains("Welcome to tmux-ui!"));
+    assert!(text.contains("[a] Attach"));
+    assert!(text.contains("[q] Quit"));
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE))
+        .await
+        .unwrap();
+    let text = render(&mut app, 100, 20);
+    assert!(text.contains("New session name:"));
+    assert!(text.contains("[Enter] Create  [Tab] Next field  [Esc] Cancel"));
+    assert!(!text.contains("[q] Quit"));
+}
+
+#[tokio::test]
+async fn test_session_table_sorts_by_column() {
+    let mock = MockBackend::new()
+        .with_session("beta")
+        .with_session("alpha");
+    let mut app = App::new(mock).with_config(
+        Config::parse(r#"table_columns = ["name", "windows", "panes", "attached"]"#).unwrap(),
+    );
+    app.load_initial_state().await.unwrap();
+
+    let text = render(&mut app, 100, 20);
+    assert!(text.contains("Name"));
+    assert!(text.contains("Windows"));
+    assert!(text.contains("Panes"));
+    assert!(!text.contains("Created"));
+    assert!(text.find("beta").unwrap() < text.find("alpha").unwrap());
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('>'), KeyModifiers::NONE))
+        .await
+        .unwrap();
+    let text = render(&mut app, 100, 20);
+    assert!(text.contains("Name ▲"));
+    assert!(text.find("alpha").unwrap() < text.find("beta").unwrap());
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('<'), KeyModifiers::NONE))
+        .await
+        .unwrap();
+    let text = render(&mut app, 100, 20);
+    assert!(text.contains("Name ▼"));
+    assert!(text.find("beta").unwrap() < text.find("alpha").unwrap());
+}
+
+#[tokio::test]
+async fn test_page_and_jump_through_long_session_list() {
+    let mut mock = MockBackend::new();
+    for i in 0..30 {
+        mock = mock.with_session(&format!("s{:02}", i));
+    }
+    let mut app = App::new(mock);
+    app.load_initial_state().await.unwrap();
+    // 20 rows: 2 margin, 3 title, 3 status, 2 borders and a header leave 9 sessions
+    let text = render(&mut app, 80, 20);
+    assert!(text.contains("s08") && !text.contains("s09"));
+    assert!(text.contains('█'));
+
+    let press = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    app.handle_key(press(KeyCode::PageDown)).await.unwrap();
+    app.handle_key(press(KeyCode::PageDown)).await.unwrap();
+    assert!(render(&mut app, 80, 20).contains(">> › s18"));
+
+    app.handle_key(press(KeyCode::End)).await.unwrap();
+    assert!(render(&mut app, 80, 20).contains(">> › s29"));
+    app.handle_key(press(KeyCode::PageUp)).await.unwrap();
+    assert!(render(&mut app, 80, 20).contains(">> › s20"));
+    app.handle_key(press(KeyCode::Home)).await.unwrap();
+    assert!(render(&mut app, 80, 20).contains(">> › s00"));
+}
+
+#[tokio::test]
+async fn test_details_panel_of_selected_session() {
+    let mut app = App::new(MockBackend::new().with_session("work"));
+    app.load_initial_state().await.unwrap();
+    assert!(!render(&mut app, 120, 24).contains("Details of"));
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE))
+        .await
+        .unwrap();
+    let text = render(&mut app, 120, 24);
+    assert!(text.contains("Details of 'work'"));
+    assert!(text.contains("Size        80x24"));
+    assert!(text.contains("Group       none"));
+    assert!(text.contains("Clients     none attached"));
+}
+
+#[tokio::test]
+async fn test_processes_panel_of_selected_pane() {
+    let mut app = App::new(MockBackend::new().with_session("work"));
+    app.load_initial_state().await.unwrap();
+    for code in [KeyCode::Tab, KeyCode::Tab, KeyCode::Char('i')] {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+            .await
+            .unwrap();
+    }
+
+    // Mock panes have no process to read
+    let text = render(&mut app, 120, 24);
+    assert!(text.contains("Processes of pane 0"));
+    assert!(text.contains("tmux reports no process for this pane"));
+}
+
+fn tmux(socket: &str, args: &[&str]) {
+    let status = std::process::Command::new("tmux")
+        .arg("-L")
+        .arg(socket)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[tokio::test]
+async fn test_combined_view_groups_sessions_by_server() {
+    let primary = format!("tmux-ui-test-primary-{}", std::process::id());
+    let other = format!("tmux-ui-test-other-{}", std::process::id());
+    tmux(&primary, &["new-session", "-d", "-s", "alpha", "sh"]);
+    tmux(&primary, &["new-session", "-d", "-s", "beta", "sh"]);
+    tmux(&other, &["new-session", "-d", "-s", "gamma", "sh"]);
+
+    let config = Config::parse(&format!(
+        "[[servers]]\nname = \"other\"\nsocket_name = \"{}\"",
+        other
+    ))
+    .unwrap();
+    let client = TmuxClient::new().with_socket(Socket::Name(primary.clone()));
+    let mut app = App::new(client).with_config(config);
+    app.load_initial_state().await.unwrap();
+
+    let width = 100;
+    let text = render(&mut app, width, 30);
+    let lines: Vec<String> = text
+        .chars()
+        .collect::<Vec<_>>()
+        .chunks(usize::from(width))
+        .map(|line| line.iter().collect())
+        .collect();
+    let row = |name: &str| {
+        lines
+            .iter()
+            .position(|l| l.contains(&format!(" {} ", name)))
+            .unwrap()
+    };
+
+    // The default server comes first, with its name on the first row of its group
+    assert!(row("alpha") < row("beta") && row("beta") < row("gamma"));
+    assert!(lines[row("alpha")].contains("default ├─"));
+    assert!(lines[row("beta")].contains("└─"));
+    assert!(!lines[row("beta")].contains("default"));
+    assert!(lines[row("gamma")].contains("other   └─"));
+
+    tmux(&primary, &["kill-server"]);
+    tmux(&other, &["kill-server"]);
+}
+
+#[tokio::test]
+async fn test_prune_kills_idle_sessions_on_every_server() {
+    let primary = format!("tmux-ui-test-prune-primary-{}", std::process::id());
+    let other = format!("tmux-ui-test-prune-other-{}", std::process::id());
+    tmux(&primary, &["new-session", "-d", "-s", "alpha"]);
+    tmux(&primary, &["new-session", "-d", "-s", "busy", "sleep 600"]);
+    tmux(&other, &["new-session", "-d", "-s", "gamma"]);
+    tmux(&other, &["new-session", "-d", "-s", "build", "sleep 600"]);
+
+    let config = Config::parse(&format!(
+        "[[servers]]\nname = \"other\"\nsocket_name = \"{}\"",
+        other
+    ))
+    .unwrap();
+    let client = TmuxClient::new().with_socket(Socket::Name(primary.clone()));
+    let mut app = App::new(client).with_config(config);
+    app.load_initial_state().await.unwrap();
+    // Give the shells time to start, so the panes report them
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    for code in [KeyCode::Char('P'), KeyCode::Char('y')] {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+            .await
+            .unwrap();
+    }
+
+    let names = |socket: &str| {
+        let output = std::process::Command::new("tmux")
+            .args(["-L", socket, "list-sessions", "-F", "#{session_name}"])
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(names(&primary), "busy\n");
+    assert_eq!(names(&other), "build\n");
+
+    tmux(&primary, &["kill-server"]);
+    tmux(&other, &["kill-server"]);
+}
+
+#[tokio::test]
+async fn test_error_banner_hints_use_the_keymap() {
+    for (keys, retry, dismiss) in [
+        ("", Some("[R] retry"), Some("[C] dismiss")),
+        (
+            "refresh = \"F5\"\ndismiss_error = []",
+            Some("[F5] retry"),
+            None,
+        ),
+    ] {
+        let mock = MockBackend::new().with_session("work");
+        let config = Config::parse(&format!("[keys]\n{}", keys)).unwrap();
+        let mut app = App::new(mock.clone()).with_config(config);
+        app.load_initial_state().await.unwrap();
+
+        // The window cannot be created once the session is gone
+        mock.kill_session("work").unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE))
+            .await
+            .unwrap();
+        let text = render(&mut app, 160, 24);
+        assert!(text.contains("Error creating window"));
+        for (hint, expected) in [("retry", retry), ("dismiss", dismiss)] {
+            match expected {
+                Some(expected) => assert!(text.contains(expected), "{}", text),
+                None => assert!(!text.contains(hint), "{}", text),
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_vim_mode_motions_and_counts() {
+    let mut mock = MockBackend::new();
+    for i in 0..30 {
+        mock = mock.with_session(&format!("s{:02}", i));
+    }
+    let mut app = App::new(mock).with_config(Config::parse("vim_mode = true").unwrap());
+    app.load_initial_state().await.unwrap();
+    let type_keys = |keys: &'static str| {
+        keys.chars()
+            .map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+            .collect::<Vec<_>>()
+    };
+
+    let text = render(&mut app, 80, 20);
+    assert!(text.contains("Press 'F1' for help"));
+    for key in type_keys("5j") {
+        app.handle_key(key).await.unwrap();
+    }
+    assert!(render(&mut app, 80, 20).contains(">> › s05"));
+    for key in type_keys("12jk") {
+        app.handle_key(key).await.unwrap();
+    }
+    assert!(render(&mut app, 80, 20).contains(">> › s16"));
+    // 9 rows show, so half a page is 4
+    app.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL))
+        .await
+        .unwrap();
+    assert!(render(&mut app, 80, 20).contains(">> › s12"));
+
+    for key in type_keys("G") {
+        app.handle_key(key).await.unwrap();
+    }
+    assert!(render(&mut app, 80, 20).contains(">> › s29"));
+    for key in type_keys("gg") {
+        app.handle_key(key).await.unwrap();
+    }
+    assert!(render(&mut app, 80, 20).contains(">> › s00"));
+    for key in type_keys("10G") {
+        app.handle_key(key).await.unwrap();
+    }
+    assert!(render(&mut app, 80, 20).contains(">> › s09"));
+
+    // l shows the windows of the selected session, h goes back
+    for key in type_keys("l") {
+        app.handle_key(key).await.unwrap();
+    }
+    assert!(render(&mut app, 80, 20).contains("Windows of 's09'"));
+    for key in type_keys("h") {
+        app.handle_key(key).await.unwrap();
+    }
+    assert!(render(&mut app, 80, 20).contains("tmux Sessions"));
+}
+
+#[tokio::test]
+async fn test_popup_layout_is_compact() {
+    let mut app = App::new(MockBackend::new().with_session("work")).with_popup(true);
+    app.load_initial_state().await.unwrap();
+
+    // Neither the title bar nor the margin, even with room for them
+    let text = render(&mut app, 80, 24);
+    assert!(!text.contains("Session Manager"));
+    assert!(text.starts_with("┌"));
+    assert!(text.contains("work"));
+    assert!(!render(&mut app, 60, 8).contains("Terminal too small"));
+}
@@ -0,0 +1,87 @@
+use tmux_ui::usage::{
+    format_duration, recent_sessions, EventKind, ReportFormat, Since, UsageEvent, UsageLog,
+    UsageReport,
+};
+
+fn event(timestamp: u64, kind: EventKind, session: &str) -> UsageEvent {
+    UsageEvent {
+        timestamp,
+        kind,
+        session: session.to_string(),
+    }
+}
+
+#[test]
+fn test_since_parsing() {
+    assert_eq!("7d".parse::<Since>().unwrap(), Since(7 * 86_400));
+    assert_eq!("12h".parse::<Since>().unwrap(), Since(12 * 3_600));
+    assert_eq!("2w".parse::<Since>().unwrap(), Since(14 * 86_400));
+    assert!("7y".parse::<Since>().is_err());
+    assert!("d".parse::<Since>().is_err());
+    assert!("99999999999999999w".parse::<Since>().is_err());
+}
+
+#[test]
+fn test_report_attach_time_and_counts() {
+    let events = vec![
+        event(1_000, EventKind::Created, "api"),
+        event(1_000, EventKind::Attached, "api"),
+        event(4_600, EventKind::Attached, "web"),
+        event(5_200, EventKind::WindowCreated, "web"),
+        event(5_800, EventKind::Detached, "web"),
+        event(6_000, EventKind::Killed, "web"),
+        // Outside the report window
+        event(10, EventKind::Created, "old"),
+    ];
+
+    let report = UsageReport::build(&events, 500, 7_000);
+    assert_eq!(report.sessions_created, 1);
+    assert_eq!(report.sessions_killed, 1);
+    assert_eq!(report.projects[0].session, "api");
+    assert_eq!(report.projects[0].attached_seconds, 3_600);
+    assert_eq!(report.projects[1].session, "web");
+    assert_eq!(report.projects[1].attached_seconds, 1_200);
+    assert_eq!(report.projects[1].windows_created, 1);
+
+    let markdown = report.render(ReportFormat::Markdown).unwrap();
+    assert!(markdown.contains("| api | 1h 0m | 1 | 0 |"));
+    let json = report.render(ReportFormat::Json).unwrap();
+    assert!(json.contains("\"attached_seconds\": 3600"));
+}
+
+#[test]
+fn test_usage_log_round_trip() {
+    let path = std::env::temp_dir()
+        .join(format!("tmux-ui-usage-{}", std::process::id()))
+        .join("usage.log");
+    let log = UsageLog::new(path.clone());
+
+    log.record(EventKind::Created, "round\ttrip");
+    log.record(EventKind::Killed, "round\ttrip");
+    let events = log.read().unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].kind, EventKind::Created);
+    assert_eq!(events[1].session, "round trip");
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(59), "0m");
+    assert_eq!(format_duration(3_660), "1h 1m");
+    assert_eq!(format_duration(90_000), "1d 1h");
+}
+
+#[test]
+fn test_recent_sessions() {
+    let events = vec![
+        event(1, EventKind::Attached, "a"),
+        event(2, EventKind::Attached, "b"),
+        event(3, EventKind::Created, "c"),
+        event(4, EventKind::Attached, "a"),
+        event(5, EventKind::Detached, "a"),
+    ];
+    assert_eq!(recent_sessions(&events, 10), vec!["a", "b"]);
+    assert_eq!(recent_sessions(&events, 1), vec!["a"]);
+}
@@ -0,0 +1,72 @@
+use tmux_ui::tmux::{Capabilities, Error, ServerInfo, Socket, TmuxClient, TmuxVersion};
+
+fn version(s: &str) -> TmuxVersion {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_parse_tmux_versions() {
+    assert_eq!(
+        version("tmux 3.3a"),
+        TmuxVersion {
+            major: 3,
+            minor: 3,
+            patch: Some('a'),
+        }
+    );
+    assert_eq!(version("3.2").to_string(), "3.2");
+    assert_eq!(version("tmux next-3.4\n").to_string(), "3.4");
+    assert!("tmux master".parse::<TmuxVersion>().is_err());
+    assert!("tmux openbsd-7.4".parse::<TmuxVersion>().is_err());
+    assert!("3.3ab".parse::<TmuxVersion>().is_err());
+}
+
+#[test]
+fn test_versions_compare_by_release() {
+    assert!(version("3.3a") > version("3.3"));
+    assert!(version("3.10") > version("3.9"));
+    assert!(version("2.9a") < version("3.0"));
+}
+
+#[test]
+fn test_require_minimum_version() {
+    let info = |description: &str| ServerInfo {
+        description: description.to_string(),
+        version: description.parse().ok(),
+    };
+    assert!(info("tmux 3.3a").require(version("3.2")).is_ok());
+    assert!(info("tmux master").require(version("3.2")).is_ok());
+    let error = info("tmux 2.8").require(version("3.2")).unwrap_err();
+    assert!(matches!(error, Error::TmuxTooOld { .. }));
+    assert_eq!(
+        error.to_string(),
+        "tmux 2.8 is too old; tmux-ui needs tmux 3.2 or newer"
+    );
+}
+
+#[test]
+fn test_server_info_reports_the_installed_version() {
+    let socket = format!("tmux-ui-test-version-{}", std::process::id());
+    let client = TmuxClient::new().with_socket(Socket::Name(socket));
+    if let Ok(info) = client.server_info() {
+        assert!(info.description.starts_with("tmux "));
+    }
+}
+
+#[test]
+fn test_capabilities_follow_the_version() {
+    let old = Capabilities::new(Some(version("3.0a")));
+    assert!(!old.has_popup());
+    assert!(!old.has_extended_keys());
+    assert!(!old.has_client_flags());
+    assert!(old.has_pane_titles());
+    assert!(!Capabilities::new(Some(version("2.5"))).has_pane_titles());
+
+    let current = Capabilities::new(Some(version("3.2")));
+    assert!(current.has_popup() && current.has_extended_keys() && current.has_client_flags());
+
+    // Builds without a release number are taken to be recent
+    let unknown = Capabilities::new(None);
+    assert_eq!(unknown, Capabilities::default());
+    assert!(unknown.has_popup() && unknown.has_pane_titles());
+}
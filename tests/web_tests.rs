@@ -0,0 +1,98 @@
+#![cfg(all(feature = "web", unix))]
+
+use futures_util::{SinkExt, StreamExt};
+use tmux_ui::daemon::Daemon;
+use tmux_ui::serve::Api;
+use tmux_ui::tmux::mock::MockBackend;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::Duration;
+use tokio_tungstenite::tungstenite::{Error, Message};
+use tokio_tungstenite::{client_async, WebSocketStream};
+
+async fn serve(mock: MockBackend, token: Option<&str>) -> std::net::SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let api = Api::new(Daemon::new(mock), token.map(str::to_string));
+    tokio::spawn(api.serve(listener, Duration::from_secs(60)));
+    addr
+}
+
+/// Open the page's socket at `target`
+async fn open(
+    addr: std::net::SocketAddr,
+    target: &str,
+) -> Result<WebSocketStream<TcpStream>, Error> {
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let url = format!("ws://localhost:{}{}", addr.port(), target);
+    client_async(url, stream).await.map(|(socket, _)| socket)
+}
+
+async fn next_json(socket: &mut WebSocketStream<TcpStream>) -> serde_json::Value {
+    match socket.next().await {
+        Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+        other => panic!("expected a text message, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_page_and_socket() {
+    let mock = MockBackend::new().with_session("work");
+    let addr = serve(mock.clone(), None).await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut page = String::new();
+    stream.read_to_string(&mut page).await.unwrap();
+    assert!(page.starts_with("HTTP/1.1 200 OK"));
+    assert!(page.contains("text/html"));
+    assert!(page.contains("new WebSocket"));
+
+    let mut socket = open(addr, "/ws").await.unwrap();
+    let event = next_json(&mut socket).await;
+    assert_eq!(event["event"], "sessions");
+    assert_eq!(event["sessions"][0]["name"], "work");
+
+    let request =
+        r#"{"id": 1, "method": "new-window", "params": {"session": "work", "name": "logs"}}"#;
+    socket.send(Message::text(request)).await.unwrap();
+    // Creating the window changes the session list, so an event may come first
+    let mut response = next_json(&mut socket).await;
+    while response["event"] == "sessions" {
+        response = next_json(&mut socket).await;
+    }
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["created"]["name"], "logs");
+    assert!(mock.calls().iter().any(|call| call.contains("new-window")));
+
+    socket.send(Message::Ping("ping".into())).await.unwrap();
+    let mut message = socket.next().await.unwrap().unwrap();
+    while message.is_text() {
+        message = socket.next().await.unwrap().unwrap();
+    }
+    assert_eq!(message, Message::Pong("ping".into()));
+
+    socket.close(None).await.unwrap();
+    let mut message = socket.next().await;
+    while let Some(Ok(Message::Text(_))) = message {
+        message = socket.next().await;
+    }
+    assert!(
+        matches!(message, Some(Ok(Message::Close(_)))),
+        "{:?}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn test_socket_needs_the_token() {
+    let addr = serve(MockBackend::new(), Some("secret")).await;
+    match open(addr, "/ws").await {
+        Err(Error::Http(response)) => assert_eq!(response.status(), 401),
+        other => panic!("expected a refusal, got {:?}", other.map(|_| ())),
+    }
+    assert!(open(addr, "/ws?token=secret").await.is_ok());
+}
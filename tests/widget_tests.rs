@@ -0,0 +1,87 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tmux_ui::tui::widgets::{Jump, TextInput};
+
+fn press(input: &mut TextInput, code: KeyCode, modifiers: KeyModifiers) -> bool {
+    input.handle_key(&KeyEvent::new(code, modifiers))
+}
+
+fn typed(text: &str) -> TextInput {
+    let mut input = TextInput::new();
+    for c in text.chars() {
+        press(&mut input, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    input
+}
+
+#[test]
+fn test_typing_at_the_cursor() {
+    let mut input = typed("helo");
+    press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+    press(&mut input, KeyCode::Char('l'), KeyModifiers::NONE);
+    assert_eq!(input.as_str(), "hello");
+    assert_eq!(input.cursor(), 4);
+
+    press(&mut input, KeyCode::Home, KeyModifiers::NONE);
+    press(&mut input, KeyCode::Delete, KeyModifiers::NONE);
+    press(&mut input, KeyCode::Char('H'), KeyModifiers::SHIFT);
+    assert_eq!(input.as_str(), "Hello");
+
+    // Keys that are not editing keys are left to the prompt
+    assert!(!press(&mut input, KeyCode::Enter, KeyModifiers::NONE));
+    assert!(!press(
+        &mut input,
+        KeyCode::Char('x'),
+        KeyModifiers::CONTROL
+    ));
+    assert_eq!(input.as_str(), "Hello");
+}
+
+#[test]
+fn test_word_movement_and_deletion() {
+    let mut input = typed("git commit --amend");
+    press(&mut input, KeyCode::Char('b'), KeyModifiers::ALT);
+    assert_eq!(input.cursor(), 13);
+    press(&mut input, KeyCode::Char('b'), KeyModifiers::ALT);
+    assert_eq!(input.cursor(), 4);
+    press(&mut input, KeyCode::Char('f'), KeyModifiers::ALT);
+    assert_eq!(input.cursor(), 10);
+
+    // Ctrl+W deletes back to the previous whitespace
+    press(&mut input, KeyCode::End, KeyModifiers::NONE);
+    press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+    assert_eq!(input.as_str(), "git commit ");
+    press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+    assert_eq!(input.as_str(), "git ");
+
+    press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+    press(&mut input, KeyCode::Char('u'), KeyModifiers::CONTROL);
+    assert_eq!(input.as_str(), " ");
+    assert_eq!(input.cursor(), 0);
+}
+
+#[test]
+fn test_paste_and_multibyte_text() {
+    let mut input = typed("café");
+    press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+    press(&mut input, KeyCode::Backspace, KeyModifiers::NONE);
+    assert_eq!(input.as_str(), "caé");
+
+    input.set("echo ");
+    input.insert_str("one\ntwo\t\n");
+    assert_eq!(input.as_str(), "echo one two");
+    assert_eq!(input.cursor(), 12);
+}
+
+#[test]
+fn test_jumps_stop_at_the_ends_of_the_list() {
+    assert_eq!(Jump::First.apply(Some(5), 10), Some(0));
+    assert_eq!(Jump::Last.apply(None, 10), Some(9));
+    assert_eq!(Jump::Down(4).apply(Some(3), 10), Some(7));
+    assert_eq!(Jump::Down(4).apply(Some(7), 10), Some(9));
+    assert_eq!(Jump::Up(4).apply(Some(2), 10), Some(0));
+    // A selection left beyond a shrunk list moves back into it
+    assert_eq!(Jump::Up(4).apply(Some(20), 10), Some(5));
+    assert_eq!(Jump::To(4).apply(Some(0), 10), Some(4));
+    assert_eq!(Jump::To(40).apply(Some(0), 10), Some(9));
+    assert_eq!(Jump::Last.apply(Some(0), 0), None);
+}